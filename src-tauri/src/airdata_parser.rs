@@ -17,9 +17,9 @@ use std::path::Path;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
 
-use crate::database::Database;
-use crate::models::{FlightMessage, FlightMetadata, FlightStats, TelemetryPoint};
-use crate::parser::{LogParser, ParseResult, ParserError};
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMessage, FlightMetadata, FlightStats, ParsedFlight, TelemetryPoint};
+use crate::parser::{LogParser, ParseOptions, ParserError};
 
 // ---------------------------------------------------------------------------
 // Unit detection & conversion
@@ -213,56 +213,60 @@ fn parse_csv_line(line: &str) -> Vec<String> {
     fields
 }
 
-// ---------------------------------------------------------------------------
-// AirdataParser
-// ---------------------------------------------------------------------------
+/// Airdata CSV export format
+pub struct AirdataFormat;
 
-/// Airdata CSV Parser
-pub struct AirdataParser<'a> {
-    db: &'a Database,
-}
+impl FlightLogFormat for AirdataFormat {
+    fn id(&self) -> &'static str {
+        "airdata_csv"
+    }
 
-impl<'a> AirdataParser<'a> {
-    pub fn new(db: &'a Database) -> Self {
-        Self { db }
+    fn name(&self) -> &'static str {
+        "Airdata CSV"
     }
 
-    // ------------------------------------------------------------------
-    // Format detection
-    // ------------------------------------------------------------------
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
 
-    /// Heuristic check: is this file an Airdata CSV export?
-    ///
     /// Looks for characteristic Airdata columns that don't appear in Litchi
     /// or DroneLogbook exports: `time(millisecond)`, `datetime(utc)`,
-    /// `flycstate`, `height_above_takeoff`.
-    pub fn is_airdata_csv(path: &Path) -> bool {
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("");
-        if !ext.eq_ignore_ascii_case("csv") {
-            return false;
+    /// `flycstate`.
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8 {
+        if !formats::has_extension(path, "csv") {
+            return 0;
         }
-
-        if let Ok(file) = File::open(path) {
-            let reader = BufReader::new(file);
-            if let Some(Ok(first_line)) = reader.lines().next() {
-                let lower = first_line.to_lowercase();
-                // Airdata-specific markers
-                return lower.contains("time(millisecond)")
-                    && lower.contains("datetime(utc)")
-                    && lower.contains("flycstate");
-            }
+        match formats::header_line(header) {
+            Some(lower) if lower.contains("time(millisecond)")
+                && lower.contains("datetime(utc)")
+                && lower.contains("flycstate") => 95,
+            _ => 0,
         }
-        false
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, _options: &'a ParseOptions) -> ParseFuture<'a> {
+        formats::ready(AirdataParser::new().parse(path, file_hash))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AirdataParser
+// ---------------------------------------------------------------------------
+
+/// Airdata CSV Parser
+#[derive(Default)]
+pub struct AirdataParser;
+
+impl AirdataParser {
+    pub fn new() -> Self {
+        Self
     }
 
     // ------------------------------------------------------------------
     // Main parse entry point
     // ------------------------------------------------------------------
 
-    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
         let parse_start = std::time::Instant::now();
         log::info!("Parsing Airdata CSV file: {:?}", file_path);
 
@@ -386,7 +390,7 @@ impl<'a> AirdataParser<'a> {
         tags.insert(0, "Airdata".to_string());
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult {
+        Ok(ParsedFlight {
             metadata,
             points,
             tags,
@@ -394,6 +398,8 @@ impl<'a> AirdataParser<'a> {
             notes: None,
             color: None,
             messages,
+            side_data: Default::default(),
+            equipment: Default::default(),
         })
     }
 
@@ -590,7 +596,7 @@ impl<'a> AirdataParser<'a> {
         let (photo_count, video_count) = crate::models::count_media_events(points);
 
         Ok(FlightMetadata {
-            id: self.db.generate_flight_id(),
+            id: 0,
             file_name,
             display_name,
             file_hash: Some(file_hash.to_string()),
@@ -7,11 +7,18 @@
 //! 1. Environment variable: DJI_API_KEY
 //! 2. Config file in app data directory: config.json
 //! 3. .env file in the project root (development)
+//!
+//! The keychain endpoint defaults to DJI's service and can be replaced
+//! (e.g. a proxy) via the DJI_KEYCHAIN_ENDPOINT environment variable or
+//! `dji_keychain_endpoint` in config.json. The web server points it at the
+//! mock server in [`crate::keychain_mock`] with
+//! [`set_default_keychain_endpoint`].
 
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{OnceLock, RwLock};
 
+use dji_log_parser::keychain::{KeychainFeaturePoint, KeychainsRequest};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -19,6 +26,17 @@ use thiserror::Error;
 static API_KEY: OnceLock<RwLock<Option<String>>> = OnceLock::new();
 const DEFAULT_DJI_API_KEY: &str = "7860e0c278e44617fd4c64fd86cfeaa";
 
+/// Process-wide keychain endpoint, set once at startup
+static DEFAULT_KEYCHAIN_ENDPOINT: OnceLock<String> = OnceLock::new();
+
+/// Send keychain requests of every [`DjiApi`] to `endpoint` unless
+/// DJI_KEYCHAIN_ENDPOINT or the client itself names another one.
+/// Only the first call has an effect; returns whether it was this one.
+#[allow(dead_code)]
+pub fn set_default_keychain_endpoint(endpoint: String) -> bool {
+    DEFAULT_KEYCHAIN_ENDPOINT.set(endpoint).is_ok()
+}
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("HTTP error: {0}")]
@@ -39,11 +57,16 @@ pub enum ApiError {
 pub struct AppConfig {
     #[serde(default)]
     pub dji_api_key: Option<String>,
+    /// Keychain endpoint overriding DJI's service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dji_keychain_endpoint: Option<String>,
 }
 
 /// DJI API client for key fetching
 pub struct DjiApi {
     app_data_dir: Option<PathBuf>,
+    /// Endpoint set in code, taking precedence over env and config
+    keychain_endpoint: Option<String>,
 }
 
 impl DjiApi {
@@ -51,6 +74,7 @@ impl DjiApi {
     pub fn new() -> Self {
         Self {
             app_data_dir: None,
+            keychain_endpoint: None,
         }
     }
 
@@ -58,9 +82,46 @@ impl DjiApi {
     pub fn with_app_data_dir(app_data_dir: PathBuf) -> Self {
         Self {
             app_data_dir: Some(app_data_dir),
+            keychain_endpoint: None,
         }
     }
 
+    /// Send keychain requests to `endpoint` instead of the configured one
+    #[allow(dead_code)]
+    pub fn with_keychain_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.keychain_endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Get the keychain endpoint override, if any
+    ///
+    /// Priority:
+    /// 1. Endpoint set with [`DjiApi::with_keychain_endpoint`]
+    /// 2. Environment variable DJI_KEYCHAIN_ENDPOINT
+    /// 3. Endpoint set with [`set_default_keychain_endpoint`]
+    /// 4. `dji_keychain_endpoint` in config.json
+    ///
+    /// `None` means DJI's own keychain service.
+    pub fn keychain_endpoint(&self) -> Option<String> {
+        if let Some(ref endpoint) = self.keychain_endpoint {
+            return Some(endpoint.clone());
+        }
+        if let Ok(endpoint) = std::env::var("DJI_KEYCHAIN_ENDPOINT") {
+            if !endpoint.trim().is_empty() {
+                return Some(endpoint.trim().to_string());
+            }
+        }
+        if let Some(endpoint) = DEFAULT_KEYCHAIN_ENDPOINT.get() {
+            return Some(endpoint.clone());
+        }
+        let config_path = self.app_data_dir.as_ref()?.join("config.json");
+        let content = fs::read_to_string(config_path).ok()?;
+        serde_json::from_str::<AppConfig>(&content)
+            .ok()?
+            .dji_keychain_endpoint
+            .filter(|e| !e.trim().is_empty())
+    }
+
     /// Get the DJI API key from various sources (cached)
     ///
     /// Priority:
@@ -281,6 +342,26 @@ impl Default for DjiApi {
     }
 }
 
+/// Source of decryption keychains for encrypted (V13+) DJI logs.
+///
+/// The parser only needs keychains, not an API key, so library users can
+/// plug in a cache or an offline store instead of [`DjiApi`].
+pub trait KeychainProvider: Send + Sync {
+    /// Fetch the keychains described by a request built from the log.
+    /// Return [`ApiError::ApiKeyNotConfigured`] when no credentials are available.
+    fn fetch_keychains(&self, request: &KeychainsRequest) -> Result<Vec<Vec<KeychainFeaturePoint>>, ApiError>;
+}
+
+impl KeychainProvider for DjiApi {
+    fn fetch_keychains(&self, request: &KeychainsRequest) -> Result<Vec<Vec<KeychainFeaturePoint>>, ApiError> {
+        let api_key = self.get_api_key().ok_or(ApiError::ApiKeyNotConfigured)?;
+        let endpoint = self.keychain_endpoint();
+        request
+            .fetch(&api_key, endpoint.as_deref())
+            .map_err(|e| ApiError::ApiResponse(e.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,6 +370,7 @@ mod tests {
     fn test_config_serialization() {
         let config = AppConfig {
             dji_api_key: Some("test_key".to_string()),
+            dji_keychain_endpoint: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -297,4 +379,42 @@ mod tests {
         let parsed: AppConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.dji_api_key, Some("test_key".to_string()));
     }
+
+    #[cfg(feature = "web")]
+    #[test]
+    fn test_keychains_from_mock_server() {
+        use crate::keychain_mock::{self, KeychainStore};
+        use crate::models::{KeychainBundle, KeychainBundleEntry};
+        use sha2::{Digest, Sha256};
+
+        let request = KeychainsRequest { version: 13, department: 3, keychains: Vec::new() };
+        let body = serde_json::to_string(&request).unwrap();
+        let bundle = KeychainBundle {
+            format: "open-dronelog-keychains".to_string(),
+            version: 1,
+            exported_at: String::new(),
+            entries: vec![KeychainBundleEntry {
+                request_hash: format!("{:x}", Sha256::digest(body.as_bytes())),
+                keychains: serde_json::json!([[{
+                    "featurePoint": "FR_Standardization_Feature_Base_1",
+                    "aesKey": "a2V5",
+                    "aesIv": "aXY="
+                }]]),
+                fetched_at: None,
+            }],
+        };
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+        let endpoint = format!("http://{}{}", listener.local_addr().unwrap(), keychain_mock::MOCK_PATH);
+        runtime.spawn(keychain_mock::serve(listener, KeychainStore::from_bundle(&bundle)));
+
+        let api = DjiApi::new().with_keychain_endpoint(endpoint);
+        let keychains = api.fetch_keychains(&request).unwrap();
+        assert_eq!(keychains[0][0].aes_key, "a2V5");
+
+        // Logs missing from the file are reported as API errors
+        let unknown = KeychainsRequest { version: 14, ..request };
+        assert!(matches!(api.fetch_keychains(&unknown), Err(ApiError::ApiResponse(_))));
+    }
 }
@@ -0,0 +1,411 @@
+//! Safe expansion of flight log archives (.zip, .tar.gz).
+//!
+//! Pilots often send a zipped `FlightRecord` folder instead of single logs.
+//! Archives are unpacked into a scratch directory with limits on the entry
+//! count and the uncompressed size. Entries that would land outside the
+//! scratch directory (zip-slip), links, and files that are not a supported
+//! flight log are skipped. Each entry is written to its own numbered
+//! subdirectory, so entries with the same name do not overwrite each other
+//! and keep their original file name.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use thiserror::Error;
+
+use crate::formats::FORMATS;
+
+/// Maximum number of entries (files and directories) in an archive
+const MAX_ENTRIES: usize = 10_000;
+
+/// Maximum uncompressed size of a single extracted log
+const MAX_ENTRY_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Maximum uncompressed size of all extracted logs together
+const MAX_TOTAL_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Extraction limits, separate from the constants so tests can use small ones
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    entries: usize,
+    entry_bytes: u64,
+    total_bytes: u64,
+}
+
+const LIMITS: Limits = Limits {
+    entries: MAX_ENTRIES,
+    entry_bytes: MAX_ENTRY_BYTES,
+    total_bytes: MAX_TOTAL_BYTES,
+};
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid archive: {0}")]
+    Invalid(String),
+
+    #[error("Archive has more than {0} entries")]
+    TooManyEntries(usize),
+
+    #[error("Archive entry '{0}' is larger than {1} MB")]
+    EntryTooLarge(String, u64),
+
+    #[error("Archive expands to more than {0} MB")]
+    TooLarge(u64),
+}
+
+/// A flight log extracted from an archive
+#[derive(Debug, Clone)]
+pub struct ExtractedEntry {
+    /// Path of the entry inside the archive
+    pub name: String,
+    /// Location of the extracted file
+    pub path: PathBuf,
+}
+
+/// Extracted archive contents. The scratch directory is removed on drop.
+pub struct ExtractedArchive {
+    dir: PathBuf,
+    pub entries: Vec<ExtractedEntry>,
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            log::warn!("Failed to remove archive scratch directory {:?}: {}", self.dir, e);
+        }
+    }
+}
+
+/// Check if a file is a supported archive (.zip, .tar.gz or .tgz)
+pub fn is_archive(path: &Path) -> bool {
+    let name = file_name_lower(path);
+    name.ends_with(".zip") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Extract the flight logs contained in an archive into a scratch directory
+pub fn extract(archive: &Path) -> Result<ExtractedArchive, ArchiveError> {
+    extract_with_limits(archive, LIMITS)
+}
+
+fn extract_with_limits(archive: &Path, limits: Limits) -> Result<ExtractedArchive, ArchiveError> {
+    let dir = std::env::temp_dir()
+        .join("drone-logbook-archives")
+        .join(uuid::Uuid::new_v4().to_string());
+    fs::create_dir_all(&dir)?;
+
+    // Created before extraction so the directory is cleaned up on error
+    let mut extracted = ExtractedArchive { dir, entries: Vec::new() };
+    let mut total_bytes = 0u64;
+
+    if file_name_lower(archive).ends_with(".zip") {
+        let mut zip = zip::ZipArchive::new(File::open(archive)?)
+            .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+        if zip.len() > limits.entries {
+            return Err(ArchiveError::TooManyEntries(limits.entries));
+        }
+
+        for i in 0..zip.len() {
+            let mut file = zip
+                .by_index(i)
+                .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+            if !file.is_file() {
+                continue;
+            }
+            let name = file.name().to_string();
+            let Some(relative) = file.enclosed_name() else {
+                log::warn!("Skipping archive entry with unsafe path: {}", name);
+                continue;
+            };
+            if !is_supported_log(&relative) {
+                log::debug!("Skipping unsupported archive entry: {}", name);
+                continue;
+            }
+            if file.size() > limits.entry_bytes {
+                return Err(limits.entry_too_large(name));
+            }
+            extracted.write_entry(i, name, &relative, &mut file, &mut total_bytes, limits)?;
+        }
+    } else {
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(archive)?));
+        let entries = tar
+            .entries()
+            .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+
+        for (i, entry) in entries.enumerate() {
+            if i >= limits.entries {
+                return Err(ArchiveError::TooManyEntries(limits.entries));
+            }
+            let mut entry = entry.map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let entry_path = entry
+                .path()
+                .map_err(|e| ArchiveError::Invalid(e.to_string()))?
+                .into_owned();
+            let name = entry_path.to_string_lossy().to_string();
+            let Some(relative) = enclosed_tar_path(&entry_path) else {
+                log::warn!("Skipping archive entry with unsafe path: {}", name);
+                continue;
+            };
+            if !is_supported_log(&relative) {
+                log::debug!("Skipping unsupported archive entry: {}", name);
+                continue;
+            }
+            if entry.size() > limits.entry_bytes {
+                return Err(limits.entry_too_large(name));
+            }
+            extracted.write_entry(i, name, &relative, &mut entry, &mut total_bytes, limits)?;
+        }
+    }
+
+    extracted.entries.sort_by(|a, b| a.name.cmp(&b.name));
+    log::info!(
+        "Extracted {} flight logs ({:.1} MB) from {:?}",
+        extracted.entries.len(),
+        total_bytes as f64 / (1024.0 * 1024.0),
+        archive
+    );
+    Ok(extracted)
+}
+
+impl Limits {
+    fn entry_too_large(&self, name: String) -> ArchiveError {
+        ArchiveError::EntryTooLarge(name, self.entry_bytes / (1024 * 1024))
+    }
+}
+
+impl ExtractedArchive {
+    /// Copy one entry to `<scratch>/<index>/<file name>`, enforcing the size
+    /// limits on the bytes actually read rather than the sizes claimed by the
+    /// headers
+    fn write_entry(
+        &mut self,
+        index: usize,
+        name: String,
+        relative: &Path,
+        reader: &mut dyn Read,
+        total_bytes: &mut u64,
+        limits: Limits,
+    ) -> Result<(), ArchiveError> {
+        let file_name = relative
+            .file_name()
+            .ok_or_else(|| ArchiveError::Invalid(format!("Entry without a file name: {}", name)))?;
+        let entry_dir = self.dir.join(index.to_string());
+        fs::create_dir_all(&entry_dir)?;
+        let dest = entry_dir.join(file_name);
+
+        let mut out = File::create(&dest)?;
+        let written = io::copy(&mut reader.take(limits.entry_bytes + 1), &mut out)?;
+        if written > limits.entry_bytes {
+            return Err(limits.entry_too_large(name));
+        }
+
+        *total_bytes += written;
+        if *total_bytes > limits.total_bytes {
+            return Err(ArchiveError::TooLarge(limits.total_bytes / (1024 * 1024)));
+        }
+
+        self.entries.push(ExtractedEntry { name, path: dest });
+        Ok(())
+    }
+}
+
+/// Relative path of a tar entry, or None if it is absolute or contains `..`
+fn enclosed_tar_path(path: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Whether an archive entry looks like a log one of the registered formats accepts.
+/// macOS resource forks (`__MACOSX/`, `._name`) are skipped.
+fn is_supported_log(relative: &Path) -> bool {
+    if relative.components().any(|c| c.as_os_str() == "__MACOSX") {
+        return false;
+    }
+    let file_name = relative.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if file_name.starts_with("._") {
+        return false;
+    }
+    let ext = relative
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    FORMATS.iter().any(|f| f.extensions().contains(&ext.as_str()))
+}
+
+fn file_name_lower(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    /// Headers are filled in by hand because `tar::Builder` refuses `..` paths
+    fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+        let gz = flate2::write::GzEncoder::new(
+            File::create(path).unwrap(),
+            flate2::Compression::fast(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        for (name, data) in files {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            tar.append(&header, *data).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    /// Build the same archive as .zip and .tar.gz
+    fn archives(dir: &Path, files: &[(&str, &[u8])]) -> Vec<PathBuf> {
+        let zip = dir.join("logs.zip");
+        let tgz = dir.join("logs.tar.gz");
+        write_zip(&zip, files);
+        write_tar_gz(&tgz, files);
+        vec![zip, tgz]
+    }
+
+    fn small_limits() -> Limits {
+        Limits {
+            entries: 10,
+            entry_bytes: 16,
+            total_bytes: 24,
+        }
+    }
+
+    #[test]
+    fn test_unsafe_paths_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        for archive in archives(
+            tmp.path(),
+            &[
+                ("../evil.txt", b"evil"),
+                ("logs/../../evil.txt", b"evil"),
+                ("/abs.txt", b"evil"),
+                ("logs/DJIFlightRecord_1.txt", b"log"),
+            ],
+        ) {
+            let extracted = extract(&archive).unwrap();
+            let names: Vec<&str> = extracted.entries.iter().map(|e| e.name.as_str()).collect();
+            assert_eq!(names, ["logs/DJIFlightRecord_1.txt"], "{:?}", archive);
+            assert!(extracted.entries[0].path.starts_with(&extracted.dir));
+            assert!(!extracted.dir.parent().unwrap().join("evil.txt").exists());
+        }
+    }
+
+    #[test]
+    fn test_duplicate_names_do_not_overwrite() {
+        let tmp = tempfile::tempdir().unwrap();
+        for archive in archives(
+            tmp.path(),
+            &[("a/flight.txt", b"first"), ("b/flight.txt", b"second")],
+        ) {
+            let extracted = extract(&archive).unwrap();
+            assert_eq!(extracted.entries.len(), 2);
+            let contents: Vec<String> = extracted
+                .entries
+                .iter()
+                .map(|e| {
+                    assert_eq!(e.path.file_name().unwrap(), "flight.txt");
+                    fs::read_to_string(&e.path).unwrap()
+                })
+                .collect();
+            assert_eq!(contents, ["first", "second"]);
+        }
+
+        // A tar may even hold the same path twice
+        let tgz = tmp.path().join("twice.tgz");
+        write_tar_gz(&tgz, &[("flight.txt", b"first"), ("flight.txt", b"second")]);
+        let extracted = extract(&tgz).unwrap();
+        let contents: Vec<String> = extracted
+            .entries
+            .iter()
+            .map(|e| fs::read_to_string(&e.path).unwrap())
+            .collect();
+        assert_eq!(contents, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_entry_size_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        for archive in archives(tmp.path(), &[("big.txt", &[0u8; 17])]) {
+            let err = extract_with_limits(&archive, small_limits()).err().unwrap();
+            assert!(matches!(err, ArchiveError::EntryTooLarge(ref name, _) if name == "big.txt"));
+        }
+    }
+
+    #[test]
+    fn test_total_size_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        for archive in archives(tmp.path(), &[("a.txt", &[0u8; 16]), ("b.txt", &[0u8; 16])]) {
+            let err = extract_with_limits(&archive, small_limits()).err().unwrap();
+            assert!(matches!(err, ArchiveError::TooLarge(_)), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn test_streamed_size_is_enforced() {
+        // The bytes read count, whatever size the header claims
+        let tmp = tempfile::tempdir().unwrap();
+        let mut extracted = ExtractedArchive {
+            dir: tmp.path().join("scratch"),
+            entries: Vec::new(),
+        };
+        let mut total = 0;
+        let err = extracted
+            .write_entry(
+                0,
+                "big.txt".to_string(),
+                Path::new("big.txt"),
+                &mut io::repeat(0),
+                &mut total,
+                small_limits(),
+            )
+            .err()
+            .unwrap();
+        assert!(matches!(err, ArchiveError::EntryTooLarge(..)));
+    }
+
+    #[test]
+    fn test_too_many_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let names: Vec<String> = (0..11).map(|i| format!("{}.txt", i)).collect();
+        let files: Vec<(&str, &[u8])> = names.iter().map(|n| (n.as_str(), &b""[..])).collect();
+        for archive in archives(tmp.path(), &files) {
+            let err = extract_with_limits(&archive, small_limits()).err().unwrap();
+            assert!(matches!(err, ArchiveError::TooManyEntries(10)), "{:?}", err);
+        }
+    }
+}
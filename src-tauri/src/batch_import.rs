@@ -0,0 +1,495 @@
+//! Concurrent import of many log files.
+//!
+//! Files are hashed (in `spawn_blocking`) and parsed by up to `workers`
+//! tasks at once, while every database write — inserting flights, recording
+//! failures, queueing logs for decryption, caching keychains fetched from
+//! the DJI API — goes through a single writer loop. Parsing dominates
+//! import time, so a large backfill scales with the number of workers while
+//! DuckDB only ever sees one writer.
+//!
+//! A batch can be cancelled through a shared flag: files not yet picked up
+//! by a worker are left alone, files already being parsed still finish.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+use crate::database::{Database, FetchedKeychains, ImportError, ImportOptions};
+use crate::models::{BatchImportProgress, ImportResult, ParsedFlight};
+use crate::parser::{LogParser, ParserError};
+
+/// Upper bound for the default worker count; more rarely helps since the
+/// writer and the DJI keychain API become the bottleneck
+const MAX_DEFAULT_WORKERS: usize = 8;
+
+/// Number of concurrent parse workers: `IMPORT_WORKERS` when set, otherwise
+/// the available CPU cores (capped at 8)
+pub fn default_workers() -> usize {
+    std::env::var("IMPORT_WORKERS")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(MAX_DEFAULT_WORKERS)
+        })
+}
+
+/// What happened to one file of a batch
+#[derive(Debug, Clone)]
+pub enum BatchOutcome {
+    Imported { flight_id: i64, point_count: usize },
+    /// Already in the logbook, by file hash or by flight signature
+    Duplicate { message: String },
+    /// Blacklisted, or ignored after earlier failures
+    Skipped { message: String },
+    /// Encrypted and missing keychains; moved to the pending decryption queue
+    Queued { message: String },
+    Failed { message: String },
+    /// Not processed because the batch was cancelled
+    Cancelled,
+}
+
+/// Result for one file of a batch
+#[derive(Debug, Clone)]
+pub struct BatchFileResult {
+    pub path: PathBuf,
+    pub file_hash: Option<String>,
+    pub outcome: BatchOutcome,
+}
+
+impl BatchOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Imported { .. } => "imported",
+            Self::Duplicate { .. } => "duplicate",
+            Self::Skipped { .. } => "skipped",
+            Self::Queued { .. } => "queued",
+            Self::Failed { .. } => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl BatchFileResult {
+    /// Convert to the result shape the frontend uses for single imports
+    pub fn to_import_result(&self) -> ImportResult {
+        let (success, flight_id, point_count, message) = match &self.outcome {
+            BatchOutcome::Imported { flight_id, point_count } => (
+                true,
+                Some(*flight_id),
+                *point_count,
+                format!("Successfully imported {} telemetry points", point_count),
+            ),
+            BatchOutcome::Duplicate { message }
+            | BatchOutcome::Skipped { message }
+            | BatchOutcome::Queued { message }
+            | BatchOutcome::Failed { message } => (false, None, 0, message.clone()),
+            BatchOutcome::Cancelled => (false, None, 0, "Import cancelled".to_string()),
+        };
+        ImportResult {
+            success,
+            flight_id,
+            message,
+            point_count,
+            file_hash: self.file_hash.clone(),
+        }
+    }
+}
+
+/// Results of a whole batch, in input order
+#[derive(Debug, Clone)]
+pub struct BatchSummary {
+    pub progress: BatchImportProgress,
+    pub files: Vec<BatchFileResult>,
+}
+
+/// Per-file progress reported by [`BatchImporter::run_with_events`]
+#[derive(Debug)]
+pub enum BatchEvent<'a> {
+    /// A worker picked up the file
+    Started { index: usize, path: &'a Path },
+    /// The file was parsed and reached the writer
+    Parsed { index: usize, path: &'a Path },
+    /// The file is done; `progress` already counts it
+    Finished { index: usize, result: &'a BatchFileResult, progress: &'a BatchImportProgress },
+}
+
+/// Messages from the workers to the writer
+enum WorkerMsg {
+    Started(usize, PathBuf),
+    Item(WorkItem),
+}
+
+/// Hashing/parsing result handed from a worker to the writer
+enum Stage {
+    Parsed(Box<ParsedFlight>),
+    ParseFailed(ParserError),
+    Done(BatchOutcome),
+}
+
+struct WorkItem {
+    index: usize,
+    path: PathBuf,
+    file_hash: Option<String>,
+    stage: Stage,
+    /// Keychains fetched while parsing, for the writer to cache
+    keychains: Vec<FetchedKeychains>,
+}
+
+/// Imports batches of log files into one profile database
+#[derive(Clone)]
+pub struct BatchImporter {
+    db: Arc<Database>,
+    options: ImportOptions,
+    workers: usize,
+    record_failures: bool,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl BatchImporter {
+    pub fn new(db: Arc<Database>, options: ImportOptions) -> Self {
+        Self {
+            db,
+            options,
+            workers: default_workers(),
+            record_failures: false,
+            cancel: None,
+        }
+    }
+
+    /// Number of files hashed and parsed concurrently (at least 1)
+    #[allow(dead_code)]
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Record failures in the import failure registry and skip files marked
+    /// as ignored there (folder sync behaviour)
+    pub fn with_failure_registry(mut self) -> Self {
+        self.record_failures = true;
+        self
+    }
+
+    /// Stop picking up new files once `cancel` is set
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+
+    /// Import a single file through the same pipeline as [`BatchImporter::run`]
+    pub async fn import_one(&self, path: &Path) -> BatchFileResult {
+        let item = Self::prepare(&self.db, self.record_failures, 0, path.to_path_buf()).await;
+        self.finish(item)
+    }
+
+    /// Import `paths` with up to `workers` files in flight. `on_progress` is
+    /// called from the writer after every finished file.
+    pub async fn run<F>(&self, paths: Vec<PathBuf>, on_progress: F) -> BatchSummary
+    where
+        F: Fn(&BatchImportProgress),
+    {
+        self.run_with_events(paths, |event| {
+            if let BatchEvent::Finished { progress, .. } = event {
+                on_progress(progress);
+            }
+        })
+        .await
+    }
+
+    /// Like [`BatchImporter::run`], reporting every step of every file.
+    /// `on_event` is always called from the writer, in the calling task.
+    pub async fn run_with_events<F>(&self, paths: Vec<PathBuf>, on_event: F) -> BatchSummary
+    where
+        F: Fn(BatchEvent),
+    {
+        let start = std::time::Instant::now();
+        let mut progress = BatchImportProgress {
+            total: paths.len(),
+            ..Default::default()
+        };
+        log::info!("Batch import of {} files with {} workers", paths.len(), self.workers);
+
+        // Files whose worker crashed keep this placeholder result
+        let mut files: Vec<BatchFileResult> = paths
+            .iter()
+            .map(|path| BatchFileResult {
+                path: path.clone(),
+                file_hash: None,
+                outcome: BatchOutcome::Failed { message: "Import worker crashed".to_string() },
+            })
+            .collect();
+        let mut finished = vec![false; files.len()];
+
+        let semaphore = Arc::new(Semaphore::new(self.workers));
+        let (tx, mut rx) = mpsc::channel::<WorkerMsg>(self.workers);
+        let mut tasks = JoinSet::new();
+        for (index, path) in paths.into_iter().enumerate() {
+            let semaphore = semaphore.clone();
+            let tx = tx.clone();
+            let db = self.db.clone();
+            let record_failures = self.record_failures;
+            let cancel = self.cancel.clone();
+            tasks.spawn(async move {
+                // Hold the permit until the writer accepted the parsed flight,
+                // so at most `workers` parsed logs are in memory at once
+                let Ok(_permit) = semaphore.acquire_owned().await else { return };
+                if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                    return;
+                }
+                if tx.send(WorkerMsg::Started(index, path.clone())).await.is_err() {
+                    return;
+                }
+                let item = Self::prepare(&db, record_failures, index, path).await;
+                let _ = tx.send(WorkerMsg::Item(item)).await;
+            });
+        }
+        drop(tx);
+
+        // Single writer: all database writes for the batch happen here
+        while let Some(msg) = rx.recv().await {
+            let item = match msg {
+                WorkerMsg::Started(index, path) => {
+                    on_event(BatchEvent::Started { index, path: &path });
+                    continue;
+                }
+                WorkerMsg::Item(item) => item,
+            };
+            let index = item.index;
+            if matches!(item.stage, Stage::Parsed(_)) {
+                on_event(BatchEvent::Parsed { index, path: &item.path });
+            }
+            let result = self.finish(item);
+            match result.outcome {
+                BatchOutcome::Imported { .. } => progress.imported += 1,
+                BatchOutcome::Duplicate { .. } | BatchOutcome::Skipped { .. } => progress.skipped += 1,
+                BatchOutcome::Queued { .. } => progress.queued += 1,
+                BatchOutcome::Failed { ref message } => {
+                    log::warn!("Batch import: failed to import {:?}: {}", result.path, message);
+                    progress.failed += 1;
+                }
+                BatchOutcome::Cancelled => progress.cancelled += 1,
+            }
+            progress.completed += 1;
+            progress.elapsed_secs = start.elapsed().as_secs_f64();
+            on_event(BatchEvent::Finished { index, result: &result, progress: &progress });
+            files[index] = result;
+            finished[index] = true;
+        }
+
+        // Worker panics leave their file at the placeholder failure, files
+        // skipped after a cancel are marked as such
+        while let Some(joined) = tasks.join_next().await {
+            if let Err(e) = joined {
+                log::error!("Batch import worker crashed: {}", e);
+            }
+        }
+        let cancelled = self.is_cancelled();
+        for (file, _) in files.iter_mut().zip(&finished).filter(|(_, done)| !**done) {
+            if cancelled {
+                file.outcome = BatchOutcome::Cancelled;
+                progress.cancelled += 1;
+            } else {
+                progress.failed += 1;
+            }
+        }
+        progress.completed = progress.total;
+        progress.elapsed_secs = start.elapsed().as_secs_f64();
+
+        log::info!(
+            "Batch import {}: {} imported, {} skipped, {} queued, {} failed, {} cancelled in {:.1}s",
+            if cancelled { "cancelled" } else { "complete" },
+            progress.imported, progress.skipped, progress.queued, progress.failed, progress.cancelled, progress.elapsed_secs
+        );
+        BatchSummary { progress, files }
+    }
+
+    /// Worker side: hash and parse a file. Only reads from the database.
+    async fn prepare(db: &Database, record_failures: bool, index: usize, path: PathBuf) -> WorkItem {
+        let hash_path = path.clone();
+        let file_hash = match tokio::task::spawn_blocking(move || LogParser::calculate_file_hash(&hash_path)).await {
+            Ok(Ok(hash)) => hash,
+            Ok(Err(e)) => {
+                return WorkItem {
+                    index,
+                    path,
+                    file_hash: None,
+                    stage: Stage::Done(BatchOutcome::Failed { message: format!("Failed to compute hash: {}", e) }),
+                    keychains: Vec::new(),
+                };
+            }
+            Err(e) => {
+                return WorkItem {
+                    index,
+                    path,
+                    file_hash: None,
+                    stage: Stage::Done(BatchOutcome::Failed { message: format!("Hashing task failed: {}", e) }),
+                    keychains: Vec::new(),
+                };
+            }
+        };
+
+        if record_failures && db.is_import_ignored(&file_hash).unwrap_or(false) {
+            return WorkItem {
+                index,
+                path,
+                file_hash: Some(file_hash),
+                stage: Stage::Done(BatchOutcome::Skipped {
+                    message: "Ignored after earlier import failures".to_string(),
+                }),
+                keychains: Vec::new(),
+            };
+        }
+
+        let parser = LogParser::new(db);
+        let stage = match parser.parse_log_with_hash(&path, &file_hash).await {
+            Ok(parsed) => Stage::Parsed(Box::new(parsed)),
+            Err(e) => Stage::ParseFailed(e),
+        };
+        WorkItem {
+            index,
+            path,
+            file_hash: Some(file_hash),
+            stage,
+            keychains: parser.take_fetched_keychains(),
+        }
+    }
+
+    /// Writer side: store the outcome of a prepared file
+    fn finish(&self, item: WorkItem) -> BatchFileResult {
+        let WorkItem { path, file_hash, stage, keychains, .. } = item;
+        if let Err(e) = self.db.cache_keychains(&keychains) {
+            log::warn!("Failed to cache keychains for {:?}: {}", path, e);
+        }
+        let hash = file_hash.clone().unwrap_or_default();
+        let record_failure = |kind: &str, message: &str| {
+            if self.record_failures && !hash.is_empty() {
+                if let Err(e) = self.db.record_import_failure(&hash, &path.to_string_lossy(), kind, message) {
+                    log::warn!("Failed to record import failure for {:?}: {}", path, e);
+                }
+            }
+        };
+        let clear_failure = || {
+            if self.record_failures && !hash.is_empty() {
+                if let Err(e) = self.db.clear_import_failure(&hash) {
+                    log::warn!("Failed to clear import failure for {:?}: {}", path, e);
+                }
+            }
+        };
+
+        let outcome = match stage {
+            Stage::Done(outcome) => outcome,
+            Stage::ParseFailed(ParserError::AlreadyImported(matching_flight)) => {
+                clear_failure();
+                BatchOutcome::Duplicate {
+                    message: format!("This flight log has already been imported (matches: {})", matching_flight),
+                }
+            }
+            Stage::ParseFailed(ParserError::Blacklisted) => {
+                clear_failure();
+                BatchOutcome::Skipped {
+                    message: "This file is on the import blacklist".to_string(),
+                }
+            }
+            Stage::ParseFailed(e) if e.needs_keychain() => match self.db.queue_pending_decryption(&path, &hash, &e.to_string()) {
+                Ok(()) => {
+                    clear_failure();
+                    BatchOutcome::Queued {
+                        message: format!("{} — queued for decryption, it will be imported once its keychains are available", e),
+                    }
+                }
+                Err(err) => {
+                    log::warn!("Failed to queue {:?} for decryption: {}", path, err);
+                    record_failure(e.kind(), &e.to_string());
+                    BatchOutcome::Failed { message: format!("Failed to parse log: {}", e) }
+                }
+            },
+            Stage::ParseFailed(e) => {
+                record_failure(e.kind(), &e.to_string());
+                BatchOutcome::Failed { message: format!("Failed to parse log: {}", e) }
+            }
+            Stage::Parsed(parsed) => match self.db.import_parsed_flight(&parsed, &self.options) {
+                Ok((flight_id, point_count)) => {
+                    clear_failure();
+                    BatchOutcome::Imported { flight_id, point_count }
+                }
+                Err(ImportError::Duplicate(matching_flight)) => {
+                    clear_failure();
+                    BatchOutcome::Duplicate {
+                        message: format!("Duplicate flight: matches '{}' (same drone, battery, and start time)", matching_flight),
+                    }
+                }
+                Err(e) => {
+                    record_failure("import", &e.to_string());
+                    BatchOutcome::Failed { message: e.to_string() }
+                }
+            },
+        };
+
+        BatchFileResult { path, file_hash, outcome }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_litchi_csv(dir: &Path, name: &str, minute: u32) -> PathBuf {
+        let mut csv = String::from("latitude,longitude,altitude(m),datetime(utc),isFlying\n");
+        for second in 0..5 {
+            csv.push_str(&format!(
+                "47.{0},8.{0},{0}.0,2024-05-01 10:{1:02}:{2:02}.000,1\n",
+                second, minute, second
+            ));
+        }
+        let path = dir.join(name);
+        std::fs::write(&path, csv).unwrap();
+        path
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_batch_import_keeps_order_and_records_failures() {
+        let temp_dir = tempdir().unwrap();
+        let db = Arc::new(Database::new(temp_dir.path().join("data"), "default").unwrap());
+
+        let mut paths: Vec<PathBuf> = (0..5)
+            .map(|i| write_litchi_csv(temp_dir.path(), &format!("flight{}.csv", i), i))
+            .collect();
+        let broken = temp_dir.path().join("broken.csv");
+        std::fs::write(&broken, "not,a,flight\n1,2,3\n").unwrap();
+        paths.insert(2, broken.clone());
+
+        let importer = BatchImporter::new(db.clone(), ImportOptions::default())
+            .with_workers(3)
+            .with_failure_registry();
+        let updates = std::sync::Mutex::new(Vec::new());
+        let summary = importer
+            .run(paths.clone(), |progress| updates.lock().unwrap().push(progress.completed))
+            .await;
+
+        assert_eq!(summary.progress.imported, 5);
+        assert_eq!(summary.progress.failed, 1);
+        assert_eq!(*updates.lock().unwrap(), (1..=6).collect::<Vec<_>>());
+        let result_paths: Vec<&PathBuf> = summary.files.iter().map(|f| &f.path).collect();
+        assert_eq!(result_paths, paths.iter().collect::<Vec<_>>());
+        assert!(matches!(summary.files[2].outcome, BatchOutcome::Failed { .. }));
+        assert_eq!(db.get_all_flights().unwrap().len(), 5);
+        assert_eq!(db.get_import_failures().unwrap().len(), 1);
+
+        // A second run finds everything already imported
+        let again = importer.run(paths, |_| {}).await;
+        assert_eq!(again.progress.imported, 0);
+        assert_eq!(again.progress.skipped, 5);
+    }
+}
@@ -6,15 +6,86 @@
 //! - Optimized bulk inserts using Appender
 //! - Downsampled query retrieval for large datasets
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, OnceLock};
 
+use dji_log_parser::keychain::{KeychainFeaturePoint, KeychainsRequest};
 use duckdb::{params, Connection, OptionalExt, Result as DuckResult};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use crate::models::{BatteryHealthPoint, BatteryUsage, DroneUsage, Flight, FlightDateCount, FlightMessage, FlightMetadata, FlightTag, OverviewStats, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight};
+use crate::api::{ApiError, KeychainProvider};
+use crate::models::{AvoidanceState, BatteryHealthPoint, BlacklistEntry, BatteryUsage, DroneUsage, EquipmentItem, FirmwareVersion, Flight, FlightDateCount, FlightEquipment, FlightMessage, FlightMetadata, FlightSideData, FlightTag, HomeUpdate, ImportFailure, KeychainBundle, KeychainBundleEntry, MediaClip, MediaFrame, MediaImportResult, MotorStatus, OverviewStats, ParsedFlight, ParsedMediaClip, PendingDecryption, RcGpsPoint, SyncRun, SyncRunDetail, SyncRunFile, SyncTrigger, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight, WindWarning};
+use crate::solar::DaylightSummary;
+use crate::timezone::TimezoneLookup;
+
+/// Per-flight tables holding extra DJI data (see [`FlightSideData`] and
+/// [`FlightEquipment`]). Cleaned up and backed up together with telemetry.
+const SIDE_TABLES: &[&str] = &[
+    "flight_equipment",
+    "flight_home_updates",
+    "flight_rc_gps",
+    "flight_avoidance",
+    "flight_motor_status",
+    "flight_wind_warnings",
+];
+
+/// Video clip tables, included in backups
+const MEDIA_TABLES: &[&str] = &["media_clips", "media_frames"];
+
+/// Flight start as seconds since the epoch of its local wall-clock time
+/// (UTC for flights without a local time zone), comparable to the camera
+/// times of media clips
+const FLIGHT_LOCAL_START_SECS: &str =
+    "(COALESCE(epoch_ms(f.local_start_time), epoch_ms(f.start_time)) / 1000)";
+
+/// DuckDB `TIMESTAMP` literal for a local wall-clock time
+fn format_local_time(local: &chrono::NaiveDateTime) -> String {
+    local.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+/// Time zone lookup shared by all imports, built on first use since it
+/// loads the GeoNames index
+fn timezone_lookup() -> &'static TimezoneLookup {
+    static LOOKUP: OnceLock<TimezoneLookup> = OnceLock::new();
+    LOOKUP.get_or_init(TimezoneLookup::new)
+}
+
+/// Time zone name and local start time for a flight, resolved from its home point
+fn local_start(flight: &FlightMetadata) -> (Option<String>, Option<String>) {
+    let (Some(start), Some(lat), Some(lon)) = (flight.start_time, flight.home_lat, flight.home_lon) else {
+        return (None, None);
+    };
+    match timezone_lookup().local_time(start, lat, lon) {
+        Some((tz, local)) => (Some(tz.name().to_string()), Some(format_local_time(&local))),
+        None => (None, None),
+    }
+}
+
+/// Sync runs kept in the history of each profile
+pub const MAX_SYNC_RUNS: i64 = 200;
+
+/// `format` of keychain bundles written by [`Database::export_keychain_bundle`]
+pub const KEYCHAIN_BUNDLE_FORMAT: &str = "open-dronelog-keychains";
+const KEYCHAIN_BUNDLE_VERSION: u32 = 1;
+
+/// Cache key for a keychain request: SHA256 of its JSON body
+fn keychain_request_hash(request: &KeychainsRequest) -> Result<String, ApiError> {
+    let body = serde_json::to_string(request).map_err(|e| ApiError::ApiResponse(e.to_string()))?;
+    Ok(format!("{:x}", Sha256::digest(body.as_bytes())))
+}
+
+/// Seconds of the flight flown after the end of civil twilight at its home point
+fn night_secs(flight: &FlightMetadata) -> Option<f64> {
+    let (Some(start), Some(duration), Some(lat), Some(lon)) =
+        (flight.start_time, flight.duration_secs, flight.home_lat, flight.home_lon)
+    else {
+        return None;
+    };
+    Some(DaylightSummary::for_flight(start, duration, lat, lon).night_secs)
+}
 
 #[derive(Error, Debug)]
 pub enum DatabaseError {
@@ -28,10 +99,128 @@ pub enum DatabaseError {
     FlightNotFound(i64),
 }
 
+/// Why [`Database::import_parsed_flight`] did not import a flight
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Duplicate flight: matches '{0}' (same drone, battery, and start time)")]
+    Duplicate(String),
+
+    #[error("Failed to insert flight: {0}")]
+    Flight(DatabaseError),
+
+    #[error("Failed to insert telemetry data: {0}")]
+    Telemetry(DatabaseError),
+}
+
+/// Size, modification time and hash of a sync folder file at its last import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncSeenFile {
+    pub size: i64,
+    /// Modification time in milliseconds since the Unix epoch
+    pub modified_ms: i64,
+    pub file_hash: String,
+}
+
+/// Per-profile settings applied when importing a parsed flight
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    pub smart_tags_enabled: bool,
+    /// Only keep smart tags of these types (all types when `None`)
+    pub enabled_tag_types: Option<Vec<String>>,
+    /// Profile name tag, set for non-default profiles
+    pub profile_tag: Option<String>,
+}
+
+impl ImportOptions {
+    /// Build import options from a profile's config.json contents
+    pub fn from_config(config: &serde_json::Value, profile: &str) -> Self {
+        Self {
+            smart_tags_enabled: config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true),
+            enabled_tag_types: config.get("enabled_tag_types").and_then(|v| v.as_array()).map(|types| {
+                types.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            }),
+            profile_tag: (profile != "default").then(|| profile.to_string()),
+        }
+    }
+
+    /// Smart tags that would be stored for a flight, after the enabled/type filters
+    pub fn smart_tags(&self, tags: &[String]) -> Vec<String> {
+        if !self.smart_tags_enabled {
+            return Vec::new();
+        }
+        match self.enabled_tag_types {
+            Some(ref types) => crate::parser::LogParser::filter_smart_tags(tags.to_vec(), types),
+            None => tags.to_vec(),
+        }
+    }
+}
+
 /// Thread-safe database manager
 pub struct Database {
     conn: Mutex<Connection>,
     pub data_dir: PathBuf,
+    /// Copies of logs in the pending decryption queue (per profile)
+    pending_dir: PathBuf,
+}
+
+/// [`KeychainProvider`] that serves keychains from the `keychains` table,
+/// falling back to an upstream provider. It never writes: keychains fetched
+/// upstream are kept until [`KeychainCache::take_fetched`] hands them to the
+/// database writer, which stores them with [`Database::cache_keychains`] so
+/// each encrypted log only needs the DJI API once. Created with
+/// [`Database::keychain_cache`].
+pub struct KeychainCache {
+    conn: Mutex<Connection>,
+    upstream: Option<Arc<dyn KeychainProvider>>,
+    fetched: Mutex<Vec<FetchedKeychains>>,
+}
+
+/// Keychains fetched upstream for one request, not stored yet
+#[derive(Debug, Clone)]
+pub struct FetchedKeychains {
+    request_hash: String,
+    keychains: Vec<Vec<KeychainFeaturePoint>>,
+}
+
+impl KeychainCache {
+    /// Keychains fetched upstream since the last call
+    pub fn take_fetched(&self) -> Vec<FetchedKeychains> {
+        std::mem::take(&mut *self.fetched.lock().unwrap())
+    }
+}
+
+impl KeychainProvider for KeychainCache {
+    fn fetch_keychains(&self, request: &KeychainsRequest) -> Result<Vec<Vec<KeychainFeaturePoint>>, ApiError> {
+        let request_hash = keychain_request_hash(request)?;
+        if let Some(fetched) = self.fetched.lock().unwrap().iter().find(|f| f.request_hash == request_hash) {
+            return Ok(fetched.keychains.clone());
+        }
+        let cached: Option<String> = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT keychains FROM keychains WHERE request_hash = ?",
+                params![request_hash],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| ApiError::ApiResponse(e.to_string()))?;
+        if let Some(keychains) = cached.and_then(|json| serde_json::from_str(&json).ok()) {
+            log::debug!("Using cached keychains for request {}", request_hash);
+            return Ok(keychains);
+        }
+
+        let upstream = self.upstream.as_ref().ok_or(ApiError::ApiKeyNotConfigured)?;
+        let keychains = upstream.fetch_keychains(request)?;
+        self.fetched.lock().unwrap().push(FetchedKeychains {
+            request_hash,
+            keychains: keychains.clone(),
+        });
+        Ok(keychains)
+    }
 }
 
 impl Drop for Database {
@@ -54,7 +243,8 @@ impl Database {
     /// {app_data_dir}/
     /// ├── flights.db              # DuckDB database file (default profile)
     /// ├── flights_{profile}.db    # DuckDB database file (named profile)
-    /// └── keychains/              # Cached decryption keys
+    /// ├── keychains/              # Cached decryption keys
+    /// └── pending_decryption/     # Encrypted logs waiting for keychains
     /// ```
     pub fn new(app_data_dir: PathBuf, profile: &str) -> Result<Self, DatabaseError> {
         // Ensure directory structure exists
@@ -82,6 +272,7 @@ impl Database {
 
         let db = Self {
             conn: Mutex::new(conn),
+            pending_dir: pending_decryption_folder(&app_data_dir, profile),
             data_dir: app_data_dir,
         };
 
@@ -209,7 +400,10 @@ impl Database {
                 cycle_count     INTEGER,                 -- Battery cycle count (from SmartBatteryStatic)
                 imported_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
                 notes           VARCHAR,
-                color           VARCHAR DEFAULT '#7dd3fc'  -- Flight color label (hex, default light blue)
+                color           VARCHAR DEFAULT '#7dd3fc', -- Flight color label (hex, default light blue)
+                local_timezone  VARCHAR,                 -- IANA zone at the home point
+                local_start_time TIMESTAMP,              -- Wall-clock start time in local_timezone
+                night_secs      DOUBLE                   -- Seconds flown after civil twilight
             );
 
             -- Index for sorting by flight date
@@ -283,10 +477,12 @@ impl Database {
 
             -- ============================================================
             -- KEYCHAIN TABLE: Store cached decryption keys for V13+ logs
+            -- Keyed by the request built from a log, so a log is only sent
+            -- to the DJI API once and can be decrypted offline afterwards
             -- ============================================================
             CREATE TABLE IF NOT EXISTS keychains (
-                serial_number   VARCHAR PRIMARY KEY,
-                encryption_key  VARCHAR NOT NULL,
+                request_hash    VARCHAR PRIMARY KEY,     -- SHA256 of the keychain request JSON
+                keychains       VARCHAR NOT NULL,        -- JSON keychain feature points from the API
                 fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
             );
 
@@ -316,16 +512,39 @@ impl Database {
             );
 
             -- ============================================================
-            -- EQUIPMENT_NAMES TABLE: Custom display names for batteries/aircraft
+            -- EQUIPMENT_NAMES TABLE: Equipment inventory with optional custom
+            -- display names. Rows are registered on import; display_name is
+            -- NULL until the user names the item.
             -- ============================================================
             CREATE TABLE IF NOT EXISTS equipment_names (
-                serial          VARCHAR NOT NULL,        -- battery or aircraft serial number
-                equipment_type  VARCHAR NOT NULL,        -- 'battery' or 'aircraft'
-                display_name    VARCHAR NOT NULL,
+                serial          VARCHAR NOT NULL,        -- equipment serial number
+                equipment_type  VARCHAR NOT NULL,        -- 'battery', 'aircraft', 'rc' or 'camera'
+                display_name    VARCHAR,
                 updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                first_seen      TIMESTAMP WITH TIME ZONE, -- Earliest flight start
+                last_seen       TIMESTAMP WITH TIME ZONE, -- Latest flight start
+                firmware        VARCHAR,                 -- Firmware on the latest flight
                 PRIMARY KEY (serial, equipment_type)
             );
 
+            -- ============================================================
+            -- FLIGHT_EQUIPMENT TABLE: App, firmware and component serials
+            -- from the log header, one row per flight
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_equipment (
+                flight_id           BIGINT PRIMARY KEY,
+                app_name            VARCHAR,
+                app_platform        VARCHAR,
+                app_version         VARCHAR,
+                aircraft_firmware   VARCHAR,
+                battery_firmware    VARCHAR,
+                rc_serial           VARCHAR,
+                rc_firmware         VARCHAR,
+                camera_serial       VARCHAR,
+                camera_firmware     VARCHAR,
+                gimbal_firmware     VARCHAR
+            );
+
             -- ============================================================
             -- FLIGHT_MESSAGES TABLE: App messages (tips/warnings/cautions) per flight
             -- ============================================================
@@ -339,6 +558,177 @@ impl Database {
 
             CREATE INDEX IF NOT EXISTS idx_flight_messages_flight 
                 ON flight_messages(flight_id);
+
+            -- ============================================================
+            -- DJI SIDE TABLES: records beyond the telemetry stream, stored
+            -- as change-points keyed by (flight_id, timestamp_ms)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_home_updates (
+                flight_id           BIGINT NOT NULL,
+                timestamp_ms        BIGINT NOT NULL,
+                latitude            DOUBLE,
+                longitude           DOUBLE,
+                altitude            DOUBLE,              -- Home point altitude (MSL)
+                go_home_height      DOUBLE,              -- RTH altitude setting
+                max_allowed_height  DOUBLE,
+                go_home_mode        VARCHAR,
+                dynamic_home_point  BOOLEAN,
+                PRIMARY KEY (flight_id, timestamp_ms)
+            );
+
+            CREATE TABLE IF NOT EXISTS flight_rc_gps (
+                flight_id           BIGINT NOT NULL,
+                timestamp_ms        BIGINT NOT NULL,
+                latitude            DOUBLE,              -- Pilot / RC position
+                longitude           DOUBLE,
+                satellites          INTEGER,
+                accuracy            DOUBLE,
+                PRIMARY KEY (flight_id, timestamp_ms)
+            );
+
+            CREATE TABLE IF NOT EXISTS flight_avoidance (
+                flight_id                   BIGINT NOT NULL,
+                timestamp_ms                BIGINT NOT NULL,
+                obstacle_avoidance_enabled  BOOLEAN,
+                user_avoidance_enabled      BOOLEAN,
+                vision_positioning_used     BOOLEAN,
+                ultrasonic_working          BOOLEAN,
+                vision_height               DOUBLE,      -- Downward sensor height (m)
+                PRIMARY KEY (flight_id, timestamp_ms)
+            );
+
+            CREATE TABLE IF NOT EXISTS flight_motor_status (
+                flight_id           BIGINT NOT NULL,
+                timestamp_ms        BIGINT NOT NULL,
+                motors_on           BOOLEAN,
+                motor_blocked       BOOLEAN,
+                not_enough_force    BOOLEAN,
+                propeller_catapult  BOOLEAN,
+                start_failed_cause  VARCHAR,
+                PRIMARY KEY (flight_id, timestamp_ms)
+            );
+
+            CREATE TABLE IF NOT EXISTS flight_wind_warnings (
+                flight_id           BIGINT NOT NULL,
+                timestamp_ms        BIGINT NOT NULL,
+                message_type        VARCHAR NOT NULL,    -- 'tip' or 'warn'
+                message             VARCHAR NOT NULL,
+                PRIMARY KEY (flight_id, timestamp_ms, message_type, message)
+            );
+
+            -- ============================================================
+            -- IMPORT_FAILURES TABLE: Files that failed to import during sync
+            -- Ignored entries are skipped by later syncs
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS import_failures (
+                file_hash       VARCHAR PRIMARY KEY,     -- SHA256 of the failing file
+                file_path       VARCHAR NOT NULL,
+                error_kind      VARCHAR NOT NULL,        -- ParserError variant, e.g. 'timeout'
+                message         VARCHAR NOT NULL,
+                first_attempt   TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                last_attempt    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                attempts        INTEGER NOT NULL DEFAULT 1,
+                ignored         BOOLEAN NOT NULL DEFAULT FALSE
+            );
+
+            -- ============================================================
+            -- IMPORT_BLACKLIST TABLE: Files the user never wants imported again
+            -- (e.g. deleted flights), honoured by every import path
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS import_blacklist (
+                file_hash       VARCHAR PRIMARY KEY,     -- SHA256 of the blacklisted file
+                file_name       VARCHAR,
+                added_at        TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- PENDING_DECRYPTION TABLE: Encrypted logs whose keychains could
+            -- not be fetched; a copy of each log is kept until it imports
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS pending_decryption (
+                file_hash       VARCHAR PRIMARY KEY,     -- SHA256 of the log
+                file_name       VARCHAR NOT NULL,
+                source_path     VARCHAR NOT NULL,        -- Where the log was imported from
+                message         VARCHAR NOT NULL,        -- Last keychain error
+                added_at        TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                last_attempt    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                attempts        INTEGER NOT NULL DEFAULT 1
+            );
+
+            -- ============================================================
+            -- SYNC_SEEN_FILES TABLE: Sync folder files already imported, so
+            -- unchanged files are skipped without hashing them again
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS sync_seen_files (
+                path            VARCHAR PRIMARY KEY,     -- Absolute path of the file
+                size            BIGINT NOT NULL,
+                modified_ms     BIGINT NOT NULL,         -- mtime, ms since epoch
+                file_hash       VARCHAR NOT NULL,
+                seen_at         TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- SYNC_RUNS TABLE: History of folder syncs of this profile,
+            -- with the outcome of each file in SYNC_RUN_FILES
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS sync_runs (
+                id              BIGINT PRIMARY KEY,
+                triggered_by    VARCHAR NOT NULL,        -- 'cron', 'manual' or 'watch'
+                source_id       VARCHAR,                 -- Sync source, NULL for SYNC_LOGS_PATH
+                sync_path       VARCHAR NOT NULL,
+                started_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+                finished_at     TIMESTAMP WITH TIME ZONE NOT NULL,
+                imported        INTEGER NOT NULL DEFAULT 0,
+                skipped         INTEGER NOT NULL DEFAULT 0,
+                unchanged       INTEGER NOT NULL DEFAULT 0,
+                queued          INTEGER NOT NULL DEFAULT 0,
+                failed          INTEGER NOT NULL DEFAULT 0,
+                cancelled       INTEGER NOT NULL DEFAULT 0,
+                archived        INTEGER NOT NULL DEFAULT 0,
+                rejected        INTEGER NOT NULL DEFAULT 0,
+                error           VARCHAR
+            );
+
+            CREATE TABLE IF NOT EXISTS sync_run_files (
+                run_id          BIGINT NOT NULL,
+                path            VARCHAR NOT NULL,
+                outcome         VARCHAR NOT NULL,        -- BatchOutcome, e.g. 'imported'
+                message         VARCHAR NOT NULL,
+                flight_id       BIGINT
+            );
+
+            -- ============================================================
+            -- MEDIA_CLIPS TABLE: Videos imported from their DJI .SRT
+            -- sidecars, with one MEDIA_FRAMES row per subtitle frame
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS media_clips (
+                id              BIGINT PRIMARY KEY,
+                flight_id       BIGINT,                  -- Overlapping flight, NULL until one is imported
+                srt_file        VARCHAR NOT NULL,
+                video_file      VARCHAR NOT NULL,
+                file_hash       VARCHAR NOT NULL,
+                drone_serial    VARCHAR,
+                start_time      TIMESTAMP NOT NULL,      -- Camera wall-clock time
+                end_time        TIMESTAMP NOT NULL,
+                frame_count     INTEGER NOT NULL,
+                imported_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS media_frames (
+                clip_id         BIGINT NOT NULL,
+                offset_ms       BIGINT NOT NULL,         -- Position in the video
+                end_ms          BIGINT NOT NULL,
+                latitude        DOUBLE,
+                longitude       DOUBLE,
+                rel_altitude    DOUBLE,
+                abs_altitude    DOUBLE,
+                iso             INTEGER,
+                shutter         VARCHAR,
+                fnum            DOUBLE,
+                ev              DOUBLE,
+                focal_length    DOUBLE,
+                color_temp      INTEGER
+            );
             "#,
         )?;
 
@@ -347,6 +737,8 @@ impl Database {
         Self::migrate_telemetry_table(&conn)?;
         Self::migrate_flight_tags_table(&conn)?;
         Self::migrate_flight_messages_table(&conn)?;
+        Self::migrate_equipment_names_table(&conn)?;
+        Self::migrate_keychains_table(&conn)?;
 
         // Run type optimization migration (DOUBLE -> FLOAT for non-critical metrics)
         // Must run before column order check since it recreates the table
@@ -379,9 +771,14 @@ impl Database {
             ("video_count", "ALTER TABLE flights ADD COLUMN video_count INTEGER"),
             ("color", "ALTER TABLE flights ADD COLUMN color VARCHAR DEFAULT '#7dd3fc'"),
             ("cycle_count", "ALTER TABLE flights ADD COLUMN cycle_count INTEGER"),
+            ("local_timezone", "ALTER TABLE flights ADD COLUMN local_timezone VARCHAR"),
+            ("local_start_time", "ALTER TABLE flights ADD COLUMN local_start_time TIMESTAMP"),
+            ("night_secs", "ALTER TABLE flights ADD COLUMN night_secs DOUBLE"),
         ];
 
         let need_backfill = !columns.contains("photo_count");
+        let need_local_time_backfill = !columns.contains("local_timezone");
+        let need_night_backfill = !columns.contains("night_secs");
 
         for (col_name, sql) in migrations {
             if !columns.contains(*col_name) {
@@ -415,9 +812,87 @@ impl Database {
             }
         }
 
+        if need_local_time_backfill {
+            match Self::backfill_local_times(conn) {
+                Ok(count) => log::info!("Backfilled local time zone for {} flights", count),
+                Err(e) => log::warn!("Failed to backfill local time zones: {}", e),
+            }
+        }
+
+        if need_night_backfill {
+            match Self::backfill_night_secs(conn) {
+                Ok(count) => log::info!("Backfilled night flying time for {} flights", count),
+                Err(e) => log::warn!("Failed to backfill night flying time: {}", e),
+            }
+        }
+
         Ok(())
     }
 
+    /// Resolve `local_timezone` / `local_start_time` for flights that have a start
+    /// time and home point but no zone yet. Returns the number of flights updated.
+    fn backfill_local_times(conn: &Connection) -> Result<usize, DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, epoch_ms(start_time), home_lat, home_lon
+            FROM flights
+            WHERE local_timezone IS NULL
+              AND start_time IS NOT NULL
+              AND home_lat IS NOT NULL AND home_lon IS NOT NULL
+            "#,
+        )?;
+        let pending: Vec<(i64, i64, f64, f64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let lookup = timezone_lookup();
+        let mut updated = 0;
+        for (id, start_ms, lat, lon) in pending {
+            let Some(start) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(start_ms) else {
+                continue;
+            };
+            if let Some((tz, local)) = lookup.local_time(start, lat, lon) {
+                conn.execute(
+                    "UPDATE flights SET local_timezone = ?, local_start_time = ? WHERE id = ?",
+                    params![tz.name(), format_local_time(&local), id],
+                )?;
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Compute `night_secs` for flights with a start time, duration and home point
+    /// that have not been classified yet. Returns the number of flights updated.
+    fn backfill_night_secs(conn: &Connection) -> Result<usize, DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, epoch_ms(start_time), duration_secs, home_lat, home_lon
+            FROM flights
+            WHERE night_secs IS NULL
+              AND start_time IS NOT NULL AND duration_secs IS NOT NULL
+              AND home_lat IS NOT NULL AND home_lon IS NOT NULL
+            "#,
+        )?;
+        let pending: Vec<(i64, i64, f64, f64, f64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut updated = 0;
+        for (id, start_ms, duration, lat, lon) in pending {
+            let Some(start) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(start_ms) else {
+                continue;
+            };
+            let night = DaylightSummary::for_flight(start, duration, lat, lon).night_secs;
+            conn.execute("UPDATE flights SET night_secs = ? WHERE id = ?", params![night, id])?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
     /// Migrate telemetry table - only add missing columns
     fn migrate_telemetry_table(conn: &Connection) -> Result<(), DatabaseError> {
         let columns = Self::get_table_columns(conn, "telemetry")?;
@@ -468,6 +943,69 @@ impl Database {
         Ok(())
     }
 
+    /// Replace the original serial-keyed keychains table, which was never
+    /// written to, with one keyed by keychain request.
+    fn migrate_keychains_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "keychains")?;
+        if columns.contains("request_hash") {
+            return Ok(());
+        }
+
+        log::info!("Migrating keychains table: keying cached keychains by request");
+        conn.execute_batch(
+            r#"
+            DROP TABLE IF EXISTS keychains;
+            CREATE TABLE keychains (
+                request_hash    VARCHAR PRIMARY KEY,
+                keychains       VARCHAR NOT NULL,
+                fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+        )?;
+        Ok(())
+    }
+
+    /// Migrate equipment_names table into the equipment inventory: add the
+    /// first/last seen and firmware columns, allow unnamed rows, and register
+    /// the aircraft and batteries of already imported flights.
+    fn migrate_equipment_names_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "equipment_names")?;
+        if columns.contains("first_seen") {
+            return Ok(());
+        }
+
+        log::info!("Migrating equipment_names table: adding inventory columns");
+        conn.execute_batch(
+            r#"
+            ALTER TABLE equipment_names ADD COLUMN first_seen TIMESTAMP WITH TIME ZONE;
+            ALTER TABLE equipment_names ADD COLUMN last_seen TIMESTAMP WITH TIME ZONE;
+            ALTER TABLE equipment_names ADD COLUMN firmware VARCHAR;
+            ALTER TABLE equipment_names ALTER COLUMN display_name DROP NOT NULL;
+            "#,
+        )?;
+
+        let backfill_sql = r#"
+            INSERT INTO equipment_names (serial, equipment_type, first_seen, last_seen)
+            SELECT serial, equipment_type, MIN(start_time), MAX(start_time)
+            FROM (
+                SELECT UPPER(TRIM(drone_serial)) AS serial, 'aircraft' AS equipment_type, start_time
+                FROM flights WHERE drone_serial IS NOT NULL AND TRIM(drone_serial) <> ''
+                UNION ALL
+                SELECT UPPER(TRIM(battery_serial)), 'battery', start_time
+                FROM flights WHERE battery_serial IS NOT NULL AND TRIM(battery_serial) <> ''
+            ) seen
+            GROUP BY serial, equipment_type
+            ON CONFLICT (serial, equipment_type) DO UPDATE SET
+                first_seen = excluded.first_seen,
+                last_seen = excluded.last_seen
+        "#;
+        match conn.execute_batch(backfill_sql) {
+            Ok(()) => log::info!("Backfilled equipment inventory from existing flights"),
+            Err(e) => log::warn!("Failed to backfill equipment inventory: {}", e),
+        }
+        Ok(())
+    }
+
     /// Migrate flight_messages table — expand PK to include message text.
     /// Old PK was (flight_id, timestamp_ms, message_type) which silently dropped
     /// multiple messages at the same timestamp+type. State-change tracking can
@@ -857,6 +1395,8 @@ impl Database {
 
     /// Insert flight metadata and return the flight ID
     pub fn insert_flight(&self, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
+        let (local_timezone, local_start_time) = local_start(flight);
+        let night_secs = night_secs(flight);
         let conn = self.conn.lock().unwrap();
 
         conn.execute(
@@ -866,8 +1406,8 @@ impl Database {
                 aircraft_name, battery_serial, cycle_count,
                 start_time, end_time, duration_secs, total_distance,
                 max_altitude, max_speed, home_lat, home_lon, point_count,
-                photo_count, video_count
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                photo_count, video_count, local_timezone, local_start_time, night_secs
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             params![
                 flight.id,
@@ -890,6 +1430,9 @@ impl Database {
                 flight.point_count,
                 flight.photo_count,
                 flight.video_count,
+                local_timezone,
+                local_start_time,
+                night_secs,
             ],
         )?;
 
@@ -905,8 +1448,10 @@ impl Database {
         flight_id: i64,
         points: &[TelemetryPoint],
     ) -> Result<usize, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+        Self::write_telemetry(&self.conn.lock().unwrap(), flight_id, points)
+    }
 
+    fn write_telemetry(conn: &Connection, flight_id: i64, points: &[TelemetryPoint]) -> Result<usize, DatabaseError> {
         // Use DuckDB Appender for high-performance bulk inserts
         let mut appender = conn.appender("telemetry")?;
 
@@ -996,7 +1541,8 @@ impl Database {
                 duration_secs, total_distance,
                 max_altitude, max_speed, home_lat, home_lon, point_count,
                 photo_count, video_count, notes, COALESCE(color, '#7dd3fc') AS color,
-                cycle_count
+                cycle_count, local_timezone,
+                CAST(local_start_time AS VARCHAR) AS local_start_time, night_secs
             FROM flights
             ORDER BY start_time DESC
             "#,
@@ -1015,6 +1561,9 @@ impl Database {
                     battery_serial: row.get(7)?,
                     cycle_count: row.get(20)?,
                     start_time: row.get(8)?,
+                    local_timezone: row.get(21)?,
+                    local_start_time: row.get(22)?,
+                    night_secs: row.get(23)?,
                     duration_secs: row.get(9)?,
                     total_distance: row.get(10)?,
                     max_altitude: row.get(11)?,
@@ -1075,7 +1624,8 @@ impl Database {
                 duration_secs, total_distance,
                 max_altitude, max_speed, home_lat, home_lon, point_count,
                 photo_count, video_count, notes, COALESCE(color, '#7dd3fc') AS color,
-                cycle_count
+                cycle_count, local_timezone,
+                CAST(local_start_time AS VARCHAR) AS local_start_time, night_secs
             FROM flights
             WHERE id = ?
             "#,
@@ -1092,6 +1642,9 @@ impl Database {
                     battery_serial: row.get(7)?,
                     cycle_count: row.get(20)?,
                     start_time: row.get(8)?,
+                    local_timezone: row.get(21)?,
+                    local_start_time: row.get(22)?,
+                    night_secs: row.get(23)?,
                     duration_secs: row.get(9)?,
                     total_distance: row.get(10)?,
                     max_altitude: row.get(11)?,
@@ -1231,7 +1784,13 @@ impl Database {
                 rc_throttle,
                 rc_rudder,
                 is_photo,
-                is_video
+                is_video,
+                altitude_abs,
+                battery_current,
+                gimbal_pitch,
+                gimbal_roll,
+                gimbal_yaw,
+                gps_signal
             FROM telemetry
             WHERE flight_id = ?
             ORDER BY timestamp_ms ASC
@@ -1275,6 +1834,12 @@ impl Database {
                     rc_rudder: row.get(25)?,
                     is_photo: row.get(26)?,
                     is_video: row.get(27)?,
+                    altitude_abs: row.get(28)?,
+                    battery_current: row.get(29)?,
+                    gimbal_pitch: row.get(30)?,
+                    gimbal_roll: row.get(31)?,
+                    gimbal_yaw: row.get(32)?,
+                    gps_signal: row.get(33)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -1332,7 +1897,13 @@ impl Database {
                     AVG(rc_throttle) AS rc_throttle,
                     AVG(rc_rudder) AS rc_rudder,
                     BOOL_OR(is_photo) AS is_photo,
-                    BOOL_OR(is_video) AS is_video
+                    BOOL_OR(is_video) AS is_video,
+                    AVG(altitude_abs) AS altitude_abs,
+                    AVG(battery_current) AS battery_current,
+                    AVG(gimbal_pitch) AS gimbal_pitch,
+                    AVG(gimbal_roll) AS gimbal_roll,
+                    AVG(gimbal_yaw) AS gimbal_yaw,
+                    ROUND(AVG(gps_signal))::INTEGER AS gps_signal
                 FROM telemetry
                 WHERE flight_id = ?
                 GROUP BY bucket_ts
@@ -1379,6 +1950,12 @@ impl Database {
                     rc_rudder: row.get(25)?,
                     is_photo: row.get(26)?,
                     is_video: row.get(27)?,
+                    altitude_abs: row.get(28)?,
+                    battery_current: row.get(29)?,
+                    gimbal_pitch: row.get(30)?,
+                    gimbal_roll: row.get(31)?,
+                    gimbal_yaw: row.get(32)?,
+                    gps_signal: row.get(33)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -1405,6 +1982,11 @@ impl Database {
             "DELETE FROM flight_messages WHERE flight_id = ?",
             params![flight_id],
         );
+        for table in SIDE_TABLES {
+            let _ = conn.execute(&format!("DELETE FROM {} WHERE flight_id = ?", table), params![flight_id]);
+        }
+        // Keep the flight's videos; they are linked again if it is re-imported
+        let _ = conn.execute("UPDATE media_clips SET flight_id = NULL WHERE flight_id = ?", params![flight_id]);
         conn.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
 
         log::info!("Deleted flight {} in {:.1}ms", flight_id, start.elapsed().as_secs_f64() * 1000.0);
@@ -1419,6 +2001,10 @@ impl Database {
         conn.execute("DELETE FROM telemetry", params![])?;
         let _ = conn.execute("DELETE FROM flight_tags", params![]);
         let _ = conn.execute("DELETE FROM flight_messages", params![]);
+        for table in SIDE_TABLES {
+            let _ = conn.execute(&format!("DELETE FROM {}", table), params![]);
+        }
+        let _ = conn.execute("UPDATE media_clips SET flight_id = NULL WHERE flight_id IS NOT NULL", params![]);
         conn.execute("DELETE FROM flights", params![])?;
 
         log::info!("Deleted all flights and telemetry in {:.1}ms", start.elapsed().as_secs_f64() * 1000.0);
@@ -1431,7 +2017,7 @@ impl Database {
         let conn = self.conn.lock().unwrap();
 
         // Basic aggregate stats
-        let (total_flights, total_distance, total_duration, total_points, total_photos, total_videos, max_altitude): (i64, f64, f64, i64, i64, i64, f64) =
+        let (total_flights, total_distance, total_duration, total_points, total_photos, total_videos, max_altitude, total_night): (i64, f64, f64, i64, i64, i64, f64, f64) =
             conn.query_row(
                 r#"
                 SELECT
@@ -1441,11 +2027,12 @@ impl Database {
                     COALESCE(SUM(point_count), 0)::BIGINT,
                     COALESCE(SUM(photo_count), 0)::BIGINT,
                     COALESCE(SUM(video_count), 0)::BIGINT,
-                    COALESCE(MAX(max_altitude), 0)::DOUBLE
+                    COALESCE(MAX(max_altitude), 0)::DOUBLE,
+                    COALESCE(SUM(night_secs), 0)::DOUBLE
                 FROM flights
                 "#,
                 [],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?)),
             )?;
 
         // Battery usage with total duration and max cycle count
@@ -1506,16 +2093,17 @@ impl Database {
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Flights by date for activity heatmap (last 365 days)
+        // Flights by date for activity heatmap (last 365 days), bucketed by the
+        // flight's local calendar day when its time zone is known
         let mut stmt = conn.prepare(
             r#"
             SELECT 
-                CAST(DATE_TRUNC('day', start_time) AS DATE)::VARCHAR AS flight_date,
+                COALESCE(CAST(local_start_time AS DATE), CAST(DATE_TRUNC('day', start_time) AS DATE))::VARCHAR AS flight_date,
                 COUNT(*)::BIGINT AS count
             FROM flights
             WHERE start_time IS NOT NULL 
               AND start_time >= CURRENT_DATE - INTERVAL '365 days'
-            GROUP BY DATE_TRUNC('day', start_time)
+            GROUP BY flight_date
             ORDER BY flight_date ASC
             "#,
         )?;
@@ -1646,6 +2234,7 @@ impl Database {
             total_flights,
             total_distance_m: total_distance,
             total_duration_secs: total_duration,
+            total_night_secs: total_night,
             total_points,
             total_photos,
             total_videos,
@@ -1781,7 +2370,10 @@ impl Database {
 
     /// Replace all auto tags for a flight with new ones (keeps manual tags)
     pub fn replace_auto_tags(&self, flight_id: i64, new_tags: &[String]) -> Result<(), DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+        Self::write_auto_tags(&self.conn.lock().unwrap(), flight_id, new_tags)
+    }
+
+    fn write_auto_tags(conn: &Connection, flight_id: i64, new_tags: &[String]) -> Result<(), DatabaseError> {
         // Delete existing auto tags
         conn.execute(
             "DELETE FROM flight_tags WHERE flight_id = ? AND tag_type = 'auto'",
@@ -1828,10 +2420,13 @@ impl Database {
 
     /// Insert flight messages (tips and warnings) for a flight
     pub fn insert_flight_messages(&self, flight_id: i64, messages: &[FlightMessage]) -> Result<(), DatabaseError> {
+        Self::write_flight_messages(&self.conn.lock().unwrap(), flight_id, messages)
+    }
+
+    fn write_flight_messages(conn: &Connection, flight_id: i64, messages: &[FlightMessage]) -> Result<(), DatabaseError> {
         if messages.is_empty() {
             return Ok(());
         }
-        let conn = self.conn.lock().unwrap();
         for msg in messages {
             // Use INSERT OR IGNORE to avoid duplicate key errors
             conn.execute(
@@ -1872,63 +2467,1170 @@ impl Database {
         Ok(())
     }
 
-    // ========================================================================
-    // EQUIPMENT NAMES
-    // ========================================================================
+    // ================================================================
+    // SIDE DATA (extra DJI records)
+    // ================================================================
 
-    /// Set a custom display name for a battery or aircraft
-    pub fn set_equipment_name(&self, serial: &str, equipment_type: &str, display_name: &str) -> Result<(), DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-        let serial_upper = serial.trim().to_uppercase();
-        
-        if display_name.trim().is_empty() {
-            // Empty name = delete the mapping
+    /// Insert home point, RC GPS, avoidance, motor and wind records for a flight
+    pub fn insert_flight_side_data(&self, flight_id: i64, side: &FlightSideData) -> Result<(), DatabaseError> {
+        Self::write_flight_side_data(&self.conn.lock().unwrap(), flight_id, side)
+    }
+
+    fn write_flight_side_data(conn: &Connection, flight_id: i64, side: &FlightSideData) -> Result<(), DatabaseError> {
+        if side.is_empty() {
+            return Ok(());
+        }
+        for h in &side.home_updates {
             conn.execute(
-                "DELETE FROM equipment_names WHERE serial = ? AND equipment_type = ?",
-                params![serial_upper, equipment_type],
+                r#"
+                INSERT OR IGNORE INTO flight_home_updates
+                    (flight_id, timestamp_ms, latitude, longitude, altitude, go_home_height,
+                     max_allowed_height, go_home_mode, dynamic_home_point)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    flight_id, h.timestamp_ms, h.latitude, h.longitude, h.altitude, h.go_home_height,
+                    h.max_allowed_height, h.go_home_mode, h.dynamic_home_point
+                ],
             )?;
-            log::info!("Removed {} name for serial {}", equipment_type, serial_upper);
-        } else {
+        }
+        for r in &side.rc_gps {
             conn.execute(
-                "INSERT OR REPLACE INTO equipment_names (serial, equipment_type, display_name, updated_at) 
-                 VALUES (?, ?, ?, CURRENT_TIMESTAMP)",
-                params![serial_upper, equipment_type, display_name.trim()],
+                "INSERT OR IGNORE INTO flight_rc_gps (flight_id, timestamp_ms, latitude, longitude, satellites, accuracy) VALUES (?, ?, ?, ?, ?, ?)",
+                params![flight_id, r.timestamp_ms, r.latitude, r.longitude, r.satellites, r.accuracy],
+            )?;
+        }
+        for a in &side.avoidance {
+            conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_avoidance
+                    (flight_id, timestamp_ms, obstacle_avoidance_enabled, user_avoidance_enabled,
+                     vision_positioning_used, ultrasonic_working, vision_height)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    flight_id, a.timestamp_ms, a.obstacle_avoidance_enabled, a.user_avoidance_enabled,
+                    a.vision_positioning_used, a.ultrasonic_working, a.vision_height
+                ],
+            )?;
+        }
+        for m in &side.motor_status {
+            conn.execute(
+                r#"
+                INSERT OR IGNORE INTO flight_motor_status
+                    (flight_id, timestamp_ms, motors_on, motor_blocked, not_enough_force,
+                     propeller_catapult, start_failed_cause)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    flight_id, m.timestamp_ms, m.motors_on, m.motor_blocked, m.not_enough_force,
+                    m.propeller_catapult, m.start_failed_cause
+                ],
             )?;
-            log::info!("Set {} name for serial {}: {}", equipment_type, serial_upper, display_name.trim());
         }
+        for w in &side.wind_warnings {
+            conn.execute(
+                "INSERT OR IGNORE INTO flight_wind_warnings (flight_id, timestamp_ms, message_type, message) VALUES (?, ?, ?, ?)",
+                params![flight_id, w.timestamp_ms, w.message_type, w.message],
+            )?;
+        }
+        log::debug!(
+            "Inserted side data for flight {}: {} home, {} RC GPS, {} avoidance, {} motor, {} wind",
+            flight_id,
+            side.home_updates.len(),
+            side.rc_gps.len(),
+            side.avoidance.len(),
+            side.motor_status.len(),
+            side.wind_warnings.len()
+        );
         Ok(())
     }
 
-    /// Get all equipment names of a given type (battery or aircraft)
-    pub fn get_equipment_names(&self, equipment_type: &str) -> Result<Vec<(String, String)>, DatabaseError> {
+    /// Get the home point, RC GPS, avoidance, motor and wind records for a flight
+    pub fn get_flight_side_data(&self, flight_id: i64) -> Result<FlightSideData, DatabaseError> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT serial, display_name FROM equipment_names WHERE equipment_type = ? ORDER BY serial"
-        )?;
-        let names = stmt
-            .query_map(params![equipment_type], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+
+        let home_updates = conn
+            .prepare(
+                r#"
+                SELECT timestamp_ms, latitude, longitude, altitude, go_home_height,
+                       max_allowed_height, go_home_mode, dynamic_home_point
+                FROM flight_home_updates WHERE flight_id = ? ORDER BY timestamp_ms
+                "#,
+            )?
+            .query_map(params![flight_id], |row| {
+                Ok(HomeUpdate {
+                    timestamp_ms: row.get(0)?,
+                    latitude: row.get(1)?,
+                    longitude: row.get(2)?,
+                    altitude: row.get(3)?,
+                    go_home_height: row.get(4)?,
+                    max_allowed_height: row.get(5)?,
+                    go_home_mode: row.get(6)?,
+                    dynamic_home_point: row.get(7)?,
+                })
             })?
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(names)
-    }
 
-    /// Get all equipment names (both batteries and aircraft) as a map
-    pub fn get_all_equipment_names(&self) -> Result<(Vec<(String, String)>, Vec<(String, String)>), DatabaseError> {
-        let battery_names = self.get_equipment_names("battery")?;
-        let aircraft_names = self.get_equipment_names("aircraft")?;
-        Ok((battery_names, aircraft_names))
-    }
+        let rc_gps = conn
+            .prepare(
+                "SELECT timestamp_ms, latitude, longitude, satellites, accuracy FROM flight_rc_gps WHERE flight_id = ? ORDER BY timestamp_ms",
+            )?
+            .query_map(params![flight_id], |row| {
+                Ok(RcGpsPoint {
+                    timestamp_ms: row.get(0)?,
+                    latitude: row.get(1)?,
+                    longitude: row.get(2)?,
+                    satellites: row.get(3)?,
+                    accuracy: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
-    /// Check if a file has already been imported (by hash)
-    /// Returns the display_name of the matching flight if found, None otherwise
-    pub fn is_file_imported(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+        let avoidance = conn
+            .prepare(
+                r#"
+                SELECT timestamp_ms, obstacle_avoidance_enabled, user_avoidance_enabled,
+                       vision_positioning_used, ultrasonic_working, vision_height
+                FROM flight_avoidance WHERE flight_id = ? ORDER BY timestamp_ms
+                "#,
+            )?
+            .query_map(params![flight_id], |row| {
+                Ok(AvoidanceState {
+                    timestamp_ms: row.get(0)?,
+                    obstacle_avoidance_enabled: row.get(1)?,
+                    user_avoidance_enabled: row.get(2)?,
+                    vision_positioning_used: row.get(3)?,
+                    ultrasonic_working: row.get(4)?,
+                    vision_height: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let result: Option<String> = conn.query_row(
-            "SELECT COALESCE(display_name, file_name) FROM flights WHERE file_hash = ? LIMIT 1",
-            params![file_hash],
-            |row| row.get(0),
+        let motor_status = conn
+            .prepare(
+                r#"
+                SELECT timestamp_ms, motors_on, motor_blocked, not_enough_force,
+                       propeller_catapult, start_failed_cause
+                FROM flight_motor_status WHERE flight_id = ? ORDER BY timestamp_ms
+                "#,
+            )?
+            .query_map(params![flight_id], |row| {
+                Ok(MotorStatus {
+                    timestamp_ms: row.get(0)?,
+                    motors_on: row.get(1)?,
+                    motor_blocked: row.get(2)?,
+                    not_enough_force: row.get(3)?,
+                    propeller_catapult: row.get(4)?,
+                    start_failed_cause: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let wind_warnings = conn
+            .prepare(
+                "SELECT timestamp_ms, message_type, message FROM flight_wind_warnings WHERE flight_id = ? ORDER BY timestamp_ms",
+            )?
+            .query_map(params![flight_id], |row| {
+                Ok(WindWarning {
+                    timestamp_ms: row.get(0)?,
+                    message_type: row.get(1)?,
+                    message: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(FlightSideData { home_updates, rc_gps, avoidance, motor_status, wind_warnings })
+    }
+
+    // ========================================================================
+    // IMPORT FAILURES
+    // ========================================================================
+
+    /// Record a failed import attempt, bumping the attempt count for known files
+    pub fn record_import_failure(&self, file_hash: &str, file_path: &str, error_kind: &str, message: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO import_failures (file_hash, file_path, error_kind, message)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (file_hash) DO UPDATE SET
+                file_path = excluded.file_path,
+                error_kind = excluded.error_kind,
+                message = excluded.message,
+                last_attempt = now(),
+                attempts = import_failures.attempts + 1
+            "#,
+            params![file_hash, file_path, error_kind, message],
+        )?;
+        Ok(())
+    }
+
+    /// Point a recorded failure at the file's new location after it was moved
+    pub fn update_import_failure_path(&self, file_hash: &str, file_path: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE import_failures SET file_path = ? WHERE file_hash = ?",
+            params![file_path, file_hash],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a failure once the file imports (or is found to be already imported)
+    pub fn clear_import_failure(&self, file_hash: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM import_failures WHERE file_hash = ?", params![file_hash])?;
+        Ok(())
+    }
+
+    /// List recorded import failures, most recent first
+    pub fn get_import_failures(&self) -> Result<Vec<ImportFailure>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT file_hash, file_path, error_kind, message,
+                   CAST(first_attempt AS VARCHAR), CAST(last_attempt AS VARCHAR),
+                   attempts, ignored
+            FROM import_failures
+            ORDER BY last_attempt DESC
+            "#,
+        )?;
+        let failures = stmt
+            .query_map([], |row| {
+                Ok(ImportFailure {
+                    file_hash: row.get(0)?,
+                    file_path: row.get(1)?,
+                    error_kind: row.get(2)?,
+                    message: row.get(3)?,
+                    first_attempt: row.get(4)?,
+                    last_attempt: row.get(5)?,
+                    attempts: row.get(6)?,
+                    ignored: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(failures)
+    }
+
+    /// Get the recorded file path of a failed import
+    pub fn get_import_failure_path(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let path = conn.query_row(
+            "SELECT file_path FROM import_failures WHERE file_hash = ?",
+            params![file_hash],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(path)
+    }
+
+    /// Mark a failed file as permanently ignored (or un-ignore it).
+    /// Returns false if no failure is recorded for the hash.
+    pub fn set_import_failure_ignored(&self, file_hash: &str, ignored: bool) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE import_failures SET ignored = ? WHERE file_hash = ?",
+            params![ignored, file_hash],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Check whether a file hash has been marked as ignored
+    pub fn is_import_ignored(&self, file_hash: &str) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let ignored: Option<bool> = conn.query_row(
+            "SELECT ignored FROM import_failures WHERE file_hash = ?",
+            params![file_hash],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(ignored.unwrap_or(false))
+    }
+
+    // ========================================================================
+    // KEYCHAINS & PENDING DECRYPTION
+    // ========================================================================
+
+    /// Keychain provider backed by this database's keychain cache, falling
+    /// back to `upstream` (usually the DJI API) for keychains not cached yet.
+    /// It only reads, so parser workers can use it next to the writer.
+    pub fn keychain_cache(&self, upstream: Option<Arc<dyn KeychainProvider>>) -> Result<KeychainCache, DatabaseError> {
+        let conn = self.conn.lock().unwrap().try_clone()?;
+        Ok(KeychainCache {
+            conn: Mutex::new(conn),
+            upstream,
+            fetched: Mutex::default(),
+        })
+    }
+
+    /// Store keychains a [`KeychainCache`] fetched upstream
+    pub fn cache_keychains(&self, fetched: &[FetchedKeychains]) -> Result<(), DatabaseError> {
+        if fetched.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO keychains (request_hash, keychains, fetched_at) VALUES (?, ?, now())",
+        )?;
+        for entry in fetched {
+            match serde_json::to_string(&entry.keychains) {
+                Ok(json) => {
+                    stmt.execute(params![entry.request_hash, json])?;
+                }
+                Err(e) => log::warn!("Failed to serialize keychains: {}", e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Export every cached keychain as a portable bundle
+    pub fn export_keychain_bundle(&self) -> Result<KeychainBundle, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT request_hash, keychains, CAST(fetched_at AS VARCHAR) FROM keychains ORDER BY fetched_at",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let entries = rows
+            .into_iter()
+            .filter_map(|(request_hash, json, fetched_at)| {
+                let keychains = serde_json::from_str(&json).ok()?;
+                Some(KeychainBundleEntry { request_hash, keychains, fetched_at })
+            })
+            .collect();
+
+        Ok(KeychainBundle {
+            format: KEYCHAIN_BUNDLE_FORMAT.to_string(),
+            version: KEYCHAIN_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            entries,
+        })
+    }
+
+    /// Add the keychains of a bundle to the cache, replacing existing entries.
+    /// Entries that are not valid keychains are skipped. Returns how many were stored.
+    pub fn import_keychain_bundle(&self, bundle: &KeychainBundle) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO keychains (request_hash, keychains, fetched_at) VALUES (?, ?, COALESCE(TRY_CAST(? AS TIMESTAMPTZ), now()))",
+        )?;
+        let mut imported = 0;
+        for entry in &bundle.entries {
+            if serde_json::from_value::<Vec<Vec<KeychainFeaturePoint>>>(entry.keychains.clone()).is_err() {
+                log::warn!("Skipping invalid keychain bundle entry {}", entry.request_hash);
+                continue;
+            }
+            stmt.execute(params![entry.request_hash, entry.keychains.to_string(), entry.fetched_at])?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    /// Queue an encrypted log whose keychains are missing. A copy of the file
+    /// is kept in the data directory so the retry does not depend on the
+    /// original location. Re-queuing a known file bumps its attempt count.
+    pub fn queue_pending_decryption(&self, file_path: &std::path::Path, file_hash: &str, message: &str) -> Result<(), DatabaseError> {
+        let file_name = file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("{}.txt", file_hash));
+        let folder = self.pending_dir.join(file_hash);
+        let stored = folder.join(&file_name);
+        if file_path != stored {
+            fs::create_dir_all(&folder)?;
+            fs::copy(file_path, &stored)?;
+        }
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO pending_decryption (file_hash, file_name, source_path, message)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (file_hash) DO UPDATE SET
+                message = excluded.message,
+                last_attempt = now(),
+                attempts = pending_decryption.attempts + 1
+            "#,
+            params![file_hash, file_name, file_path.to_string_lossy().to_string(), message],
+        )?;
+        Ok(())
+    }
+
+    /// List logs waiting for keychains, oldest first
+    pub fn get_pending_decryption(&self) -> Result<Vec<PendingDecryption>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT file_hash, file_name, source_path, message,
+                   CAST(added_at AS VARCHAR), CAST(last_attempt AS VARCHAR), attempts
+            FROM pending_decryption
+            ORDER BY added_at
+            "#,
+        )?;
+        let pending = stmt
+            .query_map([], |row| {
+                Ok(PendingDecryption {
+                    file_hash: row.get(0)?,
+                    file_name: row.get(1)?,
+                    source_path: row.get(2)?,
+                    message: row.get(3)?,
+                    added_at: row.get(4)?,
+                    last_attempt: row.get(5)?,
+                    attempts: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(pending)
+    }
+
+    /// Path of the kept copy of a queued log
+    pub fn pending_decryption_file(&self, entry: &PendingDecryption) -> PathBuf {
+        self.pending_dir.join(&entry.file_hash).join(&entry.file_name)
+    }
+
+    /// Drop a log from the pending decryption queue and delete its kept copy.
+    /// Returns false if the hash was not queued.
+    pub fn remove_pending_decryption(&self, file_hash: &str) -> Result<bool, DatabaseError> {
+        let removed = {
+            let conn = self.conn.lock().unwrap();
+            conn.execute("DELETE FROM pending_decryption WHERE file_hash = ?", params![file_hash])?
+        };
+        let folder = self.pending_dir.join(file_hash);
+        if !file_hash.is_empty() && folder.exists() {
+            let _ = fs::remove_dir_all(&folder);
+        }
+        Ok(removed > 0)
+    }
+
+    // ========================================================================
+    // SYNC SEEN FILES
+    // ========================================================================
+
+    /// Seen index of sync folder files, keyed by absolute path
+    pub fn get_sync_seen_files(&self) -> Result<HashMap<String, SyncSeenFile>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT path, size, modified_ms, file_hash FROM sync_seen_files")?;
+        let seen = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    SyncSeenFile {
+                        size: row.get(1)?,
+                        modified_ms: row.get(2)?,
+                        file_hash: row.get(3)?,
+                    },
+                ))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(seen)
+    }
+
+    /// Add or refresh entries of the seen index
+    pub fn record_sync_seen_files(&self, files: &[(String, SyncSeenFile)]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            INSERT INTO sync_seen_files (path, size, modified_ms, file_hash)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (path) DO UPDATE SET
+                size = excluded.size,
+                modified_ms = excluded.modified_ms,
+                file_hash = excluded.file_hash,
+                seen_at = now()
+            "#,
+        )?;
+        for (path, seen) in files {
+            stmt.execute(params![path, seen.size, seen.modified_ms, seen.file_hash])?;
+        }
+        Ok(())
+    }
+
+    /// Drop entries of the seen index, e.g. for files deleted from the sync folder
+    pub fn forget_sync_seen_files(&self, paths: &[String]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("DELETE FROM sync_seen_files WHERE path = ?")?;
+        for path in paths {
+            stmt.execute(params![path])?;
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // SYNC RUN HISTORY
+    // ========================================================================
+
+    /// Save a finished sync run and its files; returns the run's id. Only
+    /// the newest [`MAX_SYNC_RUNS`] runs are kept.
+    pub fn record_sync_run(&self, run: &SyncRun, files: &[SyncRunFile]) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let id: i64 = conn.query_row("SELECT COALESCE(MAX(id), 0) + 1 FROM sync_runs", [], |row| row.get(0))?;
+        conn.execute(
+            r#"
+            INSERT INTO sync_runs (id, triggered_by, source_id, sync_path, started_at, finished_at,
+                                   imported, skipped, unchanged, queued, failed, cancelled, archived, rejected, error)
+            VALUES (?, ?, ?, ?, CAST(? AS TIMESTAMPTZ), CAST(? AS TIMESTAMPTZ), ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                id,
+                run.trigger.as_str(),
+                run.source_id,
+                run.sync_path,
+                run.started_at,
+                run.finished_at,
+                run.imported as i64,
+                run.skipped as i64,
+                run.unchanged as i64,
+                run.queued as i64,
+                run.failed as i64,
+                run.cancelled as i64,
+                run.archived as i64,
+                run.rejected as i64,
+                run.error,
+            ],
+        )?;
+        let mut stmt = conn.prepare("INSERT INTO sync_run_files (run_id, path, outcome, message, flight_id) VALUES (?, ?, ?, ?, ?)")?;
+        for file in files {
+            stmt.execute(params![id, file.path, file.outcome, file.message, file.flight_id])?;
+        }
+
+        conn.execute("DELETE FROM sync_runs WHERE id <= ?", params![id - MAX_SYNC_RUNS])?;
+        conn.execute("DELETE FROM sync_run_files WHERE run_id <= ?", params![id - MAX_SYNC_RUNS])?;
+        Ok(id)
+    }
+
+    /// Sync runs, newest first. With `source_id`, only the runs of that
+    /// source (`Some(None)` for the SYNC_LOGS_PATH folder).
+    pub fn get_sync_runs(&self, limit: usize, source_id: Option<Option<&str>>) -> Result<Vec<SyncRun>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        match source_id {
+            None => Self::query_sync_runs(&conn, "", &[], limit),
+            Some(None) => Self::query_sync_runs(&conn, "WHERE source_id IS NULL", &[], limit),
+            Some(Some(id)) => Self::query_sync_runs(&conn, "WHERE source_id = ?", &[&id], limit),
+        }
+    }
+
+    fn query_sync_runs(conn: &Connection, filter: &str, args: &[&dyn duckdb::ToSql], limit: usize) -> Result<Vec<SyncRun>, DatabaseError> {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT id, triggered_by, source_id, sync_path,
+                   CAST(started_at AS VARCHAR), CAST(finished_at AS VARCHAR),
+                   imported, skipped, unchanged, queued, failed, cancelled, archived, rejected, error
+            FROM sync_runs
+            {}
+            ORDER BY id DESC
+            LIMIT {}
+            "#,
+            filter, limit
+        ))?;
+        let runs = stmt
+            .query_map(args, |row| {
+                let trigger: String = row.get(1)?;
+                let count = |i: usize| row.get::<_, i32>(i).map(|v| v as usize);
+                Ok(SyncRun {
+                    id: row.get(0)?,
+                    trigger: trigger.parse().unwrap_or(SyncTrigger::Manual),
+                    source_id: row.get(2)?,
+                    sync_path: row.get(3)?,
+                    started_at: row.get(4)?,
+                    finished_at: row.get(5)?,
+                    imported: count(6)?,
+                    skipped: count(7)?,
+                    unchanged: count(8)?,
+                    queued: count(9)?,
+                    failed: count(10)?,
+                    cancelled: count(11)?,
+                    archived: count(12)?,
+                    rejected: count(13)?,
+                    error: row.get(14)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(runs)
+    }
+
+    /// A sync run with the outcome of each of its files
+    pub fn get_sync_run(&self, id: i64) -> Result<Option<SyncRunDetail>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let Some(run) = Self::query_sync_runs(&conn, "WHERE id = ?", &[&id], 1)?.pop() else {
+            return Ok(None);
+        };
+        let mut stmt = conn.prepare("SELECT path, outcome, message, flight_id FROM sync_run_files WHERE run_id = ? ORDER BY path")?;
+        let files = stmt
+            .query_map(params![id], |row| {
+                Ok(SyncRunFile {
+                    path: row.get(0)?,
+                    outcome: row.get(1)?,
+                    message: row.get(2)?,
+                    flight_id: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(SyncRunDetail { run, files }))
+    }
+
+    // ========================================================================
+    // MEDIA CLIPS
+    // ========================================================================
+
+    /// Import a parsed subtitle sidecar and link it to the flight it was
+    /// shot during: the flight overlapping the clip the longest, of
+    /// `drone_serial` when given. Clips are deduplicated by file hash.
+    pub fn import_media_clip(&self, clip: &ParsedMediaClip, drone_serial: Option<&str>) -> Result<MediaImportResult, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let existing: Option<i64> = conn
+            .query_row("SELECT id FROM media_clips WHERE file_hash = ?", params![clip.file_hash], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            return Ok(MediaImportResult {
+                success: false,
+                clip_id: Some(id),
+                flight_id: None,
+                message: format!("Video subtitles already imported: {}", clip.srt_file),
+                frame_count: 0,
+            });
+        }
+
+        let start = clip.start_time.and_utc().timestamp_millis() as f64 / 1000.0;
+        let end = clip.end_time.and_utc().timestamp_millis() as f64 / 1000.0;
+        let flight = Self::overlapping_flight(&conn, start, end, drone_serial)?;
+        let serial = drone_serial.map(str::to_string).or_else(|| flight.as_ref().and_then(|(_, serial)| serial.clone()));
+        let flight_id = flight.map(|(id, _)| id);
+
+        let id: i64 = conn.query_row("SELECT COALESCE(MAX(id), 0) + 1 FROM media_clips", [], |row| row.get(0))?;
+        conn.execute(
+            r#"
+            INSERT INTO media_clips (id, flight_id, srt_file, video_file, file_hash, drone_serial, start_time, end_time, frame_count)
+            VALUES (?, ?, ?, ?, ?, ?, CAST(? AS TIMESTAMP), CAST(? AS TIMESTAMP), ?)
+            "#,
+            params![
+                id,
+                flight_id,
+                clip.srt_file,
+                clip.video_file,
+                clip.file_hash,
+                serial,
+                format_local_time(&clip.start_time),
+                format_local_time(&clip.end_time),
+                clip.frames.len() as i64,
+            ],
+        )?;
+
+        let mut appender = conn.appender("media_frames")?;
+        for f in &clip.frames {
+            appender.append_row(params![
+                id, f.offset_ms, f.end_ms, f.latitude, f.longitude, f.rel_altitude, f.abs_altitude,
+                f.iso, f.shutter, f.fnum, f.ev, f.focal_length, f.color_temp
+            ])?;
+        }
+        appender.flush()?;
+
+        Ok(MediaImportResult {
+            success: true,
+            clip_id: Some(id),
+            flight_id,
+            message: match flight_id {
+                Some(flight_id) => format!("Imported {} frames, linked to flight {}", clip.frames.len(), flight_id),
+                None => format!("Imported {} frames, no flight found for this video yet", clip.frames.len()),
+            },
+            frame_count: clip.frames.len(),
+        })
+    }
+
+    /// The flight overlapping `[start, end]` (local epoch seconds) the
+    /// longest, with its drone serial
+    fn overlapping_flight(conn: &Connection, start: f64, end: f64, drone_serial: Option<&str>) -> Result<Option<(i64, Option<String>)>, DatabaseError> {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT id, drone_serial,
+                   LEAST(start_secs + duration, ?) - GREATEST(start_secs, ?) AS overlap
+            FROM (
+                SELECT f.id, f.drone_serial, {} AS start_secs, COALESCE(f.duration_secs, 0) AS duration
+                FROM flights f
+                WHERE f.start_time IS NOT NULL
+            )
+            WHERE start_secs <= ? AND start_secs + duration >= ?
+              AND (CAST(? AS VARCHAR) IS NULL OR lower(drone_serial) = lower(CAST(? AS VARCHAR)))
+            ORDER BY overlap DESC
+            LIMIT 1
+            "#,
+            FLIGHT_LOCAL_START_SECS
+        ))?;
+        let flight = stmt
+            .query_row(params![end, start, end, start, drone_serial, drone_serial], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?;
+        Ok(flight)
+    }
+
+    /// Link media clips without a flight to `flight_id` if they were shot
+    /// during it (and by its drone, for clips with a serial)
+    pub fn link_media_clips(&self, flight_id: i64) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let linked = conn.execute(
+            &format!(
+                r#"
+                UPDATE media_clips SET flight_id = f.id, drone_serial = COALESCE(media_clips.drone_serial, f.drone_serial)
+                FROM (
+                    SELECT f.id, f.drone_serial, {start} AS start_secs, {start} + COALESCE(f.duration_secs, 0) AS end_secs
+                    FROM flights f WHERE f.id = ? AND f.start_time IS NOT NULL
+                ) f
+                WHERE media_clips.flight_id IS NULL
+                  AND epoch_ms(media_clips.start_time) / 1000 <= f.end_secs
+                  AND epoch_ms(media_clips.end_time) / 1000 >= f.start_secs
+                  AND (media_clips.drone_serial IS NULL OR lower(media_clips.drone_serial) = lower(f.drone_serial))
+                "#,
+                start = FLIGHT_LOCAL_START_SECS
+            ),
+            params![flight_id],
+        )?;
+        if linked > 0 {
+            log::info!("Linked {} media clip(s) to flight {}", linked, flight_id);
+        }
+        Ok(linked)
+    }
+
+    /// Video clips shot during a flight, by start time
+    pub fn get_flight_media_clips(&self, flight_id: i64) -> Result<Vec<MediaClip>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Self::query_media_clips(&conn, "WHERE c.flight_id = ?", &[&flight_id])
+    }
+
+    /// All imported video clips, newest first
+    pub fn get_media_clips(&self) -> Result<Vec<MediaClip>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut clips = Self::query_media_clips(&conn, "", &[])?;
+        clips.reverse();
+        Ok(clips)
+    }
+
+    fn query_media_clips(conn: &Connection, filter: &str, args: &[&dyn duckdb::ToSql]) -> Result<Vec<MediaClip>, DatabaseError> {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT c.id, c.flight_id, c.srt_file, c.video_file, c.file_hash, c.drone_serial,
+                   CAST(c.start_time AS VARCHAR), CAST(c.end_time AS VARCHAR),
+                   (epoch_ms(c.end_time) - epoch_ms(c.start_time)) / 1000, c.frame_count,
+                   epoch_ms(c.start_time) / 1000 - {}
+            FROM media_clips c
+            LEFT JOIN flights f ON f.id = c.flight_id
+            {}
+            ORDER BY c.start_time, c.id
+            "#,
+            FLIGHT_LOCAL_START_SECS, filter
+        ))?;
+        let mut clips = stmt
+            .query_map(args, |row| {
+                Ok(MediaClip {
+                    id: row.get(0)?,
+                    flight_id: row.get(1)?,
+                    srt_file: row.get(2)?,
+                    video_file: row.get(3)?,
+                    file_hash: row.get(4)?,
+                    drone_serial: row.get(5)?,
+                    start_time: row.get(6)?,
+                    end_time: row.get(7)?,
+                    duration_secs: row.get(8)?,
+                    frame_count: row.get::<_, i32>(9)? as usize,
+                    iso: None,
+                    shutter: None,
+                    fnum: None,
+                    flight_offset_secs: row.get(10)?,
+                    track: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Camera settings of the first frame and a thinned-out track
+        let mut frames = conn.prepare(
+            r#"
+            SELECT longitude, latitude, rel_altitude, iso, shutter, fnum
+            FROM media_frames WHERE clip_id = ? ORDER BY offset_ms
+            "#,
+        )?;
+        for clip in &mut clips {
+            let step = ((clip.frame_count + MediaClip::TRACK_POINTS - 1) / MediaClip::TRACK_POINTS).max(1);
+            let mut rows = frames.query(params![clip.id])?;
+            let mut index = 0usize;
+            while let Some(row) = rows.next()? {
+                if index == 0 {
+                    clip.iso = row.get(3)?;
+                    clip.shutter = row.get(4)?;
+                    clip.fnum = row.get(5)?;
+                }
+                if index % step == 0 {
+                    let (lng, lat): (Option<f64>, Option<f64>) = (row.get(0)?, row.get(1)?);
+                    if let (Some(lng), Some(lat)) = (lng, lat) {
+                        clip.track.push([lng, lat, row.get::<_, Option<f64>>(2)?.unwrap_or(0.0)]);
+                    }
+                }
+                index += 1;
+            }
+        }
+        Ok(clips)
+    }
+
+    /// Every frame of a video clip
+    pub fn get_media_frames(&self, clip_id: i64) -> Result<Vec<MediaFrame>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT offset_ms, end_ms, latitude, longitude, rel_altitude, abs_altitude,
+                   iso, shutter, fnum, ev, focal_length, color_temp
+            FROM media_frames WHERE clip_id = ? ORDER BY offset_ms
+            "#,
+        )?;
+        let frames = stmt
+            .query_map(params![clip_id], |row| {
+                Ok(MediaFrame {
+                    offset_ms: row.get(0)?,
+                    end_ms: row.get(1)?,
+                    latitude: row.get(2)?,
+                    longitude: row.get(3)?,
+                    rel_altitude: row.get(4)?,
+                    abs_altitude: row.get(5)?,
+                    iso: row.get(6)?,
+                    shutter: row.get(7)?,
+                    fnum: row.get(8)?,
+                    ev: row.get(9)?,
+                    focal_length: row.get(10)?,
+                    color_temp: row.get(11)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(frames)
+    }
+
+    /// Delete a video clip and its frames; returns `false` if it did not exist
+    pub fn delete_media_clip(&self, clip_id: i64) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM media_frames WHERE clip_id = ?", params![clip_id])?;
+        Ok(conn.execute("DELETE FROM media_clips WHERE id = ?", params![clip_id])? > 0)
+    }
+
+    // ========================================================================
+    // IMPORT BLACKLIST
+    // ========================================================================
+
+    /// Add a file hash to the import blacklist (no-op if already present)
+    pub fn add_to_blacklist(&self, file_hash: &str, file_name: Option<&str>) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO import_blacklist (file_hash, file_name)
+            VALUES (?, ?)
+            ON CONFLICT (file_hash) DO UPDATE SET
+                file_name = COALESCE(excluded.file_name, import_blacklist.file_name)
+            "#,
+            params![file_hash, file_name],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a file hash from the import blacklist.
+    /// Returns false if the hash was not blacklisted.
+    pub fn remove_from_blacklist(&self, file_hash: &str) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM import_blacklist WHERE file_hash = ?", params![file_hash])?;
+        Ok(removed > 0)
+    }
+
+    /// Remove every entry from the import blacklist, returning how many were removed
+    pub fn clear_blacklist(&self) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM import_blacklist", [])?;
+        Ok(removed)
+    }
+
+    /// List blacklisted files, most recently added first
+    pub fn get_blacklist(&self) -> Result<Vec<BlacklistEntry>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT file_hash, file_name, CAST(added_at AS VARCHAR)
+            FROM import_blacklist
+            ORDER BY added_at DESC
+            "#,
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(BlacklistEntry {
+                    file_hash: row.get(0)?,
+                    file_name: row.get(1)?,
+                    added_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Check whether a file hash is on the import blacklist
+    pub fn is_blacklisted(&self, file_hash: &str) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM import_blacklist WHERE file_hash = ?",
+            params![file_hash],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    // ========================================================================
+    // EQUIPMENT NAMES
+    // ========================================================================
+
+    /// Set a custom display name for a battery or aircraft
+    pub fn set_equipment_name(&self, serial: &str, equipment_type: &str, display_name: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let serial_upper = serial.trim().to_uppercase();
+        
+        if display_name.trim().is_empty() {
+            // Empty name = clear the mapping, keeping inventory rows seen in flights
+            conn.execute(
+                "DELETE FROM equipment_names WHERE serial = ? AND equipment_type = ? AND first_seen IS NULL",
+                params![serial_upper, equipment_type],
+            )?;
+            conn.execute(
+                "UPDATE equipment_names SET display_name = NULL, updated_at = now() WHERE serial = ? AND equipment_type = ?",
+                params![serial_upper, equipment_type],
+            )?;
+            log::info!("Removed {} name for serial {}", equipment_type, serial_upper);
+        } else {
+            conn.execute(
+                r#"
+                INSERT INTO equipment_names (serial, equipment_type, display_name, updated_at)
+                VALUES (?, ?, ?, now())
+                ON CONFLICT (serial, equipment_type) DO UPDATE SET
+                    display_name = excluded.display_name,
+                    updated_at = now()
+                "#,
+                params![serial_upper, equipment_type, display_name.trim()],
+            )?;
+            log::info!("Set {} name for serial {}: {}", equipment_type, serial_upper, display_name.trim());
+        }
+        Ok(())
+    }
+
+    /// Get all equipment names of a given type (battery or aircraft)
+    pub fn get_equipment_names(&self, equipment_type: &str) -> Result<Vec<(String, String)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT serial, display_name FROM equipment_names WHERE equipment_type = ? AND display_name IS NOT NULL ORDER BY serial"
+        )?;
+        let names = stmt
+            .query_map(params![equipment_type], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(names)
+    }
+
+    /// Get all equipment names (both batteries and aircraft) as a map
+    #[allow(clippy::type_complexity)]
+    pub fn get_all_equipment_names(&self) -> Result<(Vec<(String, String)>, Vec<(String, String)>), DatabaseError> {
+        let battery_names = self.get_equipment_names("battery")?;
+        let aircraft_names = self.get_equipment_names("aircraft")?;
+        Ok((battery_names, aircraft_names))
+    }
+
+    /// Store a flight's equipment details and register its components in the
+    /// inventory (first/last seen, latest firmware)
+    pub fn insert_flight_equipment(
+        &self,
+        flight_id: i64,
+        metadata: &FlightMetadata,
+        equipment: &FlightEquipment,
+    ) -> Result<(), DatabaseError> {
+        Self::write_flight_equipment(&self.conn.lock().unwrap(), flight_id, metadata, equipment)
+    }
+
+    fn write_flight_equipment(conn: &Connection, flight_id: i64, metadata: &FlightMetadata, equipment: &FlightEquipment) -> Result<(), DatabaseError> {
+        if !equipment.is_empty() {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO flight_equipment
+                    (flight_id, app_name, app_platform, app_version, aircraft_firmware, battery_firmware,
+                     rc_serial, rc_firmware, camera_serial, camera_firmware, gimbal_firmware)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    flight_id,
+                    equipment.app_name,
+                    equipment.app_platform,
+                    equipment.app_version,
+                    equipment.aircraft_firmware,
+                    equipment.battery_firmware,
+                    equipment.rc_serial,
+                    equipment.rc_firmware,
+                    equipment.camera_serial,
+                    equipment.camera_firmware,
+                    equipment.gimbal_firmware,
+                ],
+            )?;
+        }
+
+        let components = [
+            ("aircraft", &metadata.drone_serial, &equipment.aircraft_firmware),
+            ("battery", &metadata.battery_serial, &equipment.battery_firmware),
+            ("rc", &equipment.rc_serial, &equipment.rc_firmware),
+            ("camera", &equipment.camera_serial, &equipment.camera_firmware),
+        ];
+        let seen = metadata.start_time.map(|t| t.to_rfc3339());
+        for (equipment_type, serial, firmware) in components {
+            let serial = match serial.as_deref().map(str::trim) {
+                Some(sn) if !sn.is_empty() => sn.to_uppercase(),
+                _ => continue,
+            };
+            // Firmware follows the most recent flight, so older re-imports don't roll it back
+            conn.execute(
+                r#"
+                INSERT INTO equipment_names (serial, equipment_type, first_seen, last_seen, firmware)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT (serial, equipment_type) DO UPDATE SET
+                    first_seen = LEAST(equipment_names.first_seen, excluded.first_seen),
+                    last_seen = GREATEST(equipment_names.last_seen, excluded.last_seen),
+                    firmware = CASE
+                        WHEN equipment_names.last_seen IS NULL
+                          OR excluded.last_seen >= equipment_names.last_seen
+                        THEN COALESCE(excluded.firmware, equipment_names.firmware)
+                        ELSE COALESCE(equipment_names.firmware, excluded.firmware)
+                    END
+                "#,
+                params![serial, equipment_type, seen, seen, firmware],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Get the equipment details recorded for a flight, if any
+    pub fn get_flight_equipment(&self, flight_id: i64) -> Result<Option<FlightEquipment>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let equipment = conn
+            .query_row(
+                r#"
+                SELECT app_name, app_platform, app_version, aircraft_firmware, battery_firmware,
+                       rc_serial, rc_firmware, camera_serial, camera_firmware, gimbal_firmware
+                FROM flight_equipment WHERE flight_id = ?
+                "#,
+                params![flight_id],
+                |row| {
+                    Ok(FlightEquipment {
+                        app_name: row.get(0)?,
+                        app_platform: row.get(1)?,
+                        app_version: row.get(2)?,
+                        aircraft_firmware: row.get(3)?,
+                        battery_firmware: row.get(4)?,
+                        rc_serial: row.get(5)?,
+                        rc_firmware: row.get(6)?,
+                        camera_serial: row.get(7)?,
+                        camera_firmware: row.get(8)?,
+                        gimbal_firmware: row.get(9)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(equipment)
+    }
+
+    /// Get the equipment inventory with per-item flight counts and firmware history
+    pub fn get_equipment_inventory(&self) -> Result<Vec<EquipmentItem>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        // Which serial flew which flight, with the firmware it reported
+        const USAGE_SQL: &str = r#"
+            WITH usage AS (
+                SELECT UPPER(TRIM(f.drone_serial)) AS serial, 'aircraft' AS equipment_type,
+                       f.start_time, fe.aircraft_firmware AS firmware
+                FROM flights f LEFT JOIN flight_equipment fe ON fe.flight_id = f.id
+                WHERE f.drone_serial IS NOT NULL
+                UNION ALL
+                SELECT UPPER(TRIM(f.battery_serial)), 'battery', f.start_time, fe.battery_firmware
+                FROM flights f LEFT JOIN flight_equipment fe ON fe.flight_id = f.id
+                WHERE f.battery_serial IS NOT NULL
+                UNION ALL
+                SELECT UPPER(TRIM(fe.rc_serial)), 'rc', f.start_time, fe.rc_firmware
+                FROM flight_equipment fe JOIN flights f ON f.id = fe.flight_id
+                WHERE fe.rc_serial IS NOT NULL
+                UNION ALL
+                SELECT UPPER(TRIM(fe.camera_serial)), 'camera', f.start_time, fe.camera_firmware
+                FROM flight_equipment fe JOIN flights f ON f.id = fe.flight_id
+                WHERE fe.camera_serial IS NOT NULL
+            )
+        "#;
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            {}
+            SELECT e.serial, e.equipment_type, e.display_name,
+                   CAST(e.first_seen AS VARCHAR), CAST(e.last_seen AS VARCHAR), e.firmware,
+                   (SELECT COUNT(*) FROM usage u WHERE u.serial = e.serial AND u.equipment_type = e.equipment_type)
+            FROM equipment_names e
+            ORDER BY e.equipment_type, e.last_seen DESC NULLS LAST, e.serial
+            "#,
+            USAGE_SQL
+        ))?;
+        let mut items = stmt
+            .query_map([], |row| {
+                Ok(EquipmentItem {
+                    serial: row.get(0)?,
+                    equipment_type: row.get(1)?,
+                    display_name: row.get(2)?,
+                    first_seen: row.get(3)?,
+                    last_seen: row.get(4)?,
+                    firmware: row.get(5)?,
+                    flight_count: row.get(6)?,
+                    firmware_history: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            {}
+            SELECT serial, equipment_type, firmware,
+                   CAST(MIN(start_time) AS VARCHAR), CAST(MAX(start_time) AS VARCHAR), COUNT(*)
+            FROM usage
+            WHERE firmware IS NOT NULL
+            GROUP BY serial, equipment_type, firmware
+            ORDER BY MIN(start_time)
+            "#,
+            USAGE_SQL
+        ))?;
+        let history = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    FirmwareVersion {
+                        version: row.get(2)?,
+                        first_flight: row.get(3)?,
+                        last_flight: row.get(4)?,
+                        flight_count: row.get(5)?,
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (serial, equipment_type, version) in history {
+            if let Some(item) = items
+                .iter_mut()
+                .find(|i| i.serial == serial && i.equipment_type == equipment_type)
+            {
+                item.firmware_history.push(version);
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Check if a file has already been imported (by hash)
+    /// Returns the display_name of the matching flight if found, None otherwise
+    pub fn is_file_imported(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let result: Option<String> = conn.query_row(
+            "SELECT COALESCE(display_name, file_name) FROM flights WHERE file_hash = ? LIMIT 1",
+            params![file_hash],
+            |row| row.get(0),
         ).optional()?;
 
         Ok(result)
@@ -2006,6 +3708,161 @@ impl Database {
         Ok(result)
     }
 
+    /// Import a parsed flight: signature dedup, flight + telemetry insert, tags, notes, color and messages.
+    /// Returns the new flight ID and the number of telemetry points inserted.
+    pub fn import_parsed_flight(&self, parsed: &ParsedFlight, options: &ImportOptions) -> Result<(i64, usize), ImportError> {
+        // Check for duplicate flight based on signature (drone_serial + battery_serial + start_time)
+        if let Some(matching_flight) = self.is_duplicate_flight(
+            parsed.metadata.drone_serial.as_deref(),
+            parsed.metadata.battery_serial.as_deref(),
+            parsed.metadata.start_time,
+        ).unwrap_or(None) {
+            return Err(ImportError::Duplicate(matching_flight));
+        }
+
+        let metadata = FlightMetadata {
+            id: self.generate_flight_id(),
+            ..parsed.metadata.clone()
+        };
+        log::debug!("Inserting flight metadata: id={}", metadata.id);
+        let flight_id = self.insert_flight(&metadata).map_err(ImportError::Flight)?;
+
+        let point_count = match self.bulk_insert_telemetry(flight_id, &parsed.points) {
+            Ok(count) => count,
+            Err(e) => {
+                log::error!("Failed to insert telemetry for flight {}: {}. Cleaning up.", flight_id, e);
+                if let Err(cleanup_err) = self.delete_flight(flight_id) {
+                    log::error!("Failed to clean up flight {}: {}", flight_id, cleanup_err);
+                }
+                return Err(ImportError::Telemetry(e));
+            }
+        };
+
+        // Insert smart tags if the feature is enabled
+        if options.smart_tags_enabled {
+            let tags = options.smart_tags(&parsed.tags);
+            if let Err(e) = self.insert_flight_tags(flight_id, &tags) {
+                log::warn!("Failed to insert tags for flight {}: {}", flight_id, e);
+            }
+        }
+
+        // Insert manual tags from re-imported CSV exports (always inserted regardless of smart_tags_enabled)
+        for manual_tag in &parsed.manual_tags {
+            if let Err(e) = self.add_flight_tag(flight_id, manual_tag) {
+                log::warn!("Failed to insert manual tag '{}' for flight {}: {}", manual_tag, flight_id, e);
+            }
+        }
+
+        // Auto-tag with profile name for non-default profiles
+        if let Some(ref profile) = options.profile_tag {
+            if let Err(e) = self.add_flight_tag(flight_id, profile) {
+                log::warn!("Failed to insert profile tag '{}' for flight {}: {}", profile, flight_id, e);
+            }
+        }
+
+        // Insert notes from re-imported CSV exports
+        if let Some(ref notes) = parsed.notes {
+            if let Err(e) = self.update_flight_notes(flight_id, Some(notes.as_str())) {
+                log::warn!("Failed to insert notes for flight {}: {}", flight_id, e);
+            }
+        }
+
+        // Apply color from re-imported CSV exports
+        if let Some(ref color) = parsed.color {
+            if let Err(e) = self.update_flight_color(flight_id, color) {
+                log::warn!("Failed to set color for flight {}: {}", flight_id, e);
+            }
+        }
+
+        // Insert app messages (tips and warnings) from the flight log
+        if !parsed.messages.is_empty() {
+            if let Err(e) = self.insert_flight_messages(flight_id, &parsed.messages) {
+                log::warn!("Failed to insert messages for flight {}: {}", flight_id, e);
+            }
+        }
+
+        // Insert extra DJI records (home point, RC GPS, avoidance, motors, wind)
+        if let Err(e) = self.insert_flight_side_data(flight_id, &parsed.side_data) {
+            log::warn!("Failed to insert side data for flight {}: {}", flight_id, e);
+        }
+
+        // Store equipment details and update the equipment inventory
+        if let Err(e) = self.insert_flight_equipment(flight_id, &parsed.metadata, &parsed.equipment) {
+            log::warn!("Failed to insert equipment for flight {}: {}", flight_id, e);
+        }
+
+        // Link videos imported before their flight
+        if let Err(e) = self.link_media_clips(flight_id) {
+            log::warn!("Failed to link media clips to flight {}: {}", flight_id, e);
+        }
+
+        Ok((flight_id, point_count))
+    }
+
+    /// Replace a flight's parsed data after re-parsing its original log.
+    /// Metadata, telemetry, auto tags and messages are replaced; the flight id,
+    /// display name, notes, color and manual tags are kept.
+    /// Returns the number of telemetry points inserted.
+    pub fn replace_flight_data(&self, flight_id: i64, parsed: &ParsedFlight, options: &ImportOptions) -> Result<usize, DatabaseError> {
+        let metadata = &parsed.metadata;
+        let (local_timezone, local_start_time) = local_start(metadata);
+        let night_secs = night_secs(metadata);
+
+        // One transaction, so a failure keeps the old data instead of a half-replaced flight
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            r#"
+            UPDATE flights SET
+                drone_model = ?, drone_serial = ?, aircraft_name = ?, battery_serial = ?,
+                cycle_count = ?, start_time = ?, end_time = ?, duration_secs = ?,
+                total_distance = ?, max_altitude = ?, max_speed = ?, home_lat = ?,
+                home_lon = ?, point_count = ?, photo_count = ?, video_count = ?,
+                local_timezone = ?, local_start_time = ?, night_secs = ?
+            WHERE id = ?
+            "#,
+            params![
+                metadata.drone_model,
+                metadata.drone_serial,
+                metadata.aircraft_name,
+                metadata.battery_serial,
+                metadata.cycle_count,
+                metadata.start_time.map(|t| t.to_rfc3339()),
+                metadata.end_time.map(|t| t.to_rfc3339()),
+                metadata.duration_secs,
+                metadata.total_distance,
+                metadata.max_altitude,
+                metadata.max_speed,
+                metadata.home_lat,
+                metadata.home_lon,
+                metadata.point_count,
+                metadata.photo_count,
+                metadata.video_count,
+                local_timezone,
+                local_start_time,
+                night_secs,
+                flight_id,
+            ],
+        )?;
+        tx.execute("DELETE FROM telemetry WHERE flight_id = ?", params![flight_id])?;
+        tx.execute("DELETE FROM flight_messages WHERE flight_id = ?", params![flight_id])?;
+        for table in SIDE_TABLES {
+            tx.execute(&format!("DELETE FROM {} WHERE flight_id = ?", table), params![flight_id])?;
+        }
+
+        let point_count = Self::write_telemetry(&tx, flight_id, &parsed.points)?;
+        if options.smart_tags_enabled {
+            Self::write_auto_tags(&tx, flight_id, &options.smart_tags(&parsed.tags))?;
+        }
+        Self::write_flight_messages(&tx, flight_id, &parsed.messages)?;
+        Self::write_flight_side_data(&tx, flight_id, &parsed.side_data)?;
+        Self::write_flight_equipment(&tx, flight_id, &parsed.metadata, &parsed.equipment)?;
+        tx.commit()?;
+
+        log::info!("Replaced data for flight {} ({} telemetry points)", flight_id, point_count);
+        Ok(point_count)
+    }
+
     /// Remove duplicate flights from the database based on exact signature match (drone_serial + battery_serial + start_time).
     /// Keeps the flight with the most telemetry points for each duplicate group.
     /// Returns the number of duplicates removed.
@@ -2089,6 +3946,18 @@ impl Database {
         )?;
         log::info!("Cleaned up {} orphaned tags", orphaned_tags);
 
+        // Clean up orphaned side-table records
+        for table in SIDE_TABLES {
+            let _ = conn.execute(
+                &format!("DELETE FROM {} WHERE flight_id NOT IN (SELECT id FROM flights)", table),
+                [],
+            );
+        }
+        let _ = conn.execute(
+            "UPDATE media_clips SET flight_id = NULL WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
+
         log::info!(
             "Deduplication complete in {:.1}s: {} total duplicate flights removed",
             start.elapsed().as_secs_f64(),
@@ -2193,6 +4062,7 @@ impl Database {
         let tags_path = temp_dir.join("flight_tags.parquet");
         let messages_path = temp_dir.join("flight_messages.parquet");
         let equipment_names_path = temp_dir.join("equipment_names.parquet");
+        let blacklist_path = temp_dir.join("import_blacklist.parquet");
 
         conn.execute_batch(&format!(
             "COPY flights    TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
@@ -2221,6 +4091,19 @@ impl Database {
             "COPY equipment_names TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
             equipment_names_path.to_string_lossy()
         ));
+        // Export import_blacklist table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY import_blacklist TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            blacklist_path.to_string_lossy()
+        ));
+        // Export DJI side tables and video clips (ignore errors if empty or missing)
+        for table in SIDE_TABLES.iter().chain(MEDIA_TABLES) {
+            let _ = conn.execute_batch(&format!(
+                "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+                table,
+                temp_dir.join(format!("{}.parquet", table)).to_string_lossy()
+            ));
+        }
 
         drop(conn); // release the lock while we tar
 
@@ -2229,8 +4112,13 @@ impl Database {
         let gz = flate2::write::GzEncoder::new(dest_file, flate2::Compression::fast());
         let mut tar = tar::Builder::new(gz);
 
-        for name in &["flights.parquet", "telemetry.parquet", "keychains.parquet", "flight_tags.parquet", "flight_messages.parquet", "equipment_names.parquet"] {
-            let file_path = temp_dir.join(name);
+        let side_table_files = SIDE_TABLES.iter().chain(MEDIA_TABLES).map(|table| format!("{}.parquet", table));
+        let names = ["flights.parquet", "telemetry.parquet", "keychains.parquet", "flight_tags.parquet", "flight_messages.parquet", "equipment_names.parquet", "import_blacklist.parquet"]
+            .into_iter()
+            .map(String::from)
+            .chain(side_table_files);
+        for name in names {
+            let file_path = temp_dir.join(&name);
             if file_path.exists() {
                 tar.append_path_with_name(&file_path, name)
                     .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
@@ -2293,7 +4181,7 @@ impl Database {
             DELETE FROM flights
             WHERE id IN (SELECT id FROM read_parquet('{}'))
                OR file_hash IN (SELECT file_hash FROM read_parquet('{}') WHERE file_hash IS NOT NULL);
-            INSERT INTO flights
+            INSERT INTO flights BY NAME
             SELECT * FROM read_parquet('{}');
             "#,
             flights_path.to_string_lossy(),
@@ -2325,15 +4213,15 @@ impl Database {
             ))?;
         }
 
-        // --- Restore keychains ---
+        // --- Restore keychains (backups from before the request-keyed cache are skipped) ---
         if keychains_path.exists() {
-            conn.execute_batch(&format!(
+            let _ = conn.execute_batch(&format!(
                 r#"
-                INSERT OR REPLACE INTO keychains
+                INSERT OR REPLACE INTO keychains BY NAME
                 SELECT * FROM read_parquet('{}');
                 "#,
                 keychains_path.to_string_lossy()
-            ))?;
+            ));
         }
 
         // --- Restore flight tags (backward compatible — may not exist in old backups) ---
@@ -2375,13 +4263,68 @@ impl Database {
         if equipment_names_path.exists() {
             let _ = conn.execute_batch(&format!(
                 r#"
-                INSERT OR REPLACE INTO equipment_names
+                INSERT OR REPLACE INTO equipment_names BY NAME
                 SELECT * FROM read_parquet('{}');
                 "#,
                 equipment_names_path.to_string_lossy()
             ));
         }
 
+        // --- Restore import blacklist (backward compatible — may not exist in old backups) ---
+        let blacklist_path = temp_dir.join("import_blacklist.parquet");
+        if blacklist_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO import_blacklist
+                SELECT * FROM read_parquet('{}');
+                "#,
+                blacklist_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore DJI side tables (backward compatible — may not exist in old backups) ---
+        for table in SIDE_TABLES {
+            let path = temp_dir.join(format!("{}.parquet", table));
+            if path.exists() {
+                let _ = conn.execute_batch(&format!(
+                    r#"
+                    DELETE FROM {table}
+                    WHERE flight_id IN (
+                        SELECT DISTINCT flight_id FROM read_parquet('{path}')
+                    );
+                    INSERT INTO {table}
+                    SELECT * FROM read_parquet('{path}');
+                    "#,
+                    table = table,
+                    path = path.to_string_lossy()
+                ));
+            }
+        }
+
+        // --- Restore video clips (backward compatible — may not exist in old backups) ---
+        let clips_path = temp_dir.join("media_clips.parquet");
+        let frames_path = temp_dir.join("media_frames.parquet");
+        if clips_path.exists() && frames_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                DELETE FROM media_frames WHERE clip_id IN (SELECT id FROM read_parquet('{clips}'));
+                DELETE FROM media_clips WHERE id IN (SELECT id FROM read_parquet('{clips}'));
+                INSERT INTO media_clips BY NAME SELECT * FROM read_parquet('{clips}');
+                INSERT INTO media_frames BY NAME SELECT * FROM read_parquet('{frames}');
+                "#,
+                clips = clips_path.to_string_lossy(),
+                frames = frames_path.to_string_lossy()
+            ));
+        }
+
+        // Backups taken before local time zones were stored have no zone columns
+        if let Err(e) = Self::backfill_local_times(&conn) {
+            log::warn!("Failed to resolve local time zones for restored flights: {}", e);
+        }
+        if let Err(e) = Self::backfill_night_secs(&conn) {
+            log::warn!("Failed to compute night flying time for restored flights: {}", e);
+        }
+
         drop(conn);
 
         // Clean up temp dir
@@ -2482,6 +4425,44 @@ pub fn default_upload_folder(data_dir: &std::path::Path, profile: &str) -> std::
     }
 }
 
+/// Return the folder holding logs queued for decryption for a given profile.
+/// "default" → `pending_decryption`, anything else → `pending_decryption/{profile}`.
+pub fn pending_decryption_folder(data_dir: &std::path::Path, profile: &str) -> std::path::PathBuf {
+    if profile == "default" {
+        data_dir.join("pending_decryption")
+    } else {
+        data_dir.join("pending_decryption").join(profile)
+    }
+}
+
+/// Locate the retained original of an imported log in a kept-files folder.
+/// Files are stored under their original name, or as `{stem}_{hash8}.{ext}`
+/// when a different file with the same name was already kept. Falls back to
+/// hashing every file in the folder.
+pub fn find_uploaded_file(folder: &std::path::Path, file_name: &str, file_hash: &str) -> Option<std::path::PathBuf> {
+    let hash_matches = |path: &std::path::Path| {
+        crate::parser::LogParser::calculate_file_hash(path).is_ok_and(|h| h == file_hash)
+    };
+
+    let original = folder.join(file_name);
+    let stem = original.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
+    let hash_suffix = &file_hash[..8.min(file_hash.len())];
+    let renamed = match original.extension().and_then(|e| e.to_str()) {
+        Some(ext) => folder.join(format!("{}_{}.{}", stem, hash_suffix, ext)),
+        None => folder.join(format!("{}_{}", stem, hash_suffix)),
+    };
+    for candidate in [original, renamed] {
+        if candidate.is_file() && hash_matches(&candidate) {
+            return Some(candidate);
+        }
+    }
+
+    fs::read_dir(folder)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .find(|path| path.is_file() && hash_matches(path))
+}
+
 /// Return the sync folder path for a given profile.
 /// When `SYNC_LOGS_PATH` provides a base path:
 ///   "default" → `{base}`, anything else → `{base}/{profile}`.
@@ -2553,6 +4534,12 @@ pub fn delete_profile(data_dir: &std::path::Path, profile: &str) -> Result<(), S
         let _ = fs::remove_dir_all(&upload_dir);
     }
 
+    // Clean up logs queued for decryption
+    let pending_dir = pending_decryption_folder(data_dir, profile);
+    if pending_dir.exists() {
+        let _ = fs::remove_dir_all(&pending_dir);
+    }
+
     // If this was the active profile, switch back to default
     if get_active_profile(data_dir) == profile {
         let _ = set_active_profile(data_dir, "default");
@@ -2566,6 +4553,7 @@ pub fn delete_profile(data_dir: &std::path::Path, profile: &str) -> Result<(), S
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     use tempfile::tempdir;
 
     #[test]
@@ -2581,4 +4569,448 @@ mod tests {
         let flights = db.get_all_flights().unwrap();
         assert!(flights.is_empty());
     }
+
+    fn sample_parsed_flight(point_count: i64) -> ParsedFlight {
+        let points: Vec<TelemetryPoint> = (0..point_count)
+            .map(|i| TelemetryPoint {
+                timestamp_ms: i * 100,
+                latitude: Some(47.0),
+                longitude: Some(8.0),
+                ..Default::default()
+            })
+            .collect();
+        ParsedFlight {
+            metadata: FlightMetadata {
+                id: 0,
+                file_name: "flight.ulg".to_string(),
+                display_name: "flight".to_string(),
+                file_hash: Some("abc".to_string()),
+                drone_model: None,
+                drone_serial: Some("SN1".to_string()),
+                aircraft_name: None,
+                battery_serial: None,
+                cycle_count: None,
+                start_time: Some(chrono::Utc::now()),
+                end_time: None,
+                duration_secs: Some(0.2),
+                total_distance: None,
+                max_altitude: None,
+                max_speed: None,
+                home_lat: None,
+                home_lon: None,
+                point_count: point_count as i32,
+                photo_count: 0,
+                video_count: 0,
+            },
+            points,
+            tags: vec!["PX4".to_string()],
+            manual_tags: Vec::new(),
+            notes: None,
+            color: None,
+            messages: Vec::new(),
+            side_data: Default::default(),
+            equipment: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_import_parsed_flight_rejects_duplicates() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        let parsed = sample_parsed_flight(3);
+        let options = ImportOptions { smart_tags_enabled: true, ..Default::default() };
+
+        let (flight_id, point_count) = db.import_parsed_flight(&parsed, &options).unwrap();
+        assert_ne!(flight_id, 0);
+        assert_eq!(point_count, 3);
+        assert!(matches!(
+            db.import_parsed_flight(&parsed, &options),
+            Err(ImportError::Duplicate(_))
+        ));
+    }
+
+    #[test]
+    fn test_replace_flight_data_keeps_user_edits() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+        let options = ImportOptions { smart_tags_enabled: true, ..Default::default() };
+
+        let (flight_id, _) = db.import_parsed_flight(&sample_parsed_flight(3), &options).unwrap();
+        db.update_flight_name(flight_id, "Renamed").unwrap();
+        db.update_flight_notes(flight_id, Some("keep me")).unwrap();
+        db.add_flight_tag(flight_id, "Survey").unwrap();
+
+        let mut reparsed = sample_parsed_flight(5);
+        reparsed.tags = vec!["ArduPilot".to_string()];
+        assert_eq!(db.replace_flight_data(flight_id, &reparsed, &options).unwrap(), 5);
+
+        let flight = db.get_flight_by_id(flight_id).unwrap();
+        assert_eq!(flight.display_name, "Renamed");
+        assert_eq!(flight.notes.as_deref(), Some("keep me"));
+        assert_eq!(flight.point_count, Some(5));
+
+        let tags: Vec<String> = db.get_flight_tags(flight_id).unwrap().into_iter().map(|t| t.tag).collect();
+        assert_eq!(tags, vec!["ArduPilot".to_string(), "Survey".to_string()]);
+    }
+
+    #[test]
+    fn test_replace_flight_data_rolls_back_on_error() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+        let options = ImportOptions::default();
+
+        let (flight_id, _) = db.import_parsed_flight(&sample_parsed_flight(3), &options).unwrap();
+        let last_side_table = SIDE_TABLES[SIDE_TABLES.len() - 1];
+        db.conn.lock().unwrap().execute_batch(&format!("DROP TABLE {}", last_side_table)).unwrap();
+
+        assert!(db.replace_flight_data(flight_id, &sample_parsed_flight(5), &options).is_err());
+        assert_eq!(db.get_flight_by_id(flight_id).unwrap().point_count, Some(3));
+        assert_eq!(db.get_flight_telemetry(flight_id, None, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_import_failures_track_attempts_and_ignore() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        db.record_import_failure("h1", "/sync/a.txt", "timeout", "timed out").unwrap();
+        db.record_import_failure("h1", "/sync/a.txt", "panic", "crashed").unwrap();
+        let failures = db.get_import_failures().unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].attempts, 2);
+        assert_eq!(failures[0].error_kind, "panic");
+        assert!(!db.is_import_ignored("h1").unwrap());
+
+        assert!(db.set_import_failure_ignored("h1", true).unwrap());
+        assert!(db.is_import_ignored("h1").unwrap());
+        assert!(!db.set_import_failure_ignored("missing", true).unwrap());
+
+        db.clear_import_failure("h1").unwrap();
+        assert!(db.get_import_failures().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_blacklist_survives_backup_restore() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        db.add_to_blacklist("h1", Some("DJIFlightRecord.txt")).unwrap();
+        db.add_to_blacklist("h1", None).unwrap();
+        assert!(db.is_blacklisted("h1").unwrap());
+        assert!(!db.is_blacklisted("h2").unwrap());
+        let entries = db.get_blacklist().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].file_name.as_deref(), Some("DJIFlightRecord.txt"));
+
+        let backup_path = temp_dir.path().join("test.db.backup");
+        db.export_backup(&backup_path).unwrap();
+        assert_eq!(db.clear_blacklist().unwrap(), 1);
+        assert!(!db.remove_from_blacklist("h1").unwrap());
+
+        db.import_backup(&backup_path).unwrap();
+        assert!(db.is_blacklisted("h1").unwrap());
+    }
+
+    #[test]
+    fn test_side_data_round_trip_and_delete() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        let mut parsed = sample_parsed_flight(3);
+        parsed.side_data.home_updates.push(HomeUpdate {
+            timestamp_ms: 0,
+            latitude: 47.1,
+            longitude: 8.5,
+            altitude: 420.0,
+            go_home_height: 60.0,
+            max_allowed_height: 120.0,
+            go_home_mode: Some("FixedHeight".to_string()),
+            dynamic_home_point: false,
+        });
+        parsed.side_data.rc_gps.push(RcGpsPoint {
+            timestamp_ms: 100,
+            latitude: 47.1001,
+            longitude: 8.5001,
+            satellites: 12,
+            accuracy: None,
+        });
+        parsed.side_data.motor_status.push(MotorStatus {
+            timestamp_ms: 200,
+            motors_on: true,
+            motor_blocked: false,
+            not_enough_force: false,
+            propeller_catapult: false,
+            start_failed_cause: None,
+        });
+
+        let (flight_id, _) = db.import_parsed_flight(&parsed, &ImportOptions::default()).unwrap();
+        let side = db.get_flight_side_data(flight_id).unwrap();
+        assert_eq!(side.home_updates.len(), 1);
+        assert_eq!(side.home_updates[0].go_home_height, 60.0);
+        assert_eq!(side.home_updates[0].go_home_mode.as_deref(), Some("FixedHeight"));
+        assert_eq!(side.rc_gps[0].satellites, 12);
+        assert!(side.motor_status[0].motors_on);
+        assert!(side.avoidance.is_empty() && side.wind_warnings.is_empty());
+
+        db.delete_flight(flight_id).unwrap();
+        assert!(db.get_flight_side_data(flight_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_equipment_inventory_tracks_firmware_and_keeps_names() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+        db.set_equipment_name("rc1", "rc", "Field RC").unwrap();
+
+        for (i, firmware) in ["01.00", "01.01"].iter().enumerate() {
+            let mut parsed = sample_parsed_flight(3);
+            parsed.metadata.file_hash = Some(format!("hash{}", i));
+            parsed.metadata.start_time = Some(chrono::Utc::now() + chrono::Duration::days(i as i64));
+            parsed.equipment = FlightEquipment {
+                app_name: Some("DJI Fly".to_string()),
+                aircraft_firmware: Some(firmware.to_string()),
+                rc_serial: Some("RC1".to_string()),
+                camera_serial: Some("CAM1".to_string()),
+                ..Default::default()
+            };
+            let (flight_id, _) = db.import_parsed_flight(&parsed, &ImportOptions::default()).unwrap();
+            assert_eq!(db.get_flight_equipment(flight_id).unwrap().unwrap().rc_serial.as_deref(), Some("RC1"));
+        }
+
+        let inventory = db.get_equipment_inventory().unwrap();
+        let aircraft = inventory.iter().find(|i| i.equipment_type == "aircraft").unwrap();
+        assert_eq!(aircraft.serial, "SN1");
+        assert_eq!(aircraft.flight_count, 2);
+        assert_eq!(aircraft.firmware.as_deref(), Some("01.01"));
+        assert_eq!(aircraft.firmware_history.len(), 2);
+        assert_eq!(aircraft.firmware_history[0].version, "01.00");
+
+        let rc = inventory.iter().find(|i| i.equipment_type == "rc").unwrap();
+        assert_eq!(rc.display_name.as_deref(), Some("Field RC"));
+        assert_eq!(rc.flight_count, 2);
+        assert!(inventory.iter().any(|i| i.equipment_type == "camera" && i.serial == "CAM1"));
+
+        // Clearing a name keeps the inventory row
+        db.set_equipment_name("RC1", "rc", "").unwrap();
+        assert!(db.get_equipment_names("rc").unwrap().is_empty());
+        assert!(db.get_equipment_inventory().unwrap().iter().any(|i| i.serial == "RC1"));
+    }
+
+    #[test]
+    fn test_local_start_time_uses_home_time_zone() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        // 22:30 UTC is already the next day in Beijing (UTC+8, no DST)
+        let mut parsed = sample_parsed_flight(3);
+        parsed.metadata.start_time = Some(chrono::Utc.with_ymd_and_hms(2024, 7, 1, 22, 30, 0).unwrap());
+        parsed.metadata.home_lat = Some(39.9);
+        parsed.metadata.home_lon = Some(116.4);
+        let (flight_id, _) = db.import_parsed_flight(&parsed, &ImportOptions::default()).unwrap();
+
+        let flight = db.get_flight_by_id(flight_id).unwrap();
+        assert_eq!(flight.local_timezone.as_deref(), Some("Asia/Shanghai"));
+        assert_eq!(flight.local_start_time.as_deref(), Some("2024-07-02 06:30:00"));
+
+        // Flights stored before the columns existed are resolved by the backfill
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute("UPDATE flights SET local_timezone = NULL, local_start_time = NULL", []).unwrap();
+            assert_eq!(Database::backfill_local_times(&conn).unwrap(), 1);
+        }
+        let flight = db.get_flight_by_id(flight_id).unwrap();
+        assert_eq!(flight.local_timezone.as_deref(), Some("Asia/Shanghai"));
+        assert_eq!(flight.local_start_time.as_deref(), Some("2024-07-02 06:30:00"));
+    }
+
+    #[test]
+    fn test_night_secs_follow_civil_twilight() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        // Berlin, 10 minute flights: midnight in winter is night, midday is not
+        let mut ids = Vec::new();
+        for (i, hour) in [23, 12].iter().enumerate() {
+            let mut parsed = sample_parsed_flight(3);
+            parsed.metadata.file_hash = Some(format!("hash{}", i));
+            parsed.metadata.start_time = Some(chrono::Utc.with_ymd_and_hms(2024, 1, 15, *hour, 0, 0).unwrap());
+            parsed.metadata.duration_secs = Some(600.0);
+            parsed.metadata.home_lat = Some(52.52);
+            parsed.metadata.home_lon = Some(13.40);
+            ids.push(db.import_parsed_flight(&parsed, &ImportOptions::default()).unwrap().0);
+        }
+
+        assert_eq!(db.get_flight_by_id(ids[0]).unwrap().night_secs, Some(600.0));
+        assert_eq!(db.get_flight_by_id(ids[1]).unwrap().night_secs, Some(0.0));
+    }
+
+    /// Upstream that always returns the same keychain and counts its calls
+    struct FixedKeychains(std::sync::atomic::AtomicUsize);
+
+    impl KeychainProvider for FixedKeychains {
+        fn fetch_keychains(&self, _request: &KeychainsRequest) -> Result<Vec<Vec<KeychainFeaturePoint>>, ApiError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(serde_json::from_value(serde_json::json!([[{
+                "featurePoint": "FR_Standardization_Feature_Base_1",
+                "aesKey": "a2V5",
+                "aesIv": "aXY="
+            }]])).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_keychain_bundle_round_trip_and_pending_queue() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        // The cache only asks upstream once per request, and only stores
+        // what it fetched when told to
+        let upstream = Arc::new(FixedKeychains(Default::default()));
+        let cache = db.keychain_cache(Some(upstream.clone())).unwrap();
+        let request = KeychainsRequest { version: 13, department: 3, keychains: Vec::new() };
+        cache.fetch_keychains(&request).unwrap();
+        cache.fetch_keychains(&request).unwrap();
+        assert_eq!(upstream.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(db.export_keychain_bundle().unwrap().entries.is_empty());
+        db.cache_keychains(&cache.take_fetched()).unwrap();
+        assert!(cache.take_fetched().is_empty());
+        db.keychain_cache(Some(upstream.clone())).unwrap().fetch_keychains(&request).unwrap();
+        assert_eq!(upstream.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let bundle = db.export_keychain_bundle().unwrap();
+        assert_eq!(bundle.format, KEYCHAIN_BUNDLE_FORMAT);
+        assert_eq!(bundle.entries.len(), 1);
+
+        // Another instance can decrypt offline with the imported bundle
+        let other_dir = tempdir().unwrap();
+        let other = Database::new(other_dir.path().to_path_buf(), "default").unwrap();
+        assert_eq!(other.import_keychain_bundle(&bundle).unwrap(), 1);
+        let offline = other.keychain_cache(None).unwrap();
+        assert_eq!(offline.fetch_keychains(&request).unwrap()[0][0].aes_key, "a2V5");
+        let unknown = KeychainsRequest { version: 14, ..request };
+        assert!(matches!(offline.fetch_keychains(&unknown), Err(ApiError::ApiKeyNotConfigured)));
+
+        // Queued logs keep a copy until removed; re-queuing bumps the attempts
+        let log_path = temp_dir.path().join("DJIFlightRecord_1.txt");
+        fs::write(&log_path, b"encrypted").unwrap();
+        db.queue_pending_decryption(&log_path, "abc123", "no key").unwrap();
+        db.queue_pending_decryption(&log_path, "abc123", "still no key").unwrap();
+        let pending = db.get_pending_decryption().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 2);
+        assert_eq!(pending[0].file_name, "DJIFlightRecord_1.txt");
+        let copy = db.pending_decryption_file(&pending[0]);
+        assert_eq!(fs::read(&copy).unwrap(), b"encrypted");
+
+        assert!(db.remove_pending_decryption("abc123").unwrap());
+        assert!(!copy.exists());
+        assert!(db.get_pending_decryption().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sync_runs_keep_files_and_filter_by_source() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        let run = |trigger, source_id: Option<&str>, imported| SyncRun {
+            id: 0,
+            trigger,
+            source_id: source_id.map(str::to_string),
+            sync_path: "/sync".to_string(),
+            started_at: "2026-01-01T10:00:00+00:00".to_string(),
+            finished_at: "2026-01-01T10:00:05+00:00".to_string(),
+            imported,
+            skipped: 0,
+            unchanged: 3,
+            queued: 0,
+            failed: 0,
+            cancelled: 0,
+            archived: 0,
+            rejected: 0,
+            error: None,
+        };
+        let file = SyncRunFile {
+            path: "/sync/a.txt".to_string(),
+            outcome: "imported".to_string(),
+            message: "Successfully imported 10 telemetry points".to_string(),
+            flight_id: Some(7),
+        };
+        let first = db.record_sync_run(&run(SyncTrigger::Cron, None, 1), &[file]).unwrap();
+        let second = db.record_sync_run(&run(SyncTrigger::Manual, Some("src1"), 0), &[]).unwrap();
+        assert!(second > first);
+
+        let runs = db.get_sync_runs(10, None).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, second);
+        assert_eq!(runs[0].trigger, SyncTrigger::Manual);
+        assert_eq!(db.get_sync_runs(10, Some(None)).unwrap()[0].id, first);
+        assert_eq!(db.get_sync_runs(10, Some(Some("src1"))).unwrap().len(), 1);
+
+        let detail = db.get_sync_run(first).unwrap().unwrap();
+        assert_eq!(detail.run.imported, 1);
+        assert_eq!(detail.run.unchanged, 3);
+        assert!(detail.run.started_at.starts_with("2026-01-01"));
+        assert_eq!(detail.files[0].flight_id, Some(7));
+        assert!(db.get_sync_run(second + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_media_clips_link_to_overlapping_flight_by_local_time() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf(), "default").unwrap();
+
+        // 06:30 to 06:40 local time in Beijing
+        let mut parsed = sample_parsed_flight(3);
+        parsed.metadata.start_time = Some(chrono::Utc.with_ymd_and_hms(2024, 7, 1, 22, 30, 0).unwrap());
+        parsed.metadata.duration_secs = Some(600.0);
+        parsed.metadata.home_lat = Some(39.9);
+        parsed.metadata.home_lon = Some(116.4);
+        let (flight_id, _) = db.import_parsed_flight(&parsed, &ImportOptions::default()).unwrap();
+
+        let clip = |hash: &str| {
+            let start = chrono::NaiveDate::from_ymd_opt(2024, 7, 2).unwrap().and_hms_opt(6, 32, 0).unwrap();
+            ParsedMediaClip {
+                srt_file: "DJI_0001.SRT".to_string(),
+                video_file: "DJI_0001.MP4".to_string(),
+                file_hash: hash.to_string(),
+                start_time: start,
+                end_time: start + chrono::Duration::seconds(60),
+                frames: (0..3)
+                    .map(|i| MediaFrame {
+                        offset_ms: i * 33,
+                        end_ms: (i + 1) * 33,
+                        latitude: Some(39.9),
+                        longitude: Some(116.4),
+                        iso: Some(100),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }
+        };
+        let result = db.import_media_clip(&clip("srt1"), None).unwrap();
+        assert!(result.success);
+        assert_eq!(result.flight_id, Some(flight_id));
+        assert!(!db.import_media_clip(&clip("srt1"), None).unwrap().success);
+        // Another drone's clip from the same time stays unlinked
+        assert_eq!(db.import_media_clip(&clip("srt2"), Some("OTHER")).unwrap().flight_id, None);
+
+        let clips = db.get_flight_media_clips(flight_id).unwrap();
+        assert_eq!(clips.len(), 1);
+        assert_eq!(clips[0].drone_serial.as_deref(), Some("SN1"));
+        assert_eq!(clips[0].flight_offset_secs, Some(120.0));
+        assert_eq!(clips[0].duration_secs, 60.0);
+        assert_eq!(clips[0].iso, Some(100));
+        assert_eq!(clips[0].track.len(), 3);
+        assert_eq!(db.get_media_frames(clips[0].id).unwrap().len(), 3);
+
+        // Re-importing a deleted flight links its clips again
+        db.delete_flight(flight_id).unwrap();
+        assert!(db.get_media_clips().unwrap().iter().all(|c| c.flight_id.is_none()));
+        let (flight_id, _) = db.import_parsed_flight(&parsed, &ImportOptions::default()).unwrap();
+        assert_eq!(db.get_flight_media_clips(flight_id).unwrap().len(), 1);
+
+        assert!(db.delete_media_clip(clips[0].id).unwrap());
+        assert!(db.get_flight_media_clips(flight_id).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,871 @@
+//! Parser module for ArduPilot DataFlash (.bin) onboard logs.
+//!
+//! DataFlash logs are a stream of `0xA3 0x95 <type>` framed messages whose
+//! layout is declared in-band by FMT messages. Points are emitted at GPS rate
+//! with the latest ATT, BAT/BCL, RCIN and MODE values held alongside. CAM
+//! events mark photos, and MSG/ERR/EV messages become flight messages.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMessage, FlightMetadata, FlightStats, ParsedFlight, TelemetryPoint};
+use crate::parser::{LogParser, ParseOptions, ParserError};
+
+/// Message sync bytes preceding every DataFlash message
+const HEAD1: u8 = 0xA3;
+const HEAD2: u8 = 0x95;
+
+/// Message type id of the FMT (format definition) message
+const FMT_TYPE: u8 = 128;
+
+/// Total length of an FMT message including its 3-byte header
+const FMT_LENGTH: usize = 89;
+
+/// Seconds between the Unix epoch and the GPS epoch (1980-01-06)
+const GPS_EPOCH_UNIX_SECS: i64 = 315_964_800;
+
+/// GPS-UTC leap second offset (stable since 2017)
+const GPS_LEAP_SECONDS: i64 = 18;
+
+/// A decoded column: byte offset into the payload and its format character
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    offset: usize,
+    kind: u8,
+}
+
+/// Message layout declared by an FMT message
+#[derive(Debug)]
+struct Format {
+    name: String,
+    /// Total message length including the 3-byte header
+    length: usize,
+    columns: HashMap<String, Column>,
+}
+
+impl Format {
+    /// Build a format from the FMT fields, or None if it uses unknown type
+    /// characters or its columns do not fit in the declared length
+    fn new(name: String, length: usize, format: &str, labels: &str) -> Option<Self> {
+        let payload_len = length.checked_sub(3)?;
+        let mut columns = HashMap::new();
+        let mut offset = 0;
+        for (kind, label) in format.bytes().zip(labels.split(',')) {
+            columns.insert(label.to_string(), Column { offset, kind });
+            offset += format_char_size(kind)?;
+        }
+        if offset > payload_len {
+            return None;
+        }
+        Some(Self { name, length, columns })
+    }
+
+    /// Read a numeric column, applying the scaling implied by its format character
+    fn get(&self, payload: &[u8], column: &str) -> Option<f64> {
+        let col = self.columns.get(column)?;
+        let bytes = payload.get(col.offset..col.offset + format_char_size(col.kind)?)?;
+        let value = match col.kind {
+            b'b' => bytes[0] as i8 as f64,
+            b'B' | b'M' => bytes[0] as f64,
+            b'h' => i16::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'H' => u16::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'i' => i32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'I' => u32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'q' => i64::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'Q' => u64::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'f' => f32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'd' => f64::from_le_bytes(bytes.try_into().ok()?),
+            b'c' => i16::from_le_bytes(bytes.try_into().ok()?) as f64 / 100.0,
+            b'C' => u16::from_le_bytes(bytes.try_into().ok()?) as f64 / 100.0,
+            b'e' => i32::from_le_bytes(bytes.try_into().ok()?) as f64 / 100.0,
+            b'E' => u32::from_le_bytes(bytes.try_into().ok()?) as f64 / 100.0,
+            b'L' => i32::from_le_bytes(bytes.try_into().ok()?) as f64 * 1e-7,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Read a finite numeric column
+    fn get_finite(&self, payload: &[u8], column: &str) -> Option<f64> {
+        self.get(payload, column).filter(|v| v.is_finite())
+    }
+
+    /// Read the TimeUS column (microseconds since boot)
+    fn time_us(&self, payload: &[u8]) -> Option<u64> {
+        self.get(payload, "TimeUS").map(|t| t as u64)
+    }
+
+    /// Read a fixed-length string column (n, N, Z)
+    fn get_str(&self, payload: &[u8], column: &str) -> Option<String> {
+        let col = self.columns.get(column)?;
+        if !matches!(col.kind, b'n' | b'N' | b'Z') {
+            return None;
+        }
+        let bytes = payload.get(col.offset..col.offset + format_char_size(col.kind)?)?;
+        Some(decode_cstr(bytes))
+    }
+
+    /// True if the message belongs to the first instance (or has no instance column)
+    fn is_primary_instance(&self, payload: &[u8]) -> bool {
+        ["I", "Inst", "Instance"]
+            .iter()
+            .find_map(|c| self.get(payload, c))
+            .map_or(true, |i| i == 0.0)
+    }
+}
+
+/// Byte size of a DataFlash format character
+fn format_char_size(kind: u8) -> Option<usize> {
+    match kind {
+        b'b' | b'B' | b'M' => Some(1),
+        b'h' | b'H' | b'c' | b'C' | b'g' => Some(2),
+        b'i' | b'I' | b'f' | b'e' | b'E' | b'L' | b'n' => Some(4),
+        b'd' | b'q' | b'Q' => Some(8),
+        b'N' => Some(16),
+        b'Z' | b'a' => Some(64),
+        _ => None,
+    }
+}
+
+/// Decode a NUL-padded fixed-length string
+fn decode_cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// ArduPilot vehicle family, used to name flight modes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Vehicle {
+    Copter,
+    Plane,
+    Rover,
+    Unknown,
+}
+
+/// Most recent values of the non-GPS messages, held until the next GPS sample
+#[derive(Debug, Default)]
+struct HeldState {
+    roll: Option<f64>,
+    pitch: Option<f64>,
+    yaw: Option<f64>,
+    battery_percent: Option<i32>,
+    battery_voltage: Option<f64>,
+    battery_current: Option<f64>,
+    battery_temp: Option<f64>,
+    cell_voltages: Option<Vec<f64>>,
+    rc_signal: Option<i32>,
+    rc_channels: Vec<f64>,
+    flight_mode: Option<String>,
+    /// Set by a CAM event and consumed by the next emitted point
+    photo_pending: bool,
+}
+
+/// ArduPilot DataFlash log format
+pub struct DataFlashFormat;
+
+impl FlightLogFormat for DataFlashFormat {
+    fn id(&self) -> &'static str {
+        "ardupilot_dataflash"
+    }
+
+    fn name(&self) -> &'static str {
+        "ArduPilot DataFlash"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["bin"]
+    }
+
+    /// `.bin` extension and the file starts with an FMT message
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8 {
+        if formats::has_extension(path, "bin") && header.starts_with(&[HEAD1, HEAD2, FMT_TYPE]) {
+            100
+        } else {
+            0
+        }
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, _options: &'a ParseOptions) -> ParseFuture<'a> {
+        formats::blocking(path, file_hash, |path, file_hash| DataFlashParser::new().parse(path, file_hash))
+    }
+}
+
+/// ArduPilot DataFlash Parser
+#[derive(Default)]
+pub struct DataFlashParser;
+
+impl DataFlashParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse an ArduPilot DataFlash log
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing ArduPilot DataFlash file: {:?}", file_path);
+
+        let data = fs::read(file_path)?;
+
+        let mut formats: HashMap<u8, Format> = HashMap::new();
+        let mut params: HashMap<String, f64> = HashMap::new();
+        let mut vehicle = Vehicle::Unknown;
+        let mut firmware: Option<String> = None;
+        let mut board_serial: Option<String> = None;
+
+        let mut state = HeldState::default();
+        let mut samples: Vec<(u64, TelemetryPoint)> = Vec::new();
+        // (TimeUS, severity, text)
+        let mut raw_messages: Vec<(u64, &'static str, String)> = Vec::new();
+        // GPS week/ms paired with the TimeUS it was sampled at
+        let mut utc_reference: Option<(DateTime<Utc>, u64)> = None;
+        let mut resync_bytes: usize = 0;
+
+        let mut offset = 0;
+        while offset + 3 <= data.len() {
+            if data[offset] != HEAD1 || data[offset + 1] != HEAD2 {
+                offset += 1;
+                resync_bytes += 1;
+                continue;
+            }
+            let msg_type = data[offset + 2];
+
+            if msg_type == FMT_TYPE {
+                let Some(msg) = data.get(offset..offset + FMT_LENGTH) else {
+                    break;
+                };
+                let defined_type = msg[3];
+                let length = msg[4] as usize;
+                let name = decode_cstr(&msg[5..9]);
+                let format = decode_cstr(&msg[9..25]);
+                let labels = decode_cstr(&msg[25..89]);
+                match Format::new(name.clone(), length, &format, &labels) {
+                    Some(fmt) => {
+                        formats.insert(defined_type, fmt);
+                    }
+                    None => log::debug!("DataFlash: skipping format '{}' with unsupported types '{}' or length {}", name, format, length),
+                }
+                offset += FMT_LENGTH;
+                continue;
+            }
+
+            let Some(fmt) = formats.get(&msg_type) else {
+                // Unknown type: treat as corruption and resync on the next header
+                offset += 1;
+                resync_bytes += 1;
+                continue;
+            };
+            let Some(msg) = data.get(offset..offset + fmt.length) else {
+                // Truncated trailing message
+                break;
+            };
+            let payload = &msg[3..];
+            offset += fmt.length;
+
+            match fmt.name.as_str() {
+                "PARM" => {
+                    if let (Some(name), Some(value)) = (fmt.get_str(payload, "Name"), fmt.get(payload, "Value")) {
+                        params.insert(name, value);
+                    }
+                }
+                "GPS" => {
+                    if !fmt.is_primary_instance(payload) {
+                        continue;
+                    }
+                    let time_us = fmt.time_us(payload).unwrap_or(0);
+                    if utc_reference.is_none() {
+                        utc_reference = gps_utc(fmt, payload).map(|utc| (utc, time_us));
+                    }
+                    let point = self.gps_point(fmt, payload, &mut state, &params);
+                    samples.push((time_us, point));
+                }
+                "ATT" => {
+                    state.roll = fmt.get_finite(payload, "Roll");
+                    state.pitch = fmt.get_finite(payload, "Pitch");
+                    state.yaw = fmt.get_finite(payload, "Yaw").map(|y| if y > 180.0 { y - 360.0 } else { y });
+                }
+                "BAT" => {
+                    if !fmt.is_primary_instance(payload) {
+                        continue;
+                    }
+                    state.battery_voltage = fmt.get_finite(payload, "Volt").filter(|v| *v > 0.0);
+                    state.battery_current = fmt.get_finite(payload, "Curr");
+                    state.battery_temp = fmt.get_finite(payload, "Temp").filter(|t| *t != 0.0);
+                    state.battery_percent = fmt
+                        .get(payload, "RemPct")
+                        .filter(|p| (0.0..=100.0).contains(p))
+                        .map(|p| p as i32);
+                }
+                "BCL" => {
+                    if !fmt.is_primary_instance(payload) {
+                        continue;
+                    }
+                    // Cell voltages in mV; 0 and 65535 mark unused cells
+                    let cells: Vec<f64> = (1..=14)
+                        .filter_map(|i| fmt.get(payload, &format!("V{}", i)))
+                        .filter(|mv| *mv > 0.0 && *mv < 65535.0)
+                        .map(|mv| mv / 1000.0)
+                        .collect();
+                    state.cell_voltages = if cells.is_empty() { None } else { Some(cells) };
+                }
+                "RCIN" => {
+                    state.rc_channels = (1..=16)
+                        .map_while(|i| fmt.get(payload, &format!("C{}", i)))
+                        .collect();
+                }
+                "RSSI" => {
+                    state.rc_signal = fmt
+                        .get_finite(payload, "RXRSSI")
+                        .filter(|r| (0.0..=1.0).contains(r))
+                        .map(|r| (r * 100.0).round() as i32);
+                }
+                "MODE" => {
+                    let mode_num = fmt.get(payload, "ModeNum").or_else(|| fmt.get(payload, "Mode"));
+                    if let Some(num) = mode_num {
+                        state.flight_mode = Some(ardupilot_mode_name(vehicle, num as u8));
+                    }
+                }
+                "CAM" if fmt.is_primary_instance(payload) => {
+                    state.photo_pending = true;
+                }
+                "MSG" => {
+                    let Some(text) = fmt.get_str(payload, "Message").filter(|t| !t.is_empty()) else {
+                        continue;
+                    };
+                    // The first MSG lines identify the firmware and the board
+                    if firmware.is_none() && text.starts_with("Ardu") {
+                        vehicle = vehicle_from_banner(&text);
+                        firmware = Some(text.split(" (").next().unwrap_or(&text).to_string());
+                    }
+                    if board_serial.is_none() {
+                        board_serial = board_uid(&text);
+                    }
+                    let time_us = fmt.time_us(payload).unwrap_or(0);
+                    raw_messages.push((time_us, msg_severity(&text), text));
+                }
+                "ERR" => {
+                    if let (Some(subsys), Some(code)) = (fmt.get(payload, "Subsys"), fmt.get(payload, "ECode")) {
+                        let time_us = fmt.time_us(payload).unwrap_or(0);
+                        let (severity, text) = err_message(subsys as u8, code as u8);
+                        raw_messages.push((time_us, severity, text));
+                    }
+                }
+                "EV" => {
+                    if let Some(id) = fmt.get(payload, "Id") {
+                        if let Some((severity, text)) = event_message(id as u8) {
+                            let time_us = fmt.time_us(payload).unwrap_or(0);
+                            raw_messages.push((time_us, severity, text.to_string()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if resync_bytes > 0 {
+            log::warn!("DataFlash: skipped {} corrupt bytes while resyncing", resync_bytes);
+        }
+        log::debug!(
+            "DataFlash decoded: {} formats, {} params, {} GPS samples, {} messages",
+            formats.len(),
+            params.len(),
+            samples.len(),
+            raw_messages.len()
+        );
+
+        // Flight time starts at the first sample with a valid position
+        let first_fix_us = samples
+            .iter()
+            .find(|(_, p)| p.latitude.is_some() && p.longitude.is_some())
+            .map(|(ts, _)| *ts)
+            .ok_or(ParserError::NoTelemetryData)?;
+
+        // Convert to relative timestamps; drop pre-fix samples and keep timestamps unique
+        let mut points: Vec<TelemetryPoint> = Vec::with_capacity(samples.len());
+        for (ts, mut point) in samples {
+            if ts < first_fix_us {
+                continue;
+            }
+            point.timestamp_ms = ((ts - first_fix_us) / 1000) as i64;
+            if points.last().is_some_and(|p| p.timestamp_ms >= point.timestamp_ms) {
+                continue;
+            }
+            points.push(point);
+        }
+
+        // Heights are relative to the first fix (takeoff point)
+        if let Some(home_alt) = points.iter().find_map(|p| p.altitude_abs) {
+            for point in &mut points {
+                if let Some(abs) = point.altitude_abs {
+                    point.altitude = Some(abs - home_alt);
+                    point.height = Some(abs - home_alt);
+                }
+            }
+        }
+
+        let mut messages: Vec<FlightMessage> = raw_messages
+            .into_iter()
+            .map(|(ts, severity, text)| FlightMessage {
+                timestamp_ms: (ts.saturating_sub(first_fix_us) / 1000) as i64,
+                message_type: severity.to_string(),
+                message: text,
+            })
+            .collect();
+        messages.sort_by_key(|m| m.timestamp_ms);
+        messages.dedup_by(|a, b| {
+            a.timestamp_ms == b.timestamp_ms && a.message_type == b.message_type && a.message == b.message
+        });
+
+        let start_time = utc_reference.map(|(utc, time_us)| {
+            utc + Duration::microseconds(first_fix_us as i64 - time_us as i64)
+        });
+
+        let stats = LogParser::calculate_stats(&points);
+        let drone_serial = board_serial.or_else(|| {
+            params
+                .get("BRD_SERIAL_NUM")
+                .filter(|s| **s > 0.0)
+                .map(|s| (*s as i64).to_string())
+        });
+        let metadata = self.build_metadata(file_path, file_hash, firmware, drone_serial, &points, &stats, start_time);
+
+        log::info!(
+            "DataFlash parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}, messages={}",
+            parse_start.elapsed().as_secs_f64(),
+            metadata.duration_secs.unwrap_or(0.0),
+            metadata.total_distance.unwrap_or(0.0),
+            metadata.max_altitude.unwrap_or(0.0),
+            points.len(),
+            messages.len()
+        );
+
+        // Generate smart tags and add "ArduPilot" source tag
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "ArduPilot".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParsedFlight { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages, side_data: Default::default(), equipment: Default::default() })
+    }
+
+    /// Build a telemetry point from a GPS message plus the currently held state
+    fn gps_point(
+        &self,
+        fmt: &Format,
+        payload: &[u8],
+        state: &mut HeldState,
+        params: &HashMap<String, f64>,
+    ) -> TelemetryPoint {
+        // Status: 0 = no GPS, 1 = no fix, 2 = 2D, 3 = 3D, 4+ = DGPS/RTK
+        let status = fmt.get(payload, "Status").unwrap_or(0.0) as i32;
+        let lat = fmt.get(payload, "Lat");
+        let lon = fmt.get(payload, "Lng");
+        let has_fix = status >= 2
+            && matches!((lat, lon), (Some(la), Some(lo))
+                if la.abs() <= 90.0 && lo.abs() <= 180.0
+                    && !(la.abs() < 1e-6 && lo.abs() < 1e-6));
+
+        // Channel mapping comes from RCMAP_* parameters (1-based), defaulting to AETR
+        let stick = |param: &str, default: f64| -> Option<f64> {
+            let ch = params.get(param).copied().filter(|c| *c > 0.0).unwrap_or(default) as usize;
+            let pwm = *state.rc_channels.get(ch.checked_sub(1)?)?;
+            if !(800.0..=2200.0).contains(&pwm) {
+                return None;
+            }
+            // PWM 1000..2000 centered at 1500 → normalized to -100..+100
+            Some(((pwm - 1500.0) / 500.0 * 100.0).clamp(-100.0, 100.0))
+        };
+
+        let mut point = TelemetryPoint {
+            satellites: fmt.get(payload, "NSats").map(|v| v as i32),
+            gps_signal: Some(status),
+
+            pitch: state.pitch,
+            roll: state.roll,
+            yaw: state.yaw,
+
+            battery_percent: state.battery_percent,
+            battery_voltage: state.battery_voltage,
+            battery_current: state.battery_current,
+            battery_temp: state.battery_temp,
+            cell_voltages: state.cell_voltages.clone(),
+
+            flight_mode: state.flight_mode.clone(),
+            rc_signal: state.rc_signal,
+            rc_aileron: stick("RCMAP_ROLL", 1.0),
+            rc_elevator: stick("RCMAP_PITCH", 2.0),
+            rc_throttle: stick("RCMAP_THROTTLE", 3.0),
+            rc_rudder: stick("RCMAP_YAW", 4.0),
+
+            is_photo: Some(state.photo_pending),
+            is_video: Some(false),
+            ..Default::default()
+        };
+        state.photo_pending = false;
+
+        if has_fix {
+            point.latitude = lat;
+            point.longitude = lon;
+            point.altitude_abs = fmt.get_finite(payload, "Alt").filter(|a| a.abs() < 10_000.0);
+
+            // Ground speed and course over ground give the horizontal velocity
+            let speed = fmt.get_finite(payload, "Spd").filter(|s| *s < 100.0);
+            let course = fmt.get_finite(payload, "GCrs");
+            point.speed = speed;
+            if let (Some(spd), Some(crs)) = (speed, course) {
+                point.velocity_x = Some(spd * crs.to_radians().cos());
+                point.velocity_y = Some(spd * crs.to_radians().sin());
+            }
+            point.velocity_z = fmt.get_finite(payload, "VZ");
+        }
+
+        point
+    }
+
+    /// Assemble flight metadata from firmware banner and computed stats
+    #[allow(clippy::too_many_arguments)]
+    fn build_metadata(
+        &self,
+        file_path: &Path,
+        file_hash: &str,
+        firmware: Option<String>,
+        drone_serial: Option<String>,
+        points: &[TelemetryPoint],
+        stats: &FlightStats,
+        start_time: Option<DateTime<Utc>>,
+    ) -> FlightMetadata {
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let duration_secs = if points.len() >= 2 {
+            Some((points.last().unwrap().timestamp_ms - points.first().unwrap().timestamp_ms) as f64 / 1000.0)
+        } else {
+            None
+        };
+
+        let end_time = match (start_time, duration_secs) {
+            (Some(s), Some(d)) => Some(s + Duration::milliseconds((d * 1000.0) as i64)),
+            _ => None,
+        };
+
+        let (photo_count, video_count) = crate::models::count_media_events(points);
+
+        FlightMetadata {
+            id: 0,
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: Some(firmware.unwrap_or_else(|| "ArduPilot".to_string())),
+            drone_serial,
+            aircraft_name: None,
+            battery_serial: None,
+            cycle_count: None,
+            start_time,
+            end_time,
+            duration_secs,
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count,
+            video_count,
+        }
+    }
+}
+
+/// Convert the GPS week / week-milliseconds of a GPS message to UTC
+fn gps_utc(fmt: &Format, payload: &[u8]) -> Option<DateTime<Utc>> {
+    let week = fmt.get(payload, "GWk")? as i64;
+    let week_ms = fmt.get(payload, "GMS")? as i64;
+    if week == 0 {
+        return None;
+    }
+    let unix_ms = (GPS_EPOCH_UNIX_SECS - GPS_LEAP_SECONDS + week * 7 * 86_400) * 1000 + week_ms;
+    Utc.timestamp_millis_opt(unix_ms).single()
+}
+
+/// Determine the vehicle family from the firmware banner (e.g. "ArduCopter V4.4.0 (abc123)")
+fn vehicle_from_banner(text: &str) -> Vehicle {
+    if text.starts_with("ArduCopter") {
+        Vehicle::Copter
+    } else if text.starts_with("ArduPlane") {
+        Vehicle::Plane
+    } else if text.starts_with("ArduRover") {
+        Vehicle::Rover
+    } else {
+        Vehicle::Unknown
+    }
+}
+
+/// Extract the board UID from a line like "CubeOrange 003A0031 3130510B 33363634"
+fn board_uid(text: &str) -> Option<String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.len() < 4 {
+        return None;
+    }
+    let uid = &tokens[tokens.len() - 3..];
+    if uid.iter().all(|t| t.len() == 8 && t.chars().all(|c| c.is_ascii_hexdigit())) {
+        Some(uid.concat().to_uppercase())
+    } else {
+        None
+    }
+}
+
+/// Classify a free-text MSG line into a message severity
+fn msg_severity(text: &str) -> &'static str {
+    let lower = text.to_lowercase();
+    if lower.contains("fail") || lower.contains("crash") || lower.contains("error") || lower.contains("emergency") {
+        "caution"
+    } else if lower.starts_with("prearm") || lower.starts_with("arm:") || lower.contains("warning") || lower.contains("lost") {
+        "warn"
+    } else {
+        "tip"
+    }
+}
+
+/// Describe an ERR message; code 0 means the condition has been resolved
+fn err_message(subsys: u8, code: u8) -> (&'static str, String) {
+    let name = match subsys {
+        1 => "Main",
+        2 => "Radio",
+        3 => "Compass",
+        4 => "Optical flow",
+        5 => "Radio failsafe",
+        6 => "Battery failsafe",
+        7 => "GPS failsafe",
+        8 => "GCS failsafe",
+        9 => "Fence failsafe",
+        10 => "Flight mode change",
+        11 => "GPS",
+        12 => "Crash check",
+        13 => "Flip",
+        14 => "Autotune",
+        15 => "Parachute",
+        16 => "EKF check",
+        17 => "EKF failsafe",
+        18 => "Barometer",
+        19 => "CPU load",
+        20 => "ADSB failsafe",
+        21 => "Terrain data",
+        22 => "Navigation",
+        23 => "Terrain failsafe",
+        24 => "EKF primary",
+        25 => "Thrust loss",
+        26 => "Sensor failsafe",
+        27 => "Leak failsafe",
+        28 => "Pilot input",
+        29 => "Vibration failsafe",
+        30 => "Internal error",
+        31 => "Dead reckoning failsafe",
+        _ => return ("caution", format!("Error: subsystem {} (code {})", subsys, code)),
+    };
+    if code == 0 {
+        ("tip", format!("{}: resolved", name))
+    } else {
+        ("caution", format!("{} error (code {})", name, code))
+    }
+}
+
+/// Describe an EV (event) message; unknown events are skipped
+fn event_message(id: u8) -> Option<(&'static str, &'static str)> {
+    Some(match id {
+        10 => ("tip", "Armed"),
+        11 => ("tip", "Disarmed"),
+        15 => ("tip", "Auto armed"),
+        17 => ("tip", "Land complete (maybe)"),
+        18 => ("tip", "Land complete"),
+        19 => ("caution", "Lost GPS"),
+        25 => ("tip", "Home position set"),
+        28 => ("tip", "Not landed"),
+        47 => ("tip", "Parachute disabled"),
+        48 => ("tip", "Parachute enabled"),
+        49 => ("caution", "Parachute released"),
+        50 => ("tip", "Landing gear deployed"),
+        51 => ("tip", "Landing gear retracted"),
+        52 => ("caution", "Motors emergency stopped"),
+        53 => ("warn", "Motors emergency stop cleared"),
+        54 => ("tip", "Motor interlock disabled"),
+        55 => ("tip", "Motor interlock enabled"),
+        57 => ("caution", "Rotor speed below critical"),
+        58 => ("warn", "EKF altitude reset"),
+        59 => ("warn", "Landing cancelled by pilot"),
+        60 => ("warn", "EKF yaw reset"),
+        62 => ("warn", "ADSB avoidance triggered"),
+        71 => ("tip", "Fence enabled"),
+        72 => ("tip", "Fence disabled"),
+        _ => return None,
+    })
+}
+
+/// Map an ArduPilot mode number to its name for the given vehicle family
+pub(crate) fn ardupilot_mode_name(vehicle: Vehicle, mode: u8) -> String {
+    let name = match vehicle {
+        Vehicle::Copter | Vehicle::Unknown => match mode {
+            0 => "Stabilize",
+            1 => "Acro",
+            2 => "AltHold",
+            3 => "Auto",
+            4 => "Guided",
+            5 => "Loiter",
+            6 => "RTL",
+            7 => "Circle",
+            9 => "Land",
+            11 => "Drift",
+            13 => "Sport",
+            14 => "Flip",
+            15 => "AutoTune",
+            16 => "PosHold",
+            17 => "Brake",
+            18 => "Throw",
+            19 => "Avoid_ADSB",
+            20 => "Guided_NoGPS",
+            21 => "Smart_RTL",
+            22 => "FlowHold",
+            23 => "Follow",
+            24 => "ZigZag",
+            25 => "SystemID",
+            26 => "Heli_Autorotate",
+            27 => "Auto RTL",
+            28 => "Turtle",
+            _ => "",
+        },
+        Vehicle::Plane => match mode {
+            0 => "Manual",
+            1 => "Circle",
+            2 => "Stabilize",
+            3 => "Training",
+            4 => "Acro",
+            5 => "FBWA",
+            6 => "FBWB",
+            7 => "Cruise",
+            8 => "Autotune",
+            10 => "Auto",
+            11 => "RTL",
+            12 => "Loiter",
+            13 => "Takeoff",
+            14 => "Avoid_ADSB",
+            15 => "Guided",
+            17 => "QStabilize",
+            18 => "QHover",
+            19 => "QLoiter",
+            20 => "QLand",
+            21 => "QRTL",
+            22 => "QAutotune",
+            23 => "QAcro",
+            24 => "Thermal",
+            25 => "Loiter to QLand",
+            _ => "",
+        },
+        Vehicle::Rover => match mode {
+            0 => "Manual",
+            1 => "Acro",
+            3 => "Steering",
+            4 => "Hold",
+            5 => "Loiter",
+            6 => "Follow",
+            7 => "Simple",
+            10 => "Auto",
+            11 => "RTL",
+            12 => "Smart_RTL",
+            15 => "Guided",
+            _ => "",
+        },
+    };
+    if name.is_empty() {
+        format!("Mode {}", mode)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const GPS_TYPE: u8 = 130;
+
+    fn padded(text: &str, len: usize) -> Vec<u8> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(len, 0);
+        bytes
+    }
+
+    fn fmt_message(defined_type: u8, length: u8, name: &str, format: &str, labels: &str) -> Vec<u8> {
+        let mut msg = vec![HEAD1, HEAD2, FMT_TYPE, defined_type, length];
+        msg.extend(padded(name, 4));
+        msg.extend(padded(format, 16));
+        msg.extend(padded(labels, 64));
+        msg
+    }
+
+    fn gps_message(time_us: u64, lat: f64, lng: f64) -> Vec<u8> {
+        let mut msg = vec![HEAD1, HEAD2, GPS_TYPE];
+        msg.extend_from_slice(&time_us.to_le_bytes());
+        msg.extend_from_slice(&[3, 12]);
+        msg.extend_from_slice(&((lat * 1e7) as i32).to_le_bytes());
+        msg.extend_from_slice(&((lng * 1e7) as i32).to_le_bytes());
+        msg.extend_from_slice(&450.0f32.to_le_bytes());
+        msg
+    }
+
+    /// A DataFlash log whose GPS format declares `gps_length` bytes
+    fn dataflash(gps_length: u8) -> Vec<u8> {
+        let mut data = fmt_message(FMT_TYPE, FMT_LENGTH as u8, "FMT", "BBnNZ", "Type,Length,Name,Format,Columns");
+        data.extend(fmt_message(GPS_TYPE, gps_length, "GPS", "QBBLLf", "TimeUS,Status,NSats,Lat,Lng,Alt"));
+        data.extend(gps_message(1_000_000, 47.1, 8.5));
+        data.extend(gps_message(1_200_000, 47.1001, 8.5001));
+        data
+    }
+
+    fn parse(data: &[u8]) -> Result<ParsedFlight, ParserError> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("00000001.BIN");
+        fs::write(&path, data).unwrap();
+        DataFlashParser::new().parse(&path, "hash")
+    }
+
+    #[test]
+    fn test_sniff_requires_extension_and_fmt_header() {
+        let data = dataflash(25);
+        assert_eq!(DataFlashFormat.sniff(Path::new("00000001.BIN"), &data), 100);
+        assert_eq!(DataFlashFormat.sniff(Path::new("00000001.txt"), &data), 0);
+        assert_eq!(DataFlashFormat.sniff(Path::new("00000001.bin"), &data[1..]), 0);
+    }
+
+    #[test]
+    fn test_parse_minimal_log() {
+        let flight = parse(&dataflash(25)).unwrap();
+        assert_eq!(flight.points.len(), 2);
+        assert_eq!(flight.points[1].timestamp_ms, 200);
+        assert!((flight.points[1].longitude.unwrap() - 8.5001).abs() < 1e-6);
+        assert_eq!(flight.points[0].satellites, Some(12));
+        assert_eq!(flight.tags[0], "ArduPilot");
+    }
+
+    #[test]
+    fn test_corrupt_fmt_records_are_skipped() {
+        // Lengths below the 3-byte header or too short for the columns
+        for length in [0, 2, 3, 24] {
+            assert!(matches!(parse(&dataflash(length)), Err(ParserError::NoTelemetryData)));
+        }
+        // Truncated log and garbage input
+        let data = dataflash(25);
+        assert!(matches!(parse(&data[..data.len() - 30]), Err(ParserError::NoTelemetryData)));
+        assert!(parse(&[0xA3; 64]).is_err());
+    }
+}
@@ -11,9 +11,9 @@ use std::path::Path;
 
 use chrono::{DateTime, NaiveDateTime, Utc, TimeZone};
 
-use crate::database::Database;
-use crate::models::{FlightMetadata, FlightMessage, FlightStats, TelemetryPoint};
-use crate::parser::{ParseResult, ParserError, LogParser};
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMetadata, FlightMessage, FlightStats, ParsedFlight, TelemetryPoint};
+use crate::parser::{LogParser, ParseOptions, ParserError};
 
 /// Parse a timestamp string flexibly, handling multiple formats:
 /// - RFC3339: "2026-02-01T14:35:52+00:00" or "2026-02-01T14:35:52Z"
@@ -140,52 +140,55 @@ impl ColumnMap {
     }
 }
 
-/// Drone Logbook CSV Parser
-pub struct DroneLogbookParser<'a> {
-    db: &'a Database,
-}
+/// Drone Logbook CSV export format (our own export, re-imported)
+pub struct DroneLogbookFormat;
 
-impl<'a> DroneLogbookParser<'a> {
-    pub fn new(db: &'a Database) -> Self {
-        Self { db }
+impl FlightLogFormat for DroneLogbookFormat {
+    fn id(&self) -> &'static str {
+        "dronelogbook_csv"
     }
 
-    /// Check if a file is a valid Drone Logbook CSV export format
-    pub fn is_dronelogbook_csv(path: &Path) -> bool {
-        // Must be a CSV file
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !ext.eq_ignore_ascii_case("csv") {
-            return false;
-        }
+    fn name(&self) -> &'static str {
+        "Open DroneLog CSV"
+    }
 
-        // Check header line for Drone Logbook-specific columns
-        if let Ok(file) = File::open(path) {
-            let reader = BufReader::new(file);
-            for line in reader.lines() {
-                if let Ok(line_content) = line {
-                    let trimmed = line_content.trim();
-                    // Skip empty lines
-                    if trimmed.is_empty() {
-                        continue;
-                    }
-                    // First non-empty line is the header - check for our characteristic columns
-                    let lower = trimmed.to_lowercase();
-                    // The combination of time_s, lat, lng, alt_m, distance_to_home_m is characteristic of our export
-                    // Metadata column is optional (for backwards compatibility)
-                    let has_basic_cols = lower.contains("time_s")
-                        && lower.contains("lat")
-                        && lower.contains("lng")
-                        && lower.contains("alt_m")
-                        && (lower.contains("distance_to_home_m") || lower.contains("height_m"));
-                    // Additional columns that make our format more unique
-                    let has_extended_cols = lower.contains("vps_height_m") 
-                        || lower.contains("rc_aileron") 
-                        || lower.contains("metadata");
-                    return has_basic_cols && has_extended_cols;
-                }
-            }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8 {
+        if !formats::has_extension(path, "csv") {
+            return 0;
         }
-        false
+        let Some(lower) = formats::header_line(header) else {
+            return 0;
+        };
+        // The combination of time_s, lat, lng, alt_m, distance_to_home_m is characteristic of our export
+        // Metadata column is optional (for backwards compatibility)
+        let has_basic_cols = lower.contains("time_s")
+            && lower.contains("lat")
+            && lower.contains("lng")
+            && lower.contains("alt_m")
+            && (lower.contains("distance_to_home_m") || lower.contains("height_m"));
+        // Additional columns that make our format more unique
+        let has_extended_cols = lower.contains("vps_height_m")
+            || lower.contains("rc_aileron")
+            || lower.contains("metadata");
+        if has_basic_cols && has_extended_cols { 100 } else { 0 }
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, _options: &'a ParseOptions) -> ParseFuture<'a> {
+        formats::ready(DroneLogbookParser::new().parse(path, file_hash))
+    }
+}
+
+/// Drone Logbook CSV Parser
+#[derive(Default)]
+pub struct DroneLogbookParser;
+
+impl DroneLogbookParser {
+    pub fn new() -> Self {
+        Self
     }
 
     /// Parse CSV field with proper quote handling
@@ -224,7 +227,7 @@ impl<'a> DroneLogbookParser<'a> {
     }
 
     /// Parse a Drone Logbook CSV file
-    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
         let parse_start = std::time::Instant::now();
         log::info!("Parsing Drone Logbook CSV file: {:?}", file_path);
 
@@ -472,7 +475,9 @@ impl<'a> DroneLogbookParser<'a> {
                 altitude: col_map.get_f64(fields, "alt_m"),
                 height: col_map.get_f64(fields, "height_m"),
                 vps_height: col_map.get_f64(fields, "vps_height_m"),
-                altitude_abs: col_map.get_f64(fields, "altitude_m"),
+                altitude_abs: col_map
+                    .get_f64(fields, "altitude_abs_m")
+                    .or_else(|| col_map.get_f64(fields, "altitude_m")),
 
                 // Velocity
                 speed: col_map.get_f64(fields, "speed_ms"),
@@ -485,21 +490,21 @@ impl<'a> DroneLogbookParser<'a> {
                 roll: col_map.get_f64(fields, "roll_deg"),
                 yaw: col_map.get_f64(fields, "yaw_deg"),
 
-                // Gimbal (not in our CSV export currently)
-                gimbal_pitch: None,
-                gimbal_roll: None,
-                gimbal_yaw: None,
+                // Gimbal (absent from exports made before gimbal columns were added)
+                gimbal_pitch: col_map.get_f64(fields, "gimbal_pitch_deg"),
+                gimbal_roll: col_map.get_f64(fields, "gimbal_roll_deg"),
+                gimbal_yaw: col_map.get_f64(fields, "gimbal_yaw_deg"),
 
                 // Power
                 battery_percent: col_map.get_i32(fields, "battery_percent"),
                 battery_voltage: col_map.get_f64(fields, "battery_voltage_v"),
-                battery_current: None, // Not in our CSV export
+                battery_current: col_map.get_f64(fields, "battery_current_a"),
                 battery_temp: col_map.get_f64(fields, "battery_temp_c"),
                 cell_voltages: col_map.get_f64_vec(fields, "cell_voltages"),
 
                 // Status
                 flight_mode: col_map.get_str(fields, "flight_mode"),
-                gps_signal: None,
+                gps_signal: col_map.get_i32(fields, "gps_signal"),
                 satellites: col_map.get_i32(fields, "satellites"),
                 rc_signal: col_map.get_i32(fields, "rc_signal"),
                 rc_uplink: col_map.get_i32(fields, "rc_uplink"),
@@ -595,7 +600,7 @@ impl<'a> DroneLogbookParser<'a> {
         let (photo_count, video_count) = crate::models::count_media_events(&points);
 
         let metadata = FlightMetadata {
-            id: self.db.generate_flight_id(),
+            id: 0,
             file_name,
             display_name,
             file_hash: Some(file_hash.to_string()),
@@ -668,7 +673,7 @@ impl<'a> DroneLogbookParser<'a> {
         log::info!("Final auto tags: {:?}, manual tags: {:?}, notes: {:?}, messages: {}", 
             tags, imported_manual_tags, meta_notes.is_some(), imported_messages.len());
 
-        Ok(ParseResult { metadata, points, tags, manual_tags: imported_manual_tags, notes: meta_notes, color: meta_color, messages: imported_messages })
+        Ok(ParsedFlight { metadata, points, tags, manual_tags: imported_manual_tags, notes: meta_notes, color: meta_color, messages: imported_messages, side_data: Default::default(), equipment: Default::default() })
     }
 }
 
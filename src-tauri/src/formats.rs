@@ -0,0 +1,186 @@
+//! Flight log format registry.
+//!
+//! Every supported log format implements [`FlightLogFormat`]. When a file is
+//! imported, each registered format sniffs the file name and the first few
+//! kilobytes of content and reports how confident it is that it can parse
+//! the file. The format with the highest confidence wins, so adding a new
+//! format only requires implementing the trait and listing it in [`FORMATS`].
+
+use std::fs::File;
+use std::future::Future;
+use std::io::Read;
+use std::path::Path;
+use std::pin::Pin;
+
+use crate::airdata_parser::AirdataFormat;
+use crate::dataflash_parser::DataFlashFormat;
+use crate::dronelogbook_parser::DroneLogbookFormat;
+use crate::litchi_parser::LitchiFormat;
+use crate::models::{ParsedFlight, SupportedFormat};
+use crate::parser::{DjiFormat, ParseOptions, ParserError};
+use crate::tlog_parser::TlogFormat;
+use crate::ulog_parser::ULogFormat;
+
+/// Number of leading bytes handed to [`FlightLogFormat::sniff`]
+const SNIFF_HEADER_LEN: u64 = 16 * 1024;
+
+/// Boxed future returned by [`FlightLogFormat::parse`]
+pub type ParseFuture<'a> = Pin<Box<dyn Future<Output = Result<ParsedFlight, ParserError>> + Send + 'a>>;
+
+/// A flight log format that can be detected and parsed
+pub trait FlightLogFormat: Sync {
+    /// Stable identifier used by the API (e.g. `px4_ulog`)
+    fn id(&self) -> &'static str;
+
+    /// Human-readable format name
+    fn name(&self) -> &'static str;
+
+    /// File extensions accepted by this format, lowercase and without the dot
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Confidence from 0 (not this format) to 100 (certain) that the file is
+    /// in this format. `header` holds up to the first 16 KB of the file.
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8;
+
+    /// Parse the file without touching the database
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, options: &'a ParseOptions) -> ParseFuture<'a>;
+}
+
+/// All registered formats. On equal confidence the earlier entry wins.
+pub static FORMATS: &[&dyn FlightLogFormat] = &[
+    &DjiFormat,
+    &ULogFormat,
+    &DataFlashFormat,
+    &TlogFormat,
+    &DroneLogbookFormat,
+    &AirdataFormat,
+    &LitchiFormat,
+];
+
+/// Pick the registered format with the highest sniff confidence for a file
+pub fn detect(path: &Path) -> Option<&'static dyn FlightLogFormat> {
+    let header = read_header(path);
+
+    let mut best: Option<(&'static dyn FlightLogFormat, u8)> = None;
+    for &format in FORMATS {
+        let confidence = format.sniff(path, &header);
+        log::debug!("Format {} sniffed {:?} with confidence {}", format.id(), path, confidence);
+        if confidence > 0 && best.map_or(true, |(_, c)| confidence > c) {
+            best = Some((format, confidence));
+        }
+    }
+    best.map(|(format, _)| format)
+}
+
+/// Describe all registered formats for the UI and API
+pub fn list_supported_formats() -> Vec<SupportedFormat> {
+    FORMATS
+        .iter()
+        .map(|f| SupportedFormat {
+            id: f.id().to_string(),
+            name: f.name().to_string(),
+            extensions: f.extensions().iter().map(|e| e.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// File extensions of all registered formats, lowercase, without the dot and
+/// without duplicates
+pub fn supported_extensions() -> Vec<&'static str> {
+    let mut extensions: Vec<&'static str> = Vec::new();
+    for ext in FORMATS.iter().flat_map(|f| f.extensions().iter().copied()) {
+        if !extensions.contains(&ext) {
+            extensions.push(ext);
+        }
+    }
+    extensions
+}
+
+/// Wrap the result of a synchronous parser in a [`ParseFuture`]
+pub(crate) fn ready<'a>(result: Result<ParsedFlight, ParserError>) -> ParseFuture<'a> {
+    Box::pin(std::future::ready(result))
+}
+
+/// Run a synchronous binary parser on the blocking pool with panic and
+/// timeout protection (see [`crate::parser::run_guarded`])
+pub(crate) fn blocking<'a, F>(path: &'a Path, file_hash: &'a str, parse: F) -> ParseFuture<'a>
+where
+    F: FnOnce(&Path, &str) -> Result<ParsedFlight, ParserError> + Send + 'static,
+{
+    let (path, file_hash) = (path.to_path_buf(), file_hash.to_string());
+    Box::pin(crate::parser::run_guarded(move || parse(&path, &file_hash)))
+}
+
+/// Case-insensitive extension check
+pub(crate) fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+/// First non-empty line of a text header, lowercased (used by the CSV formats)
+pub(crate) fn header_line(header: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(header)
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn read_header(path: &Path) -> Vec<u8> {
+    let mut header = Vec::new();
+    if let Ok(file) = File::open(path) {
+        let _ = file.take(SNIFF_HEADER_LEN).read_to_end(&mut header);
+    }
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_by_extension_and_content() {
+        let dir = tempdir().unwrap();
+        let detected = |name: &str, content: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            detect(&path).map(|f| f.id())
+        };
+        let mut tlog = vec![0u8; 8];
+        tlog.extend_from_slice(&[0xFE, 9, 0, 1, 1, 0]);
+
+        let cases: &[(&str, &[u8], Option<&str>)] = &[
+            ("DJIFlightRecord_2024-05-01.txt", b"anything", Some("dji")),
+            ("flight.ulg", &[0x55, 0x4c, 0x6f, 0x67, 0x01, 0x12, 0x35, 1], Some("px4_ulog")),
+            ("00000001.BIN", &[0xA3, 0x95, 0x80, 0x80], Some("ardupilot_dataflash")),
+            ("flight.tlog", &tlog, Some("mavlink_tlog")),
+            (
+                "export.csv",
+                b"time_s,lat,lng,alt_m,distance_to_home_m,metadata\n0,1,2,3,4,{}",
+                Some("dronelogbook_csv"),
+            ),
+            ("airdata.csv", b"time(millisecond),datetime(utc),latitude,longitude,flycState\n", Some("airdata_csv")),
+            ("litchi.csv", b"latitude,longitude,datetime(utc),isFlying\n", Some("litchi_csv")),
+            // Sniff failures: right extension, wrong content
+            ("flight.ulg", b"not a ulog", None),
+            ("00000001.bin", b"firmware", None),
+            ("flight.tlog", b"short", None),
+            ("notes.csv", b"name,value\n", None),
+            // No format reads these
+            ("FLY001.DAT", b"anything", None),
+            ("flight.log", b"anything", None),
+        ];
+        for (name, content, expected) in cases {
+            assert_eq!(detected(name, content), *expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_supported_extensions() {
+        assert_eq!(supported_extensions(), vec!["txt", "ulg", "bin", "tlog", "csv"]);
+        let formats = list_supported_formats();
+        assert!(formats.iter().all(|f| !f.extensions.is_empty()));
+    }
+}
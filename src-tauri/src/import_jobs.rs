@@ -0,0 +1,320 @@
+//! Long-running import jobs.
+//!
+//! A job wraps one [`BatchImporter`] run: starting it returns a job id right
+//! away, progress is published as [`ImportJobEvent`]s and the job can be
+//! cancelled between files. The web server streams the events over SSE, the
+//! desktop app forwards them as Tauri events.
+//!
+//! Every job keeps its full event history, so a client that subscribes late
+//! (or reconnects) replays the job from the start before following it live.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::broadcast;
+
+use crate::batch_import::{BatchEvent, BatchImporter, BatchOutcome, BatchSummary};
+use crate::models::{BatchImportProgress, ImportJobEvent, ImportJobEventKind};
+
+/// Finished jobs kept around for late subscribers
+const MAX_FINISHED_JOBS: usize = 20;
+
+/// Buffer of the live event channel. A [`JobSubscription`] that falls
+/// further behind reads the events it missed from the history instead.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// A running or finished import job
+pub struct ImportJob {
+    id: String,
+    /// Profile the job imports into; only that profile may watch or cancel it
+    profile: String,
+    cancel: Arc<AtomicBool>,
+    history: Mutex<Vec<ImportJobEvent>>,
+    sender: broadcast::Sender<ImportJobEvent>,
+}
+
+impl ImportJob {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Ask the job to stop; files already being parsed still finish
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// True once the final `done` event was published
+    pub fn is_finished(&self) -> bool {
+        self.history
+            .lock()
+            .unwrap()
+            .last()
+            .is_some_and(|e| e.kind == ImportJobEventKind::Done)
+    }
+
+    /// Events so far plus a receiver for everything after them
+    pub fn subscribe(&self) -> (Vec<ImportJobEvent>, broadcast::Receiver<ImportJobEvent>) {
+        self.subscribe_from(0)
+    }
+
+    /// Events from index `from` on plus a receiver for everything after them
+    fn subscribe_from(&self, from: usize) -> (Vec<ImportJobEvent>, broadcast::Receiver<ImportJobEvent>) {
+        // Subscribe while holding the history lock so no event falls in between
+        let history = self.history.lock().unwrap();
+        (history.get(from..).unwrap_or_default().to_vec(), self.sender.subscribe())
+    }
+
+    /// Follow the job from its first event to its `done` event
+    #[allow(dead_code)]
+    pub fn follow(self: &Arc<Self>) -> JobSubscription {
+        let (history, receiver) = self.subscribe();
+        JobSubscription {
+            job: self.clone(),
+            receiver,
+            queued: history.into(),
+            delivered: 0,
+            done: false,
+        }
+    }
+
+    fn publish(&self, event: ImportJobEvent) {
+        let mut history = self.history.lock().unwrap();
+        history.push(event.clone());
+        // No subscribers is fine, the history has the event
+        let _ = self.sender.send(event);
+    }
+
+    fn event(
+        &self,
+        kind: ImportJobEventKind,
+        index: Option<usize>,
+        path: Option<&std::path::Path>,
+        progress: &BatchImportProgress,
+    ) -> ImportJobEvent {
+        ImportJobEvent {
+            job_id: self.id.clone(),
+            kind,
+            index,
+            file_name: path.and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()),
+            message: None,
+            flight_id: None,
+            progress: progress.clone(),
+        }
+    }
+
+    /// Run `paths` through `importer`, publishing every step. `on_event` is
+    /// called with each published event (e.g. to forward it to the UI).
+    pub async fn run<F>(&self, importer: BatchImporter, paths: Vec<PathBuf>, on_event: F) -> BatchSummary
+    where
+        F: Fn(&ImportJobEvent),
+    {
+        let publish = |event: ImportJobEvent| {
+            on_event(&event);
+            self.publish(event);
+        };
+        // Started/parsed events carry the totals of the last finished file
+        let last_progress = Mutex::new(BatchImportProgress {
+            total: paths.len(),
+            ..Default::default()
+        });
+
+        let summary = importer
+            .with_cancel(self.cancel.clone())
+            .run_with_events(paths, |batch_event| {
+                let event = match batch_event {
+                    BatchEvent::Started { index, path } => {
+                        self.event(ImportJobEventKind::Started, Some(index), Some(path), &last_progress.lock().unwrap())
+                    }
+                    BatchEvent::Parsed { index, path } => {
+                        self.event(ImportJobEventKind::Parsed, Some(index), Some(path), &last_progress.lock().unwrap())
+                    }
+                    BatchEvent::Finished { index, result, progress } => {
+                        *last_progress.lock().unwrap() = progress.clone();
+                        let kind = match result.outcome {
+                            BatchOutcome::Imported { .. } => ImportJobEventKind::Imported,
+                            BatchOutcome::Duplicate { .. } => ImportJobEventKind::Duplicate,
+                            BatchOutcome::Skipped { .. } | BatchOutcome::Cancelled => ImportJobEventKind::Skipped,
+                            BatchOutcome::Queued { .. } => ImportJobEventKind::Queued,
+                            BatchOutcome::Failed { .. } => ImportJobEventKind::Failed,
+                        };
+                        let import_result = result.to_import_result();
+                        ImportJobEvent {
+                            message: Some(import_result.message),
+                            flight_id: import_result.flight_id,
+                            ..self.event(kind, Some(index), Some(&result.path), progress)
+                        }
+                    }
+                };
+                publish(event);
+            })
+            .await;
+
+        let cancelled = summary.progress.cancelled > 0;
+        publish(ImportJobEvent {
+            message: Some(if cancelled {
+                format!("Import cancelled after {} of {} files", summary.progress.total - summary.progress.cancelled, summary.progress.total)
+            } else {
+                format!("Import complete: {} files", summary.progress.total)
+            }),
+            ..self.event(ImportJobEventKind::Done, None, None, &summary.progress)
+        });
+        summary
+    }
+}
+
+/// Every event of a job in order, ending with its `done` event. Unlike a
+/// plain receiver it never skips events: when it lags behind the live
+/// channel it re-reads the missed ones from the job's history.
+pub struct JobSubscription {
+    job: Arc<ImportJob>,
+    receiver: broadcast::Receiver<ImportJobEvent>,
+    /// Events read from the history, delivered before the receiver's
+    queued: VecDeque<ImportJobEvent>,
+    /// Number of events delivered so far, i.e. the next history index
+    delivered: usize,
+    done: bool,
+}
+
+impl JobSubscription {
+    /// The next event, or `None` after the `done` event
+    #[allow(dead_code)]
+    pub async fn next(&mut self) -> Option<ImportJobEvent> {
+        if self.done {
+            return None;
+        }
+        let event = match self.queued.pop_front() {
+            Some(event) => event,
+            None => loop {
+                match self.receiver.recv().await {
+                    Ok(event) => break event,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        log::debug!("Import job {} subscriber lagged by {} events, replaying history", self.job.id, missed);
+                        let (missed, receiver) = self.job.subscribe_from(self.delivered);
+                        self.receiver = receiver;
+                        self.queued = missed.into();
+                        if let Some(event) = self.queued.pop_front() {
+                            break event;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+        };
+        self.delivered += 1;
+        self.done = event.kind == ImportJobEventKind::Done;
+        Some(event)
+    }
+}
+
+/// Registry of import jobs, shared by all profiles
+#[derive(Default)]
+pub struct ImportJobs {
+    jobs: Mutex<HashMap<String, Arc<ImportJob>>>,
+    /// Creation order, used to drop the oldest finished jobs
+    order: Mutex<Vec<String>>,
+}
+
+impl ImportJobs {
+    /// Register a new job for `profile`
+    pub fn create(&self, profile: &str) -> Arc<ImportJob> {
+        self.prune();
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let job = Arc::new(ImportJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            profile: profile.to_string(),
+            cancel: Arc::new(AtomicBool::new(false)),
+            history: Mutex::new(Vec::new()),
+            sender,
+        });
+        self.jobs.lock().unwrap().insert(job.id.clone(), job.clone());
+        self.order.lock().unwrap().push(job.id.clone());
+        job
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<ImportJob>> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    /// Forget the oldest finished jobs beyond [`MAX_FINISHED_JOBS`]
+    fn prune(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut order = self.order.lock().unwrap();
+        let finished: Vec<String> = order
+            .iter()
+            .filter(|id| jobs.get(*id).map_or(true, |job| job.is_finished()))
+            .cloned()
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        for id in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+            order.retain(|o| o != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{Database, ImportOptions};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_cancelled_job_stops_between_files() {
+        let temp_dir = tempdir().unwrap();
+        let db = Arc::new(Database::new(temp_dir.path().join("data"), "default").unwrap());
+        let paths: Vec<PathBuf> = (0..4).map(|i| temp_dir.path().join(format!("missing{}.csv", i))).collect();
+
+        let jobs = ImportJobs::default();
+        let job = jobs.create("default");
+        let importer = BatchImporter::new(db, ImportOptions::default()).with_workers(1);
+        // Cancel as soon as the first file is done
+        let summary = job
+            .run(importer, paths, |event| {
+                if event.kind == ImportJobEventKind::Failed {
+                    job.cancel();
+                }
+            })
+            .await;
+
+        assert!(summary.progress.failed >= 1);
+        assert!(summary.progress.cancelled >= 2);
+        assert_eq!(summary.progress.failed + summary.progress.cancelled, 4);
+
+        let (history, _) = jobs.get(job.id()).unwrap().subscribe();
+        assert_eq!(history.first().unwrap().kind, ImportJobEventKind::Started);
+        assert_eq!(history.last().unwrap().kind, ImportJobEventKind::Done);
+        assert!(job.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscription_replays_missed_events() {
+        let jobs = ImportJobs::default();
+        let job = jobs.create("default");
+        let progress = BatchImportProgress::default();
+        job.publish(job.event(ImportJobEventKind::Started, Some(0), None, &progress));
+        let mut subscription = job.follow();
+
+        // Overflow the live channel before the subscriber reads anything
+        for i in 0..EVENT_CHANNEL_CAPACITY + 10 {
+            job.publish(job.event(ImportJobEventKind::Imported, Some(i), None, &progress));
+        }
+        job.publish(job.event(ImportJobEventKind::Done, None, None, &progress));
+
+        let mut events = Vec::new();
+        while let Some(event) = subscription.next().await {
+            events.push(event);
+        }
+        assert_eq!(events.len(), EVENT_CHANNEL_CAPACITY + 12);
+        assert_eq!(events[1].index, Some(0));
+        assert_eq!(events[EVENT_CHANNEL_CAPACITY + 10].index, Some(EVENT_CHANNEL_CAPACITY + 9));
+        assert_eq!(events.last().unwrap().kind, ImportJobEventKind::Done);
+    }
+}
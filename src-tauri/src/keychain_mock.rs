@@ -0,0 +1,97 @@
+//! Local stand-in for DJI's keychain service.
+//!
+//! Answers keychain requests with the same JSON protocol as DJI's API, but
+//! from a keychain bundle file (see [`Database::export_keychain_bundle`])
+//! instead of the network. Point the keychain endpoint at it to import
+//! encrypted (V13+) logs in CI or on machines without access to DJI:
+//!
+//! - `DJI_KEYCHAIN_MOCK_FILE=/path/bundle.json` makes the web server start
+//!   it on its own loopback-only listener (port `DJI_KEYCHAIN_MOCK_PORT`,
+//!   random by default) and use it unless `DJI_KEYCHAIN_ENDPOINT` is set.
+//!   It is never mounted on the public router.
+//! - [`serve`] runs it on any listener, e.g. `127.0.0.1:0` in tests.
+//!
+//! Requests are matched by the SHA256 of their body, the same key the
+//! keychain cache uses, so a bundle exported from an instance that imported
+//! a log serves that log's keychains.
+//!
+//! [`Database::export_keychain_bundle`]: crate::database::Database::export_keychain_bundle
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{body::Bytes, extract::State, routing::post, Json, Router};
+use dji_log_parser::keychain::KeychainFeaturePoint;
+use sha2::{Digest, Sha256};
+
+use crate::models::KeychainBundle;
+
+/// Route the mock answers keychain requests on
+pub const MOCK_PATH: &str = "/api/keychains/mock";
+
+/// Keychains served by the mock, keyed by request hash
+#[derive(Debug, Default)]
+pub struct KeychainStore {
+    entries: HashMap<String, Vec<Vec<KeychainFeaturePoint>>>,
+}
+
+impl KeychainStore {
+    /// Build a store from a keychain bundle. Invalid entries are skipped.
+    pub fn from_bundle(bundle: &KeychainBundle) -> Self {
+        let entries = bundle
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let keychains = serde_json::from_value(entry.keychains.clone()).ok()?;
+                Some((entry.request_hash.clone(), keychains))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Load a keychain bundle JSON file
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read keychain file {}: {}", path.display(), e))?;
+        let bundle: KeychainBundle = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid keychain bundle {}: {}", path.display(), e))?;
+        Ok(Self::from_bundle(&bundle))
+    }
+
+    /// Number of logs the store has keychains for
+    pub fn keychain_count(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// POST handler speaking DJI's keychain response format
+async fn fetch_keychains(State(store): State<Arc<KeychainStore>>, body: Bytes) -> Json<serde_json::Value> {
+    let request_hash = format!("{:x}", Sha256::digest(&body));
+    Json(match store.entries.get(&request_hash) {
+        Some(keychains) => serde_json::json!({
+            "result": { "code": 0, "msg": "success" },
+            "data": keychains,
+        }),
+        None => {
+            log::warn!("Mock keychain server: no keychains for request {}", request_hash);
+            serde_json::json!({
+                "result": { "code": 404, "msg": "No keychains in the mock keychain file for this log" },
+                "data": null,
+            })
+        }
+    })
+}
+
+/// Router serving the mock at [`MOCK_PATH`]
+pub fn router(store: KeychainStore) -> Router {
+    Router::new()
+        .route(MOCK_PATH, post(fetch_keychains))
+        .with_state(Arc::new(store))
+}
+
+/// Run the mock on its own listener until the task is dropped
+#[allow(dead_code)]
+pub async fn serve(listener: tokio::net::TcpListener, store: KeychainStore) -> std::io::Result<()> {
+    axum::serve(listener, router(store)).await
+}
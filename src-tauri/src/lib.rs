@@ -1,11 +1,26 @@
 pub mod airdata_parser;
+pub mod archive;
 pub mod api;
+pub mod batch_import;
 pub mod database;
+pub mod dataflash_parser;
 pub mod dronelogbook_parser;
+pub mod formats;
+pub mod import_jobs;
 pub mod litchi_parser;
 pub mod models;
 pub mod parser;
 pub mod profile_auth;
+pub mod solar;
+pub mod srt_parser;
+pub mod sync_scan;
+pub mod sync_watch;
+pub mod timezone;
+pub mod tlog_parser;
+pub mod ulog_parser;
+
+#[cfg(feature = "web")]
+pub mod keychain_mock;
 
 #[cfg(feature = "web")]
 pub mod server;
@@ -13,9 +28,18 @@ pub mod server;
 #[cfg(feature = "web")]
 pub mod session_store;
 
-pub use database::Database;
+pub use api::KeychainProvider;
+pub use archive::{ArchiveError, ExtractedArchive};
+pub use batch_import::{BatchImporter, BatchOutcome};
+pub use import_jobs::{ImportJob, ImportJobs};
+pub use database::{Database, ImportError, ImportOptions};
 pub use models::*;
-pub use parser::LogParser;
+pub use parser::{parse_file, DjiParser, LogParser, ParseOptions};
+pub use timezone::TimezoneLookup;
+pub use formats::FlightLogFormat;
 pub use airdata_parser::AirdataParser;
+pub use dataflash_parser::DataFlashParser;
 pub use litchi_parser::LitchiParser;
 pub use dronelogbook_parser::DroneLogbookParser;
+pub use tlog_parser::TlogParser;
+pub use ulog_parser::ULogParser;
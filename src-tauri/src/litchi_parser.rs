@@ -10,9 +10,9 @@ use std::path::Path;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
 
-use crate::database::Database;
-use crate::models::{FlightMetadata, FlightStats, TelemetryPoint};
-use crate::parser::{ParseResult, ParserError, LogParser};
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMetadata, FlightStats, ParsedFlight, TelemetryPoint};
+use crate::parser::{LogParser, ParseOptions, ParserError};
 
 /// Unit type detected from column headers
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -47,7 +47,7 @@ impl Unit {
     }
 
     /// Convert value to metric units
-    fn to_metric(&self, value: f64) -> f64 {
+    fn to_metric(self, value: f64) -> f64 {
         match self {
             Unit::Feet => value * 0.3048,
             Unit::Mph => value * 0.44704,
@@ -120,41 +120,52 @@ impl ColumnMap {
     }
 }
 
-/// Litchi CSV Parser
-pub struct LitchiParser<'a> {
-    db: &'a Database,
-}
+/// Litchi CSV export format
+pub struct LitchiFormat;
 
-impl<'a> LitchiParser<'a> {
-    pub fn new(db: &'a Database) -> Self {
-        Self { db }
+impl FlightLogFormat for LitchiFormat {
+    fn id(&self) -> &'static str {
+        "litchi_csv"
     }
 
-    /// Check if a file is a valid Litchi CSV format
-    pub fn is_litchi_csv(path: &Path) -> bool {
-        // Must be a CSV file
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !ext.eq_ignore_ascii_case("csv") {
-            return false;
-        }
+    fn name(&self) -> &'static str {
+        "Litchi CSV"
+    }
 
-        // Check header line for Litchi-specific columns
-        if let Ok(file) = File::open(path) {
-            let reader = BufReader::new(file);
-            if let Some(Ok(first_line)) = reader.lines().next() {
-                let lower = first_line.to_lowercase();
-                // Litchi CSVs have these characteristic columns
-                return lower.contains("latitude") 
-                    && lower.contains("longitude")
-                    && (lower.contains("datetime(utc)") || lower.contains("datetime(local)"))
-                    && (lower.contains("dronetype") || lower.contains("planename") || lower.contains("isflying"));
-            }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8 {
+        if !formats::has_extension(path, "csv") {
+            return 0;
+        }
+        match formats::header_line(header) {
+            // Litchi CSVs have these characteristic columns
+            Some(lower) if lower.contains("latitude")
+                && lower.contains("longitude")
+                && (lower.contains("datetime(utc)") || lower.contains("datetime(local)"))
+                && (lower.contains("dronetype") || lower.contains("planename") || lower.contains("isflying")) => 90,
+            _ => 0,
         }
-        false
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, _options: &'a ParseOptions) -> ParseFuture<'a> {
+        formats::ready(LitchiParser::new().parse(path, file_hash))
+    }
+}
+
+/// Litchi CSV Parser
+#[derive(Default)]
+pub struct LitchiParser;
+
+impl LitchiParser {
+    pub fn new() -> Self {
+        Self
     }
 
     /// Parse a Litchi CSV file
-    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
         let parse_start = std::time::Instant::now();
         log::info!("Parsing Litchi CSV file: {:?}", file_path);
 
@@ -252,7 +263,7 @@ impl<'a> LitchiParser<'a> {
         tags.insert(0, "Litchi".to_string()); // Add Litchi tag at the beginning
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new() })
+        Ok(ParsedFlight { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new(), side_data: Default::default(), equipment: Default::default() })
     }
 
     /// Parse a single CSV row into a TelemetryPoint
@@ -403,7 +414,7 @@ impl<'a> LitchiParser<'a> {
         let (photo_count, video_count) = crate::models::count_media_events(points);
 
         Ok(FlightMetadata {
-            id: self.db.generate_flight_id(),
+            id: 0,
             file_name,
             display_name,
             file_hash: Some(file_hash.to_string()),
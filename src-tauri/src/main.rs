@@ -13,13 +13,28 @@
 )]
 
 mod airdata_parser;
+mod archive;
 mod api;
+mod batch_import;
 mod database;
+mod dataflash_parser;
 mod dronelogbook_parser;
+mod formats;
+mod import_jobs;
 mod litchi_parser;
 mod models;
 mod parser;
 mod profile_auth;
+mod solar;
+mod srt_parser;
+mod sync_scan;
+mod sync_watch;
+mod timezone;
+mod tlog_parser;
+mod ulog_parser;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod keychain_mock;
 
 #[cfg(all(feature = "web", not(feature = "tauri-app")))]
 mod server;
@@ -33,18 +48,24 @@ mod session_store;
 
 #[cfg(feature = "tauri-app")]
 mod tauri_app {
-    use std::path::PathBuf;
-    use std::sync::{Arc, RwLock};
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex, RwLock};
 
-    use tauri::{AppHandle, Manager, State};
+    use tauri::{AppHandle, Emitter, Manager, State};
     use tauri_plugin_log::{Target, TargetKind};
     use log::LevelFilter;
 
-    use crate::database::{self, Database, DatabaseError};
-    use crate::models::{Flight, FlightDataResponse, FlightTag, ImportResult, OverviewStats, TelemetryData};
+    use crate::archive;
+    use crate::batch_import::{BatchImporter, BatchOutcome};
+    use crate::database::{self, Database, DatabaseError, ImportOptions};
+    use crate::formats;
+    use crate::import_jobs::ImportJobs;
+    use crate::models::{ArchiveImportResult, BatchImportProgress, BlacklistEntry, EquipmentItem, Flight, FlightDataResponse, FlightEquipment, FlightSideData, FlightTag, ImportJobStarted, ImportPreview, ImportResult, KeychainBundle, KeychainImportResult, MediaClip, MediaFrame, MediaImportResult, OverviewStats, PendingDecryption, PendingRetryResult, ReprocessResult, SupportedFormat, SyncPostImport, TelemetryData};
     use crate::parser::LogParser;
     use crate::api::DjiApi;
     use crate::profile_auth;
+    use crate::sync_scan::{SyncFilter, SyncScan};
+    use crate::sync_watch::{self, SyncWatcher};
 
     /// Application state containing the database connection (swappable for profile switching)
     pub struct AppState {
@@ -52,6 +73,10 @@ mod tauri_app {
         pub data_dir: PathBuf,
         /// When true, data commands are blocked until the user authenticates.
         locked: RwLock<bool>,
+        /// Running and recently finished background import jobs
+        import_jobs: ImportJobs,
+        /// Watcher of the active profile's inbox folder, if one is set
+        inbox: Mutex<Option<SyncWatcher>>,
     }
 
     impl AppState {
@@ -101,8 +126,63 @@ mod tauri_app {
             let profile = database::get_active_profile(&self.data_dir);
             database::default_upload_folder(&self.data_dir, &profile)
         }
+
+        /// Config of the currently active profile, or an empty object if it is missing or invalid.
+        pub fn read_config(&self) -> serde_json::Value {
+            std::fs::read_to_string(self.config_path())
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or(serde_json::json!({}))
+        }
+
+        /// A single value from the currently active profile's config.
+        pub fn read_config_value(&self, key: &str) -> Option<serde_json::Value> {
+            self.read_config().get(key).cloned()
+        }
+
+        /// Change the currently active profile's config and write it back, keeping other keys.
+        pub fn update_config(&self, update: impl FnOnce(&mut serde_json::Value)) -> Result<(), String> {
+            let mut config = self.read_config();
+            update(&mut config);
+            std::fs::write(self.config_path(), serde_json::to_string_pretty(&config).unwrap())
+                .map_err(|e| format!("Failed to write config: {}", e))
+        }
+
+        /// Folder that imported logs are copied to when keeping is enabled.
+        pub fn upload_folder(&self) -> PathBuf {
+            self.read_config_value("uploaded_files_path")
+                .and_then(|v| v.as_str().map(PathBuf::from))
+                .unwrap_or_else(|| self.default_upload_folder())
+        }
+
+        /// Folder that imported logs are kept in, or `None` when keeping is disabled.
+        pub fn kept_upload_folder(&self) -> Option<PathBuf> {
+            let keep = self.read_config_value("keep_uploaded_files")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            keep.then(|| self.upload_folder())
+        }
+
+        /// Import options from the currently active profile's config.
+        pub fn import_options(&self) -> ImportOptions {
+            ImportOptions::from_config(&self.read_config(), &database::get_active_profile(&self.data_dir))
+        }
+
+        /// Inbox folder of the currently active profile, watched for new logs.
+        pub fn inbox_folder(&self) -> Option<PathBuf> {
+            self.read_config_value("inbox_folder")
+                .and_then(|v| v.as_str().map(PathBuf::from))
+        }
+
+        /// What happens to inbox files after importing them.
+        pub fn post_import(&self) -> SyncPostImport {
+            SyncPostImport::from_config(&self.read_config())
+        }
     }
 
+    /// How often queued encrypted logs are retried in the background
+    const PENDING_RETRY_INTERVAL_SECS: u64 = 600;
+
     /// Get the app data directory for storing the database and logs
     fn app_data_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
         app.path()
@@ -221,13 +301,11 @@ mod tauri_app {
         Database::new(data_dir, &profile).map_err(|e| format!("Failed to initialize database: {}", e))
     }
 
+    /// Import a single log file through the batch import pipeline
     #[tauri::command]
     pub async fn import_log(file_path: String, state: State<'_, AppState>) -> Result<ImportResult, String> {
-        let import_start = std::time::Instant::now();
         log::info!("Importing log file: {}", file_path);
-
         let path = PathBuf::from(&file_path);
-
         if !path.exists() {
             log::warn!("File not found: {}", file_path);
             return Ok(ImportResult {
@@ -238,187 +316,339 @@ mod tauri_app {
                 file_hash: None,
             });
         }
+        let db = state.db_authenticated()?;
+        let result = BatchImporter::new(db, state.import_options()).import_one(&path).await;
+        keep_imported_files(std::slice::from_ref(&result), state.kept_upload_folder());
+        Ok(result.to_import_result())
+    }
 
-        // Load config early for keep_uploaded_files setting
-        let config_path = state.config_path();
-        let config: serde_json::Value = if config_path.exists() {
-            std::fs::read_to_string(&config_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        let keep_enabled = config.get("keep_uploaded_files").and_then(|v| v.as_bool()).unwrap_or(true);
-        let default_folder = state.default_upload_folder();
-        let upload_folder = config.get("uploaded_files_path")
-            .and_then(|v| v.as_str())
-            .map(|s| PathBuf::from(s))
-            .unwrap_or(default_folder);
-
-        // Helper to copy uploaded file if setting is enabled
-        let try_copy_file = |file_hash: Option<&str>| {
-            if keep_enabled {
-                if let Err(e) = copy_uploaded_file(&path, &upload_folder, file_hash) {
+    /// Import many log files at once through the parallel batch importer.
+    /// Emits `import-progress` events with a [`BatchImportProgress`] after every
+    /// file and returns one result per path, in order.
+    #[tauri::command]
+    pub async fn import_logs(file_paths: Vec<String>, app: AppHandle, state: State<'_, AppState>) -> Result<Vec<ImportResult>, String> {
+        let paths: Vec<PathBuf> = file_paths.iter().map(PathBuf::from).collect();
+        let summary = run_batch_import(paths, &app, &state).await?;
+        Ok(summary.iter().map(|file| file.to_import_result()).collect())
+    }
+
+    /// Batch-import `paths` into the active profile, keeping copies of the
+    /// imported logs when enabled
+    async fn run_batch_import(
+        paths: Vec<PathBuf>,
+        app: &AppHandle,
+        state: &AppState,
+    ) -> Result<Vec<crate::batch_import::BatchFileResult>, String> {
+        let db = state.db_authenticated()?;
+        let summary = BatchImporter::new(db, state.import_options())
+            .run(paths, |progress: &BatchImportProgress| {
+                let _ = app.emit("import-progress", progress);
+            })
+            .await;
+        keep_imported_files(&summary.files, state.kept_upload_folder());
+        Ok(summary.files)
+    }
+
+    /// Copy imported (or already known) logs of a batch to the kept upload folder
+    fn keep_imported_files(files: &[crate::batch_import::BatchFileResult], upload_folder: Option<PathBuf>) {
+        let Some(upload_folder) = upload_folder else { return };
+        for file in files {
+            if matches!(file.outcome, BatchOutcome::Imported { .. } | BatchOutcome::Duplicate { .. }) {
+                if let Err(e) = copy_uploaded_file(&file.path, &upload_folder, file.file_hash.as_deref()) {
                     log::warn!("Failed to copy uploaded file: {}", e);
                 }
             }
-        };
+        }
+    }
 
+    /// Start importing log files in the background. Returns the job id right
+    /// away; progress is emitted as `import-job` events (one `ImportJobEvent`
+    /// per step, ending with `done`).
+    #[tauri::command]
+    pub async fn start_import_job(file_paths: Vec<String>, app: AppHandle, state: State<'_, AppState>) -> Result<ImportJobStarted, String> {
         let db = state.db_authenticated()?;
-        let parser = LogParser::new(&db);
+        let paths: Vec<PathBuf> = file_paths.iter().map(PathBuf::from).collect();
+        let total = paths.len();
+        let importer = BatchImporter::new(db, state.import_options());
+        let upload_folder = state.kept_upload_folder();
+        let job = state.import_jobs.create(&database::get_active_profile(&state.data_dir));
+        let job_id = job.id().to_string();
+        log::info!("Starting import job {} ({} files)", job_id, total);
+
+        tauri::async_runtime::spawn(async move {
+            let summary = job
+                .run(importer, paths, |event| {
+                    let _ = app.emit("import-job", event);
+                })
+                .await;
+            keep_imported_files(&summary.files, upload_folder);
+        });
 
-        let parse_result = match parser.parse_log(&path).await {
-            Ok(result) => result,
-            Err(crate::parser::ParserError::AlreadyImported(matching_flight)) => {
-                log::info!("Skipping already-imported file: {} — matches flight '{}' in database", file_path, matching_flight);
-                // Compute file hash so copy_uploaded_file can properly deduplicate
-                let file_hash = LogParser::calculate_file_hash(&path).ok();
-                try_copy_file(file_hash.as_deref());
-                return Ok(ImportResult {
-                    success: false,
-                    flight_id: None,
-                    message: format!("This flight log has already been imported (matches: {})", matching_flight),
-                    point_count: 0,
-                    file_hash,
-                });
-            }
+        Ok(ImportJobStarted { job_id, total })
+    }
+
+    /// (Re)start watching the active profile's inbox folder. Logs already in
+    /// it are imported right away, new ones once they stopped changing.
+    fn restart_inbox(app: &AppHandle) {
+        let state = app.state::<AppState>();
+        // Dropping the old watcher also ends its event loop
+        *state.inbox.lock().unwrap() = None;
+        let Some(folder) = state.inbox_folder() else { return };
+        let (watcher, events) = match SyncWatcher::new(&folder) {
+            Ok(watcher) => watcher,
             Err(e) => {
-                log::error!("Failed to parse log {}: {}", file_path, e);
-                return Ok(ImportResult {
-                    success: false,
-                    flight_id: None,
-                    message: format!("Failed to parse log: {}", e),
-                    point_count: 0,
-                    file_hash: None,
-                });
+                log::warn!("Failed to watch inbox folder {}: {}", folder.display(), e);
+                return;
             }
         };
+        *state.inbox.lock().unwrap() = Some(watcher);
+        log::info!("Watching inbox folder {}", folder.display());
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            import_inbox_files(&app, &folder, None).await;
+            events
+                .run(sync_watch::settle_from_env(), |paths| {
+                    let app = app.clone();
+                    let folder = folder.clone();
+                    async move { import_inbox_files(&app, &folder, Some(paths)).await }
+                })
+                .await;
+        });
+    }
 
-        // Check for duplicate flight based on signature (drone_serial + battery_serial + start_time)
-        if let Some(matching_flight) = db.is_duplicate_flight(
-            parse_result.metadata.drone_serial.as_deref(),
-            parse_result.metadata.battery_serial.as_deref(),
-            parse_result.metadata.start_time,
-        ).unwrap_or(None) {
-            log::info!("Skipping duplicate flight (signature match): {} - matches flight '{}' in database", file_path, matching_flight);
-            // Still copy the file even though flight is a duplicate
-            try_copy_file(parse_result.metadata.file_hash.as_deref());
-            return Ok(ImportResult {
-                success: false,
-                flight_id: None,
-                message: format!("Duplicate flight: matches '{}' (same drone, battery, and start time)", matching_flight),
-                point_count: 0,
-                file_hash: parse_result.metadata.file_hash.clone(),
-            });
+    /// Import `paths` from the inbox `folder` (all new files when `None`).
+    /// Results are emitted as an `inbox-imported` event.
+    async fn import_inbox_files(app: &AppHandle, folder: &Path, paths: Option<Vec<PathBuf>>) {
+        let state = app.state::<AppState>();
+        // The profile was switched since the files were reported
+        if state.inbox_folder().as_deref() != Some(folder) {
+            return;
         }
-
-        log::debug!("Inserting flight metadata: id={}", parse_result.metadata.id);
-        let flight_id = db
-            .insert_flight(&parse_result.metadata)
-            .map_err(|e| format!("Failed to insert flight: {}", e))?;
-
-        let point_count = match db
-            .bulk_insert_telemetry(flight_id, &parse_result.points)
-        {
-            Ok(count) => count,
+        // While locked, files wait in the inbox until the profile is unlocked
+        let Ok(db) = state.db_authenticated() else { return };
+
+        let filter = SyncFilter::from_env();
+        let (scan_db, root) = (db.clone(), folder.to_path_buf());
+        let scan = tokio::task::spawn_blocking(move || match paths {
+            Some(paths) => Ok(SyncScan::for_files(&scan_db, &root, &filter, paths)),
+            None => SyncScan::new(&scan_db, &root, &filter),
+        })
+        .await;
+        let scan = match scan {
+            Ok(Ok(scan)) => scan,
+            Ok(Err(e)) => {
+                log::warn!("Failed to read inbox folder {}: {}", folder.display(), e);
+                return;
+            }
             Err(e) => {
-                log::error!("Failed to insert telemetry for flight {}: {}. Cleaning up.", flight_id, e);
-                if let Err(cleanup_err) = db.delete_flight(flight_id) {
-                    log::error!("Failed to clean up flight {}: {}", flight_id, cleanup_err);
-                }
-                return Ok(ImportResult {
-                    success: false,
-                    flight_id: None,
-                    message: format!("Failed to insert telemetry data: {}", e),
-                    point_count: 0,
-                    file_hash: parse_result.metadata.file_hash.clone(),
-                });
+                log::warn!("Inbox scan task failed: {}", e);
+                return;
             }
         };
-
-        // Insert smart tags if the feature is enabled
-        let config_path = state.config_path();
-        let config: serde_json::Value = if config_path.exists() {
-            std::fs::read_to_string(&config_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
-        
-        if tags_enabled {
-            // Filter tags based on enabled_tag_types if configured
-            let tags = if let Some(types) = config.get("enabled_tag_types").and_then(|v| v.as_array()) {
-                let enabled_types: Vec<String> = types.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect();
-                crate::parser::LogParser::filter_smart_tags(parse_result.tags.clone(), &enabled_types)
-            } else {
-                parse_result.tags.clone()
-            };
-            if let Err(e) = db.insert_flight_tags(flight_id, &tags) {
-                log::warn!("Failed to insert tags for flight {}: {}", flight_id, e);
-            }
+        if scan.pending.is_empty() {
+            return;
         }
 
-        // Insert manual tags from re-imported CSV exports (always inserted regardless of smart_tags_enabled)
-        for manual_tag in &parse_result.manual_tags {
-            if let Err(e) = db.add_flight_tag(flight_id, manual_tag) {
-                log::warn!("Failed to insert manual tag '{}' for flight {}: {}", manual_tag, flight_id, e);
-            }
+        let summary = BatchImporter::new(db.clone(), state.import_options())
+            .with_failure_registry()
+            .run(scan.paths(), |progress: &BatchImportProgress| {
+                let _ = app.emit("import-progress", progress);
+            })
+            .await;
+        scan.record(&db, &summary.files);
+        keep_imported_files(&summary.files, state.kept_upload_folder());
+        let moved = scan.apply_post_import(&db, folder, &summary.files, &state.post_import());
+        if let Some(e) = &moved.error {
+            log::warn!("Inbox files left in place: {}", e);
         }
+        log::info!(
+            "Inbox import: {} imported, {} skipped, {} failed, {} archived, {} rejected",
+            summary.progress.imported, summary.progress.skipped, summary.progress.failed, moved.archived, moved.rejected
+        );
+        let results: Vec<ImportResult> = summary.files.iter().map(|file| file.to_import_result()).collect();
+        let _ = app.emit("inbox-imported", &results);
+    }
 
-        // Auto-tag with profile name for non-default profiles
-        {
-            let profile = database::get_active_profile(&state.data_dir);
-            if profile != "default" {
-                if let Err(e) = db.add_flight_tag(flight_id, &profile) {
-                    log::warn!("Failed to insert profile tag '{}' for flight {}: {}", profile, flight_id, e);
-                }
-            }
-        }
+    #[tauri::command]
+    pub async fn get_inbox_folder(state: State<'_, AppState>) -> Result<Option<String>, String> {
+        Ok(state.inbox_folder().map(|p| p.to_string_lossy().to_string()))
+    }
 
-        // Insert notes from re-imported CSV exports
-        if let Some(ref notes) = parse_result.notes {
-            if let Err(e) = db.update_flight_notes(flight_id, Some(notes.as_str())) {
-                log::warn!("Failed to insert notes for flight {}: {}", flight_id, e);
+    /// Set (or clear, with `None`) the active profile's inbox folder and restart its watcher
+    #[tauri::command]
+    pub async fn set_inbox_folder(path: Option<String>, app: AppHandle, state: State<'_, AppState>) -> Result<Option<String>, String> {
+        state.db_authenticated()?;
+        let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+        if let Some(folder) = &path {
+            if !Path::new(folder).is_dir() {
+                return Err(format!("Inbox folder does not exist: {}", folder));
             }
         }
+        state.update_config(|config| {
+            config["inbox_folder"] = serde_json::json!(path);
+        })?;
+        restart_inbox(&app);
+        Ok(path)
+    }
 
-        // Apply color from re-imported CSV exports
-        if let Some(ref color) = parse_result.color {
-            if let Err(e) = db.update_flight_color(flight_id, color) {
-                log::warn!("Failed to set color for flight {}: {}", flight_id, e);
-            }
-        }
+    /// Cancel an import job of the active profile; files already being parsed still finish
+    #[tauri::command]
+    pub fn cancel_import_job(job_id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        let profile = database::get_active_profile(&state.data_dir);
+        let job = state
+            .import_jobs
+            .get(&job_id)
+            .filter(|job| job.profile() == profile)
+            .ok_or("Import job not found")?;
+        job.cancel();
+        log::info!("Cancel requested for import job {}", job_id);
+        Ok(!job.is_finished())
+    }
 
-        // Insert app messages (tips and warnings) from DJI logs
-        if !parse_result.messages.is_empty() {
-            if let Err(e) = db.insert_flight_messages(flight_id, &parse_result.messages) {
-                log::warn!("Failed to insert messages for flight {}: {}", flight_id, e);
-            }
+    /// Import every flight log contained in a ZIP or tar.gz archive.
+    /// Each entry runs through the batch import pipeline and gets its own result.
+    #[tauri::command]
+    pub async fn import_archive(file_path: String, app: AppHandle, state: State<'_, AppState>) -> Result<Vec<ArchiveImportResult>, String> {
+        log::info!("Importing archive: {}", file_path);
+        let path = PathBuf::from(&file_path);
+        if !archive::is_archive(&path) {
+            return Err("Unsupported archive type (expected .zip, .tar.gz or .tgz)".to_string());
         }
 
+        let extracted = tokio::task::spawn_blocking(move || archive::extract(&path))
+            .await
+            .map_err(|e| format!("Archive extraction task failed: {}", e))?
+            .map_err(|e| format!("Failed to extract archive: {}", e))?;
+
+        let paths = extracted.entries.iter().map(|entry| entry.path.clone()).collect();
+        let files = run_batch_import(paths, &app, &state).await?;
+        let results: Vec<ArchiveImportResult> = extracted
+            .entries
+            .iter()
+            .zip(&files)
+            .map(|(entry, file)| ArchiveImportResult {
+                entry_name: entry.name.clone(),
+                result: file.to_import_result(),
+            })
+            .collect();
+
         log::info!(
-            "Successfully imported flight {} with {} points in {:.1}s",
-            flight_id,
-            point_count,
-            import_start.elapsed().as_secs_f64()
+            "Archive import finished: {} of {} logs imported",
+            results.iter().filter(|r| r.result.success).count(),
+            results.len()
         );
+        Ok(results)
+    }
 
-        // Copy uploaded file if setting is enabled
-        try_copy_file(parse_result.metadata.file_hash.as_deref());
+    /// Import DJI video subtitle (.SRT) sidecars as media clips, linked to
+    /// the flights they were shot during (of `drone_serial` when given)
+    #[tauri::command]
+    pub async fn import_media_files(
+        file_paths: Vec<String>,
+        drone_serial: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<MediaImportResult>, String> {
+        let db = state.db_authenticated()?;
+        let parser = LogParser::new(&db);
+        let drone_serial = drone_serial.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let results: Vec<MediaImportResult> = file_paths
+            .iter()
+            .map(|path| parser.import_media_file(std::path::Path::new(path), drone_serial))
+            .collect();
+        log::info!(
+            "Imported {} of {} video subtitle files",
+            results.iter().filter(|r| r.success).count(),
+            results.len()
+        );
+        Ok(results)
+    }
 
-        Ok(ImportResult {
-            success: true,
-            flight_id: Some(flight_id),
-            message: format!("Successfully imported {} telemetry points", point_count),
-            point_count,
-            file_hash: parse_result.metadata.file_hash.clone(),
-        })
+    #[tauri::command]
+    pub async fn get_media_clips(state: State<'_, AppState>) -> Result<Vec<MediaClip>, String> {
+        state
+            .db_authenticated()?
+            .get_media_clips()
+            .map_err(|e| format!("Failed to get media clips: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_media_frames(clip_id: i64, state: State<'_, AppState>) -> Result<Vec<MediaFrame>, String> {
+        state
+            .db_authenticated()?
+            .get_media_frames(clip_id)
+            .map_err(|e| format!("Failed to get media frames: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn delete_media_clip(clip_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_media_clip(clip_id)
+            .map_err(|e| format!("Failed to delete media clip: {}", e))
+    }
+
+    /// Dry-run import: parse a log and report what importing it would do, without writing to the database
+    #[tauri::command]
+    pub async fn preview_import(file_path: String, state: State<'_, AppState>) -> Result<ImportPreview, String> {
+        log::info!("Previewing import of: {}", file_path);
+        let path = PathBuf::from(&file_path);
+        if !path.exists() {
+            return Err("File not found".to_string());
+        }
+
+        let import_options = state.import_options();
+
+        let db = state.db_authenticated()?;
+        let parser = LogParser::new(&db);
+        parser
+            .preview_import(&path, &import_options)
+            .await
+            .map_err(|e| format!("Failed to parse log: {}", e))
+    }
+
+    /// Re-parse flights from their kept original files and replace telemetry, stats and auto tags.
+    /// Reprocesses every flight when `flight_ids` is omitted.
+    #[tauri::command]
+    pub async fn reprocess_flights(
+        flight_ids: Option<Vec<i64>>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<ReprocessResult>, String> {
+        let db = state.db_authenticated()?;
+        let flight_ids = match flight_ids {
+            Some(ids) => ids,
+            None => db.get_all_flight_ids().map_err(|e| format!("Failed to get flight IDs: {}", e))?,
+        };
+        log::info!("Reprocessing {} flights", flight_ids.len());
+
+        let import_options = state.import_options();
+        // Originals may have been kept on import or still sit in the inbox folder
+        let mut search_folders = vec![state.upload_folder()];
+        search_folders.extend(state.inbox_folder());
+
+        let parser = LogParser::new(&db);
+        let mut results = Vec::with_capacity(flight_ids.len());
+        for flight_id in flight_ids {
+            let result = match parser.reprocess_flight(flight_id, &search_folders, &import_options).await {
+                Ok(point_count) => ReprocessResult {
+                    flight_id,
+                    success: true,
+                    message: format!("Reprocessed {} telemetry points", point_count),
+                    point_count,
+                },
+                Err(e) => {
+                    log::warn!("Failed to reprocess flight {}: {}", flight_id, e);
+                    ReprocessResult {
+                        flight_id,
+                        success: false,
+                        message: e.to_string(),
+                        point_count: 0,
+                    }
+                }
+            };
+            results.push(result);
+        }
+        Ok(results)
     }
 
     /// Create a manual flight entry without importing a log file
@@ -549,6 +779,12 @@ mod tauri_app {
             .map_err(|e| format!("Failed to compute hash: {}", e))
     }
 
+    /// List the flight log formats the importer accepts
+    #[tauri::command]
+    pub fn list_supported_formats() -> Vec<SupportedFormat> {
+        formats::list_supported_formats()
+    }
+
     #[tauri::command]
     pub async fn get_flights(state: State<'_, AppState>) -> Result<Vec<Flight>, String> {
         let start = std::time::Instant::now();
@@ -597,6 +833,14 @@ mod tauri_app {
                 Vec::new()
             });
 
+        // Videos shot during the flight
+        let media_clips = db
+            .get_flight_media_clips(flight_id)
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to get media clips for flight {}: {}", flight_id, e);
+                Vec::new()
+            });
+
         log::debug!(
             "get_flight_data for flight {} complete in {:.1}ms: {} telemetry series, {} track points, {} messages",
             flight_id,
@@ -611,9 +855,18 @@ mod tauri_app {
             telemetry,
             track,
             messages,
+            media_clips,
         })
     }
 
+    #[tauri::command]
+    pub async fn get_flight_side_data(flight_id: i64, state: State<'_, AppState>) -> Result<FlightSideData, String> {
+        state
+            .db_authenticated()?
+            .get_flight_side_data(flight_id)
+            .map_err(|e| format!("Failed to get side data: {}", e))
+    }
+
     #[tauri::command]
     pub async fn get_overview_stats(state: State<'_, AppState>) -> Result<OverviewStats, String> {
         let start = std::time::Instant::now();
@@ -778,6 +1031,98 @@ mod tauri_app {
             .map_err(|e| format!("Failed to set equipment name: {}", e))
     }
 
+    #[tauri::command]
+    pub async fn get_equipment_inventory(state: State<'_, AppState>) -> Result<Vec<EquipmentItem>, String> {
+        state.db_authenticated()?.get_equipment_inventory()
+            .map_err(|e| format!("Failed to get equipment inventory: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_flight_equipment(flight_id: i64, state: State<'_, AppState>) -> Result<Option<FlightEquipment>, String> {
+        state.db_authenticated()?.get_flight_equipment(flight_id)
+            .map_err(|e| format!("Failed to get flight equipment: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_blacklist(state: State<'_, AppState>) -> Result<Vec<BlacklistEntry>, String> {
+        state.db_authenticated()?.get_blacklist()
+            .map_err(|e| format!("Failed to get blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn add_to_blacklist(
+        file_hash: String,
+        file_name: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        state.db_authenticated()?.add_to_blacklist(&file_hash, file_name.as_deref())
+            .map(|_| true)
+            .map_err(|e| format!("Failed to add to blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn remove_from_blacklist(file_hash: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state.db_authenticated()?.remove_from_blacklist(&file_hash)
+            .map_err(|e| format!("Failed to remove from blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn clear_blacklist(state: State<'_, AppState>) -> Result<usize, String> {
+        state.db_authenticated()?.clear_blacklist()
+            .map_err(|e| format!("Failed to clear blacklist: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn export_keychains(dest_path: String, state: State<'_, AppState>) -> Result<usize, String> {
+        log::info!("Exporting keychain bundle to: {}", dest_path);
+        let bundle = state
+            .db_authenticated()?
+            .export_keychain_bundle()
+            .map_err(|e| format!("Failed to export keychains: {}", e))?;
+        let json = serde_json::to_string_pretty(&bundle)
+            .map_err(|e| format!("Failed to serialize keychains: {}", e))?;
+        std::fs::write(&dest_path, json)
+            .map_err(|e| format!("Failed to write keychain bundle: {}", e))?;
+        Ok(bundle.entries.len())
+    }
+
+    #[tauri::command]
+    pub async fn import_keychains(src_path: String, state: State<'_, AppState>) -> Result<KeychainImportResult, String> {
+        log::info!("Importing keychain bundle from: {}", src_path);
+        let json = std::fs::read_to_string(&src_path)
+            .map_err(|e| format!("Failed to read keychain bundle: {}", e))?;
+        let bundle: KeychainBundle = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid keychain bundle: {}", e))?;
+        if bundle.format != database::KEYCHAIN_BUNDLE_FORMAT {
+            return Err("Not an Open DroneLog keychain bundle".to_string());
+        }
+
+        let db = state.db_authenticated()?;
+        let imported = db
+            .import_keychain_bundle(&bundle)
+            .map_err(|e| format!("Failed to import keychains: {}", e))?;
+        let pending = LogParser::new(&db).retry_pending_decryption(&state.import_options()).await;
+        Ok(KeychainImportResult { imported, pending })
+    }
+
+    #[tauri::command]
+    pub async fn get_pending_decryption(state: State<'_, AppState>) -> Result<Vec<PendingDecryption>, String> {
+        state.db_authenticated()?.get_pending_decryption()
+            .map_err(|e| format!("Failed to get pending decryption queue: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn retry_pending_decryption(state: State<'_, AppState>) -> Result<PendingRetryResult, String> {
+        let db = state.db_authenticated()?;
+        Ok(LogParser::new(&db).retry_pending_decryption(&state.import_options()).await)
+    }
+
+    #[tauri::command]
+    pub async fn remove_pending_decryption(file_hash: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state.db_authenticated()?.remove_pending_decryption(&file_hash)
+            .map_err(|e| format!("Failed to remove from pending decryption: {}", e))
+    }
+
     #[tauri::command]
     pub async fn export_backup(dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
         let path = std::path::PathBuf::from(&dest_path);
@@ -853,16 +1198,9 @@ mod tauri_app {
 
     #[tauri::command]
     pub async fn set_smart_tags_enabled(enabled: bool, state: State<'_, AppState>) -> Result<bool, String> {
-        let config_path = state.config_path();
-        let mut config: serde_json::Value = if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
-            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        config["smart_tags_enabled"] = serde_json::json!(enabled);
-        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.update_config(|config| {
+            config["smart_tags_enabled"] = serde_json::json!(enabled);
+        })?;
         Ok(enabled)
     }
 
@@ -882,7 +1220,8 @@ mod tauri_app {
         }
         // Default: return all tag types
         Ok(vec![
-            "night_flight".to_string(), "high_speed".to_string(), "cold_battery".to_string(),
+            "night_flight".to_string(), "twilight_flight".to_string(), "golden_hour".to_string(),
+            "high_speed".to_string(), "cold_battery".to_string(),
             "heavy_load".to_string(), "low_battery".to_string(), "high_altitude".to_string(),
             "long_distance".to_string(), "long_flight".to_string(), "short_flight".to_string(),
             "aggressive_flying".to_string(), "no_gps".to_string(), "country".to_string(),
@@ -892,16 +1231,9 @@ mod tauri_app {
 
     #[tauri::command]
     pub async fn set_enabled_tag_types(types: Vec<String>, state: State<'_, AppState>) -> Result<Vec<String>, String> {
-        let config_path = state.config_path();
-        let mut config: serde_json::Value = if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
-            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        config["enabled_tag_types"] = serde_json::json!(types.clone());
-        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.update_config(|config| {
+            config["enabled_tag_types"] = serde_json::json!(types.clone());
+        })?;
         Ok(types)
     }
 
@@ -937,22 +1269,13 @@ mod tauri_app {
 
     #[tauri::command]
     pub async fn set_keep_upload_settings(enabled: bool, folder_path: Option<String>, state: State<'_, AppState>) -> Result<KeepUploadSettings, String> {
-        let config_path = state.config_path();
         let default_folder = state.default_upload_folder().to_string_lossy().to_string();
         let actual_folder = folder_path.unwrap_or(default_folder);
         
-        let mut config: serde_json::Value = if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
-            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        
-        config["keep_uploaded_files"] = serde_json::json!(enabled);
-        config["uploaded_files_path"] = serde_json::json!(actual_folder.clone());
-        
-        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.update_config(|config| {
+            config["keep_uploaded_files"] = serde_json::json!(enabled);
+            config["uploaded_files_path"] = serde_json::json!(actual_folder.clone());
+        })?;
         
         Ok(KeepUploadSettings { enabled, folder_path: actual_folder })
     }
@@ -973,16 +1296,9 @@ mod tauri_app {
 
     #[tauri::command]
     pub async fn set_auto_logout(enabled: bool, state: State<'_, AppState>) -> Result<bool, String> {
-        let config_path = state.config_path();
-        let mut config: serde_json::Value = if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path).unwrap_or_default();
-            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        config["auto_logout"] = serde_json::json!(enabled);
-        std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.update_config(|config| {
+            config["auto_logout"] = serde_json::json!(enabled);
+        })?;
         Ok(enabled)
     }
 
@@ -1231,7 +1547,7 @@ mod tauri_app {
     /// Authenticate the current profile to unlock the app.
     /// This does NOT switch profiles — it only verifies the password and lifts the lock.
     #[tauri::command]
-    pub async fn unlock_profile(password: String, state: State<'_, AppState>) -> Result<bool, String> {
+    pub async fn unlock_profile(password: String, app: AppHandle, state: State<'_, AppState>) -> Result<bool, String> {
         let profile = database::get_active_profile(&state.data_dir);
         if !profile_auth::profile_is_protected(&state.data_dir, &profile) {
             // Not protected — just unlock
@@ -1241,6 +1557,8 @@ mod tauri_app {
         profile_auth::verify_profile_password(&state.data_dir, &profile, &password)?;
         state.unlock();
         log::info!("Profile '{}' unlocked via password", profile);
+        // Import what arrived in the inbox while locked
+        restart_inbox(&app);
         Ok(true)
     }
 
@@ -1250,6 +1568,7 @@ mod tauri_app {
         create: bool,
         password: Option<String>,
         new_password: Option<String>,
+        app: AppHandle,
         state: State<'_, AppState>,
     ) -> Result<String, String> {
         let profile = name.trim().to_string();
@@ -1313,6 +1632,7 @@ mod tauri_app {
             }
         }
 
+        restart_inbox(&app);
         log::info!("Switched to profile '{}'", profile);
         Ok(profile)
     }
@@ -1430,6 +1750,27 @@ mod tauri_app {
                     active_db: RwLock::new(Arc::new(db)),
                     data_dir,
                     locked: RwLock::new(start_locked),
+                    import_jobs: ImportJobs::default(),
+                    inbox: Mutex::new(None),
+                });
+                restart_inbox(app.handle());
+
+                // Retry logs waiting for keychains, e.g. after the network comes back
+                let retry_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        tokio::time::sleep(std::time::Duration::from_secs(PENDING_RETRY_INTERVAL_SECS)).await;
+                        let Some(state) = retry_handle.try_state::<AppState>() else { continue };
+                        let Ok(db) = state.db_authenticated() else { continue };
+                        if db.get_pending_decryption().map_or(true, |pending| pending.is_empty()) {
+                            continue;
+                        }
+                        let result = LogParser::new(&db).retry_pending_decryption(&state.import_options()).await;
+                        log::info!(
+                            "Pending decryption retry: {} imported, {} still pending, {} failed",
+                            result.imported, result.still_pending, result.failed
+                        );
+                    }
                 });
                 log::info!("Open DroneLog initialized successfully");
                 Ok(())
@@ -1470,10 +1811,24 @@ mod tauri_app {
             })
             .invoke_handler(tauri::generate_handler![
                 import_log,
+                import_logs,
+                start_import_job,
+                cancel_import_job,
+                get_inbox_folder,
+                set_inbox_folder,
+                import_archive,
+                preview_import,
+                import_media_files,
+                get_media_clips,
+                get_media_frames,
+                delete_media_clip,
+                reprocess_flights,
                 create_manual_flight,
                 compute_file_hash,
+                list_supported_formats,
                 get_flights,
                 get_flight_data,
+                get_flight_side_data,
                 get_overview_stats,
                 delete_flight,
                 delete_all_flights,
@@ -1489,6 +1844,17 @@ mod tauri_app {
                 get_app_log_dir,
                 get_equipment_names,
                 set_equipment_name,
+                get_equipment_inventory,
+                get_flight_equipment,
+                get_blacklist,
+                add_to_blacklist,
+                remove_from_blacklist,
+                clear_blacklist,
+                export_keychains,
+                import_keychains,
+                get_pending_decryption,
+                retry_pending_decryption,
+                remove_pending_decryption,
                 export_backup,
                 import_backup,
                 add_flight_tag,
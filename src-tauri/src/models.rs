@@ -45,7 +45,13 @@ pub struct Flight {
     pub battery_serial: Option<String>,
     pub cycle_count: Option<i32>,
     pub start_time: Option<String>,
+    /// IANA time zone at the home point (e.g. "Europe/Berlin")
+    pub local_timezone: Option<String>,
+    /// Start time as local wall-clock time in `local_timezone`, without offset
+    pub local_start_time: Option<String>,
     pub duration_secs: Option<f64>,
+    /// Seconds flown after the end of civil twilight (sun more than 6° below the horizon)
+    pub night_secs: Option<f64>,
     pub total_distance: Option<f64>,
     pub max_altitude: Option<f64>,
     pub max_speed: Option<f64>,
@@ -160,6 +166,12 @@ pub struct TelemetryRecord {
     pub rc_rudder: Option<f64>,
     pub is_photo: Option<bool>,
     pub is_video: Option<bool>,
+    pub altitude_abs: Option<f64>,
+    pub battery_current: Option<f64>,
+    pub gimbal_pitch: Option<f64>,
+    pub gimbal_roll: Option<f64>,
+    pub gimbal_yaw: Option<f64>,
+    pub gps_signal: Option<i32>,
 }
 
 /// Flight message (tip or warning from DJI app)
@@ -171,6 +183,150 @@ pub struct FlightMessage {
     pub message: String,
 }
 
+/// Home point / return-to-home settings as recorded by the aircraft.
+/// Only stored when one of the values changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeUpdate {
+    pub timestamp_ms: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Home point altitude (MSL, meters)
+    pub altitude: f64,
+    /// Configured RTH altitude (meters above home)
+    pub go_home_height: f64,
+    /// Configured max altitude (meters above home)
+    pub max_allowed_height: f64,
+    pub go_home_mode: Option<String>,
+    pub dynamic_home_point: bool,
+}
+
+/// Position of the remote controller (pilot location) from the RC GPS module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RcGpsPoint {
+    pub timestamp_ms: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub satellites: i32,
+    pub accuracy: Option<f64>,
+}
+
+/// Obstacle avoidance settings and downward vision sensor state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvoidanceState {
+    pub timestamp_ms: i64,
+    pub obstacle_avoidance_enabled: Option<bool>,
+    pub user_avoidance_enabled: Option<bool>,
+    pub vision_positioning_used: bool,
+    pub ultrasonic_working: bool,
+    /// Height reported by the downward vision / ultrasonic sensors (meters)
+    pub vision_height: Option<f64>,
+}
+
+/// Motor / ESC status flags reported by the flight controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotorStatus {
+    pub timestamp_ms: i64,
+    pub motors_on: bool,
+    pub motor_blocked: bool,
+    pub not_enough_force: bool,
+    pub propeller_catapult: bool,
+    pub start_failed_cause: Option<String>,
+}
+
+/// Wind-related warning raised by the DJI app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindWarning {
+    pub timestamp_ms: i64,
+    pub message_type: String, // "tip" or "warn"
+    pub message: String,
+}
+
+/// Additional DJI records kept alongside the telemetry, keyed by timestamp.
+/// Empty for formats that do not carry these records.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightSideData {
+    pub home_updates: Vec<HomeUpdate>,
+    pub rc_gps: Vec<RcGpsPoint>,
+    pub avoidance: Vec<AvoidanceState>,
+    pub motor_status: Vec<MotorStatus>,
+    pub wind_warnings: Vec<WindWarning>,
+}
+
+impl FlightSideData {
+    pub fn is_empty(&self) -> bool {
+        self.home_updates.is_empty()
+            && self.rc_gps.is_empty()
+            && self.avoidance.is_empty()
+            && self.motor_status.is_empty()
+            && self.wind_warnings.is_empty()
+    }
+}
+
+/// Equipment and software details recorded in a flight log header
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightEquipment {
+    /// Flying app, e.g. "DJI Fly" or "DJI Pilot"
+    pub app_name: Option<String>,
+    /// OS the app ran on, e.g. "IOS" or "Android"
+    pub app_platform: Option<String>,
+    pub app_version: Option<String>,
+    pub aircraft_firmware: Option<String>,
+    pub battery_firmware: Option<String>,
+    pub rc_serial: Option<String>,
+    pub rc_firmware: Option<String>,
+    pub camera_serial: Option<String>,
+    pub camera_firmware: Option<String>,
+    pub gimbal_firmware: Option<String>,
+}
+
+impl FlightEquipment {
+    pub fn is_empty(&self) -> bool {
+        self.app_name.is_none()
+            && self.app_platform.is_none()
+            && self.app_version.is_none()
+            && self.aircraft_firmware.is_none()
+            && self.battery_firmware.is_none()
+            && self.rc_serial.is_none()
+            && self.rc_firmware.is_none()
+            && self.camera_serial.is_none()
+            && self.camera_firmware.is_none()
+            && self.gimbal_firmware.is_none()
+    }
+}
+
+/// A firmware version seen on one piece of equipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareVersion {
+    pub version: String,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    pub flight_count: i64,
+}
+
+/// One aircraft, battery, remote controller or camera in the equipment inventory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipmentItem {
+    pub serial: String,
+    pub equipment_type: String, // "aircraft", "battery", "rc" or "camera"
+    pub display_name: Option<String>,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    /// Firmware reported by the most recent flight
+    pub firmware: Option<String>,
+    pub flight_count: i64,
+    /// Firmware versions in the order they were first flown
+    pub firmware_history: Vec<FirmwareVersion>,
+}
+
 /// Response format optimized for ECharts rendering
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -179,6 +335,85 @@ pub struct FlightDataResponse {
     pub telemetry: TelemetryData,
     pub track: Vec<[f64; 3]>, // [lng, lat, height] for map
     pub messages: Vec<FlightMessage>,
+    /// Video clips shot during the flight, from their .SRT sidecars
+    pub media_clips: Vec<MediaClip>,
+}
+
+/// One frame of a DJI video subtitle (.SRT) sidecar
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaFrame {
+    /// Position of the frame in the video
+    pub offset_ms: i64,
+    pub end_ms: i64,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Height above the takeoff point (meters)
+    pub rel_altitude: Option<f64>,
+    /// Altitude above sea level (meters)
+    pub abs_altitude: Option<f64>,
+    pub iso: Option<i32>,
+    /// Exposure time as written by the camera, e.g. `1/1000.0`
+    pub shutter: Option<String>,
+    pub fnum: Option<f64>,
+    pub ev: Option<f64>,
+    pub focal_length: Option<f64>,
+    /// White balance (Kelvin)
+    pub color_temp: Option<i32>,
+}
+
+/// A video clip imported from its DJI subtitle sidecar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaClip {
+    pub id: i64,
+    /// Flight the clip was shot during, `None` until a matching flight is imported
+    pub flight_id: Option<i64>,
+    pub srt_file: String,
+    pub video_file: String,
+    pub file_hash: String,
+    pub drone_serial: Option<String>,
+    /// Camera wall-clock time (no time zone)
+    pub start_time: String,
+    pub end_time: String,
+    pub duration_secs: f64,
+    pub frame_count: usize,
+    /// Camera settings of the first frame
+    pub iso: Option<i32>,
+    pub shutter: Option<String>,
+    pub fnum: Option<f64>,
+    /// Seconds from the flight's start to the clip's start
+    pub flight_offset_secs: Option<f64>,
+    /// Up to [`MediaClip::TRACK_POINTS`] positions, [lng, lat, height]
+    pub track: Vec<[f64; 3]>,
+}
+
+impl MediaClip {
+    /// Positions kept in [`MediaClip::track`]
+    pub const TRACK_POINTS: usize = 200;
+}
+
+/// A subtitle sidecar parsed by [`crate::srt_parser`], before import
+#[derive(Debug, Clone)]
+pub struct ParsedMediaClip {
+    pub srt_file: String,
+    pub video_file: String,
+    pub file_hash: String,
+    pub start_time: chrono::NaiveDateTime,
+    pub end_time: chrono::NaiveDateTime,
+    pub frames: Vec<MediaFrame>,
+}
+
+/// Result of importing one subtitle sidecar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaImportResult {
+    pub success: bool,
+    pub clip_id: Option<i64>,
+    /// Flight the clip was linked to
+    pub flight_id: Option<i64>,
+    pub message: String,
+    pub frame_count: usize,
 }
 
 /// Overview statistics across all flights
@@ -188,6 +423,8 @@ pub struct OverviewStats {
     pub total_flights: i64,
     pub total_distance_m: f64,
     pub total_duration_secs: f64,
+    /// Total time flown after the end of civil twilight
+    pub total_night_secs: f64,
     pub total_points: i64,
     pub total_photos: i64,
     pub total_videos: i64,
@@ -323,6 +560,18 @@ pub struct TelemetryData {
     pub is_video: Vec<Option<bool>>,
     /// Flight mode (e.g., "GPS", "ATTI", "Sport")
     pub flight_mode: Vec<Option<String>>,
+    /// Absolute altitude above mean sea level
+    pub altitude_abs: Vec<Option<f64>>,
+    /// Battery current in amps (positive while discharging)
+    pub battery_current: Vec<Option<f64>>,
+    /// Gimbal pitch angle
+    pub gimbal_pitch: Vec<Option<f64>>,
+    /// Gimbal roll angle
+    pub gimbal_roll: Vec<Option<f64>>,
+    /// Gimbal yaw angle
+    pub gimbal_yaw: Vec<Option<f64>>,
+    /// GPS signal level (0-5)
+    pub gps_signal: Vec<Option<i32>>,
 }
 
 impl TelemetryData {
@@ -362,6 +611,12 @@ impl TelemetryData {
         let mut is_photo = Vec::with_capacity(n);
         let mut is_video = Vec::with_capacity(n);
         let mut flight_mode = Vec::with_capacity(n);
+        let mut altitude_abs = Vec::with_capacity(n);
+        let mut battery_current = Vec::with_capacity(n);
+        let mut gimbal_pitch = Vec::with_capacity(n);
+        let mut gimbal_roll = Vec::with_capacity(n);
+        let mut gimbal_yaw = Vec::with_capacity(n);
+        let mut gps_signal = Vec::with_capacity(n);
 
         for r in records {
             time.push((r.timestamp_ms - base_time) as f64 / 1000.0);
@@ -392,6 +647,12 @@ impl TelemetryData {
             is_photo.push(r.is_photo);
             is_video.push(r.is_video);
             flight_mode.push(r.flight_mode.clone());
+            altitude_abs.push(r.altitude_abs);
+            battery_current.push(r.battery_current);
+            gimbal_pitch.push(r.gimbal_pitch);
+            gimbal_roll.push(r.gimbal_roll);
+            gimbal_yaw.push(r.gimbal_yaw);
+            gps_signal.push(r.gps_signal);
         }
 
         Self {
@@ -423,6 +684,12 @@ impl TelemetryData {
             is_photo,
             is_video,
             flight_mode,
+            altitude_abs,
+            battery_current,
+            gimbal_pitch,
+            gimbal_roll,
+            gimbal_yaw,
+            gps_signal,
         }
     }
 
@@ -487,6 +754,29 @@ pub fn count_media_events(points: &[TelemetryPoint]) -> (i32, i32) {
     (photo_count, video_count)
 }
 
+/// A parsed flight log, independent of the database.
+///
+/// `metadata.id` is 0 until the flight is imported.
+#[derive(Debug, Clone)]
+pub struct ParsedFlight {
+    pub metadata: FlightMetadata,
+    pub points: Vec<TelemetryPoint>,
+    /// Suggested smart tags
+    pub tags: Vec<String>,
+    /// Manual tags to preserve from re-imported CSV exports (inserted with 'manual' type)
+    pub manual_tags: Vec<String>,
+    /// Notes to preserve from re-imported CSV exports
+    pub notes: Option<String>,
+    /// Color label to preserve from re-imported CSV exports
+    pub color: Option<String>,
+    /// App messages (tips and warnings) from the flight log
+    pub messages: Vec<FlightMessage>,
+    /// Home point, RC GPS, avoidance, motor and wind records (DJI logs only)
+    pub side_data: FlightSideData,
+    /// App, firmware and component serials from the log header (DJI logs only)
+    pub equipment: FlightEquipment,
+}
+
 /// Import result returned to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -498,6 +788,452 @@ pub struct ImportResult {
     pub file_hash: Option<String>,
 }
 
+/// Aggregate progress of a batch import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchImportProgress {
+    pub total: usize,
+    /// Files finished so far, whatever the outcome
+    pub completed: usize,
+    pub imported: usize,
+    /// Already imported, duplicates, blacklisted or ignored
+    pub skipped: usize,
+    /// Encrypted logs moved to the pending decryption queue
+    pub queued: usize,
+    pub failed: usize,
+    /// Files left unprocessed because the import was cancelled
+    pub cancelled: usize,
+    pub elapsed_secs: f64,
+}
+
+/// Kind of an [`ImportJobEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportJobEventKind {
+    /// A worker picked up the file
+    Started,
+    /// The file was parsed and is waiting for the database writer
+    Parsed,
+    Imported,
+    Duplicate,
+    /// Blacklisted, or ignored after earlier failures
+    Skipped,
+    /// Encrypted; moved to the pending decryption queue
+    Queued,
+    Failed,
+    /// The job finished (check `progress.cancelled` for a cancelled job)
+    Done,
+}
+
+impl ImportJobEventKind {
+    /// Name used for the SSE `event:` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::Parsed => "parsed",
+            Self::Imported => "imported",
+            Self::Duplicate => "duplicate",
+            Self::Skipped => "skipped",
+            Self::Queued => "queued",
+            Self::Failed => "failed",
+            Self::Done => "done",
+        }
+    }
+}
+
+/// Progress event of an import job, streamed over SSE (web) or emitted as
+/// the `import-job` Tauri event (desktop)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJobEvent {
+    pub job_id: String,
+    pub kind: ImportJobEventKind,
+    /// Position of the file in the job, absent for `done`
+    pub index: Option<usize>,
+    pub file_name: Option<String>,
+    pub message: Option<String>,
+    pub flight_id: Option<i64>,
+    /// Job totals as of this event
+    pub progress: BatchImportProgress,
+}
+
+/// Response when an import job is started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJobStarted {
+    pub job_id: String,
+    pub total: usize,
+}
+
+/// What sync does with a source file once it is in the logbook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportedFileAction {
+    #[default]
+    Leave,
+    /// Move to `imported/YYYY/MM/` in the sync folder
+    Archive,
+}
+
+/// What sync does with a source file that failed to import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailedFileAction {
+    #[default]
+    Leave,
+    /// Move to `rejected/` in the sync folder, next to an `.error.json` sidecar
+    Reject,
+}
+
+/// Per-profile post-import actions for sync folder files
+/// (`sync_post_import` in config.json)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPostImport {
+    #[serde(default)]
+    pub imported: ImportedFileAction,
+    #[serde(default)]
+    pub failed: FailedFileAction,
+}
+
+impl SyncPostImport {
+    /// Read from a profile config; missing or invalid settings leave files alone
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get("sync_post_import")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn is_leave(&self) -> bool {
+        self.imported == ImportedFileAction::Leave && self.failed == FailedFileAction::Leave
+    }
+}
+
+/// A folder a profile syncs logs from (`sync_sources` in config.json).
+/// Sources are synced in addition to the `SYNC_LOGS_PATH` folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSource {
+    /// Assigned by the server when the source is created
+    #[serde(default)]
+    pub id: String,
+    /// Absolute folder path on the server
+    pub path: String,
+    #[serde(default = "default_true")]
+    pub recursive: bool,
+    /// Glob patterns of files to sync; empty uses `SYNC_INCLUDE`
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Glob patterns of files or folders to skip; empty uses `SYNC_EXCLUDE`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Cron expression (with seconds, like `SYNC_INTERVAL`); `None` syncs
+    /// only on demand
+    #[serde(default)]
+    pub schedule: Option<String>,
+    #[serde(default)]
+    pub post_import: SyncPostImport,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl SyncSource {
+    /// Read the sources of a profile config; invalid entries are dropped
+    pub fn list_from_config(config: &serde_json::Value) -> Vec<Self> {
+        config
+            .get("sync_sources")
+            .and_then(|v| v.as_array())
+            .map(|sources| {
+                sources
+                    .iter()
+                    .filter_map(|v| serde_json::from_value(v.clone()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// What started a sync run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncTrigger {
+    /// SYNC_INTERVAL or a sync source's schedule
+    Cron,
+    /// The Sync button, a sync job or a source's run endpoint
+    Manual,
+    /// Watch mode, including its sync at startup
+    Watch,
+}
+
+impl SyncTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cron => "cron",
+            Self::Manual => "manual",
+            Self::Watch => "watch",
+        }
+    }
+}
+
+impl std::str::FromStr for SyncTrigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cron" => Ok(Self::Cron),
+            "manual" => Ok(Self::Manual),
+            "watch" => Ok(Self::Watch),
+            other => Err(format!("Unknown sync trigger '{}'", other)),
+        }
+    }
+}
+
+/// One sync of a profile's folder, from the sync run history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRun {
+    pub id: i64,
+    pub trigger: SyncTrigger,
+    /// Sync source the run synced, `None` for the SYNC_LOGS_PATH folder
+    pub source_id: Option<String>,
+    pub sync_path: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub imported: usize,
+    /// Already imported, duplicates, blacklisted or ignored
+    pub skipped: usize,
+    /// Skipped by the seen index without reading them
+    pub unchanged: usize,
+    pub queued: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub archived: usize,
+    pub rejected: usize,
+    /// Why the run stopped early, e.g. an unreadable sync folder
+    pub error: Option<String>,
+}
+
+/// Outcome of one file of a sync run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRunFile {
+    pub path: String,
+    /// `imported`, `duplicate`, `skipped`, `queued`, `failed` or `cancelled`
+    pub outcome: String,
+    pub message: String,
+    pub flight_id: Option<i64>,
+}
+
+/// A sync run with the outcome of each file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRunDetail {
+    #[serde(flatten)]
+    pub run: SyncRun,
+    pub files: Vec<SyncRunFile>,
+}
+
+/// Scheduler state of one of a profile's sync sources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSourceStatus {
+    pub id: String,
+    pub path: String,
+    pub enabled: bool,
+    pub schedule: Option<String>,
+    /// Next time the schedule fires, `None` without a valid schedule
+    pub next_run_at: Option<String>,
+    pub running: bool,
+    pub last_run: Option<SyncRun>,
+}
+
+/// Scheduler state of a profile's folder sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub sync_path: Option<String>,
+    /// SYNC_INTERVAL cron expression, if the scheduler runs it
+    pub interval: Option<String>,
+    /// Next time SYNC_INTERVAL fires
+    pub next_run_at: Option<String>,
+    /// Whether SYNC_WATCH imports new files as they arrive
+    pub watch: bool,
+    /// Last run of the SYNC_LOGS_PATH folder
+    pub last_run: Option<SyncRun>,
+    pub sources: Vec<SyncSourceStatus>,
+}
+
+/// Post-import settings plus whether the sync folder can be written to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPostImportStatus {
+    #[serde(flatten)]
+    pub settings: SyncPostImport,
+    /// False for read-only mounts, where files are always left in place
+    pub writable: bool,
+}
+
+/// Import result for one log inside a ZIP/tar.gz archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveImportResult {
+    /// Path of the log inside the archive
+    pub entry_name: String,
+    #[serde(flatten)]
+    pub result: ImportResult,
+}
+
+/// A file that failed to import during folder sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub file_hash: String,
+    pub file_path: String,
+    /// Error variant, e.g. `timeout` or `incompatible_file`
+    pub error_kind: String,
+    pub message: String,
+    pub first_attempt: String,
+    pub last_attempt: String,
+    pub attempts: i32,
+    /// Ignored files are skipped by later syncs
+    pub ignored: bool,
+}
+
+/// A file hash that must never be imported again (e.g. a deleted flight)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlacklistEntry {
+    pub file_hash: String,
+    pub file_name: Option<String>,
+    pub added_at: String,
+}
+
+/// Cached keychains for one encrypted DJI log, as stored in a keychain bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainBundleEntry {
+    /// SHA256 of the keychain request built from the log
+    pub request_hash: String,
+    /// Keychain feature points returned by the DJI API (AES key and IV per feature point)
+    pub keychains: serde_json::Value,
+    pub fetched_at: Option<String>,
+}
+
+/// Portable export of cached keychains, for importing on an offline instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainBundle {
+    /// Always `open-dronelog-keychains`
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub entries: Vec<KeychainBundleEntry>,
+}
+
+/// An encrypted log waiting for its keychains before it can be imported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingDecryption {
+    pub file_hash: String,
+    pub file_name: String,
+    /// Where the log was imported from
+    pub source_path: String,
+    /// Last keychain error
+    pub message: String,
+    pub added_at: String,
+    pub last_attempt: String,
+    pub attempts: i32,
+}
+
+/// Outcome of retrying the pending decryption queue
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRetryResult {
+    pub imported: usize,
+    /// Still missing keychains, kept in the queue
+    pub still_pending: usize,
+    /// Decrypted but failed for another reason; moved to the import failures
+    pub failed: usize,
+}
+
+/// Result of importing a keychain bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainImportResult {
+    /// Keychains added or updated in the cache
+    pub imported: usize,
+    /// Pending logs retried with the new keychains
+    pub pending: PendingRetryResult,
+}
+
+/// Result of re-parsing one flight from its retained original file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessResult {
+    pub flight_id: i64,
+    pub success: bool,
+    pub message: String,
+    pub point_count: usize,
+}
+
+/// Dry-run import result: what importing a file would do, without writing anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    /// Registry id of the detected format (e.g. `dji`)
+    pub format_id: String,
+    pub format_name: String,
+    /// Parsed metadata (`id` is 0 since nothing is inserted)
+    pub metadata: FlightMetadata,
+    /// Tags that would be attached (smart tags after filtering, manual and profile tags)
+    pub tags: Vec<String>,
+    /// Flight with the same file hash, if the file was already imported
+    pub already_imported: Option<String>,
+    /// Flight with the same drone, battery and start time, if any
+    pub duplicate_of: Option<String>,
+    pub message_count: usize,
+    pub quality: TelemetryQuality,
+}
+
+/// Telemetry quality counters for an import preview
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryQuality {
+    pub point_count: usize,
+    /// Points with a non-zero GPS fix
+    pub gps_points: usize,
+    /// Points without latitude/longitude
+    pub missing_gps_points: usize,
+    /// Points reporting 0,0 coordinates (no fix yet)
+    pub zero_gps_points: usize,
+    /// Points with a battery percentage
+    pub battery_points: usize,
+    /// Points sharing a timestamp with the previous point
+    pub duplicate_timestamps: usize,
+    /// Points with a timestamp earlier than the previous point
+    pub out_of_order_timestamps: usize,
+    /// Longest gap between consecutive points
+    pub max_gap_ms: i64,
+    /// Average sample rate over the flight
+    pub sample_rate_hz: f64,
+}
+
+/// A flight log format accepted by the importer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedFormat {
+    /// Stable identifier (e.g. `px4_ulog`)
+    pub id: String,
+    pub name: String,
+    /// Lowercase file extensions without the dot
+    pub extensions: Vec<String>,
+}
+
 /// Statistics for a flight
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
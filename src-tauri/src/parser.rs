@@ -10,10 +10,11 @@
 use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::panic;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use chrono::{DateTime, Utc, Timelike};
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tokio::time::timeout;
@@ -21,16 +22,20 @@ use tokio::time::timeout;
 use dji_log_parser::frame::{records_to_frames, Frame};
 use dji_log_parser::layout::auxiliary::Department;
 use dji_log_parser::record::component_serial::ComponentType;
+use dji_log_parser::record::firmware::SenderType;
 use dji_log_parser::record::smart_battery_group::SmartBatteryGroup;
 use dji_log_parser::record::Record;
 use dji_log_parser::DJILog;
 
-use crate::api::DjiApi;
-use crate::database::Database;
-use crate::airdata_parser::AirdataParser;
-use crate::dronelogbook_parser::DroneLogbookParser;
-use crate::litchi_parser::LitchiParser;
-use crate::models::{FlightMessage, FlightMetadata, FlightStats, TelemetryPoint};
+use crate::api::{ApiError, DjiApi, KeychainProvider};
+use crate::database::{self, Database, FetchedKeychains, ImportError, ImportOptions};
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{
+    AvoidanceState, FlightEquipment, FlightMessage, FlightMetadata, FlightSideData, FlightStats, HomeUpdate, ImportPreview,
+    MediaImportResult, MotorStatus, ParsedFlight, PendingRetryResult, RcGpsPoint, TelemetryPoint, TelemetryQuality, WindWarning,
+};
+use crate::solar::DaylightSummary;
+use crate::srt_parser;
 
 /// Maximum time allowed for parsing a single log file (seconds)
 const PARSE_TIMEOUT_SECS: u64 = 40;
@@ -39,12 +44,40 @@ const PARSE_TIMEOUT_SECS: u64 = 40;
 /// The details header in DJI logs truncates serials to 16 bytes, but
 /// Enterprise drones (e.g. Mavic 3 Enterprise) have 20-character SNs.
 /// ComponentSerial records store the complete serial with a length prefix.
+/// Firmware versions are collected in the same pass from Firmware records.
 #[derive(Debug, Default, Clone)]
 struct ComponentSerials {
     aircraft: Option<String>,
     battery: Option<String>,
+    camera: Option<String>,
+    rc: Option<String>,
     /// Battery cycle count extracted from SmartBatteryStatic.loop_times (divided by 256)
     cycle_count: Option<i32>,
+    aircraft_firmware: Option<String>,
+    battery_firmware: Option<String>,
+    camera_firmware: Option<String>,
+    rc_firmware: Option<String>,
+    gimbal_firmware: Option<String>,
+}
+
+/// Record state that `records_to_frames` does not carry over into frames,
+/// captured once per frame while walking the raw records.
+#[derive(Debug, Default, Clone, Copy)]
+struct RecordSnapshot {
+    is_photo: bool,
+    is_video: bool,
+    /// Last RC GPS fix: (latitude, longitude, satellites, accuracy)
+    rc_gps: Option<(f64, f64, u8, f32)>,
+    obstacle_avoidance_enabled: Option<bool>,
+    user_avoidance_enabled: Option<bool>,
+}
+
+/// Output of `get_frames`: frames plus the per-frame record snapshots
+struct DjiFrames {
+    frames: Vec<Frame>,
+    snapshots: Vec<RecordSnapshot>,
+    used_djifly_fallback: bool,
+    component_serials: ComponentSerials,
 }
 
 /// Scan raw records for ComponentSerial entries and return full-length serials.
@@ -65,9 +98,27 @@ fn extract_component_serials(records: &[Record]) -> ComponentSerials {
                     log::debug!("ComponentSerial: Battery SN = {} ({} chars)", sn, sn.len());
                     result.battery = Some(sn);
                 }
+                ComponentType::Camera => result.camera = Some(sn),
+                ComponentType::RC => result.rc = Some(sn),
                 _ => {}
             }
         }
+        // Keep the first reported firmware per component ("0.0.0" means not reported)
+        if let Record::Firmware(ref fw) = record {
+            if fw.version != "0.0.0" {
+                let slot = match fw.sender_type {
+                    SenderType::MC => Some(&mut result.aircraft_firmware),
+                    SenderType::Battery => Some(&mut result.battery_firmware),
+                    SenderType::Camera => Some(&mut result.camera_firmware),
+                    SenderType::RC => Some(&mut result.rc_firmware),
+                    SenderType::Gimbal => Some(&mut result.gimbal_firmware),
+                    _ => None,
+                };
+                if let Some(slot) = slot {
+                    slot.get_or_insert_with(|| fw.version.clone());
+                }
+            }
+        }
         // Extract cycle count from SmartBatteryStatic records
         if let Record::SmartBatteryGroup(SmartBatteryGroup::SmartBatteryStatic(ref sbs)) = record {
             let raw = sbs.loop_times as i32;
@@ -95,6 +146,12 @@ pub enum ParserError {
     #[error("File already imported (matches: {0})")]
     AlreadyImported(String),
 
+    #[error("File is on the import blacklist")]
+    Blacklisted,
+
+    #[error("Original log file not found: {0}")]
+    OriginalFileNotFound(String),
+
     #[error("No valid telemetry data found")]
     NoTelemetryData,
 
@@ -110,36 +167,159 @@ pub enum ParserError {
     #[error("Parsing timed out after {0} seconds — file may be corrupt or unsupported")]
     Timeout(u64),
 
-    #[error("Incompatible file format — only DJI flight logs (.txt), Litchi CSV exports, Airdata CSV exports, and Open DroneLog CSV exports are supported")]
+    #[error("Incompatible file format — only DJI flight logs (.txt), PX4 ULog files (.ulg), ArduPilot DataFlash logs (.bin), MAVLink telemetry logs (.tlog), Litchi CSV exports, Airdata CSV exports, and Open DroneLog CSV exports are supported")]
     IncompatibleFile,
 }
 
-/// Result of parsing a DJI log file
-pub struct ParseResult {
-    pub metadata: FlightMetadata,
-    pub points: Vec<TelemetryPoint>,
-    pub tags: Vec<String>,
-    /// Manual tags to preserve from re-imported CSV exports (inserted with 'manual' type)
-    pub manual_tags: Vec<String>,
-    /// Notes to preserve from re-imported CSV exports
-    pub notes: Option<String>,
-    /// Color label to preserve from re-imported CSV exports
-    pub color: Option<String>,
-    /// App messages (tips and warnings) from the flight log
-    pub messages: Vec<FlightMessage>,
+impl ParserError {
+    /// Stable snake_case name of the error variant, stored with import failures
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParserError::Io(_) => "io",
+            ParserError::Parse(_) => "parse",
+            ParserError::AlreadyImported(_) => "already_imported",
+            ParserError::Blacklisted => "blacklisted",
+            ParserError::OriginalFileNotFound(_) => "original_file_not_found",
+            ParserError::NoTelemetryData => "no_telemetry_data",
+            ParserError::EncryptionKeyRequired => "encryption_key_required",
+            ParserError::Api(_) => "api",
+            ParserError::Panic(_) => "panic",
+            ParserError::Timeout(_) => "timeout",
+            ParserError::IncompatibleFile => "incompatible_file",
+        }
+    }
+
+    /// Whether the log is encrypted and its keychains could not be obtained
+    /// (no API key, or the DJI API unreachable). Such logs go to the pending
+    /// decryption queue instead of the import failures.
+    pub fn needs_keychain(&self) -> bool {
+        matches!(self, ParserError::EncryptionKeyRequired | ParserError::Api(_))
+    }
+}
+
+/// Options for [`parse_file`]
+#[derive(Clone, Default)]
+pub struct ParseOptions {
+    /// Keychain source for encrypted DJI logs. Without one, V13+ DJI logs
+    /// fail with [`ParserError::EncryptionKeyRequired`].
+    pub keychains: Option<Arc<dyn KeychainProvider>>,
+}
+
+/// Parse a flight log file (DJI .txt, PX4 .ulg, ArduPilot .bin, MAVLink .tlog or Litchi/Airdata .csv)
+/// without touching the database. `metadata.id` of the result is left at 0; it is assigned
+/// when the flight is imported with [`Database::import_parsed_flight`].
+#[allow(dead_code)]
+pub async fn parse_file(path: &Path, options: &ParseOptions) -> Result<ParsedFlight, ParserError> {
+    let file_hash = LogParser::calculate_file_hash(path)?;
+    parse_file_with_hash(path, &file_hash, options).await
+}
+
+async fn parse_file_with_hash(path: &Path, file_hash: &str, options: &ParseOptions) -> Result<ParsedFlight, ParserError> {
+    let file_size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    log::info!(
+        "Parsing log file: {:?} (size: {:.1} KB)",
+        path,
+        file_size as f64 / 1024.0
+    );
+
+    // Pick the registered format that best matches the file
+    let Some(format) = formats::detect(path) else {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        log::warn!("Unsupported file format: .{}", ext);
+        return Err(ParserError::IncompatibleFile);
+    };
+    log::info!("Detected {} format", format.name());
+
+    format.parse(path, file_hash, options).await
+}
+
+/// Run CPU-bound parsing of an untrusted file inside spawn_blocking +
+/// catch_unwind with a [`PARSE_TIMEOUT_SECS`] limit, so a panicking or
+/// hanging parser cannot kill or stall the async worker
+pub(crate) async fn run_guarded<T, F>(parse: F) -> Result<T, ParserError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ParserError> + Send + 'static,
+{
+    let result = timeout(
+        Duration::from_secs(PARSE_TIMEOUT_SECS),
+        tokio::task::spawn_blocking(move || panic::catch_unwind(panic::AssertUnwindSafe(parse))),
+    )
+    .await;
+
+    match result {
+        Err(_) => Err(ParserError::Timeout(PARSE_TIMEOUT_SECS)),
+        Ok(Err(join_err)) => Err(ParserError::Panic(format!("Task join error: {}", join_err))),
+        Ok(Ok(Err(panic_val))) => {
+            let msg = panic_val
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic_val.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(ParserError::Panic(msg))
+        }
+        Ok(Ok(Ok(parse_result))) => parse_result,
+    }
 }
 
-/// DJI Log Parser wrapper
+/// DJI flight log format. DJI logs have no stable magic bytes across
+/// versions, so any `.txt` file is offered to dji-log-parser with a low
+/// confidence that lets the more specific formats win.
+pub struct DjiFormat;
+
+impl FlightLogFormat for DjiFormat {
+    fn id(&self) -> &'static str {
+        "dji"
+    }
+
+    fn name(&self) -> &'static str {
+        "DJI Flight Log"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt"]
+    }
+
+    fn sniff(&self, path: &Path, _header: &[u8]) -> u8 {
+        if formats::has_extension(path, "txt") { 50 } else { 0 }
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, options: &'a ParseOptions) -> ParseFuture<'a> {
+        Box::pin(async move {
+            DjiParser::new(options.keychains.as_deref())
+                .parse(path, file_hash)
+                .await
+        })
+    }
+}
+
+/// Database-aware parsing entry point and shared helpers (stats, smart tags)
 pub struct LogParser<'a> {
     db: &'a Database,
-    api: DjiApi,
+    api: Arc<DjiApi>,
+    /// Keychains fetched from the DJI API while parsing, not cached yet
+    fetched_keychains: Mutex<Vec<FetchedKeychains>>,
 }
 
 impl<'a> LogParser<'a> {
     pub fn new(db: &'a Database) -> Self {
         Self {
             db,
-            api: DjiApi::with_app_data_dir(db.data_dir.clone()),
+            api: Arc::new(DjiApi::with_app_data_dir(db.data_dir.clone())),
+            fetched_keychains: Mutex::default(),
+        }
+    }
+
+    /// Keychains fetched from the DJI API by the parses so far, for the
+    /// caller to store with [`Database::cache_keychains`]
+    pub fn take_fetched_keychains(&self) -> Vec<FetchedKeychains> {
+        std::mem::take(&mut *self.fetched_keychains.lock().unwrap())
+    }
+
+    /// Add the keychains fetched so far to the database's keychain cache
+    fn cache_fetched_keychains(&self) {
+        if let Err(e) = self.db.cache_keychains(&self.take_fetched_keychains()) {
+            log::warn!("Failed to cache keychains: {}", e);
         }
     }
 
@@ -161,241 +341,247 @@ impl<'a> LogParser<'a> {
         Ok(format!("{:x}", hasher.finalize()))
     }
 
-    /// Parse a flight log file (DJI .txt or Litchi .csv) and extract all telemetry data
-    pub async fn parse_log(&self, file_path: &Path) -> Result<ParseResult, ParserError> {
-        let parse_start = std::time::Instant::now();
-        let file_size = fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
-        log::info!(
-            "Parsing log file: {:?} (size: {:.1} KB)",
-            file_path,
-            file_size as f64 / 1024.0
-        );
-
-        // Calculate file hash to check for duplicates
-        let file_hash = Self::calculate_file_hash(file_path)?;
-        log::debug!("File hash: {}", file_hash);
+    /// Parse a flight log file with a known hash, skipping files whose hash
+    /// is already in the database or on the import blacklist.
+    /// Encrypted DJI logs are decrypted with keychains from the database's
+    /// keychain cache, fetched through the DJI API when not cached yet. This
+    /// only reads from the database: fetched keychains are kept for
+    /// [`LogParser::take_fetched_keychains`].
+    pub async fn parse_log_with_hash(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
+        if self
+            .db
+            .is_blacklisted(file_hash)
+            .map_err(|e| ParserError::Parse(e.to_string()))?
+        {
+            log::info!("File is blacklisted, skipping: {:?}", file_path);
+            return Err(ParserError::Blacklisted);
+        }
 
         if let Some(matching_flight) = self
             .db
-            .is_file_imported(&file_hash)
+            .is_file_imported(file_hash)
             .map_err(|e| ParserError::Parse(e.to_string()))?
         {
             log::info!("File already imported (hash match), skipping — matches flight: {}", matching_flight);
             return Err(ParserError::AlreadyImported(matching_flight));
         }
 
-        // Detect file format and route to appropriate parser
-        // Check for Drone Logbook CSV format first (our own export)
-        if DroneLogbookParser::is_dronelogbook_csv(file_path) {
-            log::info!("Detected Drone Logbook CSV format, using DroneLogbookParser");
-            let dronelogbook_parser = DroneLogbookParser::new(self.db);
-            return dronelogbook_parser.parse(file_path, &file_hash);
-        }
-
-        // Check for Airdata CSV format
-        if AirdataParser::is_airdata_csv(file_path) {
-            log::info!("Detected Airdata CSV format, using AirdataParser");
-            let airdata_parser = AirdataParser::new(self.db);
-            return airdata_parser.parse(file_path, &file_hash);
-        }
-
-        // Check for Litchi CSV format
-        if LitchiParser::is_litchi_csv(file_path) {
-            log::info!("Detected Litchi CSV format, using LitchiParser");
-            let litchi_parser = LitchiParser::new(self.db);
-            return litchi_parser.parse(file_path, &file_hash);
-        }
+        self.parse_with_hash(file_path, file_hash).await
+    }
 
-        // Check if this looks like a valid DJI log file
-        let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !ext.eq_ignore_ascii_case("txt") {
-            log::warn!("Unsupported file extension: .{}", ext);
-            return Err(ParserError::IncompatibleFile);
+    async fn parse_with_hash(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
+        let cache = self.db.keychain_cache(Some(self.api.clone())).map(Arc::new);
+        let keychains: Arc<dyn KeychainProvider> = match &cache {
+            Ok(cache) => cache.clone(),
+            Err(e) => {
+                log::warn!("Keychain cache unavailable, using the DJI API directly: {}", e);
+                self.api.clone()
+            }
+        };
+        let options = ParseOptions {
+            keychains: Some(keychains),
+        };
+        let result = parse_file_with_hash(file_path, file_hash, &options).await;
+        if let Ok(cache) = cache {
+            self.fetched_keychains.lock().unwrap().extend(cache.take_fetched());
         }
+        result
+    }
 
-        // Read the file
-        let file_data = fs::read(file_path)?;
+    /// Retry every log in the pending decryption queue. Logs that import (or
+    /// turn out to be already imported, blacklisted or duplicates) leave the
+    /// queue; logs still missing keychains stay queued; any other failure
+    /// moves the log to the import failures.
+    pub async fn retry_pending_decryption(&self, import_options: &ImportOptions) -> PendingRetryResult {
+        let mut result = PendingRetryResult::default();
+        let pending = match self.db.get_pending_decryption() {
+            Ok(pending) => pending,
+            Err(e) => {
+                log::warn!("Failed to read pending decryption queue: {}", e);
+                return result;
+            }
+        };
 
-        // Parse with dji-log-parser inside spawn_blocking + catch_unwind
-        // This prevents a panicking/hanging parser from killing the app
-        let parser = {
-            let data = file_data.clone();
-            let result = timeout(
-                Duration::from_secs(PARSE_TIMEOUT_SECS),
-                tokio::task::spawn_blocking(move || {
-                    panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                        DJILog::from_bytes(data)
-                    }))
-                }),
-            )
-            .await;
-
-            match result {
-                Err(_) => return Err(ParserError::Timeout(PARSE_TIMEOUT_SECS)),
-                Ok(Err(join_err)) => return Err(ParserError::Panic(format!("Task join error: {}", join_err))),
-                Ok(Ok(Err(panic_val))) => {
-                    let msg = panic_val
-                        .downcast_ref::<String>()
-                        .map(|s| s.clone())
-                        .or_else(|| panic_val.downcast_ref::<&str>().map(|s| s.to_string()))
-                        .unwrap_or_else(|| "unknown panic".to_string());
-                    return Err(ParserError::Panic(msg));
+        for entry in pending {
+            let path = self.db.pending_decryption_file(&entry);
+            let dequeue = || {
+                if let Err(e) = self.db.remove_pending_decryption(&entry.file_hash) {
+                    log::warn!("Failed to remove {} from pending decryption: {}", entry.file_name, e);
                 }
-                Ok(Ok(Ok(parse_result))) => {
-                    parse_result.map_err(|e| ParserError::Parse(e.to_string()))?
+            };
+            let record_failure = |kind: &str, message: &str| {
+                if let Err(e) = self.db.record_import_failure(&entry.file_hash, &entry.source_path, kind, message) {
+                    log::warn!("Failed to record import failure for {}: {}", entry.file_name, e);
                 }
-            }
-        };
+            };
 
-        log::debug!(
-            "DJI Parser: version={}, product={:?}, aircraft_sn={}, aircraft_name={}",
-            parser.version,
-            parser.details.product_type,
-            parser.details.aircraft_sn,
-            parser.details.aircraft_name,
-        );
+            if !path.is_file() {
+                record_failure("original_file_not_found", "Queued copy of the log is missing");
+                dequeue();
+                result.failed += 1;
+                continue;
+            }
 
-        // Check if we need an encryption key for V13+ logs
-        let (frames, used_djifly_fallback, component_serials) = self.get_frames(&parser).await?;
-        log::info!("Extracted {} frames from log", frames.len());
+            let parsed = self.parse_log_with_hash(&path, &entry.file_hash).await;
+            self.cache_fetched_keychains();
+            let parsed = match parsed {
+                Ok(parsed) => parsed,
+                Err(ParserError::AlreadyImported(_)) | Err(ParserError::Blacklisted) => {
+                    dequeue();
+                    continue;
+                }
+                Err(e) if e.needs_keychain() => {
+                    if let Err(err) = self.db.queue_pending_decryption(&path, &entry.file_hash, &e.to_string()) {
+                        log::warn!("Failed to update pending decryption for {}: {}", entry.file_name, err);
+                    }
+                    result.still_pending += 1;
+                    continue;
+                }
+                Err(e) => {
+                    record_failure(e.kind(), &e.to_string());
+                    dequeue();
+                    result.failed += 1;
+                    continue;
+                }
+            };
 
-        // Log when ComponentSerial provides a longer serial than the header
-        if let Some(ref full_sn) = component_serials.aircraft {
-            if full_sn.len() > parser.details.aircraft_sn.trim().len() {
-                log::info!(
-                    "ComponentSerial override: aircraft_sn '{}' ({} chars) -> '{}' ({} chars)",
-                    parser.details.aircraft_sn.trim(), parser.details.aircraft_sn.trim().len(),
-                    full_sn, full_sn.len()
-                );
-            }
-        }
-        if let Some(ref full_sn) = component_serials.battery {
-            if full_sn.len() > parser.details.battery_sn.trim().len() {
-                log::info!(
-                    "ComponentSerial override: battery_sn '{}' ({} chars) -> '{}' ({} chars)",
-                    parser.details.battery_sn.trim(), parser.details.battery_sn.trim().len(),
-                    full_sn, full_sn.len()
-                );
+            match self.db.import_parsed_flight(&parsed, import_options) {
+                Ok(_) => {
+                    log::info!("Imported {} from the pending decryption queue", entry.file_name);
+                    let _ = self.db.clear_import_failure(&entry.file_hash);
+                    result.imported += 1;
+                }
+                Err(ImportError::Duplicate(_)) => {}
+                Err(e) => {
+                    record_failure("import", &e.to_string());
+                    result.failed += 1;
+                }
             }
+            dequeue();
         }
+        result
+    }
 
-        if frames.is_empty() {
-            log::warn!("No frames extracted from log file — file may be empty or corrupt");
-            return Err(ParserError::NoTelemetryData);
-        }
-
-        // Extract telemetry points
-        let details_total_time_secs = parser.details.total_time as f64;
-        let points = self.extract_telemetry(&frames, details_total_time_secs);
-        log::info!(
-            "Extracted {} valid telemetry points from {} frames ({} skipped)",
-            points.len(),
-            frames.len(),
-            frames.len() - points.len()
-        );
-
-        // Extract app messages (tips and warnings)
-        let messages = self.extract_messages(&frames, details_total_time_secs);
-        log::info!("Extracted {} app messages from log", messages.len());
-
-        if points.is_empty() {
-            log::warn!("No valid telemetry points after filtering — all frames had corrupt/missing data");
-            return Err(ParserError::NoTelemetryData);
-        }
+    /// Re-parse an imported flight from its retained original file and replace
+    /// its telemetry, stats, auto tags and messages in place. The flight id,
+    /// display name, notes, color, manual tags and equipment names are kept.
+    /// Returns the number of telemetry points stored.
+    pub async fn reprocess_flight(
+        &self,
+        flight_id: i64,
+        search_folders: &[PathBuf],
+        import_options: &ImportOptions,
+    ) -> Result<usize, ParserError> {
+        let flight = self
+            .db
+            .get_flight_by_id(flight_id)
+            .map_err(|e| ParserError::Parse(e.to_string()))?;
+        let Some(file_hash) = flight.file_hash.filter(|h| !h.is_empty()) else {
+            return Err(ParserError::OriginalFileNotFound(format!(
+                "flight {} has no log file (manual entry)",
+                flight_id
+            )));
+        };
 
-        // Calculate statistics
-        let stats = self.calculate_stats(&points);
+        let Some(path) = search_folders
+            .iter()
+            .find_map(|folder| database::find_uploaded_file(folder, &flight.file_name, &file_hash))
+        else {
+            return Err(ParserError::OriginalFileNotFound(format!(
+                "{} is not in the kept uploads folder",
+                flight.file_name
+            )));
+        };
 
-        // Build metadata
-        let file_name = file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        log::info!("Reprocessing flight {} from {:?}", flight_id, path);
+        let parsed = self.parse_with_hash(&path, &file_hash).await;
+        self.cache_fetched_keychains();
+        self.db
+            .replace_flight_data(flight_id, &parsed?, import_options)
+            .map_err(|e| ParserError::Parse(e.to_string()))
+    }
 
-        let display_name = file_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .filter(|s| !s.trim().is_empty())
-            .unwrap_or(&file_name)
-            .to_string();
+    /// Parse a flight log fully without writing to the database and report
+    /// what an import would do: detected format, metadata, tags, existing
+    /// matches and telemetry quality. Keychains fetched for an encrypted log
+    /// are not cached.
+    pub async fn preview_import(&self, file_path: &Path, import_options: &ImportOptions) -> Result<ImportPreview, ParserError> {
+        let file_hash = Self::calculate_file_hash(file_path)?;
 
-        // Count photo and video capture events from telemetry transitions
-        let (photo_count, video_count) = crate::models::count_media_events(&points);
+        let already_imported = self
+            .db
+            .is_file_imported(&file_hash)
+            .map_err(|e| ParserError::Parse(e.to_string()))?;
 
-        let metadata = FlightMetadata {
-            id: self.db.generate_flight_id(),
-            file_name,
-            display_name,
-            file_hash: Some(file_hash),
-            drone_model: self.extract_drone_model(&parser),
-            drone_serial: component_serials.aircraft.clone()
-                .or_else(|| self.extract_serial(&parser)),
-            aircraft_name: self.extract_aircraft_name(&parser),
-            battery_serial: component_serials.battery.clone()
-                .or_else(|| self.extract_battery_serial(&parser)),
-            cycle_count: component_serials.cycle_count,
-            start_time: self.extract_start_time(&parser),
-            end_time: self.extract_end_time(&parser),
-            duration_secs: Some(
-                if details_total_time_secs > 0.0 {
-                    details_total_time_secs
-                } else {
-                    stats.duration_secs
-                }
-            ),
-            total_distance: Some(stats.total_distance_m),
-            max_altitude: Some(stats.max_altitude_m),
-            max_speed: Some(stats.max_speed_ms),
-            home_lat: stats.home_location.map(|h| h[1]),
-            home_lon: stats.home_location.map(|h| h[0]),
-            point_count: points.len() as i32,
-            photo_count,
-            video_count,
-        };
+        let format = formats::detect(file_path).ok_or(ParserError::IncompatibleFile)?;
+        let parsed = self.parse_with_hash(file_path, &file_hash).await;
+        self.take_fetched_keychains();
+        let parsed = parsed?;
 
-        log::info!(
-            "Parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, max_speed={:.1}m/s, home={:?}, points={}",
-            parse_start.elapsed().as_secs_f64(),
-            stats.duration_secs,
-            stats.total_distance_m,
-            stats.max_altitude_m,
-            stats.max_speed_ms,
-            stats.home_location,
-            points.len()
-        );
+        let duplicate_of = self
+            .db
+            .is_duplicate_flight(
+                parsed.metadata.drone_serial.as_deref(),
+                parsed.metadata.battery_serial.as_deref(),
+                parsed.metadata.start_time,
+            )
+            .map_err(|e| ParserError::Parse(e.to_string()))?;
+
+        let mut tags = import_options.smart_tags(&parsed.tags);
+        tags.extend(parsed.manual_tags.iter().cloned());
+        tags.extend(import_options.profile_tag.iter().cloned());
+
+        Ok(ImportPreview {
+            format_id: format.id().to_string(),
+            format_name: format.name().to_string(),
+            quality: Self::telemetry_quality(&parsed.points),
+            message_count: parsed.messages.len(),
+            metadata: parsed.metadata,
+            tags,
+            already_imported,
+            duplicate_of,
+        })
+    }
 
-        // Generate smart tags based on flight characteristics
-        let mut tags = Self::generate_smart_tags(&metadata, &stats);
-        
-        // Add M-SDK tag if DJIFly department override was used (third-party app like Dronelink/DroneDeploy)
-        if used_djifly_fallback {
-            tags.push("M-SDK".to_string());
-            log::info!("Added M-SDK tag (third-party app detected via DJIFly fallback)");
+    /// Import a DJI video subtitle (.SRT) sidecar as a media clip, linked
+    /// to the flight it was shot during (of `drone_serial` when given)
+    pub fn import_media_file(&self, file_path: &Path, drone_serial: Option<&str>) -> MediaImportResult {
+        let result = Some(file_path)
+            .filter(|path| srt_parser::is_srt(path))
+            .ok_or_else(|| ParserError::Parse("Not a video subtitle (.SRT) file".to_string()))
+            .and_then(Self::calculate_file_hash)
+            .and_then(|file_hash| srt_parser::parse_file(file_path, &file_hash))
+            .and_then(|clip| self.db.import_media_clip(&clip, drone_serial).map_err(|e| ParserError::Parse(e.to_string())));
+        match result {
+            Ok(result) => result,
+            Err(e) => MediaImportResult {
+                success: false,
+                clip_id: None,
+                flight_id: None,
+                message: format!("Failed to import {}: {}", file_path.display(), e),
+                frame_count: 0,
+            },
         }
-        
-        log::info!("Generated smart tags: {:?}", tags);
-
-        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages })
     }
 
     /// Generate smart tags based on flight metadata and statistics
     pub fn generate_smart_tags(metadata: &FlightMetadata, stats: &FlightStats) -> Vec<String> {
         let mut tags = Vec::new();
 
-        // Night Flight: if local flying time is after 7 PM (19:00) or before 6 AM
-        if let Some(start_time) = metadata.start_time {
-            // Use home location to estimate timezone offset (rough: 1 hour per 15° longitude)
-            let utc_hour = start_time.hour();
-            let tz_offset_hours = if let Some(home) = stats.home_location {
-                (home[0] / 15.0).round() as i32 // lon / 15 = approx TZ offset
-            } else {
-                0
-            };
-            let local_hour = ((utc_hour as i32 + tz_offset_hours) % 24 + 24) % 24;
-            if local_hour >= 19 || local_hour < 6 {
+        // Night / Twilight / Golden Hour from the sun's elevation at the home point.
+        // Night is after the end of evening civil twilight (sun more than 6° down).
+        if let (Some(start_time), Some(home)) = (metadata.start_time, stats.home_location) {
+            // Very short logs still get classified by their start instant
+            let daylight = DaylightSummary::for_flight(start_time, stats.duration_secs.max(1.0), home[1], home[0]);
+            if daylight.night_secs > 0.0 {
                 tags.push("Night Flight".to_string());
             }
+            if daylight.twilight_secs > 0.0 {
+                tags.push("Twilight Flight".to_string());
+            }
+            if daylight.golden_hour_secs > 0.0 {
+                tags.push("Golden Hour".to_string());
+            }
         }
 
         // High Speed: max speed exceeds 15 m/s
@@ -482,6 +668,8 @@ impl<'a> LogParser<'a> {
         // Map of tag type IDs to the actual tag name patterns
         let type_to_tag: std::collections::HashMap<&str, &str> = [
             ("night_flight", "Night Flight"),
+            ("twilight_flight", "Twilight Flight"),
+            ("golden_hour", "Golden Hour"),
             ("high_speed", "High Speed"),
             ("cold_battery", "Cold Battery"),
             ("heavy_load", "Heavy Load"),
@@ -641,56 +829,368 @@ impl<'a> LogParser<'a> {
             "ZM" => Some("Zambia"), "ZW" => Some("Zimbabwe"),
             _ => None,
         }
-    }
+    }
+
+    /// Map ISO 3166-1 alpha-2 country code to continent name.
+    fn continent_from_cc(cc: &str) -> Option<&'static str> {
+        match cc {
+            // Europe
+            "AD"|"AL"|"AT"|"BA"|"BE"|"BG"|"BY"|"CH"|"CY"|"CZ"|"DE"|"DK"|"EE"|"ES"|"FI"|
+            "FO"|"FR"|"GB"|"GE"|"GG"|"GI"|"GR"|"HR"|"HU"|"IE"|"IM"|"IS"|"IT"|"JE"|"LI"|
+            "LT"|"LU"|"LV"|"MC"|"MD"|"ME"|"MK"|"MT"|"NL"|"NO"|"PL"|"PT"|"RO"|"RS"|"SE"|
+            "SI"|"SK"|"SM"|"UA"|"VA"|"XK" => Some("Europe"),
+            // North America
+            "AG"|"AI"|"AW"|"BB"|"BM"|"BS"|"BZ"|"CA"|"CR"|"CU"|"CW"|"DM"|"DO"|"GD"|"GL"|
+            "GP"|"GT"|"GU"|"HN"|"HT"|"JM"|"KN"|"KY"|"LC"|"MQ"|"MS"|"MX"|"NI"|"PA"|"PM"|
+            "PR"|"SV"|"SX"|"TC"|"TT"|"US"|"VC"|"VG"|"VI" => Some("North America"),
+            // South America
+            "AR"|"BO"|"BR"|"CL"|"CO"|"EC"|"FK"|"GF"|"GY"|"PE"|"PY"|"SR"|"UY"|"VE"
+                => Some("South America"),
+            // Africa
+            "AO"|"BF"|"BI"|"BJ"|"BW"|"CD"|"CF"|"CG"|"CI"|"CM"|"CV"|"DJ"|"DZ"|"EG"|"ER"|
+            "ET"|"GA"|"GH"|"GM"|"GN"|"GQ"|"GW"|"KE"|"KM"|"LR"|"LS"|"LY"|"MA"|"MG"|"ML"|
+            "MR"|"MU"|"MW"|"MZ"|"NA"|"NE"|"NG"|"RE"|"RW"|"SC"|"SD"|"SH"|"SL"|"SN"|"SO"|
+            "SS"|"ST"|"SZ"|"TD"|"TG"|"TN"|"TZ"|"UG"|"YT"|"ZA"|"ZM"|"ZW"
+                => Some("Africa"),
+            // Asia
+            "AE"|"AF"|"AM"|"AZ"|"BD"|"BH"|"BN"|"CN"|"HK"|"ID"|"IL"|"IN"|"IQ"|"IR"|"JO"|
+            "JP"|"KG"|"KH"|"KP"|"KR"|"KW"|"KZ"|"LA"|"LB"|"LK"|"MM"|"MN"|"MO"|"MV"|"MY"|
+            "NP"|"OM"|"PH"|"PK"|"PS"|"QA"|"RU"|"SA"|"SG"|"SY"|"TH"|"TJ"|"TL"|"TM"|"TR"|
+            "TW"|"UZ"|"VN"|"YE" => Some("Asia"),
+            // Oceania
+            "AS"|"AU"|"FJ"|"FM"|"KI"|"MH"|"NC"|"NF"|"NR"|"NU"|"NZ"|"PF"|"PG"|"PW"|"SB"|
+            "TK"|"TO"|"TV"|"VU"|"WF"|"WS" => Some("Oceania"),
+            // Antarctica
+            "AQ" => Some("Antarctica"),
+            _ => None,
+        }
+    }
+
+    /// Count GPS, battery and timing problems in parsed telemetry
+    pub fn telemetry_quality(points: &[TelemetryPoint]) -> TelemetryQuality {
+        let mut quality = TelemetryQuality {
+            point_count: points.len(),
+            ..Default::default()
+        };
+
+        for point in points {
+            match (point.latitude, point.longitude) {
+                (Some(lat), Some(lon)) if lat.abs() < 1e-6 && lon.abs() < 1e-6 => quality.zero_gps_points += 1,
+                (Some(_), Some(_)) => quality.gps_points += 1,
+                _ => quality.missing_gps_points += 1,
+            }
+            if point.battery_percent.is_some() {
+                quality.battery_points += 1;
+            }
+        }
+
+        for pair in points.windows(2) {
+            let gap = pair[1].timestamp_ms - pair[0].timestamp_ms;
+            if gap == 0 {
+                quality.duplicate_timestamps += 1;
+            } else if gap < 0 {
+                quality.out_of_order_timestamps += 1;
+            }
+            quality.max_gap_ms = quality.max_gap_ms.max(gap);
+        }
+
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            let span_ms = last.timestamp_ms - first.timestamp_ms;
+            if span_ms > 0 {
+                quality.sample_rate_hz = (points.len() - 1) as f64 * 1000.0 / span_ms as f64;
+            }
+        }
+
+        quality
+    }
+
+    /// Calculate flight statistics from telemetry points
+    pub fn calculate_stats(points: &[TelemetryPoint]) -> FlightStats {
+        let duration_secs = points.last().map(|p| p.timestamp_ms as f64 / 1000.0).unwrap_or(0.0);
+
+        let max_altitude = points
+            .iter()
+            .filter_map(|p| p.height.or(p.altitude))
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let max_speed = points
+            .iter()
+            .filter_map(|p| p.speed)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let avg_speed: f64 = {
+            let speeds: Vec<f64> = points.iter().filter_map(|p| p.speed).collect();
+            if speeds.is_empty() {
+                0.0
+            } else {
+                speeds.iter().sum::<f64>() / speeds.len() as f64
+            }
+        };
+
+        let min_battery = points
+            .iter()
+            .filter_map(|p| p.battery_percent)
+            .min()
+            .unwrap_or(0);
+
+        // Calculate total distance using haversine formula
+        let total_distance = Self::calculate_total_distance(points);
+
+        // Home location is the first valid GPS point
+        let home_location = points
+            .iter()
+            .find_map(|p| match (p.longitude, p.latitude) {
+                (Some(lon), Some(lat)) => Some([lon, lat]),
+                _ => None,
+            });
+
+        // Max distance from home
+        let max_distance_from_home = if let Some(home) = home_location {
+            points
+                .iter()
+                .filter_map(|p| match (p.latitude, p.longitude) {
+                    (Some(lat), Some(lon)) => Some(haversine_distance(home[1], home[0], lat, lon)),
+                    _ => None,
+                })
+                .fold(0.0_f64, f64::max)
+        } else {
+            0.0
+        };
+
+        // Start and end battery percent
+        let start_battery_percent = points.iter().find_map(|p| p.battery_percent);
+        let end_battery_percent = points.iter().rev().find_map(|p| p.battery_percent);
+
+        // Start battery temperature
+        let start_battery_temp = points.iter().find_map(|p| p.battery_temp);
+
+        FlightStats {
+            duration_secs,
+            total_distance_m: total_distance,
+            max_altitude_m: if max_altitude.is_finite() {
+                max_altitude
+            } else {
+                0.0
+            },
+            max_speed_ms: if max_speed.is_finite() { max_speed } else { 0.0 },
+            avg_speed_ms: avg_speed,
+            min_battery,
+            home_location,
+            max_distance_from_home_m: max_distance_from_home,
+            start_battery_percent,
+            end_battery_percent,
+            start_battery_temp,
+        }
+    }
+
+    /// Calculate total distance traveled using haversine formula
+    fn calculate_total_distance(points: &[TelemetryPoint]) -> f64 {
+        let mut total = 0.0;
+        let mut prev_lat: Option<f64> = None;
+        let mut prev_lon: Option<f64> = None;
+
+        for point in points {
+            if let (Some(lat), Some(lon)) = (point.latitude, point.longitude) {
+                if let (Some(p_lat), Some(p_lon)) = (prev_lat, prev_lon) {
+                    total += haversine_distance(p_lat, p_lon, lat, lon);
+                }
+                prev_lat = Some(lat);
+                prev_lon = Some(lon);
+            }
+        }
+
+        total
+    }
+}
+
+/// DJI flight log (.txt) parser built on dji-log-parser
+pub struct DjiParser<'a> {
+    keychains: Option<&'a dyn KeychainProvider>,
+}
+
+impl<'a> DjiParser<'a> {
+    /// `keychains` is only needed for encrypted (V13+) logs
+    pub fn new(keychains: Option<&'a dyn KeychainProvider>) -> Self {
+        Self { keychains }
+    }
+
+    /// Parse a DJI flight log (.txt) with dji-log-parser
+    pub async fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
+        let parse_start = std::time::Instant::now();
+
+        // Read the file
+        let file_data = fs::read(file_path)?;
+
+        // Parse with dji-log-parser inside spawn_blocking + catch_unwind
+        // This prevents a panicking/hanging parser from killing the app
+        let parser = {
+            let data = file_data.clone();
+            run_guarded(move || DJILog::from_bytes(data).map_err(|e| ParserError::Parse(e.to_string()))).await?
+        };
+
+        log::debug!(
+            "DJI Parser: version={}, product={:?}, aircraft_sn={}, aircraft_name={}",
+            parser.version,
+            parser.details.product_type,
+            parser.details.aircraft_sn,
+            parser.details.aircraft_name,
+        );
+
+        // Check if we need an encryption key for V13+ logs
+        let DjiFrames { frames, snapshots, used_djifly_fallback, component_serials } =
+            self.get_frames(&parser).await?;
+        log::info!("Extracted {} frames from log", frames.len());
+
+        // Log when ComponentSerial provides a longer serial than the header
+        if let Some(ref full_sn) = component_serials.aircraft {
+            if full_sn.len() > parser.details.aircraft_sn.trim().len() {
+                log::info!(
+                    "ComponentSerial override: aircraft_sn '{}' ({} chars) -> '{}' ({} chars)",
+                    parser.details.aircraft_sn.trim(), parser.details.aircraft_sn.trim().len(),
+                    full_sn, full_sn.len()
+                );
+            }
+        }
+        if let Some(ref full_sn) = component_serials.battery {
+            if full_sn.len() > parser.details.battery_sn.trim().len() {
+                log::info!(
+                    "ComponentSerial override: battery_sn '{}' ({} chars) -> '{}' ({} chars)",
+                    parser.details.battery_sn.trim(), parser.details.battery_sn.trim().len(),
+                    full_sn, full_sn.len()
+                );
+            }
+        }
+
+        if frames.is_empty() {
+            log::warn!("No frames extracted from log file — file may be empty or corrupt");
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        // Extract telemetry points
+        let details_total_time_secs = parser.details.total_time as f64;
+        let points = self.extract_telemetry(&frames, details_total_time_secs);
+        log::info!(
+            "Extracted {} valid telemetry points from {} frames ({} skipped)",
+            points.len(),
+            frames.len(),
+            frames.len() - points.len()
+        );
+
+        // Extract app messages (tips and warnings)
+        let messages = self.extract_messages(&frames, details_total_time_secs);
+        log::info!("Extracted {} app messages from log", messages.len());
+
+        let side_data = self.extract_side_data(&frames, &snapshots, details_total_time_secs);
+        log::info!(
+            "Extracted side records: {} home, {} RC GPS, {} avoidance, {} motor, {} wind",
+            side_data.home_updates.len(),
+            side_data.rc_gps.len(),
+            side_data.avoidance.len(),
+            side_data.motor_status.len(),
+            side_data.wind_warnings.len()
+        );
+
+        if points.is_empty() {
+            log::warn!("No valid telemetry points after filtering — all frames had corrupt/missing data");
+            return Err(ParserError::NoTelemetryData);
+        }
 
-    /// Map ISO 3166-1 alpha-2 country code to continent name.
-    fn continent_from_cc(cc: &str) -> Option<&'static str> {
-        match cc {
-            // Europe
-            "AD"|"AL"|"AT"|"BA"|"BE"|"BG"|"BY"|"CH"|"CY"|"CZ"|"DE"|"DK"|"EE"|"ES"|"FI"|
-            "FO"|"FR"|"GB"|"GE"|"GG"|"GI"|"GR"|"HR"|"HU"|"IE"|"IM"|"IS"|"IT"|"JE"|"LI"|
-            "LT"|"LU"|"LV"|"MC"|"MD"|"ME"|"MK"|"MT"|"NL"|"NO"|"PL"|"PT"|"RO"|"RS"|"SE"|
-            "SI"|"SK"|"SM"|"UA"|"VA"|"XK" => Some("Europe"),
-            // North America
-            "AG"|"AI"|"AW"|"BB"|"BM"|"BS"|"BZ"|"CA"|"CR"|"CU"|"CW"|"DM"|"DO"|"GD"|"GL"|
-            "GP"|"GT"|"GU"|"HN"|"HT"|"JM"|"KN"|"KY"|"LC"|"MQ"|"MS"|"MX"|"NI"|"PA"|"PM"|
-            "PR"|"SV"|"SX"|"TC"|"TT"|"US"|"VC"|"VG"|"VI" => Some("North America"),
-            // South America
-            "AR"|"BO"|"BR"|"CL"|"CO"|"EC"|"FK"|"GF"|"GY"|"PE"|"PY"|"SR"|"UY"|"VE"
-                => Some("South America"),
-            // Africa
-            "AO"|"BF"|"BI"|"BJ"|"BW"|"CD"|"CF"|"CG"|"CI"|"CM"|"CV"|"DJ"|"DZ"|"EG"|"ER"|
-            "ET"|"GA"|"GH"|"GM"|"GN"|"GQ"|"GW"|"KE"|"KM"|"LR"|"LS"|"LY"|"MA"|"MG"|"ML"|
-            "MR"|"MU"|"MW"|"MZ"|"NA"|"NE"|"NG"|"RE"|"RW"|"SC"|"SD"|"SH"|"SL"|"SN"|"SO"|
-            "SS"|"ST"|"SZ"|"TD"|"TG"|"TN"|"TZ"|"UG"|"YT"|"ZA"|"ZM"|"ZW"
-                => Some("Africa"),
-            // Asia
-            "AE"|"AF"|"AM"|"AZ"|"BD"|"BH"|"BN"|"CN"|"HK"|"ID"|"IL"|"IN"|"IQ"|"IR"|"JO"|
-            "JP"|"KG"|"KH"|"KP"|"KR"|"KW"|"KZ"|"LA"|"LB"|"LK"|"MM"|"MN"|"MO"|"MV"|"MY"|
-            "NP"|"OM"|"PH"|"PK"|"PS"|"QA"|"RU"|"SA"|"SG"|"SY"|"TH"|"TJ"|"TL"|"TM"|"TR"|
-            "TW"|"UZ"|"VN"|"YE" => Some("Asia"),
-            // Oceania
-            "AS"|"AU"|"FJ"|"FM"|"KI"|"MH"|"NC"|"NF"|"NR"|"NU"|"NZ"|"PF"|"PG"|"PW"|"SB"|
-            "TK"|"TO"|"TV"|"VU"|"WF"|"WS" => Some("Oceania"),
-            // Antarctica
-            "AQ" => Some("Antarctica"),
-            _ => None,
+        // Calculate statistics
+        let stats = LogParser::calculate_stats(&points);
+
+        // Build metadata
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        // Count photo and video capture events from telemetry transitions
+        let (photo_count, video_count) = crate::models::count_media_events(&points);
+
+        let metadata = FlightMetadata {
+            id: 0,
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: self.extract_drone_model(&parser),
+            drone_serial: component_serials.aircraft.clone()
+                .or_else(|| self.extract_serial(&parser)),
+            aircraft_name: self.extract_aircraft_name(&parser),
+            battery_serial: component_serials.battery.clone()
+                .or_else(|| self.extract_battery_serial(&parser)),
+            cycle_count: component_serials.cycle_count,
+            start_time: self.extract_start_time(&parser),
+            end_time: self.extract_end_time(&parser),
+            duration_secs: Some(
+                if details_total_time_secs > 0.0 {
+                    details_total_time_secs
+                } else {
+                    stats.duration_secs
+                }
+            ),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count,
+            video_count,
+        };
+
+        log::info!(
+            "Parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, max_speed={:.1}m/s, home={:?}, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            stats.max_speed_ms,
+            stats.home_location,
+            points.len()
+        );
+
+        // Generate smart tags based on flight characteristics
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        
+        // Add M-SDK tag if DJIFly department override was used (third-party app like Dronelink/DroneDeploy)
+        if used_djifly_fallback {
+            tags.push("M-SDK".to_string());
+            log::info!("Added M-SDK tag (third-party app detected via DJIFly fallback)");
         }
+        
+        log::info!("Generated smart tags: {:?}", tags);
+
+        let equipment = self.extract_equipment(&parser, component_serials);
+        log::debug!("Equipment details: {:?}", equipment);
+
+        Ok(ParsedFlight { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages, side_data, equipment })
     }
 
     /// Get frames from the parser, handling encryption if needed.
     /// Runs the CPU-bound parsing in spawn_blocking with catch_unwind
     /// to prevent panics from crashing the application.
-    /// Returns (frames, used_djifly_fallback) where used_djifly_fallback indicates
-    /// if the DJIFly department override was needed (third-party app like Dronelink).
-    async fn get_frames(&self, parser: &DJILog) -> Result<(Vec<Frame>, bool, ComponentSerials), ParserError> {
+    /// `used_djifly_fallback` in the result indicates if the DJIFly department
+    /// override was needed (third-party app like Dronelink).
+    async fn get_frames(&self, parser: &DJILog) -> Result<DjiFrames, ParserError> {
         // Version 13+ requires keychains for decryption
         let (keychains, used_djifly_fallback) = if parser.version >= 13 {
-            let api_key = self.api.get_api_key().ok_or(ParserError::EncryptionKeyRequired)?;
-            
+            let provider = self.keychains.ok_or(ParserError::EncryptionKeyRequired)?;
             // Try standard keychain fetch first
-            match parser.fetch_keychains(&api_key) {
+            let standard = parser
+                .keychains_request()
+                .map_err(|e| ApiError::ApiResponse(e.to_string()))
+                .and_then(|request| provider.fetch_keychains(&request));
+            match standard {
                 Ok(kc) => (Some(kc), false),
+                Err(ApiError::ApiKeyNotConfigured) => return Err(ParserError::EncryptionKeyRequired),
                 Err(e) => {
                     // Standard fetch failed — try fallback for third-party apps (Dronelink, DroneDeploy)
                     // These apps write non-standard metadata that causes DJI API to reject keychains.
@@ -704,7 +1204,7 @@ impl<'a> LogParser<'a> {
                         .keychains_request_with_custom_params(Some(Department::DJIFly), None)
                         .map_err(|e| ParserError::Api(format!("Failed to create keychain request: {}", e)))?;
                     
-                    let kc = request.fetch(&api_key, None).map_err(|e| {
+                    let kc = provider.fetch_keychains(&request).map_err(|e| {
                         ParserError::Api(format!("Keychain fetch failed (both standard and DJIFly fallback): {}", e))
                     })?;
                     
@@ -740,20 +1240,33 @@ impl<'a> LogParser<'a> {
                     // because not all OSD ticks have a matching camera record. This causes
                     // false `is_photo` and `is_video` transitions in the extracted frames.
                     // We must track the true persistent state out-of-band to override the frames.
-                    let mut persistent_camera_states = Vec::new();
-                    let mut current_is_photo = false;
-                    let mut current_is_video = false;
+                    // The same walk also captures RC GPS and MC parameter records, which
+                    // the library drops when building frames.
+                    let mut snapshots = Vec::new();
+                    let mut current = RecordSnapshot::default();
                     let mut osd_count = 0;
 
                     for record in &records {
                         match record {
                             dji_log_parser::record::Record::Camera(camera) => {
-                                current_is_photo = camera.is_shooting_single_photo;
-                                current_is_video = camera.is_recording;
+                                current.is_photo = camera.is_shooting_single_photo;
+                                current.is_video = camera.is_recording;
+                            }
+                            dji_log_parser::record::Record::RCGPS(rc_gps) => {
+                                current.rc_gps = Some((
+                                    rc_gps.latitude as f64 / 1e7,
+                                    rc_gps.longitude as f64 / 1e7,
+                                    rc_gps.gps_num,
+                                    rc_gps.accuracy,
+                                ));
+                            }
+                            dji_log_parser::record::Record::MCParams(params) => {
+                                current.obstacle_avoidance_enabled = Some(params.avoid_obstacle_enabled);
+                                current.user_avoidance_enabled = Some(params.user_avoid_enabled);
                             }
                             dji_log_parser::record::Record::OSD(_) => {
                                 if osd_count > 0 {
-                                    persistent_camera_states.push((current_is_photo, current_is_video));
+                                    snapshots.push(current);
                                 }
                                 osd_count += 1;
                             }
@@ -764,12 +1277,13 @@ impl<'a> LogParser<'a> {
                     let mut frames = records_to_frames(records, parser_ref.details.clone());
                     
                     // Override the artificially false values injected by the library with our persistent trackers
-                    for (frame, &(is_photo, is_video)) in frames.iter_mut().zip(persistent_camera_states.iter()) {
-                        frame.camera.is_photo = is_photo;
-                        frame.camera.is_video = is_video;
+                    for (frame, snapshot) in frames.iter_mut().zip(snapshots.iter()) {
+                        frame.camera.is_photo = snapshot.is_photo;
+                        frame.camera.is_video = snapshot.is_video;
                     }
+                    snapshots.resize(frames.len(), current);
 
-                    Ok((frames, comp_serials))
+                    Ok((frames, snapshots, comp_serials))
                 }))
             }),
         )
@@ -781,14 +1295,19 @@ impl<'a> LogParser<'a> {
             Ok(Ok(Err(panic_val))) => {
                 let msg = panic_val
                     .downcast_ref::<String>()
-                    .map(|s| s.clone())
+                    .cloned()
                     .or_else(|| panic_val.downcast_ref::<&str>().map(|s| s.to_string()))
                     .unwrap_or_else(|| "unknown panic".to_string());
                 Err(ParserError::Panic(msg))
             }
             Ok(Ok(Ok(frames_result))) => {
                 frames_result
-                    .map(|(frames, comp_serials)| (frames, used_djifly_fallback, comp_serials))
+                    .map(|(frames, snapshots, comp_serials)| DjiFrames {
+                        frames,
+                        snapshots,
+                        used_djifly_fallback,
+                        component_serials: comp_serials,
+                    })
                     .map_err(|e: dji_log_parser::Error| ParserError::Parse(e.to_string()))
             }
         }
@@ -969,104 +1488,6 @@ impl<'a> LogParser<'a> {
         points
     }
 
-    /// Calculate flight statistics from telemetry points
-    pub fn calculate_stats(&self, points: &[TelemetryPoint]) -> FlightStats {
-        let duration_secs = points.last().map(|p| p.timestamp_ms as f64 / 1000.0).unwrap_or(0.0);
-
-        let max_altitude = points
-            .iter()
-            .filter_map(|p| p.height.or(p.altitude))
-            .fold(f64::NEG_INFINITY, f64::max);
-
-        let max_speed = points
-            .iter()
-            .filter_map(|p| p.speed)
-            .fold(f64::NEG_INFINITY, f64::max);
-
-        let avg_speed: f64 = {
-            let speeds: Vec<f64> = points.iter().filter_map(|p| p.speed).collect();
-            if speeds.is_empty() {
-                0.0
-            } else {
-                speeds.iter().sum::<f64>() / speeds.len() as f64
-            }
-        };
-
-        let min_battery = points
-            .iter()
-            .filter_map(|p| p.battery_percent)
-            .min()
-            .unwrap_or(0);
-
-        // Calculate total distance using haversine formula
-        let total_distance = self.calculate_total_distance(points);
-
-        // Home location is the first valid GPS point
-        let home_location = points
-            .iter()
-            .find_map(|p| match (p.longitude, p.latitude) {
-                (Some(lon), Some(lat)) => Some([lon, lat]),
-                _ => None,
-            });
-
-        // Max distance from home
-        let max_distance_from_home = if let Some(home) = home_location {
-            points
-                .iter()
-                .filter_map(|p| match (p.latitude, p.longitude) {
-                    (Some(lat), Some(lon)) => Some(haversine_distance(home[1], home[0], lat, lon)),
-                    _ => None,
-                })
-                .fold(0.0_f64, f64::max)
-        } else {
-            0.0
-        };
-
-        // Start and end battery percent
-        let start_battery_percent = points.iter().find_map(|p| p.battery_percent);
-        let end_battery_percent = points.iter().rev().find_map(|p| p.battery_percent);
-
-        // Start battery temperature
-        let start_battery_temp = points.iter().find_map(|p| p.battery_temp);
-
-        FlightStats {
-            duration_secs,
-            total_distance_m: total_distance,
-            max_altitude_m: if max_altitude.is_finite() {
-                max_altitude
-            } else {
-                0.0
-            },
-            max_speed_ms: if max_speed.is_finite() { max_speed } else { 0.0 },
-            avg_speed_ms: avg_speed,
-            min_battery,
-            home_location,
-            max_distance_from_home_m: max_distance_from_home,
-            start_battery_percent,
-            end_battery_percent,
-            start_battery_temp,
-        }
-    }
-
-    /// Calculate total distance traveled using haversine formula
-    fn calculate_total_distance(&self, points: &[TelemetryPoint]) -> f64 {
-        let mut total = 0.0;
-        let mut prev_lat: Option<f64> = None;
-        let mut prev_lon: Option<f64> = None;
-
-        for point in points {
-            if let (Some(lat), Some(lon)) = (point.latitude, point.longitude) {
-                if let (Some(p_lat), Some(p_lon)) = (prev_lat, prev_lon) {
-                    total += haversine_distance(p_lat, p_lon, lat, lon);
-                }
-                prev_lat = Some(lat);
-                prev_lon = Some(lon);
-            }
-        }
-
-        total
-    }
-
     /// Extract app messages (tips and warnings) from parsed frames
     fn extract_messages(&self, frames: &[Frame], details_total_time_secs: f64) -> Vec<FlightMessage> {
         let mut messages = Vec::new();
@@ -1303,6 +1724,193 @@ impl<'a> LogParser<'a> {
         messages
     }
 
+    /// Extract home point, RC GPS, avoidance, motor and wind records.
+    /// Only change-points are kept; timestamps follow `extract_telemetry`
+    /// so rows line up with the telemetry points.
+    fn extract_side_data(
+        &self,
+        frames: &[Frame],
+        snapshots: &[RecordSnapshot],
+        details_total_time_secs: f64,
+    ) -> FlightSideData {
+        let mut side = FlightSideData::default();
+        let mut timestamp_ms: i64 = 0;
+
+        let has_fly_time = frames.iter().any(|f| f.osd.fly_time > 0.0);
+        let fallback_interval_ms: i64 = if !has_fly_time && details_total_time_secs > 0.0 && !frames.is_empty() {
+            ((details_total_time_secs * 1000.0) / frames.len() as f64).round() as i64
+        } else {
+            100
+        };
+
+        fn opt_enum<T: std::fmt::Debug>(v: &Option<T>) -> Option<String> {
+            v.as_ref().map(|val| format!("{:?}", val))
+        }
+
+        let mut prev_wind: Option<(String, String)> = None;
+
+        for (frame, snapshot) in frames.iter().zip(snapshots.iter()) {
+            let osd = &frame.osd;
+            let fly_time_ms = if osd.fly_time > 0.0 {
+                (osd.fly_time * 1000.0) as i64
+            } else {
+                0
+            };
+            let ts = fly_time_ms.max(timestamp_ms);
+            timestamp_ms = ts + fallback_interval_ms;
+
+            // Home point / RTH settings (zeros until the first Home record)
+            let home = &frame.home;
+            let has_home = home.latitude != 0.0 || home.longitude != 0.0 || home.go_home_height != 0;
+            if has_home && is_finite_f64(home.latitude) && is_finite_f64(home.longitude) && is_finite_f32(home.altitude) {
+                let update = HomeUpdate {
+                    timestamp_ms: ts,
+                    latitude: home.latitude,
+                    longitude: home.longitude,
+                    altitude: home.altitude as f64,
+                    go_home_height: home.go_home_height as f64,
+                    max_allowed_height: home.max_allowed_height as f64,
+                    go_home_mode: opt_enum(&home.go_home_mode),
+                    dynamic_home_point: home.is_dynamic_home_point_enabled,
+                };
+                let changed = side.home_updates.last().map_or(true, |p| {
+                    p.latitude != update.latitude
+                        || p.longitude != update.longitude
+                        || p.altitude != update.altitude
+                        || p.go_home_height != update.go_home_height
+                        || p.max_allowed_height != update.max_allowed_height
+                        || p.go_home_mode != update.go_home_mode
+                        || p.dynamic_home_point != update.dynamic_home_point
+                });
+                if changed {
+                    side.home_updates.push(update);
+                }
+            }
+
+            // RC GPS (pilot location)
+            if let Some((lat, lon, sats, accuracy)) = snapshot.rc_gps {
+                let valid = (lat != 0.0 || lon != 0.0) && (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon);
+                let changed = side.rc_gps.last().map_or(true, |p| {
+                    p.latitude != lat || p.longitude != lon || p.satellites != sats as i32
+                });
+                if valid && changed {
+                    side.rc_gps.push(RcGpsPoint {
+                        timestamp_ms: ts,
+                        latitude: lat,
+                        longitude: lon,
+                        satellites: sats as i32,
+                        accuracy: is_finite_f32(accuracy).then_some(accuracy as f64),
+                    });
+                }
+            }
+
+            // Obstacle avoidance settings and downward sensors
+            let sensors_active = osd.is_vision_used || osd.is_swave_work;
+            let vision_height = (sensors_active && is_finite_f32(osd.vps_height))
+                .then(|| (osd.vps_height as f64 * 10.0).round() / 10.0);
+            let avoidance = AvoidanceState {
+                timestamp_ms: ts,
+                obstacle_avoidance_enabled: snapshot.obstacle_avoidance_enabled,
+                user_avoidance_enabled: snapshot.user_avoidance_enabled,
+                vision_positioning_used: osd.is_vision_used,
+                ultrasonic_working: osd.is_swave_work,
+                vision_height,
+            };
+            let changed = side.avoidance.last().map_or(true, |p| {
+                p.obstacle_avoidance_enabled != avoidance.obstacle_avoidance_enabled
+                    || p.user_avoidance_enabled != avoidance.user_avoidance_enabled
+                    || p.vision_positioning_used != avoidance.vision_positioning_used
+                    || p.ultrasonic_working != avoidance.ultrasonic_working
+                    || p.vision_height != avoidance.vision_height
+            });
+            if changed {
+                side.avoidance.push(avoidance);
+            }
+
+            // Motor / ESC status
+            let motor = MotorStatus {
+                timestamp_ms: ts,
+                motors_on: osd.is_motor_on,
+                motor_blocked: osd.is_motor_blocked,
+                not_enough_force: osd.is_not_enough_force,
+                propeller_catapult: osd.is_propeller_catapult,
+                start_failed_cause: opt_enum(&osd.motor_start_failed_cause),
+            };
+            let changed = side.motor_status.last().map_or(true, |p| {
+                p.motors_on != motor.motors_on
+                    || p.motor_blocked != motor.motor_blocked
+                    || p.not_enough_force != motor.not_enough_force
+                    || p.propeller_catapult != motor.propeller_catapult
+                    || p.start_failed_cause != motor.start_failed_cause
+            });
+            if changed {
+                side.motor_status.push(motor);
+            }
+
+            // Wind warnings only exist as DJI app tip/warn text
+            for (message_type, text) in [("tip", &frame.app.tip), ("warn", &frame.app.warn)] {
+                if !text.to_lowercase().contains("wind") {
+                    continue;
+                }
+                let key = (message_type.to_string(), text.clone());
+                if prev_wind.as_ref() != Some(&key) {
+                    side.wind_warnings.push(WindWarning {
+                        timestamp_ms: ts,
+                        message_type: key.0.clone(),
+                        message: key.1.clone(),
+                    });
+                    prev_wind = Some(key);
+                }
+            }
+        }
+
+        side
+    }
+
+    /// Extract app, firmware and component serials. Full-length serials from
+    /// ComponentSerial records win over the truncated header values.
+    fn extract_equipment(&self, parser: &DJILog, serials: ComponentSerials) -> FlightEquipment {
+        fn non_empty(s: &str) -> Option<String> {
+            let s = s.trim();
+            (!s.is_empty()).then(|| s.to_uppercase())
+        }
+
+        // The flying app is only recorded (as the keychain "department") in v13+ logs
+        let app_name = if parser.version >= 13 {
+            parser
+                .keychains_request()
+                .ok()
+                .and_then(|request| match Department::from(request.department) {
+                    Department::SDK => Some("DJI SDK"),
+                    Department::DJIGO => Some("DJI GO"),
+                    Department::DJIFly => Some("DJI Fly"),
+                    Department::AgriculturalMachinery => Some("DJI Agras"),
+                    Department::Terra => Some("DJI Terra"),
+                    Department::DJIGlasses => Some("DJI Goggles"),
+                    Department::DJIPilot => Some("DJI Pilot"),
+                    Department::GSPro => Some("DJI GS Pro"),
+                    Department::Unknown(_) => None,
+                })
+                .map(String::from)
+        } else {
+            None
+        };
+
+        let details = &parser.details;
+        FlightEquipment {
+            app_name,
+            app_platform: Some(format!("{:?}", details.app_platform)),
+            app_version: Some(details.app_version.trim().to_string()).filter(|v| !v.is_empty()),
+            aircraft_firmware: serials.aircraft_firmware,
+            battery_firmware: serials.battery_firmware,
+            rc_serial: serials.rc.or_else(|| non_empty(&details.rc_sn)),
+            rc_firmware: serials.rc_firmware,
+            camera_serial: serials.camera.or_else(|| non_empty(&details.camera_sn)),
+            camera_firmware: serials.camera_firmware,
+            gimbal_firmware: serials.gimbal_firmware,
+        }
+    }
+
     /// Extract drone model from parser metadata
     fn extract_drone_model(&self, parser: &DJILog) -> Option<String> {
         let model = format!("{:?}", parser.details.product_type);
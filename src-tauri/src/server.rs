@@ -10,19 +10,29 @@ use std::sync::Arc;
 use axum::{
     extract::{DefaultBodyLimit, FromRequestParts, Multipart, Path, Query, State as AxumState},
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     routing::{delete, get, post, put},
     Json, Router,
 };
+use futures_util::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tower_http::cors::{Any, CorsLayer};
 use tokio_cron_scheduler::{Job, JobScheduler};
 
-use crate::api::DjiApi;
-use crate::database::{self, Database};
-use crate::models::{FlightDataResponse, FlightTag, ImportResult, OverviewStats, TelemetryData};
+use crate::api::{self, DjiApi};
+use crate::archive;
+use crate::batch_import::{BatchFileResult, BatchImporter, BatchOutcome, BatchSummary};
+use crate::database::{self, Database, ImportOptions};
+use crate::formats;
+use crate::import_jobs::{ImportJob, ImportJobs};
+use crate::keychain_mock;
+use crate::models::{ArchiveImportResult, BatchImportProgress, BlacklistEntry, EquipmentItem, FlightDataResponse, FlightEquipment, FlightSideData, FlightTag, ImportFailure, ImportJobEvent, ImportJobEventKind, ImportJobStarted, ImportPreview, ImportResult, KeychainBundle, KeychainImportResult, MediaClip, MediaFrame, MediaImportResult, OverviewStats, PendingDecryption, PendingRetryResult, ReprocessResult, SupportedFormat, SyncPostImport, SyncPostImportStatus, SyncRun, SyncRunDetail, SyncRunFile, SyncSource, SyncStatus, SyncSourceStatus, SyncTrigger, TelemetryData};
 use crate::parser::LogParser;
 use crate::profile_auth;
 use crate::session_store::SessionStore;
+use crate::srt_parser;
+use crate::sync_scan::{self, SourcePolicy, SyncFilter, SyncScan};
+use crate::sync_watch::{self, SyncWatcher};
 
 /// Shared application state for Axum handlers.
 ///
@@ -35,6 +45,10 @@ pub struct WebAppState {
     pub sessions: Arc<SessionStore>,
     /// Argon2id hash of PROFILE_CREATION_PASS (None if env var not set).
     pub master_password_hash: Option<String>,
+    /// Running and recently finished import jobs of all profiles
+    pub import_jobs: Arc<ImportJobs>,
+    /// Cron jobs of SYNC_INTERVAL and the profiles' sync sources
+    pub sync_scheduler: Arc<SyncScheduler>,
 }
 
 impl WebAppState {
@@ -60,6 +74,16 @@ impl WebAppState {
         Ok(db)
     }
 
+    /// Profiles with an open database connection, with their databases.
+    pub fn open_databases(&self) -> Vec<(String, Arc<Database>)> {
+        self.databases
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(profile, db)| (profile.clone(), db.clone()))
+            .collect()
+    }
+
     /// Remove a cached connection (used after profile deletion).
     pub fn evict_profile(&self, profile: &str) {
         self.databases.write().unwrap().remove(profile);
@@ -181,7 +205,7 @@ fn compute_file_hash(path: &std::path::Path) -> Result<String, String> {
 }
 
 /// Copy uploaded file to the keep folder with hash-based deduplication (web mode)
-fn copy_uploaded_file_web(src_path: &std::path::PathBuf, dest_folder: &std::path::PathBuf, file_hash: Option<&str>) -> Result<(), String> {
+fn copy_uploaded_file_web(src_path: &std::path::Path, dest_folder: &std::path::PathBuf, file_hash: Option<&str>) -> Result<(), String> {
     // Create the destination folder if it doesn't exist
     std::fs::create_dir_all(dest_folder)
         .map_err(|e| format!("Failed to create uploaded files folder: {}", e))?;
@@ -276,192 +300,279 @@ async fn import_log(
     std::fs::write(&temp_path, &data)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
 
-    let import_start = std::time::Instant::now();
     log::info!("Importing uploaded log file: {}", file_name);
+    let (keep_folder, config) = kept_upload_folder(&pdb);
+    let result = BatchImporter::new(pdb.db.clone(), ImportOptions::from_config(&config, &pdb.profile))
+        .import_one(&temp_path)
+        .await;
+    keep_uploaded_file(keep_folder.as_ref(), &temp_path, &result);
 
-    // Check if we should keep uploaded files (via env var or config) - check early for all code paths
-    let upload_config_path = pdb.config_path();
-    let upload_config: serde_json::Value = if upload_config_path.exists() {
-        std::fs::read_to_string(&upload_config_path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-    let keep_enabled = std::env::var("KEEP_UPLOADED_FILES")
-        .map(|v| v.to_lowercase() == "true" || v == "1")
-        .unwrap_or_else(|_| {
-            upload_config.get("keep_uploaded_files").and_then(|v| v.as_bool()).unwrap_or(false)
-        });
-    let default_upload_folder = pdb.default_upload_folder();
-    let upload_folder = upload_config.get("uploaded_files_path")
-        .and_then(|v| v.as_str())
-        .map(|s| std::path::PathBuf::from(s))
-        .unwrap_or(default_upload_folder);
-
-    // Helper to copy uploaded file if setting is enabled
-    let try_copy_file = |file_hash: Option<&str>| {
-        if keep_enabled {
-            if let Err(e) = copy_uploaded_file_web(&temp_path, &upload_folder, file_hash) {
-                log::warn!("Failed to copy uploaded file: {}", e);
-            }
-        }
-    };
+    // Clean up temp file
+    let _ = std::fs::remove_file(&temp_path);
 
-    let parser = LogParser::new(&pdb.db);
+    Ok(Json(result.to_import_result()))
+}
 
-    let parse_result = match parser.parse_log(&temp_path).await {
-        Ok(result) => result,
-        Err(crate::parser::ParserError::AlreadyImported(matching_flight)) => {
-            // Compute file hash so copy_uploaded_file_web can properly deduplicate
-            let file_hash = compute_file_hash(&temp_path).ok();
-            // Copy the file even though flight is already imported
-            try_copy_file(file_hash.as_deref());
-            // Clean up temp file
-            let _ = std::fs::remove_file(&temp_path);
-            return Ok(Json(ImportResult {
-                success: false,
-                flight_id: None,
-                message: format!("This flight log has already been imported (matches: {})", matching_flight),
-                point_count: 0,
-                file_hash,
-            }));
-        }
-        Err(e) => {
-            let _ = std::fs::remove_file(&temp_path);
-            log::error!("Failed to parse log {}: {}", file_name, e);
-            return Ok(Json(ImportResult {
-                success: false,
-                flight_id: None,
-                message: format!("Failed to parse log: {}", e),
-                point_count: 0,
-                file_hash: None,
-            }));
-        }
-    };
+/// POST /api/import/archive — Upload a ZIP or tar.gz archive and import every flight log in it
+async fn import_archive(
+    pdb: ProfileDb,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<ArchiveImportResult>>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    // Only keep the final path component of the client-supplied name
+    let file_name = field
+        .file_name()
+        .and_then(|n| std::path::Path::new(n).file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("upload.zip")
+        .to_string();
+    if !archive::is_archive(std::path::Path::new(&file_name)) {
+        return Err(err_response(
+            StatusCode::BAD_REQUEST,
+            "Unsupported archive type (expected .zip, .tar.gz or .tgz)",
+        ));
+    }
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
 
-    // Copy uploaded file before cleanup if enabled
-    try_copy_file(parse_result.metadata.file_hash.as_deref());
+    let temp_dir = std::env::temp_dir().join("drone-logbook-uploads");
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create temp dir: {}", e)))?;
 
-    // Clean up temp file
+    let temp_path = temp_dir.join(&file_name);
+    std::fs::write(&temp_path, &data)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+    log::info!("Importing uploaded archive: {}", file_name);
+    let archive_path = temp_path.clone();
+    let extracted = tokio::task::spawn_blocking(move || archive::extract(&archive_path))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Archive extraction task failed: {}", e)));
     let _ = std::fs::remove_file(&temp_path);
+    let extracted = extracted?
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to extract archive: {}", e)))?;
+
+    let (keep_folder, config) = kept_upload_folder(&pdb);
+    let import_options = ImportOptions::from_config(&config, &pdb.profile);
+    let paths = extracted.entries.iter().map(|entry| entry.path.clone()).collect();
+    let summary = BatchImporter::new(pdb.db.clone(), import_options)
+        .run(paths, log_batch_progress)
+        .await;
+
+    let results: Vec<ArchiveImportResult> = extracted
+        .entries
+        .iter()
+        .zip(&summary.files)
+        .map(|(entry, file)| {
+            keep_uploaded_file(keep_folder.as_ref(), &entry.path, file);
+            ArchiveImportResult {
+                entry_name: entry.name.clone(),
+                result: file.to_import_result(),
+            }
+        })
+        .collect();
 
-    // Check for duplicate flight based on signature (drone_serial + battery_serial + start_time)
-    if let Some(matching_flight) = pdb.db.is_duplicate_flight(
-        parse_result.metadata.drone_serial.as_deref(),
-        parse_result.metadata.battery_serial.as_deref(),
-        parse_result.metadata.start_time,
-    ).unwrap_or(None) {
-        log::info!("Skipping duplicate flight (signature match): {} - matches flight '{}' in database", file_name, matching_flight);
-        return Ok(Json(ImportResult {
-            success: false,
-            flight_id: None,
-            message: format!("Duplicate flight: matches '{}' (same drone, battery, and start time)", matching_flight),
-            point_count: 0,
-            file_hash: parse_result.metadata.file_hash.clone(),
-        }));
-    }
+    log::info!(
+        "Archive import finished: {} of {} logs imported",
+        results.iter().filter(|r| r.result.success).count(),
+        results.len()
+    );
+    Ok(Json(results))
+}
 
-    // Insert flight metadata
-    let flight_id = pdb.db
-        .insert_flight(&parse_result.metadata)
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to insert flight: {}", e)))?;
+/// POST /api/media/import — Upload DJI video subtitle (.SRT) files and import
+/// them as media clips. Every `file` field is imported; an optional
+/// `droneSerial` field restricts the flights they can be linked to.
+async fn import_media(
+    pdb: ProfileDb,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<MediaImportResult>>, (StatusCode, Json<ErrorResponse>)> {
+    let temp_dir = std::env::temp_dir().join("drone-logbook-uploads");
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create temp dir: {}", e)))?;
 
-    // Bulk insert telemetry data
-    let point_count = match pdb.db.bulk_insert_telemetry(flight_id, &parse_result.points) {
-        Ok(count) => count,
-        Err(e) => {
-            log::error!("Failed to insert telemetry for flight {}: {}. Cleaning up.", flight_id, e);
-            if let Err(cleanup_err) = pdb.db.delete_flight(flight_id) {
-                log::error!("Failed to clean up flight {}: {}", flight_id, cleanup_err);
-            }
-            return Ok(Json(ImportResult {
-                success: false,
-                flight_id: None,
-                message: format!("Failed to insert telemetry data: {}", e),
-                point_count: 0,
-                file_hash: parse_result.metadata.file_hash.clone(),
-            }));
+    let mut drone_serial: Option<String> = None;
+    let mut uploads = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+    {
+        if field.name() == Some("droneSerial") {
+            let value = field
+                .text()
+                .await
+                .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read droneSerial: {}", e)))?;
+            drone_serial = Some(value.trim().to_string()).filter(|s| !s.is_empty());
+            continue;
         }
-    };
-
-    // Insert smart tags if the feature is enabled
-    let config_path = pdb.config_path();
-    let config: serde_json::Value = if config_path.exists() {
-        std::fs::read_to_string(&config_path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-    let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
-    
-    if tags_enabled {
-        // Filter tags based on enabled_tag_types if configured
-        let tags = if let Some(types) = config.get("enabled_tag_types").and_then(|v| v.as_array()) {
-            let enabled_types: Vec<String> = types.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect();
-            crate::parser::LogParser::filter_smart_tags(parse_result.tags.clone(), &enabled_types)
-        } else {
-            parse_result.tags.clone()
-        };
-        if let Err(e) = pdb.db.insert_flight_tags(flight_id, &tags) {
-            log::warn!("Failed to insert tags for flight {}: {}", flight_id, e);
+        // Only keep the final path component of the client-supplied name
+        let file_name = field
+            .file_name()
+            .and_then(|n| std::path::Path::new(n).file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown.SRT")
+            .to_string();
+        if !srt_parser::is_srt(std::path::Path::new(&file_name)) {
+            return Err(err_response(StatusCode::BAD_REQUEST, format!("Not a video subtitle file: {}", file_name)));
         }
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+        let temp_path = temp_dir.join(&file_name);
+        std::fs::write(&temp_path, &data)
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+        uploads.push(temp_path);
     }
-
-    // Insert manual tags from re-imported CSV exports (always inserted regardless of smart_tags_enabled)
-    for manual_tag in &parse_result.manual_tags {
-        if let Err(e) = pdb.db.add_flight_tag(flight_id, manual_tag) {
-            log::warn!("Failed to insert manual tag '{}' for flight {}: {}", manual_tag, flight_id, e);
-        }
+    if uploads.is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "No file uploaded"));
     }
 
-    // Auto-tag with profile name for non-default profiles
-    if pdb.profile != "default" {
-        if let Err(e) = pdb.db.add_flight_tag(flight_id, &pdb.profile) {
-            log::warn!("Failed to insert profile tag '{}' for flight {}: {}", pdb.profile, flight_id, e);
-        }
-    }
+    let parser = LogParser::new(&pdb.db);
+    let results: Vec<MediaImportResult> = uploads
+        .iter()
+        .map(|path| {
+            let result = parser.import_media_file(path, drone_serial.as_deref());
+            let _ = std::fs::remove_file(path);
+            result
+        })
+        .collect();
+    log::info!(
+        "Imported {} of {} uploaded video subtitle files",
+        results.iter().filter(|r| r.success).count(),
+        results.len()
+    );
+    Ok(Json(results))
+}
 
-    // Insert notes from re-imported CSV exports
-    if let Some(ref notes) = parse_result.notes {
-        if let Err(e) = pdb.db.update_flight_notes(flight_id, Some(notes.as_str())) {
-            log::warn!("Failed to insert notes for flight {}: {}", flight_id, e);
-        }
-    }
+/// GET /api/media — All imported video clips, newest first
+async fn get_media_clips(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<MediaClip>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .get_media_clips()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get media clips: {}", e)))
+}
 
-    // Apply color from re-imported CSV exports
-    if let Some(ref color) = parse_result.color {
-        if let Err(e) = pdb.db.update_flight_color(flight_id, color) {
-            log::warn!("Failed to set color for flight {}: {}", flight_id, e);
-        }
-    }
+#[derive(Deserialize)]
+struct MediaClipQuery {
+    clip_id: i64,
+}
+
+/// GET /api/media/frames — Every frame (position and camera settings) of a video clip
+async fn get_media_frames(
+    pdb: ProfileDb,
+    Query(params): Query<MediaClipQuery>,
+) -> Result<Json<Vec<MediaFrame>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .get_media_frames(params.clip_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get media frames: {}", e)))
+}
+
+/// DELETE /api/media/delete — Delete a video clip
+async fn delete_media_clip(
+    pdb: ProfileDb,
+    Query(params): Query<MediaClipQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_media_clip(params.clip_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete media clip: {}", e)))
+}
+
+/// POST /api/import/preview — Parse an uploaded log and report what importing it would do, without writing to the database
+async fn preview_import(
+    pdb: ProfileDb,
+    mut multipart: Multipart,
+) -> Result<Json<ImportPreview>, (StatusCode, Json<ErrorResponse>)> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    // Only keep the final path component of the client-supplied name
+    let file_name = field
+        .file_name()
+        .and_then(|n| std::path::Path::new(n).file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown.txt")
+        .to_string();
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let temp_dir = std::env::temp_dir().join("drone-logbook-uploads");
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create temp dir: {}", e)))?;
+
+    let temp_path = temp_dir.join(&file_name);
+    std::fs::write(&temp_path, &data)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+    log::info!("Previewing import of uploaded log file: {}", file_name);
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let import_options = ImportOptions::from_config(&config, &pdb.profile);
+
+    let parser = LogParser::new(&pdb.db);
+    let preview = parser.preview_import(&temp_path, &import_options).await;
+
+    // Clean up temp file
+    let _ = std::fs::remove_file(&temp_path);
+
+    preview
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::UNPROCESSABLE_ENTITY, format!("Failed to parse log: {}", e)))
+}
 
-    // Insert app messages (tips and warnings) from DJI logs
-    if !parse_result.messages.is_empty() {
-        if let Err(e) = pdb.db.insert_flight_messages(flight_id, &parse_result.messages) {
-            log::warn!("Failed to insert messages for flight {}: {}", flight_id, e);
+/// Folder that uploaded logs are kept in (`None` when keeping is disabled via
+/// KEEP_UPLOADED_FILES or config), along with the profile's config.json
+fn kept_upload_folder(pdb: &ProfileDb) -> (Option<PathBuf>, serde_json::Value) {
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let keep_enabled = std::env::var("KEEP_UPLOADED_FILES")
+        .map(|v| v.to_lowercase() == "true" || v == "1")
+        .unwrap_or_else(|_| {
+            config.get("keep_uploaded_files").and_then(|v| v.as_bool()).unwrap_or(false)
+        });
+    let folder = keep_enabled.then(|| {
+        config.get("uploaded_files_path")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| pdb.default_upload_folder())
+    });
+    (folder, config)
+}
+
+/// Copy an imported (or already known) upload from `src_path` to the kept
+/// uploads folder, if there is one
+fn keep_uploaded_file(keep_folder: Option<&PathBuf>, src_path: &std::path::Path, file: &BatchFileResult) {
+    if let (Some(folder), BatchOutcome::Imported { .. } | BatchOutcome::Duplicate { .. }) = (keep_folder, &file.outcome) {
+        if let Err(e) = copy_uploaded_file_web(src_path, folder, file.file_hash.as_deref()) {
+            log::warn!("Failed to copy uploaded file: {}", e);
         }
     }
+}
 
-    log::info!(
-        "Successfully imported flight {} with {} points in {:.1}s",
-        flight_id,
-        point_count,
-        import_start.elapsed().as_secs_f64()
-    );
-
-    Ok(Json(ImportResult {
-        success: true,
-        flight_id: Some(flight_id),
-        message: format!("Successfully imported {} telemetry points", point_count),
-        point_count,
-        file_hash: parse_result.metadata.file_hash.clone(),
-    }))
+/// GET /api/formats — List the flight log formats the importer accepts
+async fn list_supported_formats() -> Json<Vec<SupportedFormat>> {
+    Json(formats::list_supported_formats())
 }
 
 /// Request payload for manual flight creation
@@ -635,14 +746,39 @@ async fn get_flight_data(
             Vec::new()
         });
 
+    // Videos shot during the flight
+    let media_clips = pdb.db
+        .get_flight_media_clips(params.flight_id)
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to get media clips for flight {}: {}", params.flight_id, e);
+            Vec::new()
+        });
+
     Ok(Json(FlightDataResponse {
         flight,
         telemetry,
         track,
         messages,
+        media_clips,
     }))
 }
 
+/// GET /api/flight_side_data — Home point, RC GPS, avoidance, motor and wind records
+#[derive(Deserialize)]
+struct FlightSideDataQuery {
+    flight_id: i64,
+}
+
+async fn get_flight_side_data(
+    pdb: ProfileDb,
+    Query(params): Query<FlightSideDataQuery>,
+) -> Result<Json<FlightSideData>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .get_flight_side_data(params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get side data: {}", e)))
+}
+
 /// GET /api/overview — Get overview statistics
 async fn get_overview_stats(
     pdb: ProfileDb,
@@ -726,10 +862,10 @@ async fn update_flight_notes(
     pdb: ProfileDb,
     Json(payload): Json<UpdateNotesPayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
-    let notes_ref = payload.notes.as_ref().map(|s| {
+    let notes_ref = payload.notes.as_ref().and_then(|s| {
         let trimmed = s.trim();
         if trimmed.is_empty() { None } else { Some(trimmed) }
-    }).flatten();
+    });
 
     log::info!("Updating notes for flight {}", payload.flight_id);
 
@@ -852,7 +988,7 @@ async fn export_backup(
     Ok((
         [
             (axum::http::header::CONTENT_TYPE, "application/octet-stream"),
-            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename).leak()),
+            (axum::http::header::CONTENT_DISPOSITION, &*Box::leak(format!("attachment; filename=\"{}\"", filename).into_boxed_str())),
         ],
         Body::from(file_bytes),
     ).into_response())
@@ -1011,7 +1147,8 @@ async fn get_enabled_tag_types(
     }
     // Default: return all tag types
     Ok(Json(vec![
-        "night_flight".to_string(), "high_speed".to_string(), "cold_battery".to_string(),
+        "night_flight".to_string(), "twilight_flight".to_string(), "golden_hour".to_string(),
+        "high_speed".to_string(), "cold_battery".to_string(),
         "heavy_load".to_string(), "low_battery".to_string(), "high_altitude".to_string(),
         "long_distance".to_string(), "long_flight".to_string(), "short_flight".to_string(),
         "aggressive_flying".to_string(), "no_gps".to_string(), "country".to_string(),
@@ -1112,6 +1249,65 @@ async fn regenerate_flight_smart_tags(
     Ok(Json("ok".to_string()))
 }
 
+/// Request payload for flight reprocessing
+#[derive(Deserialize)]
+struct ReprocessPayload {
+    /// Flights to reprocess; all flights when omitted
+    flight_ids: Option<Vec<i64>>,
+}
+
+/// POST /api/flights/reprocess — Re-parse flights from their kept original files
+async fn reprocess_flights(
+    pdb: ProfileDb,
+    Json(payload): Json<ReprocessPayload>,
+) -> Result<Json<Vec<ReprocessResult>>, (StatusCode, Json<ErrorResponse>)> {
+    let flight_ids = match payload.flight_ids {
+        Some(ids) => ids,
+        None => pdb.db.get_all_flight_ids()
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight IDs: {}", e)))?,
+    };
+    log::info!("Reprocessing {} flights", flight_ids.len());
+
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let import_options = ImportOptions::from_config(&config, &pdb.profile);
+
+    // Originals may have been kept on upload or still sit in the sync folder
+    // or one of the sync sources
+    let mut search_folders = vec![config.get("uploaded_files_path")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| pdb.default_upload_folder())];
+    search_folders.extend(pdb.sync_path());
+    search_folders.extend(sync_sources_for_profile(&pdb.data_dir, &pdb.profile).into_iter().map(|source| PathBuf::from(source.path)));
+
+    let parser = LogParser::new(&pdb.db);
+    let mut results = Vec::with_capacity(flight_ids.len());
+    for flight_id in flight_ids {
+        let result = match parser.reprocess_flight(flight_id, &search_folders, &import_options).await {
+            Ok(point_count) => ReprocessResult {
+                flight_id,
+                success: true,
+                message: format!("Reprocessed {} telemetry points", point_count),
+                point_count,
+            },
+            Err(e) => {
+                log::warn!("Failed to reprocess flight {}: {}", flight_id, e);
+                ReprocessResult {
+                    flight_id,
+                    success: false,
+                    message: e.to_string(),
+                    point_count: 0,
+                }
+            }
+        };
+        results.push(result);
+    }
+    Ok(Json(results))
+}
+
 /// POST /api/regenerate_smart_tags — Regenerate auto tags for all flights
 async fn regenerate_smart_tags(
     pdb: ProfileDb,
@@ -1208,7 +1404,7 @@ struct SyncResponse {
     errors: usize,
     message: String,
     sync_path: Option<String>,
-    /// Whether automatic scheduled sync is enabled (SYNC_INTERVAL is set)
+    /// Whether automatic sync is enabled (SYNC_INTERVAL or SYNC_WATCH is set)
     auto_sync: bool,
 }
 
@@ -1230,10 +1426,83 @@ struct SyncFileResponse {
     file_hash: Option<String>,
 }
 
+/// Outcome of importing one file from a sync folder
+enum SyncOutcome {
+    Imported { file_hash: String },
+    Skipped { message: String, file_hash: Option<String> },
+    Failed { message: String },
+}
+
+impl SyncOutcome {
+    fn into_response(self) -> SyncFileResponse {
+        match self {
+            SyncOutcome::Imported { file_hash } => SyncFileResponse {
+                success: true,
+                message: "OK".to_string(),
+                file_hash: Some(file_hash),
+            },
+            SyncOutcome::Skipped { message, file_hash } => SyncFileResponse {
+                success: false,
+                message,
+                file_hash,
+            },
+            SyncOutcome::Failed { message } => SyncFileResponse {
+                success: false,
+                message,
+                file_hash: None,
+            },
+        }
+    }
+}
+
+/// Log batch import progress every 25 files and at the end
+fn log_batch_progress(progress: &BatchImportProgress) {
+    if progress.completed % 25 == 0 || progress.completed == progress.total {
+        log::info!(
+            "Import progress: {}/{} ({} imported, {} skipped, {} queued, {} failed)",
+            progress.completed, progress.total, progress.imported, progress.skipped, progress.queued, progress.failed
+        );
+    }
+}
+
+impl From<BatchFileResult> for SyncOutcome {
+    fn from(result: BatchFileResult) -> Self {
+        match result.outcome {
+            BatchOutcome::Imported { .. } => SyncOutcome::Imported {
+                file_hash: result.file_hash.unwrap_or_default(),
+            },
+            BatchOutcome::Duplicate { message } | BatchOutcome::Skipped { message } => SyncOutcome::Skipped {
+                message,
+                file_hash: result.file_hash,
+            },
+            BatchOutcome::Queued { message } | BatchOutcome::Failed { message } => SyncOutcome::Failed { message },
+            BatchOutcome::Cancelled => SyncOutcome::Skipped {
+                message: "Import cancelled".to_string(),
+                file_hash: result.file_hash,
+            },
+        }
+    }
+}
+
+/// Import one file during folder sync. Files marked as ignored in the
+/// import failure registry are skipped without parsing; parse and insert
+/// failures are recorded there so they can be listed, retried or ignored.
+async fn sync_import_file(
+    db: &Arc<Database>,
+    file_path: &std::path::Path,
+    import_options: &ImportOptions,
+) -> SyncOutcome {
+    BatchImporter::new(db.clone(), import_options.clone())
+        .with_failure_registry()
+        .import_one(file_path)
+        .await
+        .into()
+}
+
 /// GET /api/sync/config — Get the sync folder path configuration
 async fn get_sync_config() -> Json<SyncResponse> {
     let sync_path = std::env::var("SYNC_LOGS_PATH").ok();
-    let auto_sync = std::env::var("SYNC_INTERVAL").is_ok();
+    let auto_sync = std::env::var("SYNC_INTERVAL").is_ok() || sync_watch_enabled();
     Json(SyncResponse {
         processed: 0,
         skipped: 0,
@@ -1276,42 +1545,37 @@ async fn get_sync_files(
         }));
     }
 
-    let entries = match std::fs::read_dir(&sync_dir) {
-        Ok(entries) => entries,
-        Err(e) => {
-            return Err(err_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to read sync folder: {}", e),
-            ));
-        }
-    };
+    // Files the seen index marks as imported are skipped without hashing
+    let scan = scan_sync_folder(pdb.db.clone(), sync_dir.clone(), &pdb.data_dir, &pdb.profile)
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     // Get existing file hashes to filter out already-imported files
     let existing_hashes: std::collections::HashSet<String> = pdb.db.get_all_file_hashes()
         .unwrap_or_default()
         .into_iter()
         .collect();
+    let blacklisted_hashes: std::collections::HashSet<String> = pdb.db.get_blacklist()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| entry.file_hash)
+        .collect();
 
-    let files: Vec<String> = entries
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            if let Ok(file_type) = entry.file_type() {
-                if file_type.is_file() {
-                    let name = entry.file_name().to_string_lossy().to_lowercase();
-                    return name.ends_with(".txt") || name.ends_with(".csv");
-                }
-            }
-            false
-        })
-        .filter_map(|entry| {
-            let path = entry.path();
+    let files: Vec<String> = scan
+        .pending
+        .iter()
+        .filter_map(|file| {
             // Check if file is already imported by hash
-            if let Ok(hash) = compute_file_hash(&path) {
-                if existing_hashes.contains(&hash) {
-                    return None; // Skip already imported files
+            if let Ok(hash) = compute_file_hash(&file.path) {
+                if existing_hashes.contains(&hash)
+                    || blacklisted_hashes.contains(&hash)
+                    || pdb.db.is_import_ignored(&hash).unwrap_or(false)
+                {
+                    return None; // Skip already imported, blacklisted and ignored files
                 }
             }
-            Some(entry.file_name().to_string_lossy().to_string())
+            // Relative to the sync folder, as expected by /api/sync/file
+            Some(file.relative.clone())
         })
         .collect();
 
@@ -1324,7 +1588,7 @@ async fn get_sync_files(
 
 /// POST /api/sync/file — Import a single file from the sync folder
 async fn sync_single_file(
-    AxumState(_state): AxumState<WebAppState>,
+    AxumState(state): AxumState<WebAppState>,
     pdb: ProfileDb,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<SyncFileResponse>, (StatusCode, Json<ErrorResponse>)> {
@@ -1360,134 +1624,40 @@ async fn sync_single_file(
         }));
     }
 
-    // Check smart tags setting
-    let config_path = pdb.config_path();
-    let config: serde_json::Value = if config_path.exists() {
-        std::fs::read_to_string(&config_path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-    let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
-
-    let parser = LogParser::new(&pdb.db);
+    // Files are synced one by one, so wait for a running sync rather than fail
+    let _claim = state.sync_scheduler.claim(&pdb.profile, None).await;
+    let run = SyncRunStart::new(SyncTrigger::Manual, None, &sync_dir);
+    let filter = profile_sync_filter(&pdb.data_dir, &pdb.profile);
+    let (scan_db, root) = (pdb.db.clone(), sync_dir.clone());
+    let scan = tokio::task::spawn_blocking(move || SyncScan::for_files(&scan_db, &root, &filter, vec![file_path]))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Sync scan task failed: {}", e)))?;
 
-    let parse_result = match parser.parse_log(&file_path).await {
-        Ok(result) => result,
-        Err(crate::parser::ParserError::AlreadyImported(matching_flight)) => {
-            return Ok(Json(SyncFileResponse {
-                success: false,
-                message: format!("Already imported (matches '{}')", matching_flight),
-                file_hash: None,
-            }));
-        }
-        Err(e) => {
-            return Ok(Json(SyncFileResponse {
-                success: false,
-                message: format!("Parse error: {}", e),
-                file_hash: None,
-            }));
-        }
-    };
-
-    // Check for duplicate flight
-    if let Some(matching_flight) = pdb.db.is_duplicate_flight(
-        parse_result.metadata.drone_serial.as_deref(),
-        parse_result.metadata.battery_serial.as_deref(),
-        parse_result.metadata.start_time,
-    ).unwrap_or(None) {
+    // Like a folder sync, the run is recorded and the seen index updated
+    if scan.pending.is_empty() {
+        run.finish(&pdb.db, scan.unchanged, None, &Default::default(), None);
         return Ok(Json(SyncFileResponse {
             success: false,
-            message: format!("Duplicate flight (matches '{}')", matching_flight),
-            file_hash: parse_result.metadata.file_hash.clone(),
-        }));
-    }
-
-    // Insert flight
-    let flight_id = match pdb.db.insert_flight(&parse_result.metadata) {
-        Ok(id) => id,
-        Err(e) => {
-            return Ok(Json(SyncFileResponse {
-                success: false,
-                message: format!("Failed to insert flight: {}", e),
-                file_hash: None,
-            }));
-        }
-    };
-
-    // Insert telemetry
-    if let Err(e) = pdb.db.bulk_insert_telemetry(flight_id, &parse_result.points) {
-        let _ = pdb.db.delete_flight(flight_id);
-        return Ok(Json(SyncFileResponse {
-            success: false,
-            message: format!("Failed to insert telemetry: {}", e),
+            message: if scan.unchanged > 0 {
+                "File has already been imported and is unchanged".to_string()
+            } else {
+                "File is excluded by the sync folder's include/exclude patterns".to_string()
+            },
             file_hash: None,
         }));
     }
-
-    // Insert smart tags if enabled
-    if tags_enabled {
-        // Filter tags based on enabled_tag_types if configured
-        let tags = if let Some(types) = config.get("enabled_tag_types").and_then(|v| v.as_array()) {
-            let enabled_types: Vec<String> = types.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect();
-            crate::parser::LogParser::filter_smart_tags(parse_result.tags.clone(), &enabled_types)
-        } else {
-            parse_result.tags.clone()
-        };
-        if let Err(e) = pdb.db.insert_flight_tags(flight_id, &tags) {
-            log::warn!("Failed to insert tags: {}", e);
-        }
-    }
-
-    // Insert manual tags from re-imported CSV exports (always inserted regardless of smart_tags_enabled)
-    for manual_tag in &parse_result.manual_tags {
-        if let Err(e) = pdb.db.add_flight_tag(flight_id, manual_tag) {
-            log::warn!("Failed to insert manual tag '{}': {}", manual_tag, e);
-        }
-    }
-
-    // Auto-tag with profile name for non-default profiles
-    if pdb.profile != "default" {
-        if let Err(e) = pdb.db.add_flight_tag(flight_id, &pdb.profile) {
-            log::warn!("Failed to insert profile tag '{}': {}", pdb.profile, e);
-        }
-    }
-
-    // Insert notes from re-imported CSV exports
-    if let Some(ref notes) = parse_result.notes {
-        if let Err(e) = pdb.db.update_flight_notes(flight_id, Some(notes.as_str())) {
-            log::warn!("Failed to insert notes: {}", e);
-        }
-    }
-
-    // Apply color from re-imported CSV exports
-    if let Some(ref color) = parse_result.color {
-        if let Err(e) = pdb.db.update_flight_color(flight_id, color) {
-            log::warn!("Failed to set color: {}", e);
-        }
-    }
-
-    // Insert app messages (tips and warnings) from DJI logs
-    if !parse_result.messages.is_empty() {
-        if let Err(e) = pdb.db.insert_flight_messages(flight_id, &parse_result.messages) {
-            log::warn!("Failed to insert messages: {}", e);
-        }
-    }
-
-    Ok(Json(SyncFileResponse {
-        success: true,
-        message: "OK".to_string(),
-        file_hash: parse_result.metadata.file_hash,
-    }))
+    let summary = import_sync_scan(&state, &pdb.profile, pdb.db.clone(), &sync_dir, scan, "Sync file", None, run).await;
+    let result = summary
+        .files
+        .into_iter()
+        .next()
+        .ok_or_else(|| err_response(StatusCode::INTERNAL_SERVER_ERROR, "Sync import returned no result"))?;
+    Ok(Json(SyncOutcome::from(result).into_response()))
 }
 
 /// POST /api/sync — Trigger sync from SYNC_LOGS_PATH folder
 async fn sync_from_folder(
-    AxumState(_state): AxumState<WebAppState>,
+    AxumState(state): AxumState<WebAppState>,
     pdb: ProfileDb,
 ) -> Result<Json<SyncResponse>, (StatusCode, Json<ErrorResponse>)> {
     let sync_dir = match pdb.sync_path() {
@@ -1524,50 +1694,36 @@ async fn sync_from_folder(
         }));
     }
 
+    let _claim = state.sync_scheduler.try_claim(&pdb.profile, None).ok_or_else(sync_busy)?;
     log::info!("Starting sync from folder: {}", sync_path_str);
     let start = std::time::Instant::now();
+    let run = SyncRunStart::new(SyncTrigger::Manual, None, &sync_dir);
 
-    // Read all log files from the sync folder
-    let entries = match std::fs::read_dir(&sync_dir) {
-        Ok(entries) => entries,
+    // Find new or changed log files anywhere below the sync folder
+    let scan = match scan_sync_folder(pdb.db.clone(), sync_dir.clone(), &pdb.data_dir, &pdb.profile).await {
+        Ok(scan) => scan,
         Err(e) => {
-            return Err(err_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to read sync folder: {}", e),
-            ));
+            run.finish(&pdb.db, 0, None, &Default::default(), Some(e.clone()));
+            return Err(err_response(StatusCode::INTERNAL_SERVER_ERROR, e));
         }
     };
 
-    let log_files: Vec<PathBuf> = entries
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            if let Ok(file_type) = entry.file_type() {
-                if file_type.is_file() {
-                    let name = entry.file_name().to_string_lossy().to_lowercase();
-                    return name.ends_with(".txt") || name.ends_with(".csv");
-                }
-            }
-            false
-        })
-        .map(|entry| entry.path())
-        .collect();
-
-    if log_files.is_empty() {
+    if scan.pending.is_empty() {
+        run.finish(&pdb.db, scan.unchanged, None, &Default::default(), None);
         return Ok(Json(SyncResponse {
             processed: 0,
-            skipped: 0,
+            skipped: scan.unchanged,
             errors: 0,
-            message: "No log files found in sync folder".to_string(),
+            message: if scan.unchanged > 0 {
+                format!("No new log files in sync folder ({} already imported)", scan.unchanged)
+            } else {
+                "No log files found in sync folder".to_string()
+            },
             sync_path: Some(sync_path_str),
             auto_sync: false,
         }));
     }
 
-    let parser = LogParser::new(&pdb.db);
-    let mut processed = 0usize;
-    let mut skipped = 0usize;
-    let mut errors = 0usize;
-
     // Check smart tags setting
     let config_path = pdb.config_path();
     let config: serde_json::Value = if config_path.exists() {
@@ -1578,126 +1734,696 @@ async fn sync_from_folder(
     } else {
         serde_json::json!({})
     };
-    let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+    let import_options = ImportOptions::from_config(&config, &pdb.profile);
+
+    let summary = BatchImporter::new(pdb.db.clone(), import_options)
+        .with_failure_registry()
+        .run(scan.paths(), log_batch_progress)
+        .await;
+    scan.record(&pdb.db, &summary.files);
+    let moved = scan.apply_post_import(&pdb.db, &sync_dir, &summary.files, &SyncPostImport::from_config(&config));
+    run.finish(&pdb.db, scan.unchanged, Some(&summary), &moved, None);
+    let processed = summary.progress.imported;
+    let skipped = summary.progress.skipped + scan.unchanged;
+    let errors = summary.progress.failed + summary.progress.queued;
 
-    for file_path in log_files {
-        let file_name = file_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
-        
-        let parse_result = match parser.parse_log(&file_path).await {
-            Ok(result) => result,
-            Err(crate::parser::ParserError::AlreadyImported(matching_flight)) => {
-                log::debug!("Skipping already-imported file: {} — matches flight '{}'", file_name, matching_flight);
-                skipped += 1;
-                continue;
-            }
-            Err(e) => {
-                log::warn!("Failed to parse {}: {}", file_name, e);
-                errors += 1;
-                continue;
-            }
-        };
+    let elapsed = start.elapsed().as_secs_f64();
+    let mut msg = format!(
+        "Sync complete: {} imported, {} skipped, {} errors in {:.1}s",
+        processed, skipped, errors, elapsed
+    );
+    msg.push_str(&post_import_message(&moved));
+    log::info!("{}", msg);
 
-        // Check for duplicate flight
-        if let Some(matching_flight) = pdb.db.is_duplicate_flight(
-            parse_result.metadata.drone_serial.as_deref(),
-            parse_result.metadata.battery_serial.as_deref(),
-            parse_result.metadata.start_time,
-        ).unwrap_or(None) {
-            log::debug!("Skipping duplicate flight: {} — matches flight '{}'", file_name, matching_flight);
-            skipped += 1;
-            continue;
+    Ok(Json(SyncResponse {
+        processed,
+        skipped,
+        errors,
+        message: msg,
+        sync_path: Some(sync_path_str),
+        auto_sync: false,
+    }))
+}
+
+/// Recursively scan a profile's sync folder (off the async runtime),
+/// skipping files the seen index marks as imported and unchanged
+async fn scan_sync_folder(db: Arc<Database>, sync_dir: PathBuf, data_dir: &std::path::Path, profile: &str) -> Result<SyncScan, String> {
+    let filter = profile_sync_filter(data_dir, profile);
+    tokio::task::spawn_blocking(move || SyncScan::new(&db, &sync_dir, &filter))
+        .await
+        .map_err(|e| format!("Sync scan task failed: {}", e))?
+        .map_err(|e| format!("Failed to read sync folder: {}", e))
+}
+
+/// A sync of one folder in progress, saved to the profile's sync run
+/// history by [`SyncRunStart::finish`]
+struct SyncRunStart {
+    trigger: SyncTrigger,
+    source_id: Option<String>,
+    sync_path: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SyncRunStart {
+    fn new(trigger: SyncTrigger, source_id: Option<&str>, sync_dir: &std::path::Path) -> Self {
+        Self {
+            trigger,
+            source_id: source_id.map(str::to_string),
+            sync_path: sync_dir.to_string_lossy().to_string(),
+            started_at: chrono::Utc::now(),
         }
+    }
 
-        // Insert flight
-        let flight_id = match pdb.db.insert_flight(&parse_result.metadata) {
-            Ok(id) => id,
-            Err(e) => {
-                log::warn!("Failed to insert flight from {}: {}", file_name, e);
-                errors += 1;
-                continue;
-            }
+    /// Save the run: files skipped by the seen index, the import results
+    /// (`None` when nothing was imported) and the moved files. Failing to
+    /// save only logs a warning.
+    fn finish(self, db: &Database, unchanged: usize, summary: Option<&BatchSummary>, moved: &sync_scan::PostImportSummary, error: Option<String>) {
+        let progress = summary.map(|s| s.progress.clone()).unwrap_or_default();
+        let files: Vec<SyncRunFile> = summary
+            .map(|s| s.files.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|file| {
+                let result = file.to_import_result();
+                SyncRunFile {
+                    path: file.path.to_string_lossy().to_string(),
+                    outcome: file.outcome.as_str().to_string(),
+                    message: result.message,
+                    flight_id: result.flight_id,
+                }
+            })
+            .collect();
+        let run = SyncRun {
+            id: 0,
+            trigger: self.trigger,
+            source_id: self.source_id,
+            sync_path: self.sync_path,
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            imported: progress.imported,
+            skipped: progress.skipped,
+            unchanged,
+            queued: progress.queued,
+            failed: progress.failed,
+            cancelled: progress.cancelled,
+            archived: moved.archived,
+            rejected: moved.rejected,
+            error: error.or_else(|| moved.error.clone()),
         };
-
-        // Insert telemetry
-        if let Err(e) = pdb.db.bulk_insert_telemetry(flight_id, &parse_result.points) {
-            log::warn!("Failed to insert telemetry for {}: {}", file_name, e);
-            let _ = pdb.db.delete_flight(flight_id);
-            errors += 1;
-            continue;
+        if let Err(e) = db.record_sync_run(&run, &files) {
+            log::warn!("Failed to save sync run of {}: {}", run.sync_path, e);
         }
+    }
+}
 
-        // Insert smart tags if enabled
-        if tags_enabled {
-            // Filter tags based on enabled_tag_types if configured
-            let tags = if let Some(types) = config.get("enabled_tag_types").and_then(|v| v.as_array()) {
-                let enabled_types: Vec<String> = types.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect();
-                crate::parser::LogParser::filter_smart_tags(parse_result.tags.clone(), &enabled_types)
-            } else {
-                parse_result.tags.clone()
-            };
-            if let Err(e) = pdb.db.insert_flight_tags(flight_id, &tags) {
-                log::warn!("Failed to insert tags for {}: {}", file_name, e);
-            }
-        }
+/// Next time a cron expression fires, `None` if it is invalid
+fn next_cron_time(expr: &str) -> Option<String> {
+    let schedule = expr.parse::<cron::Schedule>().ok()?;
+    schedule.upcoming(chrono::Utc).next().map(|t| t.to_rfc3339())
+}
 
-        // Insert manual tags from re-imported CSV exports (always inserted regardless of smart_tags_enabled)
-        for manual_tag in &parse_result.manual_tags {
-            if let Err(e) = pdb.db.add_flight_tag(flight_id, manual_tag) {
-                log::warn!("Failed to insert manual tag '{}' for {}: {}", manual_tag, file_name, e);
-            }
-        }
+/// Sync file filter of a profile's own sync folder
+fn profile_sync_filter(data_dir: &std::path::Path, profile: &str) -> SyncFilter {
+    // Sync sources inside the folder are synced on their own
+    let mut skipped = database::sync_path_for_profile(profile)
+        .map(|own| sync_scan::nested_source_folders(&own, &sync_sources_for_profile(data_dir, profile)))
+        .unwrap_or_default();
+    // Other profiles sync from subfolders of the default profile's folder
+    if profile == "default" {
+        skipped.extend(database::list_profiles(data_dir).into_iter().filter(|p| p != "default"));
+    }
+    SyncFilter::from_env().with_skipped_folders(skipped)
+}
 
-        // Auto-tag with profile name for non-default profiles
-        if pdb.profile != "default" {
-            if let Err(e) = pdb.db.add_flight_tag(flight_id, &pdb.profile) {
-                log::warn!("Failed to insert profile tag '{}' for {}: {}", pdb.profile, file_name, e);
-            }
-        }
+/// Post-import settings of the caller's profile
+fn profile_post_import(pdb: &ProfileDb) -> SyncPostImport {
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    SyncPostImport::from_config(&config)
+}
 
-        // Insert notes from re-imported CSV exports
-        if let Some(ref notes) = parse_result.notes {
-            if let Err(e) = pdb.db.update_flight_notes(flight_id, Some(notes.as_str())) {
-                log::warn!("Failed to insert notes for {}: {}", file_name, e);
-            }
-        }
+/// Suffix for sync messages describing moved files
+fn post_import_message(moved: &sync_scan::PostImportSummary) -> String {
+    let mut msg = String::new();
+    if moved.archived > 0 {
+        msg.push_str(&format!(", {} archived", moved.archived));
+    }
+    if moved.rejected > 0 {
+        msg.push_str(&format!(", {} rejected", moved.rejected));
+    }
+    if moved.error.is_some() {
+        msg.push_str(" (sync folder is read-only, files left in place)");
+    }
+    msg
+}
 
-        // Apply color from re-imported CSV exports
-        if let Some(ref color) = parse_result.color {
-            if let Err(e) = pdb.db.update_flight_color(flight_id, color) {
-                log::warn!("Failed to set color for {}: {}", file_name, e);
-            }
-        }
+/// GET /api/sync/post_import — What sync does with source files after importing them
+async fn get_sync_post_import(
+    pdb: ProfileDb,
+) -> Json<SyncPostImportStatus> {
+    let writable = pdb.sync_path().is_some_and(|dir| sync_scan::is_writable(&dir));
+    Json(SyncPostImportStatus {
+        settings: profile_post_import(&pdb),
+        writable,
+    })
+}
 
-        // Insert app messages (tips and warnings) from DJI logs
-        if !parse_result.messages.is_empty() {
-            if let Err(e) = pdb.db.insert_flight_messages(flight_id, &parse_result.messages) {
-                log::warn!("Failed to insert messages for {}: {}", file_name, e);
-            }
-        }
+/// POST /api/sync/post_import — Set the post-import actions for sync folder files.
+/// Body: `{ imported: "leave" | "archive", failed: "leave" | "reject" }`
+async fn set_sync_post_import(
+    pdb: ProfileDb,
+    Json(settings): Json<SyncPostImport>,
+) -> Result<Json<SyncPostImportStatus>, (StatusCode, Json<ErrorResponse>)> {
+    let config_path = pdb.config_path();
+    let mut config: serde_json::Value = if config_path.exists() {
+        let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    config["sync_post_import"] = serde_json::json!(settings);
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
 
-        processed += 1;
-        log::debug!("Synced: {}", file_name);
+    let writable = pdb.sync_path().is_some_and(|dir| sync_scan::is_writable(&dir));
+    if !settings.is_leave() && !writable {
+        log::warn!("Post-import actions set for profile '{}' but its sync folder is read-only; files will be left in place", pdb.profile);
     }
+    Ok(Json(SyncPostImportStatus { settings, writable }))
+}
 
-    let elapsed = start.elapsed().as_secs_f64();
-    let msg = format!(
-        "Sync complete: {} imported, {} skipped, {} errors in {:.1}s",
-        processed, skipped, errors, elapsed
-    );
-    log::info!("{}", msg);
+/// Sync sources stored in a profile's config
+fn sync_sources_for_profile(data_dir: &std::path::Path, profile: &str) -> Vec<SyncSource> {
+    sync_scan::read_sources(&database::config_path_for_profile(data_dir, profile))
+}
+
+fn write_sync_sources(pdb: &ProfileDb, sources: &[SyncSource]) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    sync_scan::write_sources(&pdb.config_path(), sources)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))
+}
+
+/// Where a profile's sync sources may point: inside SYNC_SOURCE_ROOTS (or
+/// the profile's own sync folder when that is unset), away from the sync
+/// folders and sources of every other profile. `replacing` is the id of a
+/// source being updated, which its new settings may overlap.
+fn sync_source_policy(data_dir: &std::path::Path, profile: &str, replacing: Option<&str>) -> SourcePolicy {
+    let own_folder = database::sync_path_for_profile(profile);
+    let roots = match std::env::var("SYNC_SOURCE_ROOTS") {
+        Ok(roots) => roots.split(',').map(str::trim).filter(|r| !r.is_empty()).map(PathBuf::from).collect(),
+        Err(_) => own_folder.iter().cloned().collect(),
+    };
+    let claimed = database::list_profiles(data_dir)
+        .into_iter()
+        .filter(|other| !other.eq_ignore_ascii_case(profile))
+        .flat_map(|other| {
+            let sources = sync_sources_for_profile(data_dir, &other).into_iter().map(|s| PathBuf::from(s.path));
+            database::sync_path_for_profile(&other).into_iter().chain(sources)
+        })
+        .collect();
+    let sources = sync_sources_for_profile(data_dir, profile)
+        .into_iter()
+        .filter(|s| Some(s.id.as_str()) != replacing)
+        .collect();
+    SourcePolicy {
+        roots,
+        own_folder,
+        sources,
+        claimed,
+        data_dir: data_dir.to_path_buf(),
+    }
+}
+
+/// Check a sync source sent by a client against the profile's
+/// [`SourcePolicy`] and validate its cron schedule
+fn validate_sync_source(
+    source: SyncSource,
+    data_dir: &std::path::Path,
+    profile: &str,
+    replacing: Option<&str>,
+) -> Result<SyncSource, String> {
+    let mut source = sync_source_policy(data_dir, profile, replacing).validate(source)?;
+    source.schedule = source.schedule.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(schedule) = &source.schedule {
+        schedule
+            .parse::<cron::Schedule>()
+            .map_err(|e| format!("Invalid cron expression '{}': {}", schedule, e))?;
+    }
+    Ok(source)
+}
+
+/// GET /api/sync/sources — Sync sources of the caller's profile
+async fn get_sync_sources(
+    pdb: ProfileDb,
+) -> Json<Vec<SyncSource>> {
+    Json(sync_sources_for_profile(&pdb.data_dir, &pdb.profile))
+}
+
+/// POST /api/sync/sources — Add a sync source; the server assigns its id
+async fn add_sync_source(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Json(source): Json<SyncSource>,
+) -> Result<Json<SyncSource>, (StatusCode, Json<ErrorResponse>)> {
+    let mut source = validate_sync_source(source, &pdb.data_dir, &pdb.profile, None)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    source.id = uuid::Uuid::new_v4().to_string();
+
+    let mut sources = sync_sources_for_profile(&pdb.data_dir, &pdb.profile);
+    sources.push(source.clone());
+    write_sync_sources(&pdb, &sources)?;
+    schedule_sync_sources(&state, &pdb.profile).await;
+    log::info!("Added sync source {} for profile '{}'", source.path, pdb.profile);
+    Ok(Json(source))
+}
+
+/// PUT /api/sync/sources/:source_id — Replace a sync source's settings
+async fn update_sync_source(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Path(source_id): Path<String>,
+    Json(source): Json<SyncSource>,
+) -> Result<Json<SyncSource>, (StatusCode, Json<ErrorResponse>)> {
+    let mut sources = sync_sources_for_profile(&pdb.data_dir, &pdb.profile);
+    let existing = sources
+        .iter_mut()
+        .find(|s| s.id == source_id)
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Sync source not found"))?;
+    let mut source = validate_sync_source(source, &pdb.data_dir, &pdb.profile, Some(&source_id))
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    source.id = source_id;
+    *existing = source.clone();
+
+    write_sync_sources(&pdb, &sources)?;
+    schedule_sync_sources(&state, &pdb.profile).await;
+    Ok(Json(source))
+}
+
+/// DELETE /api/sync/sources/:source_id — Remove a sync source. Returns
+/// `false` if there was no such source.
+async fn delete_sync_source(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Path(source_id): Path<String>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    let mut sources = sync_sources_for_profile(&pdb.data_dir, &pdb.profile);
+    let count = sources.len();
+    sources.retain(|s| s.id != source_id);
+    if sources.len() == count {
+        return Ok(Json(false));
+    }
+    write_sync_sources(&pdb, &sources)?;
+    schedule_sync_sources(&state, &pdb.profile).await;
+    Ok(Json(true))
+}
 
+/// POST /api/sync/sources/:source_id/run — Sync a source now
+async fn run_sync_source_now(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Path(source_id): Path<String>,
+) -> Result<Json<SyncResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let source = sync_sources_for_profile(&pdb.data_dir, &pdb.profile)
+        .into_iter()
+        .find(|s| s.id == source_id)
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Sync source not found"))?;
+    let (progress, unchanged) = run_sync_source(&state, &pdb.profile, &source, SyncTrigger::Manual)
+        .await
+        .map_err(|e| err_response(StatusCode::CONFLICT, e))?;
+    let skipped = progress.skipped + unchanged;
+    let errors = progress.failed + progress.queued;
     Ok(Json(SyncResponse {
-        processed,
+        processed: progress.imported,
         skipped,
         errors,
-        message: msg,
-        sync_path: Some(sync_path_str),
-        auto_sync: false,
+        message: format!("Sync complete: {} imported, {} skipped, {} errors", progress.imported, skipped, errors),
+        sync_path: Some(source.path),
+        auto_sync: source.schedule.is_some(),
+    }))
+}
+
+/// GET /api/sync/status — When the caller's profile syncs next and how its last runs went
+async fn get_sync_status(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+) -> Result<Json<SyncStatus>, (StatusCode, Json<ErrorResponse>)> {
+    let last_run = |source_id: Option<&str>| -> Result<Option<SyncRun>, (StatusCode, Json<ErrorResponse>)> {
+        Ok(pdb
+            .db
+            .get_sync_runs(1, Some(source_id))
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .pop())
+    };
+
+    let mut sources = Vec::new();
+    for source in sync_sources_for_profile(&pdb.data_dir, &pdb.profile) {
+        let next_run_at = source
+            .schedule
+            .as_deref()
+            .filter(|_| source.enabled)
+            .and_then(next_cron_time);
+        sources.push(SyncSourceStatus {
+            running: state.sync_scheduler.is_running(&pdb.profile, Some(&source.id)),
+            last_run: last_run(Some(&source.id))?,
+            next_run_at,
+            id: source.id,
+            path: source.path,
+            enabled: source.enabled,
+            schedule: source.schedule,
+        });
+    }
+
+    let sync_path = pdb.sync_path();
+    let interval = state.sync_scheduler.interval.get().filter(|_| sync_path.is_some()).cloned();
+    Ok(Json(SyncStatus {
+        next_run_at: interval.as_deref().and_then(next_cron_time),
+        interval,
+        watch: sync_path.is_some() && sync_watch_enabled(),
+        sync_path: sync_path.map(|p| p.to_string_lossy().to_string()),
+        last_run: last_run(None)?,
+        sources,
     }))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncRunsQuery {
+    limit: Option<usize>,
+    /// Only the runs of this sync source; `default` for the SYNC_LOGS_PATH folder
+    source_id: Option<String>,
+}
+
+/// GET /api/sync/runs — Sync run history of the caller's profile, newest first
+async fn get_sync_runs(
+    pdb: ProfileDb,
+    Query(query): Query<SyncRunsQuery>,
+) -> Result<Json<Vec<SyncRun>>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(50).min(database::MAX_SYNC_RUNS as usize);
+    let source_id = query.source_id.as_deref().map(|id| Some(id).filter(|id| *id != "default"));
+    pdb.db
+        .get_sync_runs(limit, source_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /api/sync/runs/:run_id — A sync run with the outcome of each file
+async fn get_sync_run(
+    pdb: ProfileDb,
+    Path(run_id): Path<i64>,
+) -> Result<Json<SyncRunDetail>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .get_sync_run(run_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Sync run not found"))
+}
+
+// ============================================================================
+// IMPORT JOBS
+// ============================================================================
+
+/// POST /api/sync/jobs — Start syncing the sync folder in the background.
+/// Returns the job id right away; follow it with `/api/jobs/{id}/events`.
+async fn start_sync_job(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+) -> Result<Json<ImportJobStarted>, (StatusCode, Json<ErrorResponse>)> {
+    let sync_dir = pdb
+        .sync_path()
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "SYNC_LOGS_PATH environment variable not configured"))?;
+    if !sync_dir.exists() {
+        if let Err(e) = std::fs::create_dir_all(&sync_dir) {
+            log::warn!("Failed to create sync folder {}: {}", sync_dir.display(), e);
+        }
+    }
+    let claim = state.sync_scheduler.try_claim(&pdb.profile, None).ok_or_else(sync_busy)?;
+    let run = SyncRunStart::new(SyncTrigger::Manual, None, &sync_dir);
+    let scan = match scan_sync_folder(pdb.db.clone(), sync_dir.clone(), &pdb.data_dir, &pdb.profile).await {
+        Ok(scan) => scan,
+        Err(e) => {
+            run.finish(&pdb.db, 0, None, &Default::default(), Some(e.clone()));
+            return Err(err_response(StatusCode::INTERNAL_SERVER_ERROR, e));
+        }
+    };
+
+    let total = scan.pending.len();
+    let db = pdb.db.clone();
+    let post_import = profile_post_import(&pdb);
+    let importer = BatchImporter::new(pdb.db.clone(), profile_import_options(&pdb)).with_failure_registry();
+    let job = state.import_jobs.create(&pdb.profile);
+    let job_id = job.id().to_string();
+    log::info!("Starting sync job {} for profile '{}' ({} files)", job_id, pdb.profile, total);
+
+    tokio::spawn(async move {
+        let _claim = claim;
+        let summary = job
+            .run(importer, scan.paths(), |event| {
+                if !matches!(event.kind, ImportJobEventKind::Started | ImportJobEventKind::Parsed) {
+                    log_batch_progress(&event.progress);
+                }
+            })
+            .await;
+        scan.record(&db, &summary.files);
+        let moved = scan.apply_post_import(&db, &sync_dir, &summary.files, &post_import);
+        run.finish(&db, scan.unchanged, Some(&summary), &moved, None);
+    });
+
+    Ok(Json(ImportJobStarted { job_id, total }))
+}
+
+/// Look up a job of the caller's profile
+fn profile_job(
+    state: &WebAppState,
+    pdb: &ProfileDb,
+    job_id: &str,
+) -> Result<Arc<ImportJob>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .import_jobs
+        .get(job_id)
+        .filter(|job| job.profile() == pdb.profile)
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Import job not found"))
+}
+
+/// GET /api/jobs/{id}/events — Server-Sent Events stream of a job's progress.
+/// Replays the events so far, then follows the job until its `done` event.
+async fn import_job_events(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Path(job_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, Json<ErrorResponse>)> {
+    let job = profile_job(&state, &pdb, &job_id)?;
+    let events = stream::unfold(job.follow(), |mut subscription| async move {
+        subscription.next().await.map(|event| (event, subscription))
+    })
+    .map(|event: ImportJobEvent| Event::default().event(event.kind.as_str()).json_data(&event));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// POST /api/jobs/{id}/cancel — Stop a job after the files already in progress
+async fn cancel_import_job(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Path(job_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let job = profile_job(&state, &pdb, &job_id)?;
+    job.cancel();
+    log::info!("Cancel requested for import job {}", job_id);
+    Ok(Json(serde_json::json!({ "cancelled": !job.is_finished() })))
+}
+
+// ============================================================================
+// IMPORT FAILURES
+// ============================================================================
+
+/// GET /api/import_failures — List files that failed to import during sync
+async fn get_import_failures(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<ImportFailure>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.get_import_failures()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import failures: {}", e)))
+}
+
+/// Request payload identifying an import failure
+#[derive(Deserialize)]
+struct ImportFailurePayload {
+    file_hash: String,
+    /// For the ignore endpoint: false un-ignores the file (defaults to true)
+    ignored: Option<bool>,
+}
+
+/// POST /api/import_failures/retry — Import a previously failed file again
+async fn retry_import_failure(
+    pdb: ProfileDb,
+    Json(payload): Json<ImportFailurePayload>,
+) -> Result<Json<SyncFileResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let file_path = pdb.db.get_import_failure_path(&payload.file_hash)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import failure: {}", e)))?
+        .map(PathBuf::from)
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "No import failure recorded for this file"))?;
+
+    if !file_path.exists() {
+        return Ok(Json(SyncFileResponse {
+            success: false,
+            message: format!("File not found: {}", file_path.display()),
+            file_hash: None,
+        }));
+    }
+
+    // An explicit retry overrides an earlier ignore
+    let _ = pdb.db.set_import_failure_ignored(&payload.file_hash, false);
+
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let import_options = ImportOptions::from_config(&config, &pdb.profile);
+
+    log::info!("Retrying failed import: {}", file_path.display());
+    let outcome = sync_import_file(&pdb.db, &file_path, &import_options).await;
+    Ok(Json(outcome.into_response()))
+}
+
+/// POST /api/import_failures/ignore — Permanently skip a failed file in future syncs
+async fn ignore_import_failure(
+    pdb: ProfileDb,
+    Json(payload): Json<ImportFailurePayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    let ignored = payload.ignored.unwrap_or(true);
+    let updated = pdb.db.set_import_failure_ignored(&payload.file_hash, ignored)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update import failure: {}", e)))?;
+    if !updated {
+        return Err(err_response(StatusCode::NOT_FOUND, "No import failure recorded for this file"));
+    }
+    Ok(Json(ignored))
+}
+
+// ============================================================================
+// KEYCHAINS & PENDING DECRYPTION
+// ============================================================================
+
+/// Read a profile's config.json into import options
+fn profile_import_options(pdb: &ProfileDb) -> ImportOptions {
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    ImportOptions::from_config(&config, &pdb.profile)
+}
+
+/// GET /api/keychains/export — Download cached keychains as a portable bundle
+async fn export_keychains(
+    pdb: ProfileDb,
+) -> Result<Json<KeychainBundle>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.export_keychain_bundle()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to export keychains: {}", e)))
+}
+
+/// POST /api/keychains/import — Add a keychain bundle to the cache and retry pending logs
+async fn import_keychains(
+    pdb: ProfileDb,
+    Json(bundle): Json<KeychainBundle>,
+) -> Result<Json<KeychainImportResult>, (StatusCode, Json<ErrorResponse>)> {
+    if bundle.format != database::KEYCHAIN_BUNDLE_FORMAT {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Not an Open DroneLog keychain bundle"));
+    }
+    let imported = pdb.db.import_keychain_bundle(&bundle)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to import keychains: {}", e)))?;
+    log::info!("Imported {} keychains from bundle", imported);
+
+    let parser = LogParser::new(&pdb.db);
+    let pending = parser.retry_pending_decryption(&profile_import_options(&pdb)).await;
+    Ok(Json(KeychainImportResult { imported, pending }))
+}
+
+/// GET /api/pending_decryption — List encrypted logs waiting for keychains
+async fn get_pending_decryption(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<PendingDecryption>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.get_pending_decryption()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get pending decryption queue: {}", e)))
+}
+
+/// POST /api/pending_decryption/retry — Retry every queued log now
+async fn retry_pending_decryption(
+    pdb: ProfileDb,
+) -> Result<Json<PendingRetryResult>, (StatusCode, Json<ErrorResponse>)> {
+    let parser = LogParser::new(&pdb.db);
+    Ok(Json(parser.retry_pending_decryption(&profile_import_options(&pdb)).await))
+}
+
+/// Request payload identifying a queued log
+#[derive(Deserialize)]
+struct PendingDecryptionPayload {
+    file_hash: String,
+}
+
+/// POST /api/pending_decryption/remove — Drop a log from the queue
+async fn remove_pending_decryption(
+    pdb: ProfileDb,
+    Json(payload): Json<PendingDecryptionPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.remove_pending_decryption(&payload.file_hash)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove from pending decryption: {}", e)))
+}
+
+// ============================================================================
+// IMPORT BLACKLIST
+// ============================================================================
+
+/// GET /api/blacklist — List file hashes that are never imported again
+async fn get_blacklist(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<BlacklistEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.get_blacklist()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get blacklist: {}", e)))
+}
+
+/// Request payload identifying a blacklisted file
+#[derive(Deserialize)]
+struct BlacklistPayload {
+    file_hash: String,
+    file_name: Option<String>,
+}
+
+/// POST /api/blacklist/add — Blacklist a file hash so no import path picks it up again
+async fn add_to_blacklist(
+    pdb: ProfileDb,
+    Json(payload): Json<BlacklistPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.file_hash.trim().is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "file_hash is required"));
+    }
+    pdb.db.add_to_blacklist(&payload.file_hash, payload.file_name.as_deref())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to add to blacklist: {}", e)))?;
+    Ok(Json(true))
+}
+
+/// POST /api/blacklist/remove — Allow a blacklisted file to be imported again
+async fn remove_from_blacklist(
+    pdb: ProfileDb,
+    Json(payload): Json<BlacklistPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.remove_from_blacklist(&payload.file_hash)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove from blacklist: {}", e)))
+}
+
+/// DELETE /api/blacklist/clear — Remove every blacklist entry
+async fn clear_blacklist(
+    pdb: ProfileDb,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db.clear_blacklist()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to clear blacklist: {}", e)))
+}
+
 // ============================================================================
 // EQUIPMENT NAMES
 // ============================================================================
@@ -1726,7 +2452,7 @@ async fn get_equipment_names(
 #[derive(Deserialize)]
 struct SetEquipmentNamePayload {
     serial: String,
-    equipment_type: String,  // "battery" or "aircraft"
+    equipment_type: String,  // "battery", "aircraft", "rc" or "camera"
     display_name: String,
 }
 
@@ -1740,6 +2466,32 @@ async fn set_equipment_name(
     Ok(Json(true))
 }
 
+/// GET /api/equipment_inventory — All known equipment with firmware history
+async fn get_equipment_inventory(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<EquipmentItem>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .get_equipment_inventory()
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get equipment inventory: {}", e)))
+}
+
+#[derive(Deserialize)]
+struct FlightEquipmentQuery {
+    flight_id: i64,
+}
+
+/// GET /api/flight_equipment — App, firmware and component serials for one flight
+async fn get_flight_equipment(
+    pdb: ProfileDb,
+    Query(params): Query<FlightEquipmentQuery>,
+) -> Result<Json<Option<FlightEquipment>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .get_flight_equipment(params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight equipment: {}", e)))
+}
+
 // ============================================================================
 // PROFILE MANAGEMENT
 // ============================================================================
@@ -1944,6 +2696,9 @@ async fn delete_profile_endpoint(
     database::delete_profile(&state.data_dir, &profile)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
+    // Drop the cron jobs of the profile's sync sources
+    schedule_sync_sources(&state, &profile).await;
+
     Ok(Json(true))
 }
 
@@ -2043,13 +2798,22 @@ pub fn build_router(state: WebAppState) -> Router {
 
     Router::new()
         .route("/api/import", post(import_log))
+        .route("/api/import/archive", post(import_archive))
+        .route("/api/import/preview", post(preview_import))
+        .route("/api/formats", get(list_supported_formats))
+        .route("/api/media", get(get_media_clips))
+        .route("/api/media/import", post(import_media))
+        .route("/api/media/frames", get(get_media_frames))
+        .route("/api/media/delete", delete(delete_media_clip))
         .route("/api/manual_flight", post(create_manual_flight))
         .route("/api/flights", get(get_flights))
         .route("/api/flight_data", get(get_flight_data))
+        .route("/api/flight_side_data", get(get_flight_side_data))
         .route("/api/overview", get(get_overview_stats))
         .route("/api/flights/delete", delete(delete_flight))
         .route("/api/flights/delete_all", delete(delete_all_flights))
         .route("/api/flights/deduplicate", post(deduplicate_flights))
+        .route("/api/flights/reprocess", post(reprocess_flights))
         .route("/api/flights/name", put(update_flight_name))
         .route("/api/flights/notes", put(update_flight_notes))
         .route("/api/flights/color", put(update_flight_color))
@@ -2075,8 +2839,35 @@ pub fn build_router(state: WebAppState) -> Router {
         .route("/api/sync/files", get(get_sync_files))
         .route("/api/sync/file", post(sync_single_file))
         .route("/api/sync", post(sync_from_folder))
+        .route("/api/sync/jobs", post(start_sync_job))
+        .route("/api/sync/post_import", get(get_sync_post_import))
+        .route("/api/sync/post_import", post(set_sync_post_import))
+        .route("/api/sync/sources", get(get_sync_sources))
+        .route("/api/sync/sources", post(add_sync_source))
+        .route("/api/sync/sources/:source_id", put(update_sync_source))
+        .route("/api/sync/sources/:source_id", delete(delete_sync_source))
+        .route("/api/sync/sources/:source_id/run", post(run_sync_source_now))
+        .route("/api/sync/status", get(get_sync_status))
+        .route("/api/sync/runs", get(get_sync_runs))
+        .route("/api/sync/runs/:run_id", get(get_sync_run))
+        .route("/api/jobs/:job_id/events", get(import_job_events))
+        .route("/api/jobs/:job_id/cancel", post(cancel_import_job))
+        .route("/api/import_failures", get(get_import_failures))
+        .route("/api/import_failures/retry", post(retry_import_failure))
+        .route("/api/import_failures/ignore", post(ignore_import_failure))
+        .route("/api/keychains/export", get(export_keychains))
+        .route("/api/keychains/import", post(import_keychains))
+        .route("/api/pending_decryption", get(get_pending_decryption))
+        .route("/api/pending_decryption/retry", post(retry_pending_decryption))
+        .route("/api/pending_decryption/remove", post(remove_pending_decryption))
+        .route("/api/blacklist", get(get_blacklist))
+        .route("/api/blacklist/add", post(add_to_blacklist))
+        .route("/api/blacklist/remove", post(remove_from_blacklist))
+        .route("/api/blacklist/clear", delete(clear_blacklist))
         .route("/api/equipment_names", get(get_equipment_names))
         .route("/api/equipment_names", post(set_equipment_name))
+        .route("/api/equipment_inventory", get(get_equipment_inventory))
+        .route("/api/flight_equipment", get(get_flight_equipment))
         .route("/api/profiles", get(list_profiles))
         .route("/api/profiles/active", get(get_active_profile))
         .route("/api/profiles/switch", post(switch_profile))
@@ -2111,30 +2902,56 @@ pub async fn start_server(data_dir: PathBuf) -> Result<(), Box<dyn std::error::E
         _ => None,
     };
 
+    // Serve keychains from a local file instead of DJI's API (CI / offline
+    // testing). Started before any import can run, on loopback only.
+    if let Ok(mock_file) = std::env::var("DJI_KEYCHAIN_MOCK_FILE") {
+        start_keychain_mock(&mock_file).await?;
+    }
+
     let state = WebAppState {
         databases: Arc::new(std::sync::RwLock::new(initial_pool)),
         data_dir,
         sessions: Arc::new(SessionStore::new()),
         master_password_hash,
+        import_jobs: Arc::new(ImportJobs::default()),
+        sync_scheduler: Arc::new(SyncScheduler::default()),
     };
 
-    // Start the scheduled sync if SYNC_INTERVAL and SYNC_LOGS_PATH are configured
-    if let (Ok(sync_path), Ok(sync_interval)) = (
-        std::env::var("SYNC_LOGS_PATH"),
-        std::env::var("SYNC_INTERVAL"),
-    ) {
-        log::info!("Scheduled sync enabled: path={}, interval={}", sync_path, sync_interval);
-        let scheduler_state = state.clone();
-        
-        tokio::spawn(async move {
-            if let Err(e) = start_sync_scheduler(scheduler_state, &sync_interval).await {
-                log::error!("Failed to start sync scheduler: {}", e);
-            }
-        });
-    } else if std::env::var("SYNC_LOGS_PATH").is_ok() {
-        log::info!("SYNC_LOGS_PATH configured but SYNC_INTERVAL not set. Sync is manual-only (via Sync button in web interface).");
+    // Schedule SYNC_INTERVAL (if SYNC_LOGS_PATH is configured) and the profiles' sync sources
+    let sync_interval = match (std::env::var("SYNC_LOGS_PATH"), std::env::var("SYNC_INTERVAL")) {
+        (Ok(sync_path), Ok(sync_interval)) => {
+            log::info!("Scheduled sync enabled: path={}, interval={}", sync_path, sync_interval);
+            Some(sync_interval)
+        }
+        (Ok(_), Err(_)) if !sync_watch_enabled() => {
+            log::info!("SYNC_LOGS_PATH configured but SYNC_INTERVAL not set. Sync is manual-only (via Sync button in web interface).");
+            None
+        }
+        _ => None,
+    };
+    let scheduler_state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = start_sync_scheduler(scheduler_state, sync_interval.as_deref()).await {
+            log::error!("Failed to start sync scheduler: {}", e);
+        }
+    });
+
+    // Import files as soon as they land in the sync folder if SYNC_WATCH is set
+    if let (Ok(sync_path), true) = (std::env::var("SYNC_LOGS_PATH"), sync_watch_enabled()) {
+        start_sync_watcher(state.clone(), PathBuf::from(sync_path));
     }
 
+    // Retry logs waiting for keychains, e.g. after the network comes back
+    let retry_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(PENDING_RETRY_INTERVAL_SECS));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            retry_all_pending_decryption(&retry_state).await;
+        }
+    });
+
     let router = build_router(state);
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
@@ -2149,10 +2966,189 @@ pub async fn start_server(data_dir: PathBuf) -> Result<(), Box<dyn std::error::E
     Ok(())
 }
 
-/// Start the cron scheduler for automatic folder sync
-async fn start_sync_scheduler(state: WebAppState, cron_expr: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let sched = JobScheduler::new().await?;
-    
+/// Serve the keychains of a bundle file on a loopback port
+/// (`DJI_KEYCHAIN_MOCK_PORT`, random by default) and send keychain requests
+/// there unless DJI_KEYCHAIN_ENDPOINT is set
+async fn start_keychain_mock(mock_file: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let store = keychain_mock::KeychainStore::from_file(std::path::Path::new(mock_file))?;
+    let port: u16 = match std::env::var("DJI_KEYCHAIN_MOCK_PORT") {
+        Ok(port) => port.parse().map_err(|_| format!("Invalid DJI_KEYCHAIN_MOCK_PORT: {}", port))?,
+        Err(_) => 0,
+    };
+    let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+    let endpoint = format!("http://{}{}", listener.local_addr()?, keychain_mock::MOCK_PATH);
+    log::info!(
+        "Mock keychain server with {} keychains from {} listening on {}",
+        store.keychain_count(), mock_file, endpoint
+    );
+    api::set_default_keychain_endpoint(endpoint);
+    tokio::spawn(async move {
+        if let Err(e) = keychain_mock::serve(listener, store).await {
+            log::error!("Mock keychain server stopped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// How often queued encrypted logs are retried in the background
+const PENDING_RETRY_INTERVAL_SECS: u64 = 600;
+
+/// Retry the pending decryption queue of every open profile database
+async fn retry_all_pending_decryption(state: &WebAppState) {
+    for (profile, db) in state.open_databases() {
+        if db.get_pending_decryption().map_or(true, |pending| pending.is_empty()) {
+            continue;
+        }
+        let config: serde_json::Value = std::fs::read_to_string(database::config_path_for_profile(&state.data_dir, &profile))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        let import_options = ImportOptions::from_config(&config, &profile);
+        let result = LogParser::new(&db).retry_pending_decryption(&import_options).await;
+        if result.imported > 0 || result.failed > 0 {
+            log::info!(
+                "Pending decryption retry for profile '{}': {} imported, {} still pending, {} failed",
+                profile, result.imported, result.still_pending, result.failed
+            );
+        }
+    }
+}
+
+/// Cron jobs for automatic folder sync. Each profile's jobs are replaced
+/// whenever it changes its sync sources, so edits apply without a restart.
+#[derive(Default)]
+pub struct SyncScheduler {
+    scheduler: tokio::sync::OnceCell<JobScheduler>,
+    /// Scheduled (source id, job id) pairs per profile
+    source_jobs: tokio::sync::Mutex<HashMap<String, Vec<(String, uuid::Uuid)>>>,
+    /// Folders currently syncing, see [`SyncScheduler::sync_key`]
+    running: std::sync::Mutex<std::collections::HashSet<String>>,
+    /// SYNC_INTERVAL cron expression, once its job is scheduled
+    interval: std::sync::OnceLock<String>,
+}
+
+impl SyncScheduler {
+    /// The running scheduler, started on first use
+    async fn scheduler(&self) -> Result<&JobScheduler, tokio_cron_scheduler::JobSchedulerError> {
+        self.scheduler
+            .get_or_try_init(|| async {
+                let sched = JobScheduler::new().await?;
+                sched.start().await?;
+                Ok(sched)
+            })
+            .await
+    }
+
+    /// Key of a profile's sync folder (`source_id` is `None`) or one of its
+    /// sync sources in the set of running syncs
+    fn sync_key(profile: &str, source_id: Option<&str>) -> String {
+        match source_id {
+            Some(id) => format!("{}/{}", profile, id),
+            None => profile.to_string(),
+        }
+    }
+
+    /// Whether the folder is being synced right now
+    fn is_running(&self, profile: &str, source_id: Option<&str>) -> bool {
+        self.running.lock().unwrap().contains(&Self::sync_key(profile, source_id))
+    }
+
+    /// Mark the folder as syncing until the returned claim is dropped, or
+    /// `None` if another sync of it is running
+    fn try_claim(self: &Arc<Self>, profile: &str, source_id: Option<&str>) -> Option<SyncClaim> {
+        let key = Self::sync_key(profile, source_id);
+        self.running.lock().unwrap().insert(key.clone()).then(|| SyncClaim {
+            scheduler: self.clone(),
+            key,
+        })
+    }
+
+    /// Like [`SyncScheduler::try_claim`], waiting for a running sync of
+    /// the folder to finish
+    async fn claim(self: &Arc<Self>, profile: &str, source_id: Option<&str>) -> SyncClaim {
+        loop {
+            if let Some(claim) = self.try_claim(profile, source_id) {
+                return claim;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+}
+
+/// A folder marked as syncing, released on drop so that manual, scheduled
+/// and watch syncs never import or move the same files at once
+struct SyncClaim {
+    scheduler: Arc<SyncScheduler>,
+    key: String,
+}
+
+impl Drop for SyncClaim {
+    fn drop(&mut self) {
+        self.scheduler.running.lock().unwrap().remove(&self.key);
+    }
+}
+
+/// Response for a sync that could not start because the folder is busy
+fn sync_busy() -> (StatusCode, Json<ErrorResponse>) {
+    err_response(StatusCode::CONFLICT, "A sync of this folder is already running")
+}
+
+/// Replace the cron jobs of a profile's sync sources with its current
+/// configuration (one job per enabled source with a schedule)
+async fn schedule_sync_sources(state: &WebAppState, profile: &str) {
+    let sched = match state.sync_scheduler.scheduler().await {
+        Ok(sched) => sched,
+        Err(e) => {
+            log::error!("Failed to start sync scheduler: {}", e);
+            return;
+        }
+    };
+    let mut source_jobs = state.sync_scheduler.source_jobs.lock().await;
+    for (_, job_id) in source_jobs.remove(profile).unwrap_or_default() {
+        if let Err(e) = sched.remove(&job_id).await {
+            log::warn!("Failed to remove sync source job {}: {}", job_id, e);
+        }
+    }
+
+    let mut scheduled = Vec::new();
+    for source in sync_sources_for_profile(&state.data_dir, profile) {
+        let Some(schedule) = source.schedule.as_deref().filter(|_| source.enabled) else { continue };
+        let (job_state, job_profile, source_id) = (state.clone(), profile.to_string(), source.id.clone());
+        let job = Job::new_async(schedule, move |_uuid, _lock| {
+            let (state, profile, source_id) = (job_state.clone(), job_profile.clone(), source_id.clone());
+            Box::pin(async move {
+                // Read the source again, it may have changed since it was scheduled
+                let Some(source) = sync_sources_for_profile(&state.data_dir, &profile).into_iter().find(|s| s.id == source_id) else {
+                    return;
+                };
+                if let Err(e) = run_sync_source(&state, &profile, &source, SyncTrigger::Cron).await {
+                    log::warn!("Scheduled sync of source {} [{}] failed: {}", source.path, profile, e);
+                }
+            })
+        });
+        match job {
+            Ok(job) => match sched.add(job).await {
+                Ok(job_id) => scheduled.push((source.id.clone(), job_id)),
+                Err(e) => log::warn!("Failed to schedule sync source {} [{}]: {}", source.path, profile, e),
+            },
+            Err(e) => log::warn!("Invalid schedule '{}' for sync source {} [{}]: {}", schedule, source.path, profile, e),
+        }
+    }
+    if !scheduled.is_empty() {
+        log::info!("Scheduled {} sync source(s) for profile '{}'", scheduled.len(), profile);
+        source_jobs.insert(profile.to_string(), scheduled);
+    }
+}
+
+/// Start the cron scheduler for automatic folder sync: the SYNC_INTERVAL
+/// job (if set) and the scheduled sync sources of every profile
+async fn start_sync_scheduler(state: WebAppState, cron_expr: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for profile in database::list_profiles(&state.data_dir) {
+        schedule_sync_sources(&state, &profile).await;
+    }
+    let Some(cron_expr) = cron_expr else { return Ok(()) };
+    let sched = state.sync_scheduler.scheduler().await?;
+
     // Validate cron expression
     let cron_schedule = cron_expr.parse::<cron::Schedule>()
         .map_err(|e| format!("Invalid cron expression '{}': {}", cron_expr, e))?;
@@ -2168,7 +3164,7 @@ async fn start_sync_scheduler(state: WebAppState, cron_expr: &str) -> Result<(),
         let state = state_clone.clone();
         Box::pin(async move {
             log::info!("Starting scheduled folder sync...");
-            match run_scheduled_sync(&state).await {
+            match run_scheduled_sync(&state, SyncTrigger::Cron).await {
                 Ok((processed, skipped, errors)) => {
                     log::info!(
                         "Scheduled sync complete: {} imported, {} skipped, {} errors",
@@ -2183,19 +3179,98 @@ async fn start_sync_scheduler(state: WebAppState, cron_expr: &str) -> Result<(),
     })?;
     
     sched.add(job).await?;
-    sched.start().await?;
+    let _ = state.sync_scheduler.interval.set(cron_expr.to_string());
     
     log::info!("Sync scheduler started with cron expression: {}", cron_expr);
-    
-    // Keep the scheduler running
-    loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+    Ok(())
+}
+
+/// Whether watch mode is enabled (SYNC_WATCH=true)
+fn sync_watch_enabled() -> bool {
+    std::env::var("SYNC_WATCH").is_ok_and(|v| v.to_lowercase() == "true" || v == "1")
+}
+
+/// Watch the sync folder and import new files as soon as they stopped
+/// changing. Files that arrived while the server was down are picked up by
+/// one full sync at startup.
+fn start_sync_watcher(state: WebAppState, sync_path: PathBuf) {
+    let settle = sync_watch::settle_from_env();
+    let (watcher, events) = match SyncWatcher::new(&sync_path) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::error!("Failed to watch sync folder {}: {}", sync_path.display(), e);
+            return;
+        }
+    };
+    log::info!("Sync watch mode enabled: path={}, settle={}s", sync_path.display(), settle.as_secs());
+
+    tokio::spawn(async move {
+        // Keep the watcher alive for as long as the server runs
+        let _watcher = watcher;
+        match run_scheduled_sync(&state, SyncTrigger::Watch).await {
+            Ok((processed, skipped, errors)) => log::info!(
+                "Startup sync complete: {} imported, {} skipped, {} errors",
+                processed, skipped, errors
+            ),
+            Err(e) => log::error!("Startup sync failed: {}", e),
+        }
+        events
+            .run(settle, |paths| {
+                let state = state.clone();
+                async move { sync_watched_files(&state, paths).await }
+            })
+            .await;
+    });
+}
+
+/// Import files reported by the sync folder watcher, each into the profile
+/// whose sync folder is its closest parent
+async fn sync_watched_files(state: &WebAppState, paths: Vec<PathBuf>) {
+    let folders: Vec<(String, PathBuf)> = database::list_profiles(&state.data_dir)
+        .into_iter()
+        .filter_map(|profile| database::sync_path_for_profile(&profile).map(|dir| (profile, dir)))
+        .collect();
+    let mut by_profile: HashMap<&str, Vec<PathBuf>> = HashMap::new();
+    for path in paths {
+        let owner = folders
+            .iter()
+            .filter(|(_, dir)| path.starts_with(dir))
+            .max_by_key(|(_, dir)| dir.components().count());
+        if let Some((profile, _)) = owner {
+            by_profile.entry(profile.as_str()).or_default().push(path);
+        }
+    }
+
+    for (profile, dir) in &folders {
+        let Some(paths) = by_profile.remove(profile.as_str()) else { continue };
+        let db = match state.db_for_profile(profile) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("Watch sync: Failed to open DB for profile '{}': {}", profile, e);
+                continue;
+            }
+        };
+        // Wait for a running sync so that no reported file is lost
+        let _claim = state.sync_scheduler.claim(profile, None).await;
+        let filter = profile_sync_filter(&state.data_dir, profile);
+        let (scan_db, root) = (db.clone(), dir.clone());
+        let scan = match tokio::task::spawn_blocking(move || SyncScan::for_files(&scan_db, &root, &filter, paths)).await {
+            Ok(scan) => scan,
+            Err(e) => {
+                log::warn!("Watch sync: scan task failed for profile '{}': {}", profile, e);
+                continue;
+            }
+        };
+        if !scan.pending.is_empty() {
+            let run = SyncRunStart::new(SyncTrigger::Watch, None, dir);
+            import_sync_scan(state, profile, db, dir, scan, "Watch sync", None, run).await;
+        }
     }
 }
 
 /// Run the folder sync operation for ALL profiles (called by scheduler).
 /// Each profile syncs from its own subfolder: base for "default", base/{profile} for others.
-async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize), String> {
+async fn run_scheduled_sync(state: &WebAppState, trigger: SyncTrigger) -> Result<(usize, usize, usize), String> {
     let _base_sync = std::env::var("SYNC_LOGS_PATH")
         .map_err(|_| "SYNC_LOGS_PATH not configured".to_string())?;
 
@@ -2215,32 +3290,11 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
             continue;
         }
 
-        let entries = match std::fs::read_dir(&sync_dir) {
-            Ok(e) => e,
-            Err(e) => {
-                log::warn!("Scheduled sync: Failed to read {} for profile '{}': {}", sync_dir.display(), profile, e);
-                total_errors += 1;
-                continue;
-            }
-        };
-
-        let log_files: Vec<PathBuf> = entries
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                if let Ok(file_type) = entry.file_type() {
-                    if file_type.is_file() {
-                        let name = entry.file_name().to_string_lossy().to_lowercase();
-                        return name.ends_with(".txt") || name.ends_with(".csv");
-                    }
-                }
-                false
-            })
-            .map(|entry| entry.path())
-            .collect();
-
-        if log_files.is_empty() {
+        // The next run picks up what a sync still running leaves behind
+        let Some(_claim) = state.sync_scheduler.try_claim(profile, None) else {
+            log::info!("Scheduled sync: skipping profile '{}', its sync folder is already syncing", profile);
             continue;
-        }
+        };
 
         // Get (or create) the DB for this profile
         let db = match state.db_for_profile(profile) {
@@ -2252,118 +3306,111 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
             }
         };
 
-        let parser = LogParser::new(&db);
-
-        // Load per-profile smart tags config
-        let config_path = database::config_path_for_profile(&state.data_dir, profile);
-        let config: serde_json::Value = if config_path.exists() {
-            std::fs::read_to_string(&config_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
-        };
-        let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
-
-        for file_path in &log_files {
-            let file_name = file_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
-
-            let parse_result = match parser.parse_log(file_path).await {
-                Ok(result) => result,
-                Err(crate::parser::ParserError::AlreadyImported(_)) => {
-                    total_skipped += 1;
-                    continue;
-                }
-                Err(e) => {
-                    log::warn!("Scheduled sync [{}]: Failed to parse {}: {}", profile, file_name, e);
-                    total_errors += 1;
-                    continue;
-                }
-            };
-
-            // Check for duplicate flight
-            if db.is_duplicate_flight(
-                parse_result.metadata.drone_serial.as_deref(),
-                parse_result.metadata.battery_serial.as_deref(),
-                parse_result.metadata.start_time,
-            ).unwrap_or(None).is_some() {
-                total_skipped += 1;
-                continue;
-            }
-
-            // Insert flight
-            let flight_id = match db.insert_flight(&parse_result.metadata) {
-                Ok(id) => id,
-                Err(e) => {
-                    log::warn!("Scheduled sync [{}]: Failed to insert flight from {}: {}", profile, file_name, e);
-                    total_errors += 1;
-                    continue;
-                }
-            };
-
-            // Insert telemetry
-            if let Err(e) = db.bulk_insert_telemetry(flight_id, &parse_result.points) {
-                log::warn!("Scheduled sync [{}]: Failed to insert telemetry for {}: {}", profile, file_name, e);
-                let _ = db.delete_flight(flight_id);
+        let run = SyncRunStart::new(trigger, None, &sync_dir);
+        let scan = match scan_sync_folder(db.clone(), sync_dir.clone(), &state.data_dir, profile).await {
+            Ok(scan) => scan,
+            Err(e) => {
+                log::warn!("Scheduled sync: {} ({}) for profile '{}'", e, sync_dir.display(), profile);
+                run.finish(&db, 0, None, &Default::default(), Some(e));
                 total_errors += 1;
                 continue;
             }
+        };
+        total_skipped += scan.unchanged;
 
-            // Insert smart tags if enabled
-            if tags_enabled {
-                let tags = if let Some(types) = config.get("enabled_tag_types").and_then(|v| v.as_array()) {
-                    let enabled_types: Vec<String> = types.iter()
-                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                        .collect();
-                    crate::parser::LogParser::filter_smart_tags(parse_result.tags.clone(), &enabled_types)
-                } else {
-                    parse_result.tags.clone()
-                };
-                if let Err(e) = db.insert_flight_tags(flight_id, &tags) {
-                    log::warn!("Scheduled sync [{}]: Failed to insert tags for {}: {}", profile, file_name, e);
-                }
-            }
-
-            // Insert manual tags from re-imported CSV exports
-            for manual_tag in &parse_result.manual_tags {
-                if let Err(e) = db.add_flight_tag(flight_id, manual_tag) {
-                    log::warn!("Scheduled sync [{}]: Failed to insert manual tag '{}' for {}: {}", profile, manual_tag, file_name, e);
-                }
-            }
-
-            // Auto-tag with profile name for non-default profiles
-            if profile != "default" {
-                if let Err(e) = db.add_flight_tag(flight_id, profile) {
-                    log::warn!("Scheduled sync [{}]: Failed to insert profile tag for {}: {}", profile, file_name, e);
-                }
-            }
-
-            // Insert notes from re-imported CSV exports
-            if let Some(ref notes) = parse_result.notes {
-                if let Err(e) = db.update_flight_notes(flight_id, Some(notes.as_str())) {
-                    log::warn!("Scheduled sync [{}]: Failed to insert notes for {}: {}", profile, file_name, e);
-                }
-            }
+        if scan.pending.is_empty() {
+            run.finish(&db, scan.unchanged, None, &Default::default(), None);
+            continue;
+        }
 
-            // Apply color from re-imported CSV exports
-            if let Some(ref color) = parse_result.color {
-                if let Err(e) = db.update_flight_color(flight_id, color) {
-                    log::warn!("Scheduled sync [{}]: Failed to set color for {}: {}", profile, file_name, e);
-                }
-            }
+        let progress = import_sync_scan(state, profile, db, &sync_dir, scan, "Scheduled sync", None, run).await.progress;
+        total_processed += progress.imported;
+        total_skipped += progress.skipped;
+        total_errors += progress.failed + progress.queued;
+    }
 
-            // Insert app messages (tips and warnings) from DJI logs
-            if !parse_result.messages.is_empty() {
-                if let Err(e) = db.insert_flight_messages(flight_id, &parse_result.messages) {
-                    log::warn!("Scheduled sync [{}]: Failed to insert messages for {}: {}", profile, file_name, e);
-                }
-            }
+    Ok((total_processed, total_skipped, total_errors))
+}
 
-            total_processed += 1;
-            log::debug!("Scheduled sync [{}]: Imported {}", profile, file_name);
+/// Sync one of a profile's sources now. Returns the import progress and the
+/// number of files skipped as unchanged.
+async fn run_sync_source(state: &WebAppState, profile: &str, source: &SyncSource, trigger: SyncTrigger) -> Result<(BatchImportProgress, usize), String> {
+    if !source.enabled {
+        return Err("Sync source is disabled".to_string());
+    }
+    let Some(_claim) = state.sync_scheduler.try_claim(profile, Some(&source.id)) else {
+        return Err("Sync source is already syncing".to_string());
+    };
+    let root = PathBuf::from(&source.path);
+    if !root.is_dir() {
+        return Err(format!("Sync source folder does not exist: {}", source.path));
+    }
+    let filter = SyncFilter::for_source(source)?;
+    let db = state.db_for_profile(profile)?;
+    let run = SyncRunStart::new(trigger, Some(&source.id), &root);
+    let scan_db = db.clone();
+    let scan_root = root.clone();
+    let scan = tokio::task::spawn_blocking(move || SyncScan::new(&scan_db, &scan_root, &filter))
+        .await
+        .map_err(|e| format!("Sync scan task failed: {}", e))
+        .and_then(|scan| scan.map_err(|e| format!("Failed to read sync folder: {}", e)));
+    let scan = match scan {
+        Ok(scan) => scan,
+        Err(e) => {
+            run.finish(&db, 0, None, &Default::default(), Some(e.clone()));
+            return Err(e);
         }
+    };
+    let unchanged = scan.unchanged;
+    if scan.pending.is_empty() {
+        run.finish(&db, unchanged, None, &Default::default(), None);
+        return Ok((BatchImportProgress::default(), unchanged));
     }
-
-    Ok((total_processed, total_skipped, total_errors))
+    let label = format!("Source sync {}", source.path);
+    let progress = import_sync_scan(state, profile, db, &root, scan, &label, Some(source.post_import), run).await.progress;
+    Ok((progress, unchanged))
+}
+
+/// Import the pending files of a background sync scan into `profile`, then
+/// record them in the seen index and apply the post-import actions
+/// (`post_import`, or the profile's `sync_post_import` when `None`).
+/// `source` names the sync in the log; `run` is saved to the sync run
+/// history once the files are done. Returns the results of the files.
+#[allow(clippy::too_many_arguments)]
+async fn import_sync_scan(
+    state: &WebAppState,
+    profile: &str,
+    db: Arc<Database>,
+    sync_dir: &std::path::Path,
+    scan: SyncScan,
+    source: &str,
+    post_import: Option<SyncPostImport>,
+    run: SyncRunStart,
+) -> BatchSummary {
+    // Load per-profile smart tags config
+    let config_path = database::config_path_for_profile(&state.data_dir, profile);
+    let config: serde_json::Value = if config_path.exists() {
+        std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    let import_options = ImportOptions::from_config(&config, profile);
+
+    let summary = BatchImporter::new(db.clone(), import_options)
+        .with_failure_registry()
+        .run(scan.paths(), log_batch_progress)
+        .await;
+    scan.record(&db, &summary.files);
+    let post_import = post_import.unwrap_or_else(|| SyncPostImport::from_config(&config));
+    let moved = scan.apply_post_import(&db, sync_dir, &summary.files, &post_import);
+    log::info!(
+        "{} [{}]: {} imported, {} skipped, {} queued for decryption, {} failed{}",
+        source, profile, summary.progress.imported, summary.progress.skipped, summary.progress.queued, summary.progress.failed,
+        post_import_message(&moved)
+    );
+    run.finish(&db, scan.unchanged, Some(&summary), &moved, None);
+    summary
 }
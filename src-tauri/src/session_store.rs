@@ -59,6 +59,12 @@ pub struct SessionStore {
     failures: RwLock<HashMap<String, FailureRecord>>,
 }
 
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SessionStore {
     pub fn new() -> Self {
         Self {
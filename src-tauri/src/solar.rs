@@ -0,0 +1,192 @@
+//! Solar position for night / twilight classification.
+//!
+//! Uses the NOAA solar position equations (accurate to well under a
+//! degree for dates between 1900 and 2100), which is plenty for deciding
+//! whether a flight happened in darkness. Night follows the aviation
+//! definition: from the end of evening civil twilight to the start of
+//! morning civil twilight, i.e. the sun's centre more than 6° below the
+//! horizon.
+//!
+//! Known limits:
+//! - The elevation is geometric. Refraction is only accounted for in the
+//!   -0.833° sunrise/sunset threshold, so the twilight and golden hour
+//!   boundaries are off by up to half a degree near the horizon.
+//! - The sun is sampled at the home point and ground level. A drone at
+//!   120 m sees the horizon about 0.35° lower and far-flying missions can
+//!   cross into different light than the home point reports.
+//! - UT is used in place of terrestrial time (ΔT ≈ 70 s), which shifts
+//!   sunrise and sunset by a few seconds at most.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Sun elevation below which it is night (end of civil twilight)
+const CIVIL_TWILIGHT_DEG: f64 = -6.0;
+/// Sun elevation at sunrise/sunset, including refraction and the solar disc
+const SUNSET_DEG: f64 = -0.833;
+/// Sun elevation below which the light counts as golden hour
+const GOLDEN_HOUR_DEG: f64 = 6.0;
+/// Sampling step when integrating a flight over time
+const SAMPLE_SECS: f64 = 30.0;
+
+/// Light condition for a given sun elevation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Daylight {
+    Night,
+    /// Sun below the horizon but less than 6° down (civil twilight)
+    Twilight,
+    /// Sun up but less than 6° above the horizon
+    GoldenHour,
+    Day,
+}
+
+impl Daylight {
+    pub fn from_elevation(elevation_deg: f64) -> Self {
+        if elevation_deg < CIVIL_TWILIGHT_DEG {
+            Daylight::Night
+        } else if elevation_deg < SUNSET_DEG {
+            Daylight::Twilight
+        } else if elevation_deg < GOLDEN_HOUR_DEG {
+            Daylight::GoldenHour
+        } else {
+            Daylight::Day
+        }
+    }
+
+    /// Light condition at `utc` for the given coordinates
+    pub fn at(utc: DateTime<Utc>, lat: f64, lon: f64) -> Self {
+        Self::from_elevation(solar_elevation(utc, lat, lon))
+    }
+}
+
+/// Seconds of a flight spent in each light condition
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DaylightSummary {
+    pub night_secs: f64,
+    pub twilight_secs: f64,
+    pub golden_hour_secs: f64,
+    pub day_secs: f64,
+}
+
+impl DaylightSummary {
+    /// Split a flight of `duration_secs` starting at `start` into light conditions,
+    /// sampling the sun at the home point every 30 seconds
+    pub fn for_flight(start: DateTime<Utc>, duration_secs: f64, lat: f64, lon: f64) -> Self {
+        let mut summary = Self::default();
+        if !duration_secs.is_finite() || duration_secs <= 0.0 {
+            return summary;
+        }
+
+        let mut offset = 0.0;
+        while offset < duration_secs {
+            let step = SAMPLE_SECS.min(duration_secs - offset);
+            let mid = start + Duration::milliseconds(((offset + step / 2.0) * 1000.0) as i64);
+            let bucket = match Daylight::at(mid, lat, lon) {
+                Daylight::Night => &mut summary.night_secs,
+                Daylight::Twilight => &mut summary.twilight_secs,
+                Daylight::GoldenHour => &mut summary.golden_hour_secs,
+                Daylight::Day => &mut summary.day_secs,
+            };
+            *bucket += step;
+            offset += step;
+        }
+        summary
+    }
+}
+
+/// Geometric elevation of the sun's centre above the horizon, in degrees
+pub fn solar_elevation(utc: DateTime<Utc>, lat: f64, lon: f64) -> f64 {
+    let unix_secs = utc.timestamp() as f64 + f64::from(utc.timestamp_subsec_millis()) / 1000.0;
+    let julian_day = unix_secs / 86400.0 + 2440587.5;
+    let t = (julian_day - 2451545.0) / 36525.0;
+
+    let mean_long = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360.0);
+    let mean_anomaly = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+    let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+    let m = mean_anomaly.to_radians();
+
+    let center = m.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + (2.0 * m).sin() * (0.019993 - 0.000101 * t)
+        + (3.0 * m).sin() * 0.000289;
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_long = (mean_long + center - 0.00569 - 0.00478 * omega.sin()).to_radians();
+
+    let mean_obliquity = 23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+    let declination = (obliquity.sin() * apparent_long.sin()).asin();
+
+    // Equation of time, in minutes
+    let y = (obliquity / 2.0).tan().powi(2);
+    let l0 = mean_long.to_radians();
+    let eq_time = 4.0
+        * (y * (2.0 * l0).sin() - 2.0 * eccentricity * m.sin()
+            + 4.0 * eccentricity * y * m.sin() * (2.0 * l0).cos()
+            - 0.5 * y * y * (4.0 * l0).sin()
+            - 1.25 * eccentricity * eccentricity * (2.0 * m).sin())
+        .to_degrees();
+
+    let utc_minutes = unix_secs.rem_euclid(86400.0) / 60.0;
+    let true_solar_minutes = (utc_minutes + eq_time + 4.0 * lon).rem_euclid(1440.0);
+    let hour_angle = (true_solar_minutes / 4.0 - 180.0).to_radians();
+
+    let lat = lat.to_radians();
+    let cos_zenith = lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn test_solar_elevation_reference_points() {
+        // (time, latitude, longitude, expected elevation, tolerance)
+        let cases = [
+            // At the pole the elevation equals the sun's declination
+            ("2024-06-21T00:00:00Z", 90.0, 0.0, 23.44, 0.05),
+            ("2024-12-21T00:00:00Z", 90.0, 0.0, -23.44, 0.05),
+            ("2024-03-20T03:06:00Z", 90.0, 0.0, 0.0, 0.05),
+            // Solar noon on the Tropic of Cancer at the June solstice
+            ("2024-06-21T12:02:00Z", 23.44, 0.0, 90.0, 0.1),
+            // Published sunrise and sunset at Greenwich (04:43 and 21:21 BST)
+            ("2024-06-21T03:43:00Z", 51.48, 0.0, SUNSET_DEG, 0.15),
+            ("2024-06-21T20:21:00Z", 51.48, 0.0, SUNSET_DEG, 0.15),
+        ];
+        for (time, lat, lon, expected, tolerance) in cases {
+            let elevation = solar_elevation(at(time), lat, lon);
+            assert!((elevation - expected).abs() < tolerance, "{} at {}: {}", time, lat, elevation);
+        }
+    }
+
+    #[test]
+    fn test_daylight_classification() {
+        let cases = [
+            // Midnight sun and polar night in Tromsø
+            ("2024-06-21T22:00:00Z", 69.65, 18.96, Daylight::GoldenHour),
+            ("2024-12-21T11:00:00Z", 69.65, 18.96, Daylight::Twilight),
+            ("2024-06-21T12:00:00Z", 51.48, 0.0, Daylight::Day),
+            ("2024-06-21T23:59:00Z", 40.0, -3.7, Daylight::Night),
+            // Winter night in Sydney
+            ("2024-12-21T11:00:00Z", -33.87, 151.21, Daylight::Night),
+        ];
+        for (time, lat, lon, expected) in cases {
+            assert_eq!(Daylight::at(at(time), lat, lon), expected, "{} at {}", time, lat);
+        }
+    }
+
+    #[test]
+    fn test_flight_across_dusk() {
+        let summary = DaylightSummary::for_flight(at("2024-06-21T20:00:00Z"), 5400.0, 51.48, 0.0);
+        let total = summary.night_secs + summary.twilight_secs + summary.golden_hour_secs + summary.day_secs;
+        assert!((total - 5400.0).abs() < 1e-6);
+        assert!(summary.golden_hour_secs > 0.0 && summary.twilight_secs > 0.0 && summary.night_secs > 0.0);
+        assert_eq!(summary.day_secs, 0.0);
+
+        assert_eq!(DaylightSummary::for_flight(at("2024-06-21T20:00:00Z"), 0.0, 51.48, 0.0), DaylightSummary::default());
+        assert_eq!(DaylightSummary::for_flight(at("2024-06-21T20:00:00Z"), f64::NAN, 51.48, 0.0), DaylightSummary::default());
+    }
+}
@@ -0,0 +1,314 @@
+//! Parser for DJI video subtitle (.SRT) sidecar files.
+//!
+//! DJI cameras write one subtitle cue per video frame (or per second on
+//! older models) holding the recording time, the aircraft position and the
+//! camera settings. Three layouts are in use:
+//!
+//! - Bracketed fields (Mini, Air 2S, Mavic 3 and newer):
+//!   `2023-03-18 14:24:45.497 [iso: 100] [shutter: 1/1000.0] [fnum: 1.7]
+//!   [ev: 0] [latitude: 47.123456] [longitude: 8.123456] [rel_alt: 1.200 abs_alt: 430.512]`
+//! - Comma-separated fields (Mavic 2):
+//!   `F/2.8, SS 320, ISO 100, EV 0, GPS (8.1234, 47.1234, 19), D 24.28m, H 6.80m`
+//! - Key/value fields (Phantom 4, Mavic Pro):
+//!   `HOME(8.1234,47.1234) 2017.08.05 14:11:51 GPS(8.1235,47.1235,16) BAROMETER:1.9 ISO:100 Shutter:60 EV:0 Fnum:F2.2`
+//!
+//! Recording times are camera wall-clock times without a time zone.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::models::{MediaFrame, ParsedMediaClip};
+use crate::parser::ParserError;
+
+/// Video extensions looked for next to a sidecar, in order
+const VIDEO_EXTENSIONS: &[&str] = &["MP4", "mp4", "MOV", "mov"];
+
+/// Parse a DJI subtitle file into a media clip
+pub fn parse_file(path: &Path, file_hash: &str) -> Result<ParsedMediaClip, ParserError> {
+    let bytes = std::fs::read(path)?;
+    let content = String::from_utf8_lossy(&bytes);
+    let (start_time, frames) = parse_srt(&content)?;
+    let end_time = start_time + Duration::milliseconds(frames.last().map_or(0, |f| f.end_ms));
+
+    Ok(ParsedMediaClip {
+        srt_file: file_name(path),
+        video_file: video_file_for(path),
+        file_hash: file_hash.to_string(),
+        start_time,
+        end_time,
+        frames,
+    })
+}
+
+/// Whether a file looks like a subtitle sidecar (by extension)
+pub fn is_srt(path: &Path) -> bool {
+    crate::formats::has_extension(path, "srt")
+}
+
+/// Parse subtitle text into the clip's start time and its frames
+pub fn parse_srt(content: &str) -> Result<(NaiveDateTime, Vec<MediaFrame>), ParserError> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut frames = Vec::new();
+    let mut start_time: Option<NaiveDateTime> = None;
+
+    for cue in content.split("\n\n") {
+        let mut lines = cue.lines().map(str::trim).filter(|l| !l.is_empty());
+        let Some(mut line) = lines.next() else { continue };
+        // The cue number is optional in some exports
+        if line.chars().all(|c| c.is_ascii_digit()) {
+            let Some(next) = lines.next() else { continue };
+            line = next;
+        }
+        let Some((start_ms, end_ms)) = parse_cue_times(line) else { continue };
+        let text = strip_tags(&lines.collect::<Vec<_>>().join(" "));
+
+        let mut frame = MediaFrame {
+            offset_ms: start_ms,
+            end_ms,
+            ..Default::default()
+        };
+        if start_time.is_none() {
+            start_time = find_datetime(&text).map(|t| t - Duration::milliseconds(start_ms));
+        }
+        parse_fields(&text, &mut frame);
+        frames.push(frame);
+    }
+
+    if frames.is_empty() {
+        return Err(ParserError::Parse("No subtitle frames found".to_string()));
+    }
+    let start_time = start_time.ok_or_else(|| ParserError::Parse("Subtitle file has no recording time".to_string()))?;
+    Ok((start_time, frames))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The video next to the sidecar (`DJI_0001.SRT` → `DJI_0001.MP4`). When
+/// no video is found, e.g. for uploads, the DJI default `.MP4` is assumed.
+fn video_file_for(path: &Path) -> String {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    VIDEO_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .find(|p: &PathBuf| p.is_file())
+        .map(|p| file_name(&p))
+        .unwrap_or_else(|| format!("{}.MP4", stem))
+}
+
+/// `00:00:01,033 --> 00:00:01,066` → (1033, 1066)
+fn parse_cue_times(line: &str) -> Option<(i64, i64)> {
+    let (start, end) = line.split_once("-->")?;
+    Some((parse_timecode(start.trim())?, parse_timecode(end.trim())?))
+}
+
+fn parse_timecode(s: &str) -> Option<i64> {
+    let (hms, ms) = s.split_once([',', '.']).unwrap_or((s, "0"));
+    let mut parts = hms.split(':').map(|p| p.parse::<i64>().ok());
+    let (h, m, sec) = (parts.next()??, parts.next()??, parts.next()??);
+    Some(((h * 60 + m) * 60 + sec) * 1000 + ms.parse::<i64>().ok()?)
+}
+
+/// Remove `<font ...>` and other markup from a cue
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// First `date time` pair in a cue. Dates use `-`, `.` or `/`; fractions of
+/// a second use `.` or `,` (`14:24:45,497,960` on some Mini models).
+fn find_datetime(text: &str) -> Option<NaiveDateTime> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    tokens.windows(2).find_map(|pair| {
+        let date = pair[0].trim_start_matches(|c: char| !c.is_ascii_digit()).replace(['.', '/'], "-");
+        let mut time_parts = pair[1].trim_end_matches(|c: char| !c.is_ascii_digit()).split(',');
+        let mut time = time_parts.next()?.to_string();
+        if let Some(fraction) = time_parts.next() {
+            time = format!("{}.{}", time, fraction);
+        }
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S%.f").ok()
+    })
+}
+
+/// Read the position and camera settings of a cue into `frame`
+fn parse_fields(text: &str, frame: &mut MediaFrame) {
+    let mut text = text.to_string();
+
+    // GPS(lon, lat, satellites) of the older layouts; HOME(...) is ignored
+    for group in ["GPS", "HOME"] {
+        while let Some((values, rest)) = take_group(&text, group) {
+            if group == "GPS" {
+                let values: Vec<f64> = values.split(',').filter_map(|v| v.trim().parse().ok()).collect();
+                if let [lon, lat, ..] = values[..] {
+                    frame.longitude = Some(lon);
+                    frame.latitude = Some(lat);
+                }
+            }
+            text = rest;
+        }
+    }
+
+    let cleaned = text.replace(['[', ']', ','], " ");
+    let tokens: Vec<&str> = cleaned.split_whitespace().collect();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        i += 1;
+
+        // `F/2.8`
+        if let Some(f) = token.strip_prefix("F/").or_else(|| token.strip_prefix("f/")) {
+            frame.fnum = number(f).map(normalize_fnum);
+            continue;
+        }
+        if !token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+        let (key, value) = match token.split_once(':') {
+            // `iso:100`
+            Some((key, value)) if !value.is_empty() => (key, value),
+            // `iso: 100`
+            Some((key, _)) => match tokens.get(i) {
+                Some(value) => {
+                    i += 1;
+                    (key, *value)
+                }
+                None => break,
+            },
+            None => match (tokens.get(i), tokens.get(i + 1)) {
+                // `latitude : 47.1`
+                (Some(&":"), Some(value)) => {
+                    i += 2;
+                    (token, *value)
+                }
+                // `ISO 100`
+                (Some(value), _) if value.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+') => {
+                    i += 1;
+                    (token, *value)
+                }
+                _ => continue,
+            },
+        };
+        apply_field(frame, &key.to_ascii_lowercase(), value);
+    }
+}
+
+fn apply_field(frame: &mut MediaFrame, key: &str, value: &str) {
+    match key {
+        "iso" => frame.iso = number(value).map(|v| v as i32),
+        "shutter" | "ss" => frame.shutter = Some(normalize_shutter(value)),
+        "fnum" => frame.fnum = number(value.trim_start_matches(['F', 'f'])).map(normalize_fnum),
+        "ev" => frame.ev = number(value),
+        "ct" => frame.color_temp = number(value).map(|v| v as i32),
+        "focal_len" => frame.focal_length = number(value),
+        // DJI misspells these on some models
+        "latitude" | "latitud" | "lat" => frame.latitude = number(value),
+        "longitude" | "longtitude" | "lon" | "lng" => frame.longitude = number(value),
+        "rel_alt" | "barometer" | "h" => frame.rel_altitude = number(value),
+        "abs_alt" | "altitude" => frame.abs_altitude = number(value),
+        _ => {}
+    }
+}
+
+/// Take `NAME(...)` (optionally `NAME (...)`) out of `text`
+fn take_group(text: &str, name: &str) -> Option<(String, String)> {
+    let start = text.find(name)?;
+    let after = &text[start + name.len()..];
+    let open = after.find('(').filter(|&i| after[..i].trim().is_empty())?;
+    let close = after.find(')')?;
+    let values = after[open + 1..close].to_string();
+    let rest = format!("{} {}", &text[..start], &after[close + 1..]);
+    Some((values, rest))
+}
+
+/// Leading number of a value, ignoring units (`6.80m`, `33ms`)
+fn number(value: &str) -> Option<f64> {
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || ((c == '-' || c == '+') && i == 0)))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().ok()
+}
+
+/// Some models write f/2.8 as `280`
+fn normalize_fnum(fnum: f64) -> f64 {
+    if fnum >= 100.0 {
+        fnum / 100.0
+    } else {
+        fnum
+    }
+}
+
+/// Older layouts write 1/320 s as `320`
+fn normalize_shutter(value: &str) -> String {
+    match number(value) {
+        Some(v) if !value.contains('/') && v >= 1.0 => format!("1/{}", value),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bracketed_layout() {
+        let srt = "1\n00:00:00,000 --> 00:00:00,033\n<font size=\"28\">FrameCnt: 1, DiffTime: 33ms\n\
+                   2023-03-18 14:24:45.497\n[iso: 100] [shutter: 1/1000.0] [fnum: 1.7] [ev: 0] [ct: 5500] \
+                   [focal_len: 24.00] [latitude: 47.123456] [longitude: 8.123456] [rel_alt: 1.200 abs_alt: 430.512] </font>\n\n\
+                   2\n00:00:00,033 --> 00:00:00,066\n<font size=\"28\">FrameCnt: 2, DiffTime: 33ms\n\
+                   2023-03-18 14:24:45.530\n[iso: 200] [latitude: 47.123460] [longitude: 8.123470] [rel_alt: 1.300 abs_alt: 430.612] </font>\n";
+        let (start, frames) = parse_srt(srt).unwrap();
+        assert_eq!(start.to_string(), "2023-03-18 14:24:45.497");
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].iso, Some(100));
+        assert_eq!(frames[0].shutter.as_deref(), Some("1/1000.0"));
+        assert_eq!(frames[0].fnum, Some(1.7));
+        assert_eq!(frames[0].color_temp, Some(5500));
+        assert_eq!(frames[0].latitude, Some(47.123456));
+        assert_eq!(frames[0].abs_altitude, Some(430.512));
+        assert_eq!(frames[1].offset_ms, 33);
+        assert_eq!(frames[1].rel_altitude, Some(1.3));
+    }
+
+    #[test]
+    fn test_parse_legacy_layouts() {
+        let phantom = "1\n00:00:01,000 --> 00:00:02,000\nHOME(149.0251,-20.2532) 2017.08.05 14:11:51\n\
+                       GPS(149.0251,-20.2533,16) BAROMETER:1.9\nISO:100 Shutter:60 EV:0 Fnum:F2.2\n";
+        let (start, frames) = parse_srt(phantom).unwrap();
+        assert_eq!(start.to_string(), "2017-08-05 14:11:50");
+        assert_eq!(frames[0].longitude, Some(149.0251));
+        assert_eq!(frames[0].latitude, Some(-20.2533));
+        assert_eq!(frames[0].rel_altitude, Some(1.9));
+        assert_eq!(frames[0].shutter.as_deref(), Some("1/60"));
+        assert_eq!(frames[0].fnum, Some(2.2));
+
+        let mavic2 = "1\n00:00:00,000 --> 00:00:00,033\n2019-06-01 10:00:00,120,331\n\
+                      F/2.8, SS 320, ISO 100, EV 0, GPS (8.1234, 47.1234, 19), D 24.28m, H 6.80m, HS 0.00m/s, VS 0.00m/s\n";
+        let (start, frames) = parse_srt(mavic2).unwrap();
+        assert_eq!(start.to_string(), "2019-06-01 10:00:00.120");
+        assert_eq!(frames[0].fnum, Some(2.8));
+        assert_eq!(frames[0].shutter.as_deref(), Some("1/320"));
+        assert_eq!(frames[0].iso, Some(100));
+        assert_eq!(frames[0].latitude, Some(47.1234));
+        assert_eq!(frames[0].rel_altitude, Some(6.8));
+
+        assert!(parse_srt("1\n00:00:00,000 --> 00:00:01,000\nno telemetry\n").is_err());
+    }
+}
@@ -0,0 +1,929 @@
+//! Sync folder scanning.
+//!
+//! Sync folders are scanned recursively (DJI RC controllers and NAS mirrors
+//! nest logs in dated subfolders) and filtered with include/exclude glob
+//! patterns:
+//!
+//! - `SYNC_INCLUDE`: comma-separated patterns a file must match. Defaults to
+//!   the supported log extensions (`*.txt,*.csv,*.ulg,*.bin,*.tlog`).
+//! - `SYNC_EXCLUDE`: comma-separated patterns for files and folders to skip,
+//!   e.g. `.*,Cache,**/tmp/**`.
+//!
+//! Sync sources configured per profile bring their own patterns and may
+//! turn off recursion, see [`SyncFilter::for_source`].
+//!
+//! A pattern without `/` is matched against a single name (the file name
+//! for includes, any file or folder name for excludes); a pattern with `/`
+//! is matched against the path relative to the sync folder. Matching is
+//! case-insensitive.
+//!
+//! Files are looked up in the profile's `sync_seen_files` index first: when
+//! size and modification time are unchanged and the recorded hash still
+//! belongs to a flight, the file is skipped without reading it.
+//!
+//! After an import the profile's [`SyncPostImport`] settings may move files
+//! into the `imported/` and `rejected/` folders at the top of the sync
+//! folder. Those two folders are never scanned. Files that disappeared in
+//! the meantime are skipped; on read-only mounts the first failed move
+//! stops the rest and files stay where they are.
+
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+use crate::batch_import::{BatchFileResult, BatchOutcome};
+use crate::database::{Database, SyncSeenFile};
+use crate::formats;
+use crate::models::{FailedFileAction, ImportedFileAction, SyncPostImport, SyncSource};
+
+/// Top-level folder receiving archived files (`imported/YYYY/MM/`).
+/// Folders of this name are skipped at every depth.
+pub const IMPORTED_FOLDER: &str = "imported";
+
+/// Top-level folder receiving files that failed to import. Folders of this
+/// name are skipped at every depth.
+pub const REJECTED_FOLDER: &str = "rejected";
+
+/// Folders nested deeper than this are not scanned
+const MAX_DEPTH: usize = 32;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Include/exclude rules for sync folder files
+#[derive(Debug, Clone)]
+pub struct SyncFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    /// Folders (relative paths, `/`-separated) that are never scanned
+    skipped_folders: Vec<String>,
+    /// False to only scan the top level of the sync folder
+    recursive: bool,
+}
+
+impl Default for SyncFilter {
+    fn default() -> Self {
+        Self::new(&default_include(), &[]).expect("default sync patterns are valid")
+    }
+}
+
+/// Include patterns used when `SYNC_INCLUDE` is not set: one `*.<ext>` per
+/// extension of the registered formats
+fn default_include() -> Vec<String> {
+    formats::supported_extensions().iter().map(|ext| format!("*.{}", ext)).collect()
+}
+
+/// Comma-separated patterns from an environment variable
+fn env_patterns(var: &str) -> Option<Vec<String>> {
+    let value = std::env::var(var).ok()?;
+    Some(value.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect())
+}
+
+impl SyncFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| Pattern::new(p).map_err(|e| format!("Invalid sync pattern '{}': {}", p, e)))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+            skipped_folders: Vec::new(),
+            recursive: true,
+        })
+    }
+
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Never scan these folders (relative paths, `/`-separated), e.g. the
+    /// sync folders of other profiles nested in the default profile's sync
+    /// folder, or the profile's own sync sources
+    pub fn with_skipped_folders(mut self, folders: Vec<String>) -> Self {
+        self.skipped_folders = folders;
+        self
+    }
+
+    /// Filter from `SYNC_INCLUDE` / `SYNC_EXCLUDE`. Falls back to the
+    /// defaults (and logs why) when a pattern is invalid.
+    pub fn from_env() -> Self {
+        let include = env_patterns("SYNC_INCLUDE").unwrap_or_else(default_include);
+        let exclude = env_patterns("SYNC_EXCLUDE").unwrap_or_default();
+        Self::new(&include, &exclude).unwrap_or_else(|e| {
+            log::warn!("{} — using the default sync patterns", e);
+            Self::default()
+        })
+    }
+
+    /// Filter of a configured sync source. Empty pattern lists fall back to
+    /// `SYNC_INCLUDE` / `SYNC_EXCLUDE`.
+    pub fn for_source(source: &SyncSource) -> Result<Self, String> {
+        let include = if source.include.is_empty() {
+            env_patterns("SYNC_INCLUDE").unwrap_or_else(default_include)
+        } else {
+            source.include.clone()
+        };
+        let exclude = if source.exclude.is_empty() {
+            env_patterns("SYNC_EXCLUDE").unwrap_or_default()
+        } else {
+            source.exclude.clone()
+        };
+        Ok(Self::new(&include, &exclude)?.with_recursive(source.recursive))
+    }
+
+    fn matches(pattern: &Pattern, relative: &str) -> bool {
+        if pattern.as_str().contains('/') {
+            pattern.matches_with(relative, MATCH_OPTIONS)
+        } else {
+            relative.rsplit('/').next().is_some_and(|name| pattern.matches_with(name, MATCH_OPTIONS))
+        }
+    }
+
+    /// Whether a file or folder (relative path, `/`-separated) is excluded
+    fn is_excluded(&self, relative: &str) -> bool {
+        self.exclude.iter().any(|p| Self::matches(p, relative))
+    }
+
+    /// Whether a file (relative path, `/`-separated) should be synced
+    pub fn accepts(&self, relative: &str) -> bool {
+        self.include.iter().any(|p| Self::matches(p, relative)) && !self.is_excluded(relative)
+    }
+
+    /// Whether a folder is left out of scans; `depth` 0 is the sync folder's top level
+    fn skips_folder(&self, name: &str, relative: &str, depth: usize) -> bool {
+        let skipped =
+            name == IMPORTED_FOLDER || name == REJECTED_FOLDER || self.skipped_folders.iter().any(|f| f == relative);
+        !self.recursive || depth >= MAX_DEPTH || skipped || self.is_excluded(relative)
+    }
+
+    /// Like [`SyncFilter::accepts`], but also applies the folder rules to
+    /// each parent folder, i.e. whether a scan would pick the file up
+    pub fn accepts_nested(&self, relative: &str) -> bool {
+        let folders: Vec<&str> = relative.split('/').collect();
+        let Some((_, parents)) = folders.split_last() else { return false };
+        let skipped = parents
+            .iter()
+            .enumerate()
+            .any(|(depth, name)| self.skips_folder(name, &folders[..=depth].join("/"), depth));
+        !skipped && self.accepts(relative)
+    }
+}
+
+/// Folders of `sources` inside `root`, relative to it and `/`-separated.
+/// A scan of `root` skips these so no file is synced twice.
+pub fn nested_source_folders(root: &Path, sources: &[SyncSource]) -> Vec<String> {
+    let root = canonical(root);
+    sources
+        .iter()
+        .filter_map(|source| {
+            let relative = canonical(Path::new(&source.path)).strip_prefix(&root).ok()?.to_path_buf();
+            let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+            (!parts.is_empty()).then(|| parts.join("/"))
+        })
+        .collect()
+}
+
+/// Where the sync sources of one profile may point. Sync may move files,
+/// so sources are denied unless they are inside one of `roots`.
+#[derive(Debug, Clone, Default)]
+pub struct SourcePolicy {
+    /// `SYNC_SOURCE_ROOTS`, or the profile's own sync folder when that is unset
+    pub roots: Vec<PathBuf>,
+    /// The profile's own sync folder
+    pub own_folder: Option<PathBuf>,
+    /// The profile's other sync sources
+    pub sources: Vec<SyncSource>,
+    /// Sync folders and sources of the other profiles
+    pub claimed: Vec<PathBuf>,
+    /// Holds the databases and configs
+    pub data_dir: PathBuf,
+}
+
+/// Canonical form of a folder that may not exist (yet)
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl SourcePolicy {
+    /// Check a sync source sent by a client and normalize its path and
+    /// patterns. Sources must be existing absolute folders inside one of
+    /// the roots that overlap neither the data directory, the profile's own
+    /// sync folder and other sources, nor the folders of other profiles.
+    /// Sources may sit inside the own sync folder, whose scan then skips them.
+    pub fn validate(&self, mut source: SyncSource) -> Result<SyncSource, String> {
+        source.path = source.path.trim().to_string();
+        let path = PathBuf::from(&source.path);
+        if !path.is_absolute() {
+            return Err("Sync source path must be absolute".to_string());
+        }
+        let folder = path
+            .canonicalize()
+            .ok()
+            .filter(|p| p.is_dir())
+            .ok_or_else(|| format!("Sync source folder does not exist: {}", source.path))?;
+        // A folder overlaps another if it is inside it, or contains it and is scanned recursively
+        let overlaps = |other: &Path| folder.starts_with(other) || (source.recursive && other.starts_with(&folder));
+
+        if self.roots.is_empty() {
+            return Err("Sync sources are disabled: set SYNC_SOURCE_ROOTS or SYNC_LOGS_PATH".to_string());
+        }
+        if !self.roots.iter().any(|root| folder.starts_with(canonical(root))) {
+            let roots: Vec<String> = self.roots.iter().map(|r| r.display().to_string()).collect();
+            return Err(format!("Sync sources must be inside one of: {}", roots.join(", ")));
+        }
+        if overlaps(&canonical(&self.data_dir)) {
+            return Err("Sync sources cannot include the data directory".to_string());
+        }
+        let own_folder = self.own_folder.as_deref().map(canonical);
+        for claimed in self.claimed.iter().map(|c| canonical(c)) {
+            // The default profile's sync folder holds the other profiles' folders
+            // but never scans them
+            let holds_own = own_folder.as_ref().is_some_and(|own| own.starts_with(&claimed) && folder.starts_with(own));
+            if overlaps(&claimed) && !holds_own {
+                return Err(format!("Sync source overlaps a folder of another profile: {}", claimed.display()));
+            }
+        }
+        if let Some(own) = &own_folder {
+            let in_post_import = [IMPORTED_FOLDER, REJECTED_FOLDER].iter().any(|f| folder.starts_with(own.join(f)));
+            if folder == *own || (source.recursive && own.starts_with(&folder)) || in_post_import {
+                return Err(format!("Sync source overlaps the profile's sync folder: {}", own.display()));
+            }
+        }
+        for other in &self.sources {
+            let other_folder = canonical(Path::new(&other.path));
+            let nested = folder.starts_with(&other_folder) && other.recursive;
+            if folder == other_folder || nested || (source.recursive && other_folder.starts_with(&folder)) {
+                return Err(format!("Sync source overlaps another sync source: {}", other.path));
+            }
+        }
+
+        let clean = |patterns: Vec<String>| -> Vec<String> {
+            patterns.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+        };
+        source.include = clean(source.include);
+        source.exclude = clean(source.exclude);
+        SyncFilter::for_source(&source)?;
+        Ok(source)
+    }
+}
+
+/// Sync sources stored in a profile config file
+pub fn read_sources(config_path: &Path) -> Vec<SyncSource> {
+    let config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    SyncSource::list_from_config(&config)
+}
+
+/// Replace the sync sources of a profile config file, keeping its other settings
+pub fn write_sources(config_path: &Path, sources: &[SyncSource]) -> std::io::Result<()> {
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config["sync_sources"] = serde_json::json!(sources);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config)?)
+}
+
+/// A log file found in a sync folder
+#[derive(Debug, Clone)]
+pub struct SyncFile {
+    pub path: PathBuf,
+    /// Path relative to the sync folder, `/`-separated
+    pub relative: String,
+    pub size: i64,
+    pub modified_ms: i64,
+}
+
+/// Recursively list the files under `root` accepted by `filter`, sorted by
+/// relative path. Symlinked folders are not followed.
+pub fn scan(root: &Path, filter: &SyncFilter) -> std::io::Result<Vec<SyncFile>> {
+    let mut files = Vec::new();
+    scan_dir(root, "", 0, filter, &mut files)?;
+    files.sort_by(|a, b| a.relative.cmp(&b.relative));
+    Ok(files)
+}
+
+fn scan_dir(dir: &Path, prefix: &str, depth: usize, filter: &SyncFilter, files: &mut Vec<SyncFile>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)?.filter_map(|entry| entry.ok()) {
+        let Ok(file_type) = entry.file_type() else { continue };
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = format!("{}{}", prefix, name);
+        if file_type.is_dir() {
+            if !filter.skips_folder(&name, &relative, depth) {
+                // Unreadable subfolders should not abort the whole scan
+                if let Err(e) = scan_dir(&entry.path(), &format!("{}/", relative), depth + 1, filter, files) {
+                    log::warn!("Sync: skipping unreadable folder {}: {}", entry.path().display(), e);
+                }
+            }
+            continue;
+        }
+        if filter.accepts(&relative) {
+            files.extend(SyncFile::stat(entry.path(), relative));
+        }
+    }
+    Ok(())
+}
+
+impl SyncFile {
+    /// Size and modification time of a regular file (symlinks are followed)
+    fn stat(path: PathBuf, relative: String) -> Option<Self> {
+        let metadata = std::fs::metadata(&path).ok().filter(|m| m.is_file())?;
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis() as i64);
+        Some(Self {
+            path,
+            relative,
+            size: metadata.len() as i64,
+            modified_ms,
+        })
+    }
+}
+
+/// Files of a sync folder that need importing
+#[derive(Debug, Default)]
+pub struct SyncScan {
+    /// New or changed files, sorted by relative path
+    pub pending: Vec<SyncFile>,
+    /// Files skipped because the seen index says they are already imported
+    pub unchanged: usize,
+}
+
+impl SyncScan {
+    /// Scan `root` and drop files the seen index marks as imported and
+    /// unchanged. Index entries of files that disappeared are removed.
+    pub fn new(db: &Database, root: &Path, filter: &SyncFilter) -> std::io::Result<Self> {
+        let (scan, seen) = Self::check_seen(db, scan(root, filter)?);
+
+        // What is left in `seen` under this root was not found again
+        let root_key = root.to_string_lossy().to_string();
+        let gone: Vec<String> = seen.into_keys().filter(|path| Path::new(path).starts_with(&root_key)).collect();
+        if !gone.is_empty() {
+            if let Err(e) = db.forget_sync_seen_files(&gone) {
+                log::warn!("Failed to prune the sync seen index: {}", e);
+            }
+        }
+        Ok(scan)
+    }
+
+    /// Like [`SyncScan::new`] for just `paths` (e.g. files a watcher
+    /// reported), skipping those outside `root` or rejected by `filter`
+    pub fn for_files(db: &Database, root: &Path, filter: &SyncFilter, paths: Vec<PathBuf>) -> Self {
+        let mut files: Vec<SyncFile> = paths
+            .into_iter()
+            .filter_map(|path| {
+                let relative = path
+                    .strip_prefix(root)
+                    .ok()?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if !filter.accepts_nested(&relative) {
+                    return None;
+                }
+                SyncFile::stat(path, relative)
+            })
+            .collect();
+        files.sort_by(|a, b| a.relative.cmp(&b.relative));
+        files.dedup_by(|a, b| a.path == b.path);
+        Self::check_seen(db, files).0
+    }
+
+    /// Split `files` into pending and unchanged; returns the seen index
+    /// entries that matched none of them
+    fn check_seen(db: &Database, files: Vec<SyncFile>) -> (Self, HashMap<String, SyncSeenFile>) {
+        let mut seen = db.get_sync_seen_files().unwrap_or_else(|e| {
+            log::warn!("Failed to read the sync seen index: {}", e);
+            Default::default()
+        });
+        let flight_hashes: HashSet<String> = db.get_all_file_hashes().unwrap_or_default().into_iter().collect();
+
+        let mut scan = Self::default();
+        for file in files {
+            let key = file.path.to_string_lossy().to_string();
+            let known = seen.remove(&key).is_some_and(|entry| {
+                entry.size == file.size && entry.modified_ms == file.modified_ms && flight_hashes.contains(&entry.file_hash)
+            });
+            if known {
+                scan.unchanged += 1;
+            } else {
+                scan.pending.push(file);
+            }
+        }
+        (scan, seen)
+    }
+
+    /// Paths of the pending files, for the batch importer
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.pending.iter().map(|file| file.path.clone()).collect()
+    }
+
+    /// Add imported (or already known) files to the seen index. `results`
+    /// are the batch results for [`SyncScan::paths`], in the same order.
+    pub fn record(&self, db: &Database, results: &[BatchFileResult]) {
+        let seen: Vec<(String, SyncSeenFile)> = self
+            .pending
+            .iter()
+            .zip(results)
+            .filter(|(_, result)| matches!(result.outcome, BatchOutcome::Imported { .. } | BatchOutcome::Duplicate { .. }))
+            .filter_map(|(file, result)| {
+                Some((
+                    file.path.to_string_lossy().to_string(),
+                    SyncSeenFile {
+                        size: file.size,
+                        modified_ms: file.modified_ms,
+                        file_hash: result.file_hash.clone()?,
+                    },
+                ))
+            })
+            .collect();
+        if let Err(e) = db.record_sync_seen_files(&seen) {
+            log::warn!("Failed to update the sync seen index: {}", e);
+        }
+    }
+}
+
+/// Files moved by the post-import actions of one sync
+#[derive(Debug, Default)]
+pub struct PostImportSummary {
+    pub archived: usize,
+    pub rejected: usize,
+    /// Set when moving stopped, e.g. on a read-only mount
+    pub error: Option<String>,
+}
+
+impl SyncScan {
+    /// Apply the post-import actions to the batch results for
+    /// [`SyncScan::paths`]. Stops at the first file that cannot be moved
+    /// because the sync folder is not writable.
+    pub fn apply_post_import(&self, db: &Database, root: &Path, results: &[BatchFileResult], settings: &SyncPostImport) -> PostImportSummary {
+        let mut summary = PostImportSummary::default();
+        if settings.is_leave() {
+            return summary;
+        }
+        for (file, result) in self.pending.iter().zip(results) {
+            match apply_post_import(db, root, &file.path, result, settings) {
+                Ok(Some(_)) if matches!(result.outcome, BatchOutcome::Failed { .. }) => summary.rejected += 1,
+                Ok(Some(_)) => summary.archived += 1,
+                Ok(None) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    log::debug!("Sync: {} is gone, not moving it", file.path.display());
+                }
+                Err(e) if is_unwritable(&e) => {
+                    log::warn!("Sync folder {} is not writable ({}); leaving source files in place", root.display(), e);
+                    summary.error = Some(e.to_string());
+                    break;
+                }
+                Err(e) => log::warn!("Failed to move {}: {}", file.path.display(), e),
+            }
+        }
+        summary
+    }
+}
+
+/// Move a synced file according to `settings`: imported (or already known)
+/// files to `imported/YYYY/MM/`, failures to `rejected/` with an
+/// `.error.json` sidecar. Returns the new path if the file was moved.
+pub fn apply_post_import(
+    db: &Database,
+    root: &Path,
+    path: &Path,
+    result: &BatchFileResult,
+    settings: &SyncPostImport,
+) -> std::io::Result<Option<PathBuf>> {
+    let folder = match (&result.outcome, settings) {
+        (BatchOutcome::Imported { .. } | BatchOutcome::Duplicate { .. }, SyncPostImport { imported: ImportedFileAction::Archive, .. }) => {
+            root.join(IMPORTED_FOLDER).join(chrono::Local::now().format("%Y/%m").to_string())
+        }
+        (BatchOutcome::Failed { .. }, SyncPostImport { failed: FailedFileAction::Reject, .. }) => root.join(REJECTED_FOLDER),
+        _ => return Ok(None),
+    };
+    let Some(file_name) = path.file_name() else { return Ok(None) };
+
+    std::fs::create_dir_all(&folder)?;
+    let destination = unique_destination(&folder, &file_name.to_string_lossy());
+    std::fs::rename(path, &destination)?;
+
+    if let BatchOutcome::Failed { message } = &result.outcome {
+        let sidecar = serde_json::json!({
+            "file": path.strip_prefix(root).unwrap_or(path).to_string_lossy(),
+            "fileHash": result.file_hash,
+            "message": message,
+            "rejectedAt": chrono::Utc::now().to_rfc3339(),
+        });
+        let mut sidecar_path = destination.clone().into_os_string();
+        sidecar_path.push(".error.json");
+        if let Err(e) = std::fs::write(&sidecar_path, serde_json::to_string_pretty(&sidecar).unwrap_or_default()) {
+            log::warn!("Failed to write error sidecar for {}: {}", destination.display(), e);
+        }
+        // Keep "retry" in the import failures list working
+        if let Some(hash) = &result.file_hash {
+            if let Err(e) = db.update_import_failure_path(hash, &destination.to_string_lossy()) {
+                log::warn!("Failed to update import failure path: {}", e);
+            }
+        }
+    }
+    log::info!("Sync: moved {} to {}", path.display(), destination.display());
+    Ok(Some(destination))
+}
+
+/// Whether a failed move means no file of the sync folder can be moved
+fn is_unwritable(e: &std::io::Error) -> bool {
+    // EROFS; `ErrorKind::ReadOnlyFilesystem` is newer than our MSRV
+    const EROFS: i32 = 30;
+    e.kind() == ErrorKind::PermissionDenied || (cfg!(unix) && e.raw_os_error() == Some(EROFS))
+}
+
+/// `folder/name`, or `folder/stem (n).ext` if that is taken
+fn unique_destination(folder: &Path, name: &str) -> PathBuf {
+    let candidate = folder.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name, String::new()),
+    };
+    (1..)
+        .map(|n| folder.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded range")
+}
+
+/// Whether files can be created in (and so moved out of) a sync folder
+pub fn is_writable(root: &Path) -> bool {
+    let probe = root.join(".open-dronelog-write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_recursive_scan_with_patterns() {
+        let root = tempdir().unwrap();
+        for file in ["top.txt", "2024-05/DJIFlightRecord_1.TXT", "2024-05/notes.md", "Cache/tmp.txt", ".hidden/x.csv", "a/b/c/flight.ulg"] {
+            let path = root.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+        let relative = |filter: &SyncFilter| -> Vec<String> {
+            scan(root.path(), filter).unwrap().into_iter().map(|f| f.relative).collect()
+        };
+
+        assert_eq!(
+            relative(&SyncFilter::default()),
+            vec![".hidden/x.csv", "2024-05/DJIFlightRecord_1.TXT", "Cache/tmp.txt", "a/b/c/flight.ulg", "top.txt"]
+        );
+
+        let filter = SyncFilter::new(
+            &["**/DJIFlightRecord_*.txt".to_string(), "*.ulg".to_string()],
+            &[".*".to_string(), "cache".to_string()],
+        )
+        .unwrap();
+        assert_eq!(relative(&filter), vec!["2024-05/DJIFlightRecord_1.TXT", "a/b/c/flight.ulg"]);
+
+        let filter = SyncFilter::default().with_skipped_folders(vec!["a".to_string(), "2024-05".to_string()]);
+        assert_eq!(relative(&filter), vec![".hidden/x.csv", "Cache/tmp.txt", "top.txt"]);
+        assert!(!filter.accepts_nested("a/b/c/flight.ulg"));
+        assert!(!filter.accepts_nested("imported/2024/05/flight.txt"));
+        assert!(!filter.accepts_nested("b/imported/flight.txt"));
+        assert!(!filter.accepts_nested("b/c/rejected/flight.txt"));
+        assert!(filter.accepts_nested("b/flight.txt"));
+
+        assert_eq!(relative(&SyncFilter::default().with_recursive(false)), vec!["top.txt"]);
+
+        assert!(SyncFilter::new(&["[".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_seen_index_skips_unchanged_files() {
+        let data = tempdir().unwrap();
+        let db = Database::new(data.path().to_path_buf(), "default").unwrap();
+        db.insert_flight(&crate::models::FlightMetadata {
+            id: 0,
+            file_name: "a.txt".to_string(),
+            display_name: "a".to_string(),
+            file_hash: Some("hash-a".to_string()),
+            drone_model: None,
+            drone_serial: None,
+            aircraft_name: None,
+            battery_serial: None,
+            cycle_count: None,
+            start_time: None,
+            end_time: None,
+            duration_secs: None,
+            total_distance: None,
+            max_altitude: None,
+            max_speed: None,
+            home_lat: None,
+            home_lon: None,
+            point_count: 0,
+            photo_count: 0,
+            video_count: 0,
+        })
+        .unwrap();
+        let root = tempdir().unwrap();
+        for file in ["a.txt", "b.txt"] {
+            std::fs::write(root.path().join(file), "x").unwrap();
+        }
+
+        let scan = SyncScan::new(&db, root.path(), &SyncFilter::default()).unwrap();
+        assert_eq!((scan.pending.len(), scan.unchanged), (2, 0));
+        let results = vec![
+            BatchFileResult {
+                path: scan.pending[0].path.clone(),
+                file_hash: Some("hash-a".to_string()),
+                outcome: BatchOutcome::Imported { flight_id: 1, point_count: 1 },
+            },
+            BatchFileResult {
+                path: scan.pending[1].path.clone(),
+                file_hash: Some("hash-b".to_string()),
+                outcome: BatchOutcome::Failed { message: "bad".to_string() },
+            },
+        ];
+        scan.record(&db, &results);
+
+        let scan = SyncScan::new(&db, root.path(), &SyncFilter::default()).unwrap();
+        assert_eq!(scan.unchanged, 1);
+        assert_eq!(scan.paths(), vec![root.path().join("b.txt")]);
+
+        // A changed file is imported again
+        std::fs::write(root.path().join("a.txt"), "changed").unwrap();
+        assert_eq!(SyncScan::new(&db, root.path(), &SyncFilter::default()).unwrap().pending.len(), 2);
+
+        // Entries of files that are gone are dropped
+        std::fs::remove_file(root.path().join("a.txt")).unwrap();
+        SyncScan::new(&db, root.path(), &SyncFilter::default()).unwrap();
+        assert!(db.get_sync_seen_files().unwrap().is_empty());
+    }
+
+    fn source(path: &Path) -> SyncSource {
+        SyncSource {
+            id: String::new(),
+            path: path.to_string_lossy().to_string(),
+            recursive: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            schedule: None,
+            post_import: SyncPostImport::default(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_source_policy_denies_by_default() {
+        let root = tempdir().unwrap();
+        let dir = |name: &str| {
+            let path = root.path().join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            path
+        };
+        let (sync, own, other, data, outside) = (dir("sync"), dir("sync/alice"), dir("sync/bob"), dir("data"), dir("elsewhere"));
+        let other_source = dir("nas/bob");
+        let policy = SourcePolicy {
+            roots: vec![sync.clone()],
+            own_folder: Some(own.clone()),
+            sources: Vec::new(),
+            // The default profile's folder, bob's folder and one of bob's sources
+            claimed: vec![sync.clone(), other.clone(), other_source.clone()],
+            data_dir: data.clone(),
+        };
+
+        let mut nested = source(&dir("sync/alice/2024"));
+        nested.include = vec![" *.txt ".to_string(), " ".to_string()];
+        assert_eq!(policy.validate(nested).unwrap().include, vec!["*.txt"]);
+
+        let rejected = |policy: &SourcePolicy, source: SyncSource, reason: &str| {
+            let err = policy.validate(source).unwrap_err();
+            assert!(err.contains(reason), "{}", err);
+        };
+        rejected(&policy, source(Path::new("relative/path")), "absolute");
+        rejected(&policy, source(&root.path().join("missing")), "does not exist");
+        rejected(&policy, source(&outside), "must be inside");
+        rejected(&policy, source(&other), "another profile");
+        rejected(&policy, source(&sync), "another profile");
+        let mut invalid = source(&dir("sync/alice/drone"));
+        invalid.exclude = vec!["[".to_string()];
+        rejected(&policy, invalid, "Invalid sync pattern");
+
+        // Roots may hold the data directory and other profiles' sources
+        let wide = SourcePolicy { roots: vec![root.path().to_path_buf()], ..policy.clone() };
+        rejected(&wide, source(&data), "data directory");
+        rejected(&wide, source(root.path()), "data directory");
+        rejected(&wide, source(&other_source), "another profile");
+        assert!(wide.validate(source(&outside)).is_ok());
+        let mut flat = source(root.path().join("nas").as_path());
+        flat.recursive = false;
+        assert!(wide.validate(flat).is_ok());
+
+        // Without SYNC_SOURCE_ROOTS or a sync folder there is nowhere to sync from
+        let none = SourcePolicy { roots: Vec::new(), ..policy };
+        rejected(&none, source(&own), "disabled");
+    }
+
+    #[test]
+    fn test_nested_sources() {
+        let root = tempdir().unwrap();
+        for file in ["top.txt", "nas/a.txt", "nas/drone/b.txt", "nas/drone/imported/2024/05/c.txt", "nas/other/d.txt"] {
+            let path = root.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+        let own = root.path().to_path_buf();
+        let nas = source(&own.join("nas/drone"));
+        let mut flat = source(&own.join("nas"));
+        flat.recursive = false;
+        let policy = SourcePolicy {
+            roots: vec![own.clone()],
+            own_folder: Some(own.clone()),
+            sources: vec![nas.clone()],
+            claimed: Vec::new(),
+            data_dir: tempdir().unwrap().path().to_path_buf(),
+        };
+
+        // The profile's own folder skips its sources, and archive folders at every depth
+        let skipped = nested_source_folders(&own, &[nas.clone(), source(Path::new("/elsewhere"))]);
+        assert_eq!(skipped, vec!["nas/drone"]);
+        let filter = SyncFilter::default().with_skipped_folders(skipped);
+        let relative: Vec<String> = scan(&own, &filter).unwrap().into_iter().map(|f| f.relative).collect();
+        assert_eq!(relative, vec!["nas/a.txt", "nas/other/d.txt", "top.txt"]);
+        let relative: Vec<String> =
+            scan(&own.join("nas/drone"), &SyncFilter::default()).unwrap().into_iter().map(|f| f.relative).collect();
+        assert_eq!(relative, vec!["b.txt"]);
+
+        // Sources may not overlap the own folder, its archive or each other
+        let rejected = |source: SyncSource, reason: &str| {
+            let err = policy.validate(source).unwrap_err();
+            assert!(err.contains(reason), "{}", err);
+        };
+        std::fs::create_dir_all(own.join("imported/2024")).unwrap();
+        std::fs::create_dir_all(own.join("rejected")).unwrap();
+        rejected(source(&own), "profile's sync folder");
+        rejected(source(&own.join("imported/2024")), "profile's sync folder");
+        rejected(source(&own.join("rejected")), "profile's sync folder");
+        rejected(source(&own.join("nas/drone")), "another sync source");
+        rejected(source(&own.join("nas/drone/imported/2024")), "another sync source");
+        rejected(source(&own.join("nas")), "another sync source");
+        assert!(policy.validate(flat).is_ok());
+        assert!(policy.validate(source(&own.join("nas/other"))).is_ok());
+    }
+
+    #[test]
+    fn test_sources_config_round_trip() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        assert!(read_sources(&config_path).is_empty());
+        std::fs::write(&config_path, r#"{"sync_interval": "0 0 * * * *", "sync_sources": [{"path": 5}]}"#).unwrap();
+        assert!(read_sources(&config_path).is_empty());
+
+        let mut first = source(dir.path());
+        first.id = "a".to_string();
+        let mut second = source(&dir.path().join("b"));
+        second.id = "b".to_string();
+        write_sources(&config_path, &[first.clone(), second.clone()]).unwrap();
+        assert_eq!(read_sources(&config_path), vec![first.clone(), second.clone()]);
+
+        // Update and delete keep the rest of the config
+        second.enabled = false;
+        second.schedule = Some("0 */5 * * * *".to_string());
+        write_sources(&config_path, &[second.clone()]).unwrap();
+        assert_eq!(read_sources(&config_path), vec![second]);
+        let config: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(config["sync_interval"], "0 0 * * * *");
+    }
+
+    fn result(path: &Path, outcome: BatchOutcome) -> BatchFileResult {
+        BatchFileResult {
+            path: path.to_path_buf(),
+            file_hash: Some("hash".to_string()),
+            outcome,
+        }
+    }
+
+    /// A sync scan of every file below `root`, without a seen index
+    fn scan_all(root: &Path) -> SyncScan {
+        SyncScan {
+            pending: scan(root, &SyncFilter::default()).unwrap(),
+            unchanged: 0,
+        }
+    }
+
+    const MOVE_ALL: SyncPostImport = SyncPostImport {
+        imported: ImportedFileAction::Archive,
+        failed: FailedFileAction::Reject,
+    };
+
+    #[test]
+    fn test_post_import_archives_and_rejects() {
+        let data = tempdir().unwrap();
+        let db = Database::new(data.path().to_path_buf(), "default").unwrap();
+        let root = tempdir().unwrap();
+        for file in ["a.txt", "b.txt", "c.txt"] {
+            std::fs::write(root.path().join(file), "x").unwrap();
+        }
+        let scan = scan_all(root.path());
+        let results = vec![
+            result(&scan.pending[0].path, BatchOutcome::Imported { flight_id: 1, point_count: 1 }),
+            result(&scan.pending[1].path, BatchOutcome::Failed { message: "bad header".to_string() }),
+            result(&scan.pending[2].path, BatchOutcome::Skipped { message: "blacklisted".to_string() }),
+        ];
+
+        assert_eq!(scan.apply_post_import(&db, root.path(), &results, &SyncPostImport::default()).archived, 0);
+        assert!(root.path().join("a.txt").exists());
+
+        let summary = scan.apply_post_import(&db, root.path(), &results, &MOVE_ALL);
+        assert_eq!((summary.archived, summary.rejected, summary.error), (1, 1, None));
+        let month = chrono::Local::now().format("%Y/%m").to_string();
+        assert!(root.path().join(IMPORTED_FOLDER).join(month).join("a.txt").exists());
+        assert!(root.path().join(REJECTED_FOLDER).join("b.txt").exists());
+        assert!(root.path().join("c.txt").exists());
+
+        let sidecar = root.path().join(REJECTED_FOLDER).join("b.txt.error.json");
+        let sidecar: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(sidecar).unwrap()).unwrap();
+        assert_eq!(sidecar["file"], "b.txt");
+        assert_eq!(sidecar["message"], "bad header");
+
+        // A second file of the same name does not overwrite the first
+        std::fs::write(root.path().join("b.txt"), "y").unwrap();
+        let path = root.path().join("b.txt");
+        assert_eq!(
+            apply_post_import(&db, root.path(), &path, &result(&path, BatchOutcome::Failed { message: String::new() }), &MOVE_ALL).unwrap(),
+            Some(root.path().join(REJECTED_FOLDER).join("b (1).txt"))
+        );
+    }
+
+    #[test]
+    fn test_post_import_skips_missing_files() {
+        let data = tempdir().unwrap();
+        let db = Database::new(data.path().to_path_buf(), "default").unwrap();
+        let root = tempdir().unwrap();
+        for file in ["a.txt", "b.txt"] {
+            std::fs::write(root.path().join(file), "x").unwrap();
+        }
+        let scan = scan_all(root.path());
+        let results: Vec<BatchFileResult> = scan
+            .pending
+            .iter()
+            .map(|file| result(&file.path, BatchOutcome::Duplicate { message: String::new() }))
+            .collect();
+        std::fs::remove_file(root.path().join("a.txt")).unwrap();
+
+        let summary = scan.apply_post_import(&db, root.path(), &results, &MOVE_ALL);
+        assert_eq!((summary.archived, summary.error), (1, None));
+        assert!(!root.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_post_import_stops_on_read_only_folder() {
+        assert!(is_unwritable(&std::io::Error::from(ErrorKind::PermissionDenied)));
+        assert!(!is_unwritable(&std::io::Error::from(ErrorKind::NotFound)));
+        #[cfg(unix)]
+        assert!(is_unwritable(&std::io::Error::from_raw_os_error(30)));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let data = tempdir().unwrap();
+            let db = Database::new(data.path().to_path_buf(), "default").unwrap();
+            let root = tempdir().unwrap();
+            for file in ["a.txt", "b.txt"] {
+                std::fs::write(root.path().join(file), "x").unwrap();
+            }
+            let scan = scan_all(root.path());
+            let results: Vec<BatchFileResult> = scan
+                .pending
+                .iter()
+                .map(|file| result(&file.path, BatchOutcome::Imported { flight_id: 1, point_count: 1 }))
+                .collect();
+            std::fs::set_permissions(root.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+            // Permissions do not apply to root
+            if !is_writable(root.path()) {
+                let summary = scan.apply_post_import(&db, root.path(), &results, &MOVE_ALL);
+                assert_eq!(summary.archived, 0);
+                assert!(summary.error.is_some());
+                assert!(root.path().join("a.txt").exists() && root.path().join("b.txt").exists());
+            }
+            std::fs::set_permissions(root.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Filesystem watch mode for sync folders.
+//!
+//! Instead of scanning on a schedule, a [`SyncWatcher`] listens for file
+//! system events (inotify on Linux, FSEvents on macOS, ReadDirectoryChangesW
+//! on Windows) and hands files over as soon as they are complete.
+//!
+//! Logs are often copied in over several seconds (card readers, network
+//! shares), so an event only marks a file as pending: it is ready once its
+//! size and modification time stayed the same for the settle time. Empty
+//! files keep waiting. Which of the ready files get imported (patterns, seen
+//! index) is up to the caller, see [`crate::sync_scan::SyncScan::for_files`].
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+/// Seconds a file must stay unchanged before it is imported (SYNC_WATCH_SETTLE_SECS)
+const DEFAULT_SETTLE_SECS: u64 = 5;
+
+/// How often pending files are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Folder depth expanded when a whole folder is moved in at once
+const MAX_DEPTH: usize = 32;
+
+/// Settle time from SYNC_WATCH_SETTLE_SECS
+pub fn settle_from_env() -> Duration {
+    let secs = std::env::var("SYNC_WATCH_SETTLE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_SETTLE_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Recursive watch on a folder; stops watching when dropped
+pub struct SyncWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Changed paths reported by a [`SyncWatcher`]
+pub struct WatchEvents {
+    receiver: mpsc::UnboundedReceiver<PathBuf>,
+}
+
+impl SyncWatcher {
+    /// Start watching `root`. Run the returned [`WatchEvents`] on the async
+    /// runtime to receive settled files.
+    pub fn new(root: &Path) -> notify::Result<(Self, WatchEvents)> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                let relevant = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write))
+                );
+                if relevant {
+                    for path in event.paths {
+                        // The receiver is gone once the watch loop ended
+                        let _ = sender.send(path);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Sync folder watch error: {}", e),
+        })?;
+        watcher.watch(root, RecursiveMode::Recursive)?;
+        Ok((Self { _watcher: watcher }, WatchEvents { receiver }))
+    }
+}
+
+/// Size and modification time, compared between polls
+type Signature = (u64, Option<SystemTime>);
+
+struct PendingFile {
+    signature: Signature,
+    /// Last time the file changed
+    since: Instant,
+}
+
+impl WatchEvents {
+    /// Collect changed files and call `on_ready` with each batch of files
+    /// that stayed unchanged for `settle`. Batches are handled one at a time;
+    /// events arriving meanwhile are queued. Returns once the watcher is dropped.
+    pub async fn run<F, Fut>(mut self, settle: Duration, mut on_ready: F)
+    where
+        F: FnMut(Vec<PathBuf>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                path = self.receiver.recv() => match path {
+                    Some(path) => track(&mut pending, path, 0),
+                    None => break,
+                },
+                _ = interval.tick() => {
+                    let ready = settled(&mut pending, settle);
+                    if !ready.is_empty() {
+                        on_ready(ready).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn signature(path: &Path) -> Option<Signature> {
+    let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+    Some((metadata.len(), metadata.modified().ok()))
+}
+
+/// Mark `path` (or every file below it, for a folder) as changed just now
+fn track(pending: &mut HashMap<PathBuf, PendingFile>, path: PathBuf, depth: usize) {
+    if path.is_dir() {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(&path) else { return };
+        for entry in entries.flatten() {
+            track(pending, entry.path(), depth + 1);
+        }
+    } else if let Some(signature) = signature(&path) {
+        pending.insert(path, PendingFile { signature, since: Instant::now() });
+    }
+}
+
+/// Take the files whose signature did not change for `settle`
+fn settled(pending: &mut HashMap<PathBuf, PendingFile>, settle: Duration) -> Vec<PathBuf> {
+    let mut ready = Vec::new();
+    pending.retain(|path, file| {
+        let Some(signature) = signature(path) else {
+            // Deleted or moved away again
+            return false;
+        };
+        if signature != file.signature {
+            file.signature = signature;
+            file.since = Instant::now();
+            return true;
+        }
+        if signature.0 > 0 && file.since.elapsed() >= settle {
+            ready.push(path.clone());
+            return false;
+        }
+        true
+    });
+    ready.sort();
+    ready
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_files_are_ready_once_settled() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let (watcher, events) = SyncWatcher::new(&root).unwrap();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(events.run(Duration::from_millis(1500), move |paths| {
+            let sender = sender.clone();
+            async move {
+                let _ = sender.send(paths);
+            }
+        }));
+
+        // A file still being written must not be handed over
+        let growing = root.join("growing.csv");
+        let mut file = std::fs::File::create(&growing).unwrap();
+        for _ in 0..4 {
+            file.write_all(b"latitude,longitude\n").unwrap();
+            file.flush().unwrap();
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            assert!(receiver.try_recv().is_err());
+        }
+        drop(file);
+
+        // A folder moved in at once is expanded to its files
+        let staging = temp_dir.path().with_extension("staging");
+        std::fs::create_dir_all(staging.join("card")).unwrap();
+        std::fs::write(staging.join("card/flight.txt"), b"data").unwrap();
+        std::fs::rename(staging.join("card"), root.join("card")).unwrap();
+
+        let mut ready = Vec::new();
+        while ready.len() < 2 {
+            let batch = tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+                .await
+                .expect("watcher did not report the files")
+                .unwrap();
+            ready.extend(batch);
+        }
+        ready.sort();
+        assert_eq!(ready, vec![root.join("card/flight.txt"), growing]);
+
+        drop(watcher);
+        tokio::time::timeout(Duration::from_secs(5), task).await.unwrap().unwrap();
+        let _ = std::fs::remove_dir_all(staging);
+    }
+}
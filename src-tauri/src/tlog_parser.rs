@@ -0,0 +1,728 @@
+//! Parser module for MAVLink telemetry logs (.tlog).
+//!
+//! A tlog is the raw MAVLink stream recorded by a ground station, with each
+//! packet prefixed by a big-endian Unix timestamp in microseconds. Both
+//! MAVLink v1 and v2 framing are accepted; packets are CRC-checked against the
+//! messages this parser understands and everything else is skipped.
+//!
+//! Points are emitted for each GLOBAL_POSITION_INT of the aircraft with the
+//! latest attitude, battery, RC and mode values held alongside. STATUSTEXT
+//! messages become flight messages.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::dataflash_parser::{ardupilot_mode_name, Vehicle};
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMessage, FlightMetadata, FlightStats, ParsedFlight, TelemetryPoint};
+use crate::parser::{LogParser, ParseOptions, ParserError};
+
+/// MAVLink v1 / v2 start-of-frame markers
+const STX_V1: u8 = 0xFE;
+const STX_V2: u8 = 0xFD;
+
+/// Size of the tlog timestamp prefix
+const TIMESTAMP_LEN: usize = 8;
+
+/// MAVLink v2 incompat flag: packet carries a 13-byte signature
+const MAVLINK_IFLAG_SIGNED: u8 = 0x01;
+
+/// MAVLink message ids decoded by this parser
+const MSG_HEARTBEAT: u32 = 0;
+const MSG_SYS_STATUS: u32 = 1;
+const MSG_PARAM_VALUE: u32 = 22;
+const MSG_GPS_RAW_INT: u32 = 24;
+const MSG_ATTITUDE: u32 = 30;
+const MSG_GLOBAL_POSITION_INT: u32 = 33;
+const MSG_RC_CHANNELS: u32 = 65;
+const MSG_BATTERY_STATUS: u32 = 147;
+const MSG_AUTOPILOT_VERSION: u32 = 148;
+const MSG_STATUSTEXT: u32 = 253;
+
+/// MAV_TYPE_GCS and MAV_AUTOPILOT_INVALID identify non-vehicle heartbeats
+const MAV_TYPE_GCS: u8 = 6;
+const MAV_AUTOPILOT_ARDUPILOTMEGA: u8 = 3;
+const MAV_AUTOPILOT_INVALID: u8 = 8;
+const MAV_AUTOPILOT_PX4: u8 = 12;
+
+/// CRC_EXTRA seed and minimum (v1) payload length for each decoded message
+fn message_info(msg_id: u32) -> Option<(u8, usize)> {
+    match msg_id {
+        MSG_HEARTBEAT => Some((50, 9)),
+        MSG_SYS_STATUS => Some((124, 31)),
+        MSG_PARAM_VALUE => Some((220, 25)),
+        MSG_GPS_RAW_INT => Some((24, 30)),
+        MSG_ATTITUDE => Some((39, 28)),
+        MSG_GLOBAL_POSITION_INT => Some((104, 28)),
+        MSG_RC_CHANNELS => Some((118, 42)),
+        MSG_BATTERY_STATUS => Some((154, 36)),
+        MSG_AUTOPILOT_VERSION => Some((178, 60)),
+        MSG_STATUSTEXT => Some((83, 51)),
+        _ => None,
+    }
+}
+
+/// MAVLink X.25 checksum (CRC-16/MCRF4XX) accumulation
+fn crc_accumulate(crc: u16, byte: u8) -> u16 {
+    let mut tmp = byte ^ (crc & 0xFF) as u8;
+    tmp ^= tmp << 4;
+    (crc >> 8) ^ ((tmp as u16) << 8) ^ ((tmp as u16) << 3) ^ ((tmp as u16) >> 4)
+}
+
+/// A decoded MAVLink packet
+struct Packet {
+    timestamp_us: u64,
+    sys_id: u8,
+    comp_id: u8,
+    msg_id: u32,
+    /// Payload zero-extended to at least the message's base length
+    payload: Vec<u8>,
+}
+
+/// Little-endian field readers over a payload
+fn u8_at(p: &[u8], off: usize) -> u8 {
+    p.get(off).copied().unwrap_or(0)
+}
+fn i8_at(p: &[u8], off: usize) -> i8 {
+    u8_at(p, off) as i8
+}
+fn u16_at(p: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([u8_at(p, off), u8_at(p, off + 1)])
+}
+fn i16_at(p: &[u8], off: usize) -> i16 {
+    u16_at(p, off) as i16
+}
+fn u32_at(p: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([u8_at(p, off), u8_at(p, off + 1), u8_at(p, off + 2), u8_at(p, off + 3)])
+}
+fn i32_at(p: &[u8], off: usize) -> i32 {
+    u32_at(p, off) as i32
+}
+fn u64_at(p: &[u8], off: usize) -> u64 {
+    (u32_at(p, off) as u64) | ((u32_at(p, off + 4) as u64) << 32)
+}
+fn f32_at(p: &[u8], off: usize) -> f32 {
+    f32::from_bits(u32_at(p, off))
+}
+
+/// Try to decode a packet at `offset`; returns the packet (if it is one we
+/// understand) and the total record length consumed
+fn read_packet(data: &[u8], offset: usize) -> Option<(Option<Packet>, usize)> {
+    let ts_bytes: [u8; 8] = data.get(offset..offset + TIMESTAMP_LEN)?.try_into().ok()?;
+    let timestamp_us = u64::from_be_bytes(ts_bytes);
+    let frame = data.get(offset + TIMESTAMP_LEN..)?;
+    let stx = *frame.first()?;
+
+    let (header_len, payload_len, sys_id, comp_id, msg_id, signed) = match stx {
+        STX_V1 => {
+            let h = frame.get(..6)?;
+            (6, h[1] as usize, h[3], h[4], h[5] as u32, false)
+        }
+        STX_V2 => {
+            let h = frame.get(..10)?;
+            let msg_id = h[7] as u32 | (h[8] as u32) << 8 | (h[9] as u32) << 16;
+            (10, h[1] as usize, h[5], h[6], msg_id, h[2] & MAVLINK_IFLAG_SIGNED != 0)
+        }
+        _ => return None,
+    };
+
+    let frame_len = header_len + payload_len + 2 + if signed { 13 } else { 0 };
+    let frame = frame.get(..frame_len)?;
+    let record_len = TIMESTAMP_LEN + frame_len;
+
+    let Some((crc_extra, base_len)) = message_info(msg_id) else {
+        // Without CRC_EXTRA the checksum can't be verified, so only skip the
+        // record when another record (or the end of the file) follows it.
+        // Otherwise a stray STX byte could swallow the real packets after it.
+        let next = offset + record_len;
+        let followed = next == data.len()
+            || data.get(next + TIMESTAMP_LEN).is_some_and(|b| matches!(*b, STX_V1 | STX_V2));
+        return followed.then_some((None, record_len));
+    };
+
+    // Checksum covers everything after STX up to the payload, plus CRC_EXTRA
+    let mut crc: u16 = 0xFFFF;
+    for &b in &frame[1..header_len + payload_len] {
+        crc = crc_accumulate(crc, b);
+    }
+    crc = crc_accumulate(crc, crc_extra);
+    let received = u16::from_le_bytes([frame[header_len + payload_len], frame[header_len + payload_len + 1]]);
+    if crc != received {
+        return None;
+    }
+
+    // MAVLink v2 truncates trailing zero bytes; restore them
+    let mut payload = frame[header_len..header_len + payload_len].to_vec();
+    if payload.len() < base_len {
+        payload.resize(base_len, 0);
+    }
+
+    Some((Some(Packet { timestamp_us, sys_id, comp_id, msg_id, payload }), record_len))
+}
+
+/// Most recent values of the non-position messages, held until the next position
+#[derive(Debug, Default)]
+struct HeldState {
+    roll: Option<f64>,
+    pitch: Option<f64>,
+    yaw: Option<f64>,
+    satellites: Option<i32>,
+    gps_fix: Option<i32>,
+    battery_percent: Option<i32>,
+    battery_voltage: Option<f64>,
+    battery_current: Option<f64>,
+    battery_temp: Option<f64>,
+    cell_voltages: Option<Vec<f64>>,
+    rc_signal: Option<i32>,
+    rc_channels: Vec<f64>,
+    flight_mode: Option<String>,
+}
+
+/// MAVLink ground station telemetry log format
+pub struct TlogFormat;
+
+impl FlightLogFormat for TlogFormat {
+    fn id(&self) -> &'static str {
+        "mavlink_tlog"
+    }
+
+    fn name(&self) -> &'static str {
+        "MAVLink Telemetry Log"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tlog"]
+    }
+
+    /// `.tlog` extension and the first record holds a MAVLink frame
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8 {
+        if formats::has_extension(path, "tlog")
+            && header.get(TIMESTAMP_LEN).is_some_and(|b| matches!(*b, STX_V1 | STX_V2))
+        {
+            100
+        } else {
+            0
+        }
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, _options: &'a ParseOptions) -> ParseFuture<'a> {
+        formats::blocking(path, file_hash, |path, file_hash| TlogParser::new().parse(path, file_hash))
+    }
+}
+
+/// MAVLink telemetry log Parser
+#[derive(Default)]
+pub struct TlogParser;
+
+impl TlogParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse a MAVLink tlog file
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing MAVLink tlog file: {:?}", file_path);
+
+        let data = fs::read(file_path)?;
+
+        // The aircraft is the first system sending a non-GCS autopilot heartbeat
+        let mut vehicle_sys: Option<(u8, u8)> = None;
+        let mut autopilot: u8 = MAV_AUTOPILOT_INVALID;
+        let mut vehicle = Vehicle::Unknown;
+        let mut drone_serial: Option<String> = None;
+        let mut params: HashMap<String, f64> = HashMap::new();
+
+        let mut state = HeldState::default();
+        let mut samples: Vec<(u64, TelemetryPoint)> = Vec::new();
+        let mut raw_messages: Vec<(u64, &'static str, String)> = Vec::new();
+        let mut skipped_bytes: usize = 0;
+
+        let mut offset = 0;
+        while offset + TIMESTAMP_LEN < data.len() {
+            let Some((packet, len)) = read_packet(&data, offset) else {
+                // Not a valid record here: resync byte by byte
+                offset += 1;
+                skipped_bytes += 1;
+                continue;
+            };
+            offset += len;
+            let Some(packet) = packet else {
+                continue;
+            };
+            let p = &packet.payload;
+
+            if packet.msg_id == MSG_HEARTBEAT {
+                let mav_type = u8_at(p, 4);
+                let mav_autopilot = u8_at(p, 5);
+                if vehicle_sys.is_none() && mav_type != MAV_TYPE_GCS && mav_autopilot != MAV_AUTOPILOT_INVALID {
+                    vehicle_sys = Some((packet.sys_id, packet.comp_id));
+                    autopilot = mav_autopilot;
+                    vehicle = vehicle_from_mav_type(mav_type);
+                    log::debug!(
+                        "tlog: aircraft is sysid={} compid={} (autopilot={}, type={})",
+                        packet.sys_id, packet.comp_id, mav_autopilot, mav_type
+                    );
+                }
+            }
+
+            // Only messages from the aircraft are of interest
+            let Some((sys_id, comp_id)) = vehicle_sys else {
+                continue;
+            };
+            if packet.sys_id != sys_id {
+                continue;
+            }
+
+            match packet.msg_id {
+                MSG_HEARTBEAT if packet.comp_id == comp_id => {
+                    state.flight_mode = Some(mode_name(autopilot, vehicle, u32_at(p, 0)));
+                }
+                MSG_GLOBAL_POSITION_INT => {
+                    let point = self.position_point(p, &state, &params);
+                    samples.push((packet.timestamp_us, point));
+                }
+                MSG_ATTITUDE => {
+                    let (roll, pitch, yaw) = (f32_at(p, 4) as f64, f32_at(p, 8) as f64, f32_at(p, 12) as f64);
+                    if roll.is_finite() && pitch.is_finite() && yaw.is_finite() {
+                        state.roll = Some(roll.to_degrees());
+                        state.pitch = Some(pitch.to_degrees());
+                        state.yaw = Some(yaw.to_degrees());
+                    }
+                }
+                MSG_GPS_RAW_INT => {
+                    state.gps_fix = Some(u8_at(p, 28) as i32);
+                    state.satellites = Some(u8_at(p, 29) as i32).filter(|s| *s != 255);
+                }
+                MSG_SYS_STATUS => {
+                    let voltage_mv = u16_at(p, 14);
+                    let current_ca = i16_at(p, 16);
+                    let remaining = i8_at(p, 30);
+                    state.battery_voltage = (voltage_mv != 0 && voltage_mv != u16::MAX).then(|| voltage_mv as f64 / 1000.0);
+                    state.battery_current = (current_ca >= 0).then(|| current_ca as f64 / 100.0);
+                    state.battery_percent = (remaining >= 0).then_some(remaining as i32);
+                }
+                MSG_BATTERY_STATUS => {
+                    // Only the primary battery; SYS_STATUS covers the basics when this is absent
+                    if u8_at(p, 32) != 0 {
+                        continue;
+                    }
+                    let temp = i16_at(p, 8);
+                    state.battery_temp = (temp != i16::MAX).then(|| temp as f64 / 100.0);
+                    let cells: Vec<f64> = (0..10)
+                        .map(|i| u16_at(p, 10 + i * 2))
+                        .filter(|mv| *mv != 0 && *mv != u16::MAX)
+                        .map(|mv| mv as f64 / 1000.0)
+                        .collect();
+                    // A single entry is the pack voltage, not a cell breakdown
+                    state.cell_voltages = if cells.len() > 1 { Some(cells) } else { None };
+                    let current_ca = i16_at(p, 30);
+                    if current_ca >= 0 {
+                        state.battery_current = Some(current_ca as f64 / 100.0);
+                    }
+                    let remaining = i8_at(p, 35);
+                    if remaining >= 0 {
+                        state.battery_percent = Some(remaining as i32);
+                    }
+                }
+                MSG_RC_CHANNELS => {
+                    let count = (u8_at(p, 40) as usize).min(18);
+                    state.rc_channels = (0..count).map(|i| u16_at(p, 4 + i * 2) as f64).collect();
+                    // RSSI is 0..254, 255 when unknown
+                    let rssi = u8_at(p, 41);
+                    state.rc_signal = (rssi != 255).then(|| (rssi as f64 / 254.0 * 100.0).round() as i32);
+                }
+                MSG_PARAM_VALUE => {
+                    let name_bytes = &p[8..24];
+                    let end = name_bytes.iter().position(|&b| b == 0).unwrap_or(name_bytes.len());
+                    let name = String::from_utf8_lossy(&name_bytes[..end]).to_string();
+                    if name.starts_with("RCMAP_") || name.starts_with("RC_MAP_") {
+                        let value = f32_at(p, 0);
+                        // PX4 sends integer params bit-cast into the float field
+                        let channel = if value.is_finite() && value.fract() == 0.0 && value >= 0.0 {
+                            value as f64
+                        } else {
+                            u32_at(p, 0) as f64
+                        };
+                        params.insert(name, channel);
+                    }
+                }
+                MSG_AUTOPILOT_VERSION => {
+                    let uid = u64_at(p, 8);
+                    if uid != 0 && drone_serial.is_none() {
+                        drone_serial = Some(format!("{:016X}", uid));
+                    }
+                }
+                MSG_STATUSTEXT => {
+                    let text_bytes = &p[1..51];
+                    let end = text_bytes.iter().position(|&b| b == 0).unwrap_or(text_bytes.len());
+                    let text = String::from_utf8_lossy(&text_bytes[..end]).trim().to_string();
+                    // MAV_SEVERITY: 0 emergency .. 7 debug
+                    let severity = match u8_at(p, 0) {
+                        0..=3 => "caution",
+                        4 => "warn",
+                        5 | 6 => "tip",
+                        _ => continue,
+                    };
+                    if !text.is_empty() {
+                        raw_messages.push((packet.timestamp_us, severity, text));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if skipped_bytes > 0 {
+            log::warn!("tlog: skipped {} unreadable bytes while resyncing", skipped_bytes);
+        }
+        log::debug!(
+            "tlog decoded: {} position samples, {} status messages, {} params",
+            samples.len(),
+            raw_messages.len(),
+            params.len()
+        );
+
+        // Flight time starts at the first sample with a valid position
+        let first_fix_us = samples
+            .iter()
+            .find(|(_, p)| p.latitude.is_some() && p.longitude.is_some())
+            .map(|(ts, _)| *ts)
+            .ok_or(ParserError::NoTelemetryData)?;
+
+        // Convert to relative timestamps; drop pre-fix samples and keep timestamps unique
+        let mut points: Vec<TelemetryPoint> = Vec::with_capacity(samples.len());
+        for (ts, mut point) in samples {
+            if ts < first_fix_us {
+                continue;
+            }
+            point.timestamp_ms = ((ts - first_fix_us) / 1000) as i64;
+            if points.last().is_some_and(|p| p.timestamp_ms >= point.timestamp_ms) {
+                continue;
+            }
+            points.push(point);
+        }
+
+        let mut messages: Vec<FlightMessage> = raw_messages
+            .into_iter()
+            .map(|(ts, severity, text)| FlightMessage {
+                timestamp_ms: (ts.saturating_sub(first_fix_us) / 1000) as i64,
+                message_type: severity.to_string(),
+                message: text,
+            })
+            .collect();
+        messages.sort_by_key(|m| m.timestamp_ms);
+        messages.dedup_by(|a, b| {
+            a.timestamp_ms == b.timestamp_ms && a.message_type == b.message_type && a.message == b.message
+        });
+
+        // tlog timestamps are the ground station's Unix clock
+        let start_time = DateTime::<Utc>::from_timestamp_micros(first_fix_us as i64);
+
+        let drone_model = Some(
+            match autopilot {
+                MAV_AUTOPILOT_ARDUPILOTMEGA => match vehicle {
+                    Vehicle::Copter => "ArduCopter",
+                    Vehicle::Plane => "ArduPlane",
+                    Vehicle::Rover => "ArduRover",
+                    Vehicle::Unknown => "ArduPilot",
+                },
+                MAV_AUTOPILOT_PX4 => "PX4",
+                _ => "MAVLink",
+            }
+            .to_string(),
+        );
+
+        let stats = LogParser::calculate_stats(&points);
+        let metadata = self.build_metadata(file_path, file_hash, drone_model, drone_serial, &points, &stats, start_time);
+
+        log::info!(
+            "tlog parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}, messages={}",
+            parse_start.elapsed().as_secs_f64(),
+            metadata.duration_secs.unwrap_or(0.0),
+            metadata.total_distance.unwrap_or(0.0),
+            metadata.max_altitude.unwrap_or(0.0),
+            points.len(),
+            messages.len()
+        );
+
+        // Generate smart tags and add "MAVLink" source tag
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "MAVLink".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParsedFlight { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages, side_data: Default::default(), equipment: Default::default() })
+    }
+
+    /// Build a telemetry point from GLOBAL_POSITION_INT plus the currently held state
+    fn position_point(&self, p: &[u8], state: &HeldState, params: &HashMap<String, f64>) -> TelemetryPoint {
+        let lat = i32_at(p, 4) as f64 * 1e-7;
+        let lon = i32_at(p, 8) as f64 * 1e-7;
+        let has_fix = lat.abs() <= 90.0
+            && lon.abs() <= 180.0
+            && !(lat.abs() < 1e-6 && lon.abs() < 1e-6)
+            && state.gps_fix.map_or(true, |fix| fix >= 2);
+
+        // Channel mapping comes from RCMAP_* (ArduPilot) or RC_MAP_* (PX4), defaulting to AETR
+        let stick = |names: [&str; 2], default: f64| -> Option<f64> {
+            let ch = names
+                .iter()
+                .find_map(|n| params.get(*n).copied())
+                .filter(|c| *c > 0.0)
+                .unwrap_or(default) as usize;
+            let pwm = *state.rc_channels.get(ch.checked_sub(1)?)?;
+            if !(800.0..=2200.0).contains(&pwm) {
+                return None;
+            }
+            // PWM 1000..2000 centered at 1500 → normalized to -100..+100
+            Some(((pwm - 1500.0) / 500.0 * 100.0).clamp(-100.0, 100.0))
+        };
+
+        let mut point = TelemetryPoint {
+            satellites: state.satellites,
+            gps_signal: state.gps_fix,
+
+            pitch: state.pitch,
+            roll: state.roll,
+            yaw: state.yaw,
+
+            battery_percent: state.battery_percent,
+            battery_voltage: state.battery_voltage,
+            battery_current: state.battery_current,
+            battery_temp: state.battery_temp,
+            cell_voltages: state.cell_voltages.clone(),
+
+            flight_mode: state.flight_mode.clone(),
+            rc_signal: state.rc_signal,
+            rc_aileron: stick(["RCMAP_ROLL", "RC_MAP_ROLL"], 1.0),
+            rc_elevator: stick(["RCMAP_PITCH", "RC_MAP_PITCH"], 2.0),
+            rc_throttle: stick(["RCMAP_THROTTLE", "RC_MAP_THROTTLE"], 3.0),
+            rc_rudder: stick(["RCMAP_YAW", "RC_MAP_YAW"], 4.0),
+
+            is_photo: Some(false),
+            is_video: Some(false),
+            ..Default::default()
+        };
+
+        if has_fix {
+            point.latitude = Some(lat);
+            point.longitude = Some(lon);
+
+            // relative_alt is already above home; alt is MSL
+            let relative = i32_at(p, 16) as f64 / 1000.0;
+            if relative.abs() < 10_000.0 {
+                point.altitude = Some(relative);
+                point.height = Some(relative);
+            }
+            let msl = i32_at(p, 12) as f64 / 1000.0;
+            point.altitude_abs = (msl.abs() < 10_000.0).then_some(msl);
+
+            let vx = i16_at(p, 20) as f64 / 100.0;
+            let vy = i16_at(p, 22) as f64 / 100.0;
+            let vz = i16_at(p, 24) as f64 / 100.0;
+            point.velocity_x = Some(vx);
+            point.velocity_y = Some(vy);
+            point.velocity_z = Some(vz);
+            let speed = (vx * vx + vy * vy).sqrt();
+            point.speed = (speed < 100.0).then_some(speed);
+        }
+
+        point
+    }
+
+    /// Assemble flight metadata from heartbeat identity and computed stats
+    #[allow(clippy::too_many_arguments)]
+    fn build_metadata(
+        &self,
+        file_path: &Path,
+        file_hash: &str,
+        drone_model: Option<String>,
+        drone_serial: Option<String>,
+        points: &[TelemetryPoint],
+        stats: &FlightStats,
+        start_time: Option<DateTime<Utc>>,
+    ) -> FlightMetadata {
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let duration_secs = if points.len() >= 2 {
+            Some((points.last().unwrap().timestamp_ms - points.first().unwrap().timestamp_ms) as f64 / 1000.0)
+        } else {
+            None
+        };
+
+        let end_time = match (start_time, duration_secs) {
+            (Some(s), Some(d)) => Some(s + Duration::milliseconds((d * 1000.0) as i64)),
+            _ => None,
+        };
+
+        let (photo_count, video_count) = crate::models::count_media_events(points);
+
+        FlightMetadata {
+            id: 0,
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model,
+            drone_serial,
+            aircraft_name: None,
+            battery_serial: None,
+            cycle_count: None,
+            start_time,
+            end_time,
+            duration_secs,
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count,
+            video_count,
+        }
+    }
+}
+
+/// Map a MAV_TYPE to an ArduPilot vehicle family
+fn vehicle_from_mav_type(mav_type: u8) -> Vehicle {
+    match mav_type {
+        1 | 16 | 19..=25 => Vehicle::Plane,
+        2..=4 | 13 | 14 | 15 | 29 | 35 => Vehicle::Copter,
+        10 | 11 => Vehicle::Rover,
+        _ => Vehicle::Unknown,
+    }
+}
+
+/// Decode HEARTBEAT.custom_mode into a flight mode name
+fn mode_name(autopilot: u8, vehicle: Vehicle, custom_mode: u32) -> String {
+    match autopilot {
+        MAV_AUTOPILOT_ARDUPILOTMEGA => ardupilot_mode_name(vehicle, custom_mode as u8),
+        MAV_AUTOPILOT_PX4 => {
+            let main_mode = (custom_mode >> 16) & 0xFF;
+            let sub_mode = (custom_mode >> 24) & 0xFF;
+            match (main_mode, sub_mode) {
+                (1, _) => "Manual".to_string(),
+                (2, _) => "Altitude".to_string(),
+                (3, _) => "Position".to_string(),
+                (4, 2) => "Takeoff".to_string(),
+                (4, 3) => "Hold".to_string(),
+                (4, 4) => "Mission".to_string(),
+                (4, 5) => "Return".to_string(),
+                (4, 6) => "Land".to_string(),
+                (4, 8) => "Follow Me".to_string(),
+                (4, 9) => "Precision Land".to_string(),
+                (4, _) => "Auto".to_string(),
+                (5, _) => "Acro".to_string(),
+                (6, _) => "Offboard".to_string(),
+                (7, _) => "Stabilized".to_string(),
+                _ => format!("Mode {}", custom_mode),
+            }
+        }
+        _ => format!("Mode {}", custom_mode),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// A tlog record holding a MAVLink v1 frame from the aircraft (sysid 1)
+    fn record(timestamp_us: u64, msg_id: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![STX_V1, payload.len() as u8, 0, 1, 1, msg_id];
+        frame.extend_from_slice(payload);
+        let (crc_extra, _) = message_info(msg_id as u32).unwrap();
+        let crc = frame[1..].iter().chain([crc_extra].iter()).fold(0xFFFF, |crc, &b| crc_accumulate(crc, b));
+        frame.extend_from_slice(&crc.to_le_bytes());
+
+        let mut record = timestamp_us.to_be_bytes().to_vec();
+        record.extend(frame);
+        record
+    }
+
+    fn position(timestamp_us: u64, lat: f64, lon: f64) -> Vec<u8> {
+        let mut payload = vec![0; 28];
+        payload[4..8].copy_from_slice(&((lat * 1e7) as i32).to_le_bytes());
+        payload[8..12].copy_from_slice(&((lon * 1e7) as i32).to_le_bytes());
+        payload[16..20].copy_from_slice(&12_000i32.to_le_bytes());
+        record(timestamp_us, MSG_GLOBAL_POSITION_INT as u8, &payload)
+    }
+
+    fn tlog() -> Vec<u8> {
+        // Quadrotor heartbeat from an ArduPilot autopilot
+        let mut data = record(1_700_000_000_000_000, MSG_HEARTBEAT as u8, &[0, 0, 0, 0, 2, 3, 0, 0, 3]);
+        data.extend(position(1_700_000_000_100_000, 47.1, 8.5));
+        data.extend(position(1_700_000_000_600_000, 47.1001, 8.5001));
+        data
+    }
+
+    fn parse(data: &[u8]) -> Result<ParsedFlight, ParserError> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("flight.tlog");
+        fs::write(&path, data).unwrap();
+        TlogParser::new().parse(&path, "hash")
+    }
+
+    #[test]
+    fn test_sniff_requires_extension_and_frame() {
+        let data = tlog();
+        assert_eq!(TlogFormat.sniff(Path::new("flight.tlog"), &data), 100);
+        assert_eq!(TlogFormat.sniff(Path::new("flight.bin"), &data), 0);
+        assert_eq!(TlogFormat.sniff(Path::new("flight.tlog"), &[0; 16]), 0);
+    }
+
+    #[test]
+    fn test_parse_minimal_log() {
+        let flight = parse(&tlog()).unwrap();
+        assert_eq!(flight.points.len(), 2);
+        assert_eq!(flight.points[1].timestamp_ms, 500);
+        assert!((flight.points[0].latitude.unwrap() - 47.1).abs() < 1e-6);
+        assert_eq!(flight.metadata.start_time.unwrap().timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_corrupt_logs_return_errors() {
+        let data = tlog();
+        // Truncated inside the first position record
+        assert!(matches!(parse(&data[..data.len() - 50]), Err(ParserError::NoTelemetryData)));
+        // Corrupted checksums make the positions unreadable
+        let mut corrupt = data.clone();
+        let len = corrupt.len();
+        corrupt[len - 1] ^= 0xFF;
+        corrupt[len - 45] ^= 0xFF;
+        assert!(matches!(parse(&corrupt), Err(ParserError::NoTelemetryData)));
+        assert!(parse(&[STX_V2; 64]).is_err());
+    }
+
+    #[test]
+    fn test_stray_frame_header_does_not_swallow_packets() {
+        let mut data = tlog();
+        // A fake v1 header for an unknown message whose length covers the
+        // first position record
+        let mut garbage = 1_700_000_000_050_000u64.to_be_bytes().to_vec();
+        garbage.extend_from_slice(&[STX_V1, 40, 0, 1, 1, 250]);
+        let first_position = data.len() - 2 * position(0, 0.0, 0.0).len();
+        data.splice(first_position..first_position, garbage);
+        assert_eq!(parse(&data).unwrap().points.len(), 2);
+
+        assert!(read_packet(&data, first_position).is_none());
+
+        // Unknown but well-framed records are still skipped in one step
+        let mut unknown = 1_700_000_000_050_000u64.to_be_bytes().to_vec();
+        unknown.extend_from_slice(&[STX_V1, 2, 0, 1, 1, 250, 7, 7, 0, 0]);
+        unknown.extend(position(1_700_000_000_100_000, 47.1, 8.5));
+        assert_eq!(read_packet(&unknown, 0).map(|(p, len)| (p.is_none(), len)), Some((true, 18)));
+    }
+}
@@ -0,0 +1,870 @@
+//! Parser module for PX4 ULog (.ulg) flight logs.
+//!
+//! ULog is a self-describing binary container: format definitions describe the
+//! layout of each uORB topic, subscriptions bind a topic to a message id, and
+//! data messages carry the samples. Only the topics needed for the logbook are
+//! decoded; everything else is skipped.
+//!
+//! Points are emitted at GPS rate with the most recent attitude, battery, RC and
+//! vehicle status samples held alongside. Logged string messages are converted
+//! into flight messages.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::formats::{self, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMessage, FlightMetadata, ParsedFlight, TelemetryPoint};
+use crate::parser::{LogParser, ParseOptions, ParserError};
+
+/// ULog file magic: "ULog" followed by 0x01 0x12 0x35
+const ULOG_MAGIC: [u8; 7] = [0x55, 0x4c, 0x6f, 0x67, 0x01, 0x12, 0x35];
+
+/// Size of the file header (magic + version + start timestamp)
+const HEADER_LEN: usize = 16;
+
+/// Size of each message header (u16 size + u8 type)
+const MSG_HEADER_LEN: usize = 3;
+
+/// Scalar field types used in ULog format definitions
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    Bool,
+    Char,
+}
+
+impl ScalarType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "int8_t" => Some(ScalarType::I8),
+            "uint8_t" => Some(ScalarType::U8),
+            "int16_t" => Some(ScalarType::I16),
+            "uint16_t" => Some(ScalarType::U16),
+            "int32_t" => Some(ScalarType::I32),
+            "uint32_t" => Some(ScalarType::U32),
+            "int64_t" => Some(ScalarType::I64),
+            "uint64_t" => Some(ScalarType::U64),
+            "float" => Some(ScalarType::F32),
+            "double" => Some(ScalarType::F64),
+            "bool" => Some(ScalarType::Bool),
+            "char" => Some(ScalarType::Char),
+            _ => None,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 | ScalarType::Bool | ScalarType::Char => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::I64 | ScalarType::U64 | ScalarType::F64 => 8,
+        }
+    }
+
+    /// Read a little-endian value of this type as f64
+    fn read(&self, data: &[u8]) -> Option<f64> {
+        let bytes = data.get(..self.size())?;
+        Some(match self {
+            ScalarType::I8 => bytes[0] as i8 as f64,
+            ScalarType::U8 | ScalarType::Char => bytes[0] as f64,
+            ScalarType::Bool => (bytes[0] != 0) as u8 as f64,
+            ScalarType::I16 => i16::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::U16 => u16::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::I32 => i32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::U32 => u32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::I64 => i64::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::U64 => u64::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::F32 => f32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            ScalarType::F64 => f64::from_le_bytes(bytes.try_into().ok()?),
+        })
+    }
+}
+
+/// Location of a scalar (or scalar array) field inside a data message
+#[derive(Debug, Clone, Copy)]
+struct FieldSlot {
+    offset: usize,
+    ty: ScalarType,
+    len: usize,
+}
+
+/// Flattened layout of a topic: nested structs are expanded as `outer.inner`
+#[derive(Debug, Default)]
+struct Layout {
+    fields: HashMap<String, FieldSlot>,
+    size: usize,
+}
+
+impl Layout {
+    /// Build the layout for a format, resolving nested types recursively
+    fn resolve(name: &str, formats: &HashMap<String, Vec<(String, String)>>, depth: usize) -> Option<Self> {
+        if depth > 8 {
+            return None;
+        }
+        let definition = formats.get(name)?;
+        let mut layout = Layout::default();
+
+        for (type_str, field_name) in definition {
+            let (base_type, count) = match type_str.find('[') {
+                Some(pos) => {
+                    let count: usize = type_str.strip_suffix(']')?.get(pos + 1..)?.parse().ok()?;
+                    (&type_str[..pos], count)
+                }
+                None => (type_str.as_str(), 1),
+            };
+
+            if let Some(ty) = ScalarType::from_name(base_type) {
+                if !field_name.starts_with("_padding") {
+                    layout.fields.insert(
+                        field_name.clone(),
+                        FieldSlot { offset: layout.size, ty, len: count },
+                    );
+                }
+                layout.size = layout.size.checked_add(ty.size().checked_mul(count)?)?;
+            } else {
+                let nested = Layout::resolve(base_type, formats, depth + 1)?;
+                if count == 1 {
+                    for (sub_name, slot) in &nested.fields {
+                        layout.fields.insert(
+                            format!("{}.{}", field_name, sub_name),
+                            FieldSlot { offset: layout.size + slot.offset, ..*slot },
+                        );
+                    }
+                }
+                layout.size = layout.size.checked_add(nested.size.checked_mul(count)?)?;
+            }
+        }
+
+        Some(layout)
+    }
+
+    /// Read a scalar field as f64
+    fn get(&self, data: &[u8], field: &str) -> Option<f64> {
+        self.get_index(data, field, 0)
+    }
+
+    /// Read one element of an array field as f64
+    fn get_index(&self, data: &[u8], field: &str, index: usize) -> Option<f64> {
+        let slot = self.fields.get(field)?;
+        if index >= slot.len {
+            return None;
+        }
+        let start = slot.offset + index * slot.ty.size();
+        slot.ty.read(data.get(start..)?)
+    }
+
+    /// Read a finite f32/f64 field, filtering out the NaN "unknown" marker
+    fn get_finite(&self, data: &[u8], field: &str) -> Option<f64> {
+        self.get(data, field).filter(|v| v.is_finite())
+    }
+
+    fn has(&self, field: &str) -> bool {
+        self.fields.contains_key(field)
+    }
+}
+
+/// uORB topics decoded by this parser
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Topic {
+    GpsPosition,
+    SensorGps,
+    Attitude,
+    BatteryStatus,
+    InputRc,
+    VehicleStatus,
+}
+
+impl Topic {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "vehicle_gps_position" => Some(Topic::GpsPosition),
+            "sensor_gps" => Some(Topic::SensorGps),
+            "vehicle_attitude" => Some(Topic::Attitude),
+            "battery_status" => Some(Topic::BatteryStatus),
+            "input_rc" => Some(Topic::InputRc),
+            "vehicle_status" => Some(Topic::VehicleStatus),
+            _ => None,
+        }
+    }
+}
+
+/// Most recent values of the non-GPS topics, held until the next GPS sample
+#[derive(Debug, Default)]
+struct HeldState {
+    roll: Option<f64>,
+    pitch: Option<f64>,
+    yaw: Option<f64>,
+    battery_percent: Option<i32>,
+    battery_voltage: Option<f64>,
+    battery_current: Option<f64>,
+    battery_temp: Option<f64>,
+    cell_voltages: Option<Vec<f64>>,
+    battery_serial: Option<String>,
+    cycle_count: Option<i32>,
+    rc_signal: Option<i32>,
+    rc_sticks: Option<[f64; 4]>,
+    flight_mode: Option<String>,
+}
+
+/// PX4 ULog format
+pub struct ULogFormat;
+
+impl FlightLogFormat for ULogFormat {
+    fn id(&self) -> &'static str {
+        "px4_ulog"
+    }
+
+    fn name(&self) -> &'static str {
+        "PX4 ULog"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ulg"]
+    }
+
+    /// `.ulg` extension and the ULog magic bytes
+    fn sniff(&self, path: &Path, header: &[u8]) -> u8 {
+        if formats::has_extension(path, "ulg") && header.starts_with(&ULOG_MAGIC) {
+            100
+        } else {
+            0
+        }
+    }
+
+    fn parse<'a>(&self, path: &'a Path, file_hash: &'a str, _options: &'a ParseOptions) -> ParseFuture<'a> {
+        formats::blocking(path, file_hash, |path, file_hash| ULogParser::new().parse(path, file_hash))
+    }
+}
+
+/// PX4 ULog Parser
+#[derive(Default)]
+pub struct ULogParser;
+
+impl ULogParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse a PX4 ULog file
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParsedFlight, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing PX4 ULog file: {:?}", file_path);
+
+        let data = fs::read(file_path)?;
+        if data.len() < HEADER_LEN || data[..7] != ULOG_MAGIC {
+            return Err(ParserError::Parse("Invalid ULog header".to_string()));
+        }
+
+        let mut formats: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let mut subscriptions: HashMap<u16, (Topic, Layout)> = HashMap::new();
+        let mut info: HashMap<String, String> = HashMap::new();
+        let mut int_params: HashMap<String, i32> = HashMap::new();
+
+        let mut state = HeldState::default();
+        // (timestamp_us, point) pairs; timestamps are made relative once the first fix is known
+        let mut samples: Vec<(u64, TelemetryPoint)> = Vec::new();
+        // Raw receiver samples, only used when the blended vehicle_gps_position topic is absent
+        let mut sensor_samples: Vec<(u64, TelemetryPoint)> = Vec::new();
+        // (timestamp_us, level, text)
+        let mut logged: Vec<(u64, u8, String)> = Vec::new();
+        // GPS UTC time paired with the log timestamp it was sampled at
+        let mut utc_reference: Option<(u64, u64)> = None;
+
+        let mut offset = HEADER_LEN;
+        while offset + MSG_HEADER_LEN <= data.len() {
+            let msg_size = u16::from_le_bytes([data[offset], data[offset + 1]]) as usize;
+            let msg_type = data[offset + 2];
+            let start = offset + MSG_HEADER_LEN;
+            let end = start + msg_size;
+            if end > data.len() {
+                // Truncated trailing message (e.g. log cut off at power loss)
+                log::debug!("ULog message at offset {} truncated, stopping", offset);
+                break;
+            }
+            let payload = &data[start..end];
+            offset = end;
+
+            match msg_type {
+                // Format definition: "name:type field;type field;..."
+                b'F' => {
+                    let text = String::from_utf8_lossy(payload);
+                    if let Some((name, body)) = text.split_once(':') {
+                        let fields = body
+                            .split(';')
+                            .filter_map(|f| {
+                                let (ty, field) = f.trim().split_once(' ')?;
+                                Some((ty.to_string(), field.to_string()))
+                            })
+                            .collect();
+                        formats.insert(name.to_string(), fields);
+                    }
+                }
+                // Information message: key is "type name", value follows
+                b'I' => {
+                    if let Some((key_type, key_name, value)) = split_key_value(payload) {
+                        if key_type.starts_with("char[") {
+                            let text = String::from_utf8_lossy(value)
+                                .trim_end_matches('\0')
+                                .trim()
+                                .to_string();
+                            info.insert(key_name.to_string(), text);
+                        }
+                    }
+                }
+                // Parameter: only int32 parameters are needed (RC channel mapping)
+                b'P' => {
+                    if let Some((key_type, key_name, value)) = split_key_value(payload) {
+                        if key_type == "int32_t" {
+                            if let Some(v) = ScalarType::I32.read(value) {
+                                int_params.insert(key_name.to_string(), v as i32);
+                            }
+                        }
+                    }
+                }
+                // Subscription: multi_id u8, msg_id u16, topic name
+                b'A' => {
+                    if payload.len() < 3 {
+                        continue;
+                    }
+                    let multi_id = payload[0];
+                    let msg_id = u16::from_le_bytes([payload[1], payload[2]]);
+                    let name = String::from_utf8_lossy(&payload[3..]).to_string();
+                    // Only the first instance of multi-instance topics is used
+                    if multi_id != 0 {
+                        continue;
+                    }
+                    if let Some(topic) = Topic::from_name(&name) {
+                        match Layout::resolve(&name, &formats, 0) {
+                            Some(layout) => {
+                                subscriptions.insert(msg_id, (topic, layout));
+                            }
+                            None => log::warn!("ULog: could not resolve format for topic '{}'", name),
+                        }
+                    }
+                }
+                // Data message: msg_id u16, then the topic payload
+                b'D' => {
+                    if payload.len() < 2 {
+                        continue;
+                    }
+                    let msg_id = u16::from_le_bytes([payload[0], payload[1]]);
+                    let Some((topic, layout)) = subscriptions.get(&msg_id) else {
+                        continue;
+                    };
+                    let body = &payload[2..];
+                    if body.len() < layout.size {
+                        continue;
+                    }
+                    let timestamp_us = layout.get(body, "timestamp").unwrap_or(0.0) as u64;
+
+                    match topic {
+                        Topic::GpsPosition | Topic::SensorGps => {
+                            if utc_reference.is_none() {
+                                let utc_us = layout.get(body, "time_utc_usec").unwrap_or(0.0) as u64;
+                                if utc_us > 0 {
+                                    utc_reference = Some((utc_us, timestamp_us));
+                                }
+                            }
+                            let point = self.gps_point(layout, body, &state);
+                            if *topic == Topic::GpsPosition {
+                                samples.push((timestamp_us, point));
+                            } else {
+                                sensor_samples.push((timestamp_us, point));
+                            }
+                        }
+                        Topic::Attitude => update_attitude(&mut state, layout, body),
+                        Topic::BatteryStatus => update_battery(&mut state, layout, body),
+                        Topic::InputRc => update_rc(&mut state, layout, body, &int_params),
+                        Topic::VehicleStatus => {
+                            if let Some(nav_state) = layout.get(body, "nav_state") {
+                                state.flight_mode = Some(nav_state_name(nav_state as u8));
+                            }
+                        }
+                    }
+                }
+                // Logged string: level u8, timestamp u64, text
+                b'L' if payload.len() > 9 => {
+                    let level = payload[0];
+                    let ts = u64::from_le_bytes(payload[1..9].try_into().unwrap_or_default());
+                    logged.push((ts, level, String::from_utf8_lossy(&payload[9..]).trim().to_string()));
+                }
+                // Tagged logged string: level u8, tag u16, timestamp u64, text
+                b'C' if payload.len() > 11 => {
+                    let level = payload[0];
+                    let ts = u64::from_le_bytes(payload[3..11].try_into().unwrap_or_default());
+                    logged.push((ts, level, String::from_utf8_lossy(&payload[11..]).trim().to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        log::debug!(
+            "ULog decoded: {} formats, {} subscriptions, {} GPS samples, {} logged messages",
+            formats.len(),
+            subscriptions.len(),
+            samples.len(),
+            logged.len()
+        );
+
+        if samples.is_empty() {
+            samples = sensor_samples;
+        }
+
+        // Flight time starts at the first sample with a valid position
+        let first_fix_us = samples
+            .iter()
+            .find(|(_, p)| p.latitude.is_some() && p.longitude.is_some())
+            .map(|(ts, _)| *ts)
+            .ok_or(ParserError::NoTelemetryData)?;
+
+        // Convert to relative timestamps; drop pre-fix samples and keep timestamps unique
+        let mut points: Vec<TelemetryPoint> = Vec::with_capacity(samples.len());
+        for (ts, mut point) in samples {
+            if ts < first_fix_us {
+                continue;
+            }
+            point.timestamp_ms = ((ts - first_fix_us) / 1000) as i64;
+            if points.last().is_some_and(|p| p.timestamp_ms >= point.timestamp_ms) {
+                continue;
+            }
+            points.push(point);
+        }
+
+        // Heights are relative to the first fix (takeoff point)
+        let home_alt = points.iter().find_map(|p| p.altitude_abs);
+        if let Some(home_alt) = home_alt {
+            for point in &mut points {
+                if let Some(abs) = point.altitude_abs {
+                    point.altitude = Some(abs - home_alt);
+                    point.height = Some(abs - home_alt);
+                }
+            }
+        }
+
+        let messages = self.convert_messages(&logged, first_fix_us);
+
+        let start_time = utc_reference
+            .and_then(|(utc_us, log_us)| utc_at(utc_us, log_us, first_fix_us))
+            .and_then(DateTime::<Utc>::from_timestamp_micros);
+
+        let stats = LogParser::calculate_stats(&points);
+        let metadata = self.build_metadata(file_path, file_hash, &info, &state, &points, &stats, start_time);
+
+        log::info!(
+            "ULog parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}, messages={}",
+            parse_start.elapsed().as_secs_f64(),
+            metadata.duration_secs.unwrap_or(0.0),
+            metadata.total_distance.unwrap_or(0.0),
+            metadata.max_altitude.unwrap_or(0.0),
+            points.len(),
+            messages.len()
+        );
+
+        // Generate smart tags and add "PX4" source tag
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "PX4".to_string());
+        log::info!("Generated smart tags: {:?}", tags);
+
+        Ok(ParsedFlight { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages, side_data: Default::default(), equipment: Default::default() })
+    }
+
+    /// Build a telemetry point from a GPS sample plus the currently held state
+    fn gps_point(&self, layout: &Layout, body: &[u8], state: &HeldState) -> TelemetryPoint {
+        // Newer PX4 releases log degrees/metres as doubles, older ones as scaled integers
+        let (lat, lon, alt) = if layout.has("latitude_deg") {
+            (
+                layout.get(body, "latitude_deg"),
+                layout.get(body, "longitude_deg"),
+                layout.get(body, "altitude_msl_m"),
+            )
+        } else {
+            (
+                layout.get(body, "lat").map(|v| v * 1e-7),
+                layout.get(body, "lon").map(|v| v * 1e-7),
+                layout.get(body, "alt").map(|v| v / 1000.0),
+            )
+        };
+
+        let fix_type = layout.get(body, "fix_type").unwrap_or(0.0) as i32;
+        let has_fix = fix_type >= 2
+            && matches!((lat, lon), (Some(la), Some(lo))
+                if la.is_finite() && lo.is_finite()
+                    && la.abs() <= 90.0 && lo.abs() <= 180.0
+                    && !(la.abs() < 1e-6 && lo.abs() < 1e-6));
+
+        let mut point = TelemetryPoint {
+            satellites: layout.get(body, "satellites_used").map(|v| v as i32),
+            gps_signal: Some(fix_type),
+
+            pitch: state.pitch,
+            roll: state.roll,
+            yaw: state.yaw,
+
+            battery_percent: state.battery_percent,
+            battery_voltage: state.battery_voltage,
+            battery_current: state.battery_current,
+            battery_temp: state.battery_temp,
+            cell_voltages: state.cell_voltages.clone(),
+
+            flight_mode: state.flight_mode.clone(),
+            rc_signal: state.rc_signal,
+            rc_aileron: state.rc_sticks.map(|s| s[0]),
+            rc_elevator: state.rc_sticks.map(|s| s[1]),
+            rc_throttle: state.rc_sticks.map(|s| s[2]),
+            rc_rudder: state.rc_sticks.map(|s| s[3]),
+
+            is_photo: Some(false),
+            is_video: Some(false),
+            ..Default::default()
+        };
+
+        if has_fix {
+            point.latitude = lat;
+            point.longitude = lon;
+            point.altitude_abs = alt.filter(|a| a.is_finite() && a.abs() < 10_000.0);
+
+            let vel_n = layout.get_finite(body, "vel_n_m_s");
+            let vel_e = layout.get_finite(body, "vel_e_m_s");
+            point.velocity_x = vel_n;
+            point.velocity_y = vel_e;
+            point.velocity_z = layout.get_finite(body, "vel_d_m_s");
+            point.speed = layout
+                .get_finite(body, "vel_m_s")
+                .or_else(|| match (vel_n, vel_e) {
+                    (Some(n), Some(e)) => Some((n * n + e * e).sqrt()),
+                    _ => None,
+                })
+                .filter(|s| *s < 100.0);
+        }
+
+        point
+    }
+
+    /// Convert ULog logged strings into flight messages
+    fn convert_messages(&self, logged: &[(u64, u8, String)], first_fix_us: u64) -> Vec<FlightMessage> {
+        let mut messages: Vec<FlightMessage> = logged
+            .iter()
+            .filter(|(_, _, text)| !text.is_empty())
+            .filter_map(|(ts, level, text)| {
+                // Levels follow syslog: '0' emergency .. '7' debug
+                let message_type = match level {
+                    b'0'..=b'3' => "caution",
+                    b'4' => "warn",
+                    b'5' | b'6' => "tip",
+                    _ => return None,
+                };
+                Some(FlightMessage {
+                    timestamp_ms: (ts.saturating_sub(first_fix_us) / 1000) as i64,
+                    message_type: message_type.to_string(),
+                    message: text.clone(),
+                })
+            })
+            .collect();
+
+        messages.sort_by_key(|m| m.timestamp_ms);
+        messages.dedup_by(|a, b| {
+            a.timestamp_ms == b.timestamp_ms && a.message_type == b.message_type && a.message == b.message
+        });
+        messages
+    }
+
+    /// Assemble flight metadata from info messages and computed stats
+    #[allow(clippy::too_many_arguments)]
+    fn build_metadata(
+        &self,
+        file_path: &Path,
+        file_hash: &str,
+        info: &HashMap<String, String>,
+        state: &HeldState,
+        points: &[TelemetryPoint],
+        stats: &crate::models::FlightStats,
+        start_time: Option<DateTime<Utc>>,
+    ) -> FlightMetadata {
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let sys_name = info.get("sys_name").filter(|s| !s.is_empty());
+        let ver_hw = info.get("ver_hw").filter(|s| !s.is_empty());
+        let drone_model = match (sys_name, ver_hw) {
+            (Some(sys), Some(hw)) => Some(format!("{} ({})", sys, hw)),
+            (Some(sys), None) => Some(sys.clone()),
+            (None, Some(hw)) => Some(format!("PX4 ({})", hw)),
+            (None, None) => Some("PX4".to_string()),
+        };
+
+        let drone_serial = info
+            .get("sys_uuid")
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty());
+
+        let duration_secs = if points.len() >= 2 {
+            Some((points.last().unwrap().timestamp_ms - points.first().unwrap().timestamp_ms) as f64 / 1000.0)
+        } else {
+            None
+        };
+
+        let end_time = match (start_time, duration_secs) {
+            (Some(s), Some(d)) => Some(s + chrono::Duration::milliseconds((d * 1000.0) as i64)),
+            _ => None,
+        };
+
+        let (photo_count, video_count) = crate::models::count_media_events(points);
+
+        FlightMetadata {
+            id: 0,
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model,
+            drone_serial,
+            aircraft_name: None,
+            battery_serial: state.battery_serial.clone(),
+            cycle_count: state.cycle_count,
+            start_time,
+            end_time,
+            duration_secs,
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count,
+            video_count,
+        }
+    }
+}
+
+/// Split an info/parameter payload into (key type, key name, value bytes)
+fn split_key_value(payload: &[u8]) -> Option<(&str, &str, &[u8])> {
+    let key_len = *payload.first()? as usize;
+    let key = std::str::from_utf8(payload.get(1..1 + key_len)?).ok()?;
+    let value = payload.get(1 + key_len..)?;
+    let (key_type, key_name) = key.split_once(' ')?;
+    Some((key_type, key_name, value))
+}
+
+/// Update held attitude from a vehicle_attitude quaternion (w, x, y, z)
+fn update_attitude(state: &mut HeldState, layout: &Layout, body: &[u8]) {
+    let q: Vec<f64> = (0..4).filter_map(|i| layout.get_index(body, "q", i)).collect();
+    if q.len() != 4 || q.iter().any(|v| !v.is_finite()) {
+        return;
+    }
+    let (w, x, y, z) = (q[0], q[1], q[2], q[3]);
+
+    let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+    let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+    let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+
+    state.roll = Some(roll.to_degrees());
+    state.pitch = Some(pitch.to_degrees());
+    state.yaw = Some(yaw.to_degrees());
+}
+
+/// Update held battery values from a battery_status sample
+fn update_battery(state: &mut HeldState, layout: &Layout, body: &[u8]) {
+    state.battery_voltage = layout.get_finite(body, "voltage_v").filter(|v| *v > 0.0);
+    state.battery_current = layout.get_finite(body, "current_a").filter(|c| *c >= 0.0);
+    // remaining is a 0..1 fraction, -1 when unknown
+    state.battery_percent = layout
+        .get_finite(body, "remaining")
+        .filter(|r| *r >= 0.0)
+        .map(|r| (r * 100.0).round().clamp(0.0, 100.0) as i32);
+    state.battery_temp = layout.get_finite(body, "temperature");
+
+    let cell_count = layout.get(body, "cell_count").unwrap_or(0.0) as usize;
+    let cells: Vec<f64> = (0..cell_count)
+        .filter_map(|i| layout.get_index(body, "voltage_cell_v", i))
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .collect();
+    state.cell_voltages = if cells.is_empty() { None } else { Some(cells) };
+
+    if let Some(serial) = layout.get(body, "serial_number").filter(|s| *s > 0.0) {
+        state.battery_serial = Some((serial as u32).to_string());
+    }
+    if let Some(cycles) = layout.get(body, "cycle_count").filter(|c| *c > 0.0) {
+        state.cycle_count = Some(cycles as i32);
+    }
+}
+
+/// Update held RC values from an input_rc sample
+fn update_rc(state: &mut HeldState, layout: &Layout, body: &[u8], int_params: &HashMap<String, i32>) {
+    // RSSI is 0..100, or -1/255 when the receiver doesn't report it
+    state.rc_signal = layout
+        .get(body, "rssi")
+        .filter(|r| (0.0..=100.0).contains(r))
+        .map(|r| r as i32);
+
+    if layout.get(body, "rc_lost").unwrap_or(0.0) != 0.0 {
+        state.rc_sticks = None;
+        return;
+    }
+
+    // Channel mapping comes from RC_MAP_* parameters (1-based), defaulting to AETR
+    let channel = |param: &str, default: i32| -> Option<f64> {
+        let ch = int_params.get(param).copied().filter(|c| *c > 0).unwrap_or(default);
+        let pwm = layout.get_index(body, "values", (ch - 1) as usize)?;
+        if !(800.0..=2200.0).contains(&pwm) {
+            return None;
+        }
+        // PWM 1000..2000 centered at 1500 → normalized to -100..+100
+        Some(((pwm - 1500.0) / 500.0 * 100.0).clamp(-100.0, 100.0))
+    };
+
+    state.rc_sticks = match (
+        channel("RC_MAP_ROLL", 1),
+        channel("RC_MAP_PITCH", 2),
+        channel("RC_MAP_THROTTLE", 3),
+        channel("RC_MAP_YAW", 4),
+    ) {
+        (Some(a), Some(e), Some(t), Some(r)) => Some([a, e, t, r]),
+        _ => None,
+    };
+}
+
+/// UTC microseconds at log time `at_us`, given a GPS sample that read `utc_us`
+/// at log time `log_us`. None when corrupt timestamps overflow an i64.
+fn utc_at(utc_us: u64, log_us: u64, at_us: u64) -> Option<i64> {
+    let offset = i64::try_from(at_us).ok()?.checked_sub(i64::try_from(log_us).ok()?)?;
+    i64::try_from(utc_us).ok()?.checked_add(offset)
+}
+
+/// Map a PX4 vehicle_status.nav_state value to a flight mode name
+fn nav_state_name(nav_state: u8) -> String {
+    match nav_state {
+        0 => "Manual".to_string(),
+        1 => "Altitude".to_string(),
+        2 => "Position".to_string(),
+        3 => "Mission".to_string(),
+        4 => "Hold".to_string(),
+        5 => "Return".to_string(),
+        10 => "Acro".to_string(),
+        12 => "Descend".to_string(),
+        13 => "Termination".to_string(),
+        14 => "Offboard".to_string(),
+        15 => "Stabilized".to_string(),
+        17 => "Takeoff".to_string(),
+        18 => "Land".to_string(),
+        19 => "Follow Me".to_string(),
+        20 => "Precision Land".to_string(),
+        21 => "Orbit".to_string(),
+        22 => "VTOL Takeoff".to_string(),
+        _ => format!("Mode {}", nav_state),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn message(kind: u8, payload: &[u8]) -> Vec<u8> {
+        let mut msg = (payload.len() as u16).to_le_bytes().to_vec();
+        msg.push(kind);
+        msg.extend_from_slice(payload);
+        msg
+    }
+
+    fn gps_sample(timestamp_us: u64, lat: f64, lon: f64) -> Vec<u8> {
+        let mut payload = vec![1, 0];
+        payload.extend_from_slice(&timestamp_us.to_le_bytes());
+        payload.extend_from_slice(&((lat * 1e7) as i32).to_le_bytes());
+        payload.extend_from_slice(&((lon * 1e7) as i32).to_le_bytes());
+        payload.extend_from_slice(&450_000i32.to_le_bytes());
+        payload.push(3);
+        message(b'D', &payload)
+    }
+
+    /// A ULog with one GPS topic, using `lat_type` for its latitude field
+    fn ulog(lat_type: &str) -> Vec<u8> {
+        let mut data = ULOG_MAGIC.to_vec();
+        data.push(1);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        let format = format!(
+            "vehicle_gps_position:uint64_t timestamp;{} lat;int32_t lon;int32_t alt;uint8_t fix_type;",
+            lat_type
+        );
+        data.extend(message(b'F', format.as_bytes()));
+        data.extend(message(b'A', b"\x00\x01\x00vehicle_gps_position"));
+        data.extend(gps_sample(1_000_000, 47.1, 8.5));
+        data.extend(gps_sample(2_000_000, 47.1001, 8.5001));
+        data
+    }
+
+    fn parse(data: &[u8]) -> Result<ParsedFlight, ParserError> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("flight.ulg");
+        fs::write(&path, data).unwrap();
+        ULogParser::new().parse(&path, "hash")
+    }
+
+    #[test]
+    fn test_sniff_requires_extension_and_magic() {
+        let data = ulog("int32_t");
+        assert_eq!(ULogFormat.sniff(Path::new("log.ulg"), &data), 100);
+        assert_eq!(ULogFormat.sniff(Path::new("log.txt"), &data), 0);
+        assert_eq!(ULogFormat.sniff(Path::new("log.ulg"), b"ULog\x00\x00\x00"), 0);
+    }
+
+    #[test]
+    fn test_parse_minimal_log() {
+        let flight = parse(&ulog("int32_t")).unwrap();
+        assert_eq!(flight.points.len(), 2);
+        assert_eq!(flight.points[1].timestamp_ms, 1000);
+        assert!((flight.points[0].latitude.unwrap() - 47.1).abs() < 1e-6);
+        assert_eq!(flight.points[0].altitude_abs, Some(450.0));
+        assert_eq!(flight.tags[0], "PX4");
+    }
+
+    #[test]
+    fn test_corrupt_logs_return_errors() {
+        assert!(parse(b"not a ulog file").is_err());
+        // Cut off inside the first data message
+        let data = ulog("int32_t");
+        assert!(matches!(parse(&data[..data.len() - 40]), Err(ParserError::NoTelemetryData)));
+        // Malformed array types leave the topic unresolved instead of panicking
+        for lat_type in ["int32_t[", "int32_t[]", "int32_t[x]", "int32_t[99999999999999999999]"] {
+            assert!(matches!(parse(&ulog(lat_type)), Err(ParserError::NoTelemetryData)));
+        }
+    }
+
+    #[test]
+    fn test_utc_at_rejects_overflow() {
+        assert_eq!(utc_at(1_700_000_000_000_000, 5_000_000, 2_000_000), Some(1_699_999_997_000_000));
+        assert_eq!(utc_at(u64::MAX, 0, 0), None);
+        assert_eq!(utc_at(i64::MAX as u64, 0, 1), None);
+        assert_eq!(utc_at(0, i64::MAX as u64, 0), Some(-i64::MAX));
+        assert_eq!(utc_at(0, u64::MAX, 0), None);
+    }
+
+    #[tokio::test]
+    async fn test_format_parse_runs_guarded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("flight.ulg");
+        fs::write(&path, ulog("int32_t")).unwrap();
+        let flight = ULogFormat.parse(&path, "hash", &ParseOptions::default()).await.unwrap();
+        assert_eq!(flight.points.len(), 2);
+    }
+}
@@ -26,7 +26,7 @@ interface TelemetryFieldDef {
   label: string;
   color: string;
   /** The key in TelemetryData to get raw values */
-  dataKey: keyof TelemetryData | 'distanceToHome';
+  dataKey: keyof TelemetryData | 'distanceToHome' | 'batteryPower';
   /** Unit string (may be overridden by unitSystem) */
   unit: string;
   /** Unit string for imperial system */
@@ -36,7 +36,7 @@ interface TelemetryFieldDef {
   /** Conversion factor for imperial (applied to raw m/s values) */
   imperialFactor?: number;
   /** Group this field belongs to for organization */
-  group: 'altitude' | 'speed' | 'battery' | 'attitude' | 'rc' | 'gps' | 'velocity';
+  group: 'altitude' | 'speed' | 'battery' | 'attitude' | 'gimbal' | 'rc' | 'gps' | 'velocity';
 }
 
 /** All available telemetry fields that can be plotted */
@@ -45,6 +45,7 @@ const TELEMETRY_FIELDS: TelemetryFieldDef[] = [
   { id: 'height', label: 'telemetry.height', color: '#00A0DC', dataKey: 'height', unit: 'm', unitImperial: 'ft', metricFactor: 1, imperialFactor: 3.28084, group: 'altitude' },
   { id: 'vpsHeight', label: 'telemetry.vpsHeight', color: '#f97316', dataKey: 'vpsHeight', unit: 'm', unitImperial: 'ft', metricFactor: 1, imperialFactor: 3.28084, group: 'altitude' },
   { id: 'altitude', label: 'telemetry.altitudeGps', color: '#22d3ee', dataKey: 'altitude', unit: 'm', unitImperial: 'ft', metricFactor: 1, imperialFactor: 3.28084, group: 'altitude' },
+  { id: 'altitudeAbs', label: 'telemetry.altitudeMsl', color: '#0284c7', dataKey: 'altitudeAbs', unit: 'm', unitImperial: 'ft', metricFactor: 1, imperialFactor: 3.28084, group: 'altitude' },
 
   // Speed group
   { id: 'speed', label: 'telemetry.speed', color: '#00D4AA', dataKey: 'speed', unit: 'km/h', unitImperial: 'mph', metricFactor: 3.6, imperialFactor: 2.236936, group: 'speed' },
@@ -56,12 +57,19 @@ const TELEMETRY_FIELDS: TelemetryFieldDef[] = [
   { id: 'battery', label: 'telemetry.batteryPercent', color: '#f59e0b', dataKey: 'battery', unit: '%', group: 'battery' },
   { id: 'batteryVoltage', label: 'telemetry.voltage', color: '#3b82f6', dataKey: 'batteryVoltage', unit: 'V', group: 'battery' },
   { id: 'batteryTemp', label: 'telemetry.temperature', color: '#e11d48', dataKey: 'batteryTemp', unit: '°C', group: 'battery' },
+  { id: 'batteryCurrent', label: 'telemetry.current', color: '#65a30d', dataKey: 'batteryCurrent', unit: 'A', group: 'battery' },
+  { id: 'batteryPower', label: 'telemetry.power', color: '#dc2626', dataKey: 'batteryPower', unit: 'W', group: 'battery' },
 
   // Attitude group
   { id: 'pitch', label: 'telemetry.pitch', color: '#8b5cf6', dataKey: 'pitch', unit: '°', group: 'attitude' },
   { id: 'roll', label: 'telemetry.roll', color: '#ec4899', dataKey: 'roll', unit: '°', group: 'attitude' },
   { id: 'yaw', label: 'telemetry.yaw', color: '#14b8a6', dataKey: 'yaw', unit: '°', group: 'attitude' },
 
+  // Gimbal group
+  { id: 'gimbalPitch', label: 'telemetry.gimbalPitch', color: '#c026d3', dataKey: 'gimbalPitch', unit: '°', group: 'gimbal' },
+  { id: 'gimbalRoll', label: 'telemetry.gimbalRoll', color: '#db2777', dataKey: 'gimbalRoll', unit: '°', group: 'gimbal' },
+  { id: 'gimbalYaw', label: 'telemetry.gimbalYaw', color: '#0d9488', dataKey: 'gimbalYaw', unit: '°', group: 'gimbal' },
+
   // RC group
   { id: 'rcSignal', label: 'telemetry.rcSignal', color: '#22c55e', dataKey: 'rcSignal', unit: '%', group: 'rc' },
   { id: 'rcUplink', label: 'telemetry.rcUplink', color: '#84cc16', dataKey: 'rcUplink', unit: '%', group: 'rc' },
@@ -101,6 +109,15 @@ function getFieldData(
     return distances.map(v => v === null ? null : v * factor);
   }
 
+  // Special handling for batteryPower (computed field: voltage × current)
+  if (field.dataKey === 'batteryPower') {
+    const current = data.batteryCurrent ?? [];
+    return data.batteryVoltage.map((v, i) => {
+      const a = current[i];
+      return v === null || a === null || a === undefined ? null : v * a;
+    });
+  }
+
   // Special handling for height - use altitude as fallback
   if (fieldId === 'height') {
     const hasHeight = data.height.some((val) => val !== null);
@@ -140,6 +157,8 @@ function getUnitCategoryLabel(unit: string, t: TFn): string {
     '%': 'Percent (%)',
     'V': t('telemetry.cellVoltageV'),
     '°C': t('telemetry.tempC'),
+    'A': 'Current (A)',
+    'W': 'Power (W)',
   };
   return unitCategories[unit] || unit;
 }
@@ -8,19 +8,20 @@
  * - Personal API keys bypass cooldown entirely
  * - Progressive UI updates show import progress without expensive refreshes
  * - Sync folder support for automatic imports from a configured directory
- * - Blacklist support for deleted files (skipped during sync)
+ * - Blacklist support for deleted files (stored per profile in the database)
  */
 
 import { useCallback, useState, useEffect, useRef } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useDropzone } from 'react-dropzone';
-import { isWebMode, pickFiles, computeFileHash, getFlights, getSyncConfig, getSyncFiles, syncSingleFile } from '@/lib/api';
+import { isWebMode, pickFiles, computeFileHash, getFlights, getSyncConfig, startSyncJob, subscribeImportJob, cancelImportJob, importArchive, importLogs, onImportProgress, isArchiveFile, ARCHIVE_EXTENSIONS, getLogExtensions, hasExtension, addToBlacklist, removeFromBlacklist, getBlacklist, isBlacklistedResult } from '@/lib/api';
 import { useFlightStore } from '@/stores/flightStore';
 import { ManualEntryModal } from './ManualEntryModal';
+import type { BatchImportProgress, ImportResult } from '@/types';
 
-// Storage keys for sync folder, blacklist, and autoscan
+// Storage keys for sync folder, legacy blacklist, and autoscan
 const SYNC_FOLDER_KEY = 'syncFolderPath';
-const BLACKLIST_KEY = 'importBlacklist';
+const LEGACY_BLACKLIST_KEY = 'importBlacklist';
 const AUTOSCAN_KEY = 'autoscanEnabled';
 
 // Get autoscan enabled setting from localStorage
@@ -52,45 +53,22 @@ export function setSyncFolderPath(path: string | null): void {
   }
 }
 
-// Get blacklisted file hashes (used when deleting flights)
-export function getBlacklist(): Set<string> {
-  if (typeof localStorage === 'undefined') return new Set();
+// Move a blacklist left in localStorage by older versions into the database
+async function migrateLocalBlacklist(): Promise<void> {
+  if (typeof localStorage === 'undefined') return;
+  const stored = localStorage.getItem(LEGACY_BLACKLIST_KEY);
+  if (!stored) return;
   try {
-    const stored = localStorage.getItem(BLACKLIST_KEY);
-    return stored ? new Set(JSON.parse(stored)) : new Set();
-  } catch {
-    return new Set();
+    const hashes: string[] = JSON.parse(stored);
+    for (const hash of hashes) {
+      if (hash) await addToBlacklist(hash);
+    }
+    localStorage.removeItem(LEGACY_BLACKLIST_KEY);
+  } catch (err) {
+    console.warn('Failed to migrate import blacklist:', err);
   }
 }
 
-// Add hash to blacklist (called when deleting a flight)
-export function addToBlacklist(hash: string): void {
-  if (typeof localStorage === 'undefined' || !hash) return;
-  const blacklist = getBlacklist();
-  blacklist.add(hash);
-  localStorage.setItem(BLACKLIST_KEY, JSON.stringify([...blacklist]));
-}
-
-// Remove hash from blacklist (when manually importing)
-export function removeFromBlacklist(hash: string): void {
-  if (typeof localStorage === 'undefined' || !hash) return;
-  const blacklist = getBlacklist();
-  blacklist.delete(hash);
-  localStorage.setItem(BLACKLIST_KEY, JSON.stringify([...blacklist]));
-}
-
-// Clear entire blacklist (e.g., when user wants to reset)
-export function clearBlacklist(): void {
-  if (typeof localStorage === 'undefined') return;
-  localStorage.removeItem(BLACKLIST_KEY);
-}
-
-// Check if a hash is blacklisted
-export function isBlacklisted(hash: string | null | undefined): boolean {
-  if (!hash) return false;
-  return getBlacklist().has(hash);
-}
-
 export function FlightImporter() {
   const { t } = useTranslation();
   const { importLog, isImporting, apiKeyType, loadApiKeyType, isBatchProcessing, setIsBatchProcessing } = useFlightStore();
@@ -105,6 +83,8 @@ export function FlightImporter() {
   const [backgroundSyncResult, setBackgroundSyncResult] = useState<string | null>(null);
   const [autoscanEnabled, setAutoscanEnabledState] = useState(() => getAutoscanEnabled());
   const [isManualEntryOpen, setIsManualEntryOpen] = useState(false);
+  const [syncJobId, setSyncJobId] = useState<string | null>(null);
+  const [logExtensions, setLogExtensions] = useState<string[]>([]);
   const backgroundSyncTriggeredRef = useRef(false);
   const backgroundSyncAbortRef = useRef(false);
 
@@ -119,6 +99,18 @@ export function FlightImporter() {
     return () => window.removeEventListener('syncFolderChanged', handleSyncFolderChanged);
   }, []);
 
+  // Carry over any blacklist still kept in localStorage by older versions
+  useEffect(() => {
+    migrateLocalBlacklist();
+  }, []);
+
+  // Load the extensions of the log formats the backend can parse
+  useEffect(() => {
+    getLogExtensions()
+      .then(setLogExtensions)
+      .catch((err) => console.warn('Failed to load supported formats:', err));
+  }, []);
+
   // Load API key type on mount to determine cooldown behavior
   useEffect(() => {
     loadApiKeyType();
@@ -139,8 +131,8 @@ export function FlightImporter() {
    * - Personal API keys: no cooldown, optimized batch import
    * - Default API key: cooldown between files, sequential import
    * - Flight list refreshes periodically so user sees progress
-   * - isManualImport: if true, removes files from blacklist (allows re-importing deleted files)
-   *                   if false (sync), checks blacklist BEFORE importing and skips blacklisted files
+   * - isManualImport: if true, removes blacklisted files from the blacklist and imports them
+   *                   if false (sync), blacklisted files are skipped by the backend
    */
   const processBatch = async (items: (string | File)[], isManualImport = true) => {
     if (items.length === 0) return;
@@ -162,26 +154,74 @@ export function FlightImporter() {
       loadFlights().then(() => loadAllTags());
     };
 
-    // Get blacklist for sync mode (check before import to avoid wasted work)
-    const blacklist = !isManualImport ? getBlacklist() : new Set<string>();
-    
-    // Helper to check if file is blacklisted (for sync mode only)
-    // Returns hash if blacklisted, null otherwise
-    const checkBlacklist = async (item: string | File): Promise<string | null> => {
-      if (isManualImport || blacklist.size === 0) return null;
-      
-      // Only works for file paths in Tauri mode
-      if (typeof item !== 'string') return null;
-      
+    // Import one picked item. Archives expand to one result per contained log.
+    const importOnce = async (item: string | File): Promise<ImportResult[]> => {
+      const itemName = typeof item === 'string' ? item : item.name;
+      if (!isArchiveFile(itemName)) {
+        return [await importLog(item, true)];
+      }
       try {
-        const hash = await computeFileHash(item);
-        return blacklist.has(hash) ? hash : null;
-      } catch {
-        // If hash computation fails, proceed with import
-        return null;
+        return await importArchive(item);
+      } catch (err) {
+        return [{
+          success: false,
+          flightId: null,
+          message: `Import failed: ${err}`,
+          pointCount: 0,
+          fileHash: null,
+        }];
       }
     };
-    
+
+    // The backend skips blacklisted files on every import path. A manual import
+    // is an explicit request, so un-blacklist those files and import them again.
+    const importItem = async (item: string | File): Promise<ImportResult[]> => {
+      const results = await importOnce(item);
+      if (!isManualImport) return results;
+      const blacklistedHashes = results
+        .filter((result) => isBlacklistedResult(result) && result.fileHash)
+        .map((result) => result.fileHash as string);
+      if (blacklistedHashes.length === 0) return results;
+      for (const hash of blacklistedHashes) {
+        await removeFromBlacklist(hash);
+      }
+      const retried = await importOnce(item);
+      return [
+        ...results.filter((result) => !isBlacklistedResult(result)),
+        ...retried.filter((result) => result.fileHash && blacklistedHashes.includes(result.fileHash)),
+      ];
+    };
+
+    // Same as importItem for many local logs at once, through the parallel
+    // batch importer (desktop only)
+    const importPaths = async (paths: string[]): Promise<ImportResult[]> => {
+      let results: ImportResult[];
+      try {
+        results = await importLogs(paths);
+      } catch (err) {
+        return paths.map(() => ({
+          success: false,
+          flightId: null,
+          message: `Import failed: ${err}`,
+          pointCount: 0,
+          fileHash: null,
+        }));
+      }
+      if (!isManualImport) return results;
+      const blacklistedIndexes = [...results.keys()].filter(
+        (index) => isBlacklistedResult(results[index]) && results[index].fileHash,
+      );
+      if (blacklistedIndexes.length === 0) return results;
+      for (const index of blacklistedIndexes) {
+        await removeFromBlacklist(results[index].fileHash as string);
+      }
+      const retried = await importLogs(blacklistedIndexes.map((index) => paths[index]));
+      blacklistedIndexes.forEach((index, retryIndex) => {
+        results[index] = retried[retryIndex];
+      });
+      return results;
+    };
+
     if (hasPersonalKey) {
       // Optimized path: batch import without cooldown
       // Refresh flight list every 2 files to show progress
@@ -192,9 +232,49 @@ export function FlightImporter() {
       let blacklisted = 0;
       const REFRESH_INTERVAL = 2;
 
-      for (let index = 0; index < items.length; index += 1) {
-        const item = items[index];
-        setBatchIndex(index + 1);
+      const tally = (results: ImportResult[]) => {
+        for (const result of results) {
+          if (!result.success) {
+            if (isBlacklistedResult(result)) {
+              blacklisted += 1;
+            } else if (result.message.toLowerCase().includes('already been imported')) {
+              skipped += 1;
+            } else if (result.message.toLowerCase().includes('duplicate flight')) {
+              duplicates += 1;
+            } else {
+              // Parse errors, corrupt files, incompatible formats, timeouts, etc.
+              invalidFiles += 1;
+            }
+          } else {
+            processed += 1;
+            // Refresh flight list periodically so user sees progress
+            if (processed % REFRESH_INTERVAL === 0) {
+              refreshFlightListBackground();
+            }
+          }
+        }
+      };
+
+      // Local logs are parsed in parallel in one call; archives and web
+      // uploads are imported one by one
+      const localPaths = items.filter((item): item is string => typeof item === 'string' && !isArchiveFile(item));
+      const otherItems = items.filter((item) => typeof item !== 'string' || !localPaths.includes(item));
+
+      if (localPaths.length > 0) {
+        setCurrentFileName(null);
+        const stopProgress = await onImportProgress((progress) => {
+          setBatchIndex(Math.min(progress.completed + 1, localPaths.length));
+        });
+        try {
+          tally(await importPaths(localPaths));
+        } finally {
+          stopProgress();
+        }
+      }
+
+      for (let index = 0; index < otherItems.length; index += 1) {
+        const item = otherItems[index];
+        setBatchIndex(localPaths.length + index + 1);
         const name =
           typeof item === 'string'
             ? getShortFileName(item)
@@ -203,35 +283,8 @@ export function FlightImporter() {
             : `${item.name.slice(0, 50)}…`;
         setCurrentFileName(name);
 
-        // For sync mode: check blacklist BEFORE importing (much faster than import+delete)
-        const blacklistedHash = await checkBlacklist(item);
-        if (blacklistedHash) {
-          blacklisted += 1;
-          continue;
-        }
-
         // Import without refreshing flight list (skipRefresh = true)
-        const result = await importLog(item, true);
-        if (!result.success) {
-          if (result.message.toLowerCase().includes('already been imported')) {
-            skipped += 1;
-          } else if (result.message.toLowerCase().includes('duplicate flight')) {
-            duplicates += 1;
-          } else {
-            // Parse errors, corrupt files, incompatible formats, timeouts, etc.
-            invalidFiles += 1;
-          }
-        } else {
-          processed += 1;
-          // For manual import, remove from blacklist (allows re-importing)
-          if (isManualImport && result.fileHash) {
-            removeFromBlacklist(result.fileHash);
-          }
-          // Refresh flight list periodically so user sees progress
-          if (processed % REFRESH_INTERVAL === 0) {
-            refreshFlightListBackground();
-          }
-        }
+        tally(await importItem(item));
       }
 
       // Final refresh at the end
@@ -276,38 +329,36 @@ export function FlightImporter() {
             : `${item.name.slice(0, 50)}…`;
         setCurrentFileName(name);
         
-        // For sync mode: check blacklist BEFORE importing (much faster than import+delete)
-        const blacklistedHash = await checkBlacklist(item);
-        if (blacklistedHash) {
-          blacklisted += 1;
-          continue;
-        }
-        
         // Use skipRefresh=true to defer refresh until batch completes
-        const result = await importLog(item, true);
-        if (!result.success) {
-          if (result.message.toLowerCase().includes('already been imported')) {
-            skipped += 1;
-          } else if (result.message.toLowerCase().includes('duplicate flight')) {
-            duplicates += 1;
-          } else {
-            // Parse errors, corrupt files, incompatible formats, timeouts, etc.
-            // Only show alert for manual imports, silently skip for sync
-            invalidFiles += 1;
-            if (isManualImport) {
-              console.warn(`Failed to import: ${result.message}`);
+        const results = await importItem(item);
+        let imported = false;
+        for (const result of results) {
+          if (!result.success) {
+            if (isBlacklistedResult(result)) {
+              blacklisted += 1;
+            } else if (result.message.toLowerCase().includes('already been imported')) {
+              skipped += 1;
+            } else if (result.message.toLowerCase().includes('duplicate flight')) {
+              duplicates += 1;
+            } else {
+              // Parse errors, corrupt files, incompatible formats, timeouts, etc.
+              // Only show alert for manual imports, silently skip for sync
+              invalidFiles += 1;
+              if (isManualImport) {
+                console.warn(`Failed to import: ${result.message}`);
+              }
             }
+          } else {
+            processed += 1;
+            imported = true;
           }
-        } else {
-          processed += 1;
-          // For manual import, remove from blacklist (allows re-importing)
-          if (isManualImport && result.fileHash) {
-            removeFromBlacklist(result.fileHash);
-          }
+        }
+
+        if (imported) {
           // Refresh flight list in background while cooldown runs
           // This way user sees new flights appear during the wait
           refreshFlightListBackground();
-          
+
           // Only apply cooldown between successful imports (not on last)
           if (!isLast) {
             await runCooldown(5);
@@ -343,17 +394,20 @@ export function FlightImporter() {
   const handleBrowse = async () => {
     if (isWebMode()) {
       // Web mode: use HTML file input
-      const files = await pickFiles('.txt,.dat,.log,.csv', true);
+      const extensions = [...(await getLogExtensions()), ...ARCHIVE_EXTENSIONS];
+      const files = await pickFiles(extensions.map((ext) => `.${ext}`).join(','), true);
       await processBatch(files);
     } else {
       // Tauri mode: use native dialog
       const { open } = await import('@tauri-apps/plugin-dialog');
+      // The dialog matches the last extension only, so .tar.gz becomes gz
+      const archiveExtensions = ARCHIVE_EXTENSIONS.map((ext) => ext.split('.').pop() as string);
       const selected = await open({
         multiple: true,
         filters: [
           {
             name: 'Drone Log Files',
-            extensions: ['txt', 'dat', 'log', 'csv'],
+            extensions: [...(await getLogExtensions()), ...archiveExtensions],
           },
         ],
       });
@@ -382,8 +436,7 @@ export function FlightImporter() {
   const { getRootProps, getInputProps, isDragActive: webDragActive } = useDropzone({
     onDrop,
     accept: {
-      'text/plain': ['.txt', '.dat', '.log'],
-      'text/csv': ['.csv'],
+      'application/octet-stream': [...logExtensions, ...ARCHIVE_EXTENSIONS].map((ext) => `.${ext}`),
     },
     multiple: true,
     noClick: true,
@@ -395,6 +448,72 @@ export function FlightImporter() {
   const processBatchRef = useRef(processBatch);
   processBatchRef.current = processBatch;
 
+  /**
+   * Sync the server's sync folder as one import job (web only) and follow its
+   * progress events. Returns the completion message, or null when there was
+   * nothing new to import.
+   */
+  const runWebSyncJob = async (): Promise<string | null> => {
+    const { jobId, total } = await startSyncJob();
+    setIsSyncing(false); // The scan is done once the job has started
+    if (total === 0) return null;
+
+    setSyncJobId(jobId);
+    setIsBatchProcessing(true);
+    setBatchTotal(total);
+    setBatchIndex(0);
+    let refreshedAt = 0;
+    let progress: BatchImportProgress;
+    try {
+      progress = await new Promise<BatchImportProgress>((resolve, reject) => {
+        let finished = false;
+        subscribeImportJob(
+          jobId,
+          (event) => {
+            if (event.kind === 'started') {
+              setBatchIndex(Math.min(event.progress.completed + 1, total));
+              if (event.fileName) {
+                setCurrentFileName(event.fileName.length > 50 ? `${event.fileName.slice(0, 50)}…` : event.fileName);
+              }
+            } else if (event.kind === 'imported' && event.progress.imported - refreshedAt >= 2) {
+              // Refresh flight list every 2 files to show progress
+              refreshedAt = event.progress.imported;
+              const { loadFlights, loadAllTags } = useFlightStore.getState();
+              loadFlights().then(() => loadAllTags());
+            } else if (event.kind === 'done') {
+              finished = true;
+              resolve(event.progress);
+            }
+          },
+          () => {
+            if (!finished) reject(new Error('Lost connection to the sync job'));
+          },
+        ).catch(reject);
+      });
+    } finally {
+      setSyncJobId(null);
+      setIsBatchProcessing(false);
+      setCurrentFileName(null);
+      setBatchTotal(0);
+      setBatchIndex(0);
+    }
+
+    // Final refresh
+    if (progress.imported > 0) {
+      const { loadFlights, loadAllTags } = useFlightStore.getState();
+      await loadFlights();
+      loadAllTags();
+    }
+
+    const errors = progress.failed + progress.queued;
+    const parts: string[] = [];
+    if (progress.imported > 0) parts.push(`${progress.imported} imported`);
+    if (progress.skipped > 0) parts.push(`${progress.skipped} skipped`);
+    if (errors > 0) parts.push(`${errors} errors`);
+    if (progress.cancelled > 0) parts.push(`${progress.cancelled} cancelled`);
+    return parts.length > 0 ? t('importer.syncComplete', { parts: parts.join(', ') }) : t('importer.noFilesToSync');
+  };
+
   // Cancel background sync when user initiates manual import/sync
   const cancelBackgroundSync = () => {
     if (isBackgroundSyncing) {
@@ -412,16 +531,15 @@ export function FlightImporter() {
     (async () => {
       try {
         const { getCurrentWebview } = await import('@tauri-apps/api/webview');
-        unlisten = await getCurrentWebview().onDragDropEvent((event) => {
+        unlisten = await getCurrentWebview().onDragDropEvent(async (event) => {
           if (event.payload.type === 'over') {
             setTauriDragActive(true);
           } else if (event.payload.type === 'drop') {
             setTauriDragActive(false);
             const paths = event.payload.paths;
             // Filter to supported extensions
-            const supported = paths.filter((p: string) =>
-              /\.(txt|dat|log|csv)$/i.test(p)
-            );
+            const extensions = [...(await getLogExtensions()), ...ARCHIVE_EXTENSIONS];
+            const supported = paths.filter((p: string) => hasExtension(p, extensions));
             if (supported.length > 0) {
               // Cancel background sync - user action takes priority
               cancelBackgroundSync();
@@ -477,70 +595,10 @@ export function FlightImporter() {
             return;
           }
           
-          // Get list of files to sync
-          const filesResponse = await getSyncFiles();
-          if (filesResponse.files.length === 0) {
-            setIsBackgroundSyncing(false);
-            return;
-          }
-          
-          // Process files one by one with progress tracking
+          // Import new files as one server-side job
           setIsBackgroundSyncing(false); // Switch to batch processing mode
-          setIsBatchProcessing(true);
-          setBatchTotal(filesResponse.files.length);
-          setBatchIndex(0);
-          
-          let processed = 0;
-          let skipped = 0;
-          let errors = 0;
-          
-          for (let i = 0; i < filesResponse.files.length; i++) {
-            if (backgroundSyncAbortRef.current) break;
-            
-            const filename = filesResponse.files[i];
-            setBatchIndex(i + 1);
-            setCurrentFileName(filename.length > 50 ? `${filename.slice(0, 50)}…` : filename);
-            
-            try {
-              const result = await syncSingleFile(filename);
-              if (result.success) {
-                processed++;
-                // Refresh flight list every 2 files to show progress
-                if (processed % 2 === 0) {
-                  const { loadFlights, loadAllTags } = useFlightStore.getState();
-                  loadFlights().then(() => loadAllTags());
-                }
-              } else if (result.message.toLowerCase().includes('already') || result.message.toLowerCase().includes('duplicate')) {
-                skipped++;
-              } else {
-                errors++;
-              }
-            } catch (e) {
-              console.error(`Failed to sync ${filename}:`, e);
-              errors++;
-            }
-          }
-          
-          setIsBatchProcessing(false);
-          setCurrentFileName(null);
-          setBatchTotal(0);
-          setBatchIndex(0);
-          
-          // Final refresh
-          if (processed > 0) {
-            const { loadFlights, loadAllTags } = useFlightStore.getState();
-            await loadFlights();
-            loadAllTags();
-          }
-          
-          // Show result message
-          if (processed > 0 || skipped > 0 || errors > 0) {
-            const parts: string[] = [];
-            if (processed > 0) parts.push(`${processed} imported`);
-            if (skipped > 0) parts.push(`${skipped} skipped`);
-            if (errors > 0) parts.push(`${errors} errors`);
-            setBatchMessage(t('importer.syncComplete', { parts: parts.join(', ') }));
-          }
+          const message = await runWebSyncJob();
+          if (message) setBatchMessage(message);
         } catch (e) {
           console.error('Background sync check failed:', e);
           setIsBackgroundSyncing(false);
@@ -586,13 +644,10 @@ export function FlightImporter() {
           return;
         }
         
-        // Filter for .txt and .csv files (DJI logs, Litchi, and Airdata exports)
+        // Filter for files of the supported log formats
+        const extensions = await getLogExtensions();
         const logFiles = entries
-          .filter((entry) => {
-            if (!entry.isFile || !entry.name) return false;
-            const name = entry.name.toLowerCase();
-            return name.endsWith('.txt') || name.endsWith('.csv');
-          })
+          .filter((entry) => entry.isFile && !!entry.name && hasExtension(entry.name, extensions))
           .map((entry) => `${folderPath}/${entry.name}`);
         
         if (logFiles.length === 0) {
@@ -603,7 +658,7 @@ export function FlightImporter() {
         // Get existing file hashes to check for new files
         const existingFlights = await getFlights();
         const existingHashes = new Set(existingFlights.map(f => f.fileHash).filter(Boolean));
-        const blacklist = getBlacklist();
+        const blacklist = new Set((await getBlacklist()).map((entry) => entry.fileHash));
         
         // Find truly new files (not already imported, not blacklisted)
         const newFiles: string[] = [];
@@ -679,84 +734,21 @@ export function FlightImporter() {
     // Cancel background sync - user action takes priority
     cancelBackgroundSync();
 
-    // Web mode: use server-side sync with file-by-file progress
+    // Web mode: use a server-side sync job with file-by-file progress
     if (isWebMode()) {
       setIsSyncing(true);
       setBatchMessage(null);
       
       try {
-        // First get the list of files to sync
-        const filesResponse = await getSyncFiles();
-        
-        if (!filesResponse.syncPath) {
+        if (!(await getSyncConfig()).syncPath) {
           setIsSyncing(false);
           setBatchMessage('NO_SYNC_FOLDER_WEB');
           return;
         }
-        
-        if (filesResponse.files.length === 0) {
-          setIsSyncing(false);
-          setBatchMessage(t('importer.noNewFiles'));
-          return;
-        }
-        
-        // Switch to batch processing mode for progress tracking
-        setIsSyncing(false);
-        setIsBatchProcessing(true);
-        setBatchTotal(filesResponse.files.length);
-        setBatchIndex(0);
-        
-        let processed = 0;
-        let skipped = 0;
-        let errors = 0;
-        
-        for (let i = 0; i < filesResponse.files.length; i++) {
-          const filename = filesResponse.files[i];
-          setBatchIndex(i + 1);
-          setCurrentFileName(filename.length > 50 ? `${filename.slice(0, 50)}…` : filename);
-          
-          try {
-            const result = await syncSingleFile(filename);
-            if (result.success) {
-              processed++;
-              // Refresh flight list every 2 files to show progress
-              if (processed % 2 === 0) {
-                const { loadFlights, loadAllTags } = useFlightStore.getState();
-                loadFlights().then(() => loadAllTags());
-              }
-            } else if (result.message.toLowerCase().includes('already') || result.message.toLowerCase().includes('duplicate')) {
-              skipped++;
-            } else {
-              errors++;
-            }
-          } catch (e) {
-            console.error(`Failed to sync ${filename}:`, e);
-            errors++;
-          }
-        }
-        
-        setIsBatchProcessing(false);
-        setCurrentFileName(null);
-        setBatchTotal(0);
-        setBatchIndex(0);
-        
-        // Final refresh
-        if (processed > 0) {
-          const { loadFlights, loadAllTags } = useFlightStore.getState();
-          await loadFlights();
-          loadAllTags();
-        }
-        
-        // Show result
-        if (processed > 0 || skipped > 0 || errors > 0) {
-          const parts: string[] = [];
-          if (processed > 0) parts.push(`${processed} imported`);
-          if (skipped > 0) parts.push(`${skipped} skipped`);
-          if (errors > 0) parts.push(`${errors} errors`);
-          setBatchMessage(t('importer.syncComplete', { parts: parts.join(', ') }));
-        } else {
-          setBatchMessage(t('importer.noFilesToSync'));
-        }
+
+        // The server scans the folder and imports new files as one job
+        const message = await runWebSyncJob();
+        setBatchMessage(message ?? t('importer.noNewFiles'));
       } catch (e) {
         console.error('Sync failed:', e);
         setBatchMessage(`Sync failed: ${e}`);
@@ -781,13 +773,10 @@ export function FlightImporter() {
       const { readDir } = await import('@tauri-apps/plugin-fs');
       const entries = await readDir(folderPath);
       
-      // Filter for .txt and .csv files (DJI logs, Litchi, and Airdata exports)
+      // Filter for files of the supported log formats
+      const extensions = await getLogExtensions();
       const logFiles = entries
-        .filter((entry) => {
-          if (!entry.isFile || !entry.name) return false;
-          const name = entry.name.toLowerCase();
-          return name.endsWith('.txt') || name.endsWith('.csv');
-        })
+        .filter((entry) => entry.isFile && !!entry.name && hasExtension(entry.name, extensions))
         .map((entry) => `${folderPath}/${entry.name}`);
 
       if (logFiles.length === 0) {
@@ -796,9 +785,8 @@ export function FlightImporter() {
         return;
       }
 
-      // For sync, we pass isManualImport=false so blacklisted files are checked
-      // The processBatch function will check each file's hash against the blacklist
-      // after import and delete any that match (files user previously deleted)
+      // For sync, we pass isManualImport=false so blacklisted files stay skipped
+      // (the backend rejects them and processBatch counts them as blacklisted)
       setIsSyncing(false);
       await processBatch(logFiles, false); // isManualImport = false for sync
     } catch (e) {
@@ -839,6 +827,15 @@ export function FlightImporter() {
               {t('importer.filesProgress', { n: batchIndex, total: batchTotal })}
             </span>
           )}
+          {syncJobId && (
+            <button
+              type="button"
+              onClick={() => cancelImportJob(syncJobId).catch((e) => console.warn('Failed to cancel sync:', e))}
+              className="text-xs text-gray-400 hover:text-white underline"
+            >
+              {t('importer.cancelSync')}
+            </button>
+          )}
         </div>
       ) : (
         <>
@@ -12,14 +12,13 @@ import {
   useState,
 } from 'react';
 import * as api from '@/lib/api';
-import { isWebMode, downloadFile, downloadBlob } from '@/lib/api';
+import { isWebMode, downloadFile, downloadBlob, addToBlacklist } from '@/lib/api';
 import { buildCsv, buildJson, buildGpx, buildKml } from '@/lib/exportUtils';
 import { useFlightStore } from '@/stores/flightStore';
 import { formatDuration, formatDateTime, formatDistance, formatAltitude, normalizeSerial } from '@/lib/utils';
 import { DayPicker, type DateRange } from 'react-day-picker';
 import type { FlightDataResponse, Flight, TelemetryData } from '@/types';
 import { useTranslation } from 'react-i18next';
-import { addToBlacklist } from './FlightImporter';
 import { FlyCardGenerator } from './FlyCardGenerator';
 import { HtmlReportModal } from './HtmlReportModal';
 import ColorPickerModal from './ColorPickerModal';
@@ -1363,7 +1362,7 @@ export function FlightList({
 
         // Add to blacklist before deleting (so sync won't re-import)
         if (flight.fileHash) {
-          addToBlacklist(flight.fileHash);
+          await addToBlacklist(flight.fileHash, flight.fileName);
         }
 
         await deleteFlight(flight.id);
@@ -2933,7 +2932,9 @@ export function FlightList({
                       e.stopPropagation();
                       // Add to blacklist before deleting (so sync won't re-import)
                       if (flight.fileHash) {
-                        addToBlacklist(flight.fileHash);
+                        addToBlacklist(flight.fileHash, flight.fileName).catch((err) =>
+                          console.error('Failed to blacklist file:', err)
+                        );
                       }
                       deleteFlight(flight.id);
                       setConfirmDeleteId(null);
@@ -10,7 +10,6 @@ import { isWebMode, getKeepUploadSettings, setKeepUploadSettings, KeepUploadSett
 import { useFlightStore } from '@/stores/flightStore';
 import { Select } from '@/components/ui/Select';
 import { PasswordInput } from '@/components/ui/PasswordInput';
-import { getBlacklist, clearBlacklist } from './FlightImporter';
 import { SMART_TAG_TYPES, getEnabledSmartTagTypes, setEnabledSmartTagTypes, SmartTagTypeId } from '@/lib/api';
 
 interface SettingsModalProps {
@@ -143,7 +142,7 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
       getAppLogDir();
       loadSmartTagsEnabled();
       fetchAppVersion();
-      setBlacklistCount(getBlacklist().size);
+      api.getBlacklist().then((entries) => setBlacklistCount(entries.length)).catch(() => setBlacklistCount(0));
       // Load enabled tag types from backend
       api.loadEnabledSmartTagTypes().then(setEnabledTagTypes);
       // Load keep upload settings (Tauri desktop only)
@@ -1245,11 +1244,15 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                         </p>
                         <div className="mt-2 flex items-center gap-3">
                           <button
-                            onClick={() => {
-                              clearBlacklist();
-                              setBlacklistCount(0);
+                            onClick={async () => {
                               setConfirmClearBlacklist(false);
-                              setMessage({ type: 'success', text: 'Blacklist cleared.' });
+                              try {
+                                await api.clearBlacklist();
+                                setBlacklistCount(0);
+                                setMessage({ type: 'success', text: 'Blacklist cleared.' });
+                              } catch (err) {
+                                setMessage({ type: 'error', text: `Failed to clear blacklist: ${err}` });
+                              }
                             }}
                             className="text-xs text-amber-300 hover:text-amber-200"
                           >
@@ -10,7 +10,7 @@
  * - "web"  (set when building for Docker/web deployment)
  */
 
-import type { Flight, FlightDataResponse, FlightTag, ImportResult, OverviewStats } from '@/types';
+import type { ArchiveImportResult, BatchImportProgress, BlacklistEntry, EquipmentItem, EquipmentType, Flight, FlightDataResponse, FlightEquipment, FlightSideData, FlightTag, ImportFailure, ImportJobEvent, ImportJobStarted, ImportPreview, ImportResult, KeychainImportResult, MediaClip, MediaFrame, MediaImportResult, OverviewStats, PendingDecryption, PendingRetryResult, ReprocessResult, SupportedFormat } from '@/types';
 
 const isWeb = import.meta.env.VITE_BACKEND === 'web';
 
@@ -158,6 +158,16 @@ export async function getFlightData(
   }) as Promise<FlightDataResponse>;
 }
 
+/** Get home point, RC GPS, avoidance, motor and wind records for a flight */
+export async function getFlightSideData(flightId: number): Promise<FlightSideData> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId) });
+    return fetchJson<FlightSideData>(`/flight_side_data?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_side_data', { flightId }) as Promise<FlightSideData>;
+}
+
 /**
  * Import a flight log.
  * - Tauri: passes a file path string
@@ -187,6 +197,264 @@ export async function importLog(
   return invoke('import_log', { filePath: fileOrPath as string }) as Promise<ImportResult>;
 }
 
+/**
+ * Import several local flight logs in parallel (desktop only).
+ * Results are returned in the order of the given paths.
+ */
+export async function importLogs(filePaths: string[]): Promise<ImportResult[]> {
+  if (isWeb) {
+    throw new Error('Batch path import is not supported in web mode. Use importLog for each file.');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('import_logs', { filePaths }) as Promise<ImportResult[]>;
+}
+
+/**
+ * Subscribe to batch import progress (desktop only).
+ * Returns a function that removes the listener.
+ */
+export async function onImportProgress(
+  handler: (progress: BatchImportProgress) => void,
+): Promise<() => void> {
+  if (isWeb) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<BatchImportProgress>('import-progress', (event) => handler(event.payload));
+}
+
+/**
+ * Start syncing the server's sync folder as a background job (web only).
+ * Follow it with subscribeImportJob; cancel it with cancelImportJob.
+ */
+export async function startSyncJob(): Promise<ImportJobStarted> {
+  return fetchJson<ImportJobStarted>('/sync/jobs', { method: 'POST' });
+}
+
+/**
+ * Follow an import job's progress until its `done` event.
+ * Web mode reads the SSE stream with fetch (EventSource cannot send the
+ * profile headers) and calls onClose when the stream ends, after `done` or
+ * on a dropped connection; desktop listens for `import-job` events.
+ * Returns a function that stops listening.
+ */
+export async function subscribeImportJob(
+  jobId: string,
+  handler: (event: ImportJobEvent) => void,
+  onClose?: () => void,
+): Promise<() => void> {
+  if (isWeb) {
+    const controller = new AbortController();
+    const response = await fetch(`${API_BASE}/jobs/${encodeURIComponent(jobId)}/events`, {
+      headers: profileHeaders(),
+      signal: controller.signal,
+    });
+    if (!response.ok || !response.body) {
+      throw new Error(await response.text());
+    }
+    const reader = response.body.pipeThrough(new TextDecoderStream()).getReader();
+    (async () => {
+      let buffer = '';
+      try {
+        for (;;) {
+          const { value, done } = await reader.read();
+          if (done) break;
+          buffer += value;
+          let end: number;
+          while ((end = buffer.indexOf('\n\n')) >= 0) {
+            const block = buffer.slice(0, end);
+            buffer = buffer.slice(end + 2);
+            const data = block
+              .split('\n')
+              .filter((line) => line.startsWith('data:'))
+              .map((line) => line.slice(5).trimStart())
+              .join('\n');
+            if (data) handler(JSON.parse(data) as ImportJobEvent);
+          }
+        }
+      } catch (e) {
+        if (!controller.signal.aborted) console.warn('Import job stream ended:', e);
+      } finally {
+        onClose?.();
+      }
+    })();
+    return () => controller.abort();
+  }
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<ImportJobEvent>('import-job', (event) => {
+    if (event.payload.jobId === jobId) handler(event.payload);
+  });
+}
+
+/** Cancel an import job; files already being parsed still finish */
+export async function cancelImportJob(jobId: string): Promise<void> {
+  if (isWeb) {
+    await fetchJson(`/jobs/${encodeURIComponent(jobId)}/cancel`, { method: 'POST' });
+    return;
+  }
+  const invoke = await getTauriInvoke();
+  await invoke('cancel_import_job', { jobId });
+}
+
+/** Inbox folder of the active profile, watched for new logs (desktop only) */
+export async function getInboxFolder(): Promise<string | null> {
+  if (isWeb) return null;
+  const invoke = await getTauriInvoke();
+  return invoke('get_inbox_folder') as Promise<string | null>;
+}
+
+/**
+ * Set (or clear, with null) the active profile's inbox folder (desktop only).
+ * Logs already in it are imported right away, new ones once fully written.
+ */
+export async function setInboxFolder(path: string | null): Promise<string | null> {
+  if (isWeb) {
+    throw new Error('Inbox folders are not supported in web mode. Use SYNC_WATCH instead.');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_inbox_folder', { path }) as Promise<string | null>;
+}
+
+/** Listen for logs imported from the inbox folder (desktop only) */
+export async function onInboxImported(
+  handler: (results: ImportResult[]) => void,
+): Promise<() => void> {
+  if (isWeb) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<ImportResult[]>('inbox-imported', (event) => handler(event.payload));
+}
+
+/** Extensions (without the dot) of the archives the archive importer unpacks */
+export const ARCHIVE_EXTENSIONS = ['zip', 'tar.gz', 'tgz'];
+
+/** True if the file name ends in one of the extensions (lowercase, without the dot) */
+export function hasExtension(name: string, extensions: string[]): boolean {
+  const lower = name.toLowerCase();
+  return extensions.some((ext) => lower.endsWith(`.${ext}`));
+}
+
+/** True for file names the archive importer accepts (.zip, .tar.gz, .tgz) */
+export function isArchiveFile(name: string): boolean {
+  return hasExtension(name, ARCHIVE_EXTENSIONS);
+}
+
+/**
+ * Import every flight log contained in a ZIP or tar.gz archive.
+ * Returns one result per log found in the archive.
+ */
+export async function importArchive(
+  fileOrPath: string | File,
+): Promise<ArchiveImportResult[]> {
+  if (isWeb) {
+    const formData = new FormData();
+    if (typeof fileOrPath === 'string') {
+      throw new Error('File path import is not supported in web mode. Please provide a File object.');
+    }
+    formData.append('file', fileOrPath, fileOrPath.name);
+    const response = await fetch(`${API_BASE}/import/archive`, {
+      method: 'POST',
+      body: formData,
+      headers: profileHeaders(),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('import_archive', { filePath: fileOrPath as string }) as Promise<ArchiveImportResult[]>;
+}
+
+/**
+ * Dry-run import: parse a flight log and report the detected format,
+ * metadata, tags, duplicate matches and telemetry quality without
+ * writing anything to the database.
+ */
+export async function previewImport(
+  fileOrPath: string | File,
+): Promise<ImportPreview> {
+  if (isWeb) {
+    const formData = new FormData();
+    if (typeof fileOrPath === 'string') {
+      throw new Error('File path import is not supported in web mode. Please provide a File object.');
+    }
+    formData.append('file', fileOrPath, fileOrPath.name);
+    const response = await fetch(`${API_BASE}/import/preview`, {
+      method: 'POST',
+      body: formData,
+      headers: profileHeaders(),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('preview_import', { filePath: fileOrPath as string }) as Promise<ImportPreview>;
+}
+
+/**
+ * Import DJI video subtitle (.SRT) files as media clips, linked to the
+ * flights they were shot during. `droneSerial` restricts the flights they
+ * can be linked to.
+ */
+export async function importMediaFiles(
+  filesOrPaths: (string | File)[],
+  droneSerial?: string,
+): Promise<MediaImportResult[]> {
+  if (isWeb) {
+    const formData = new FormData();
+    for (const file of filesOrPaths) {
+      if (typeof file === 'string') {
+        throw new Error('File path import is not supported in web mode. Please provide a File object.');
+      }
+      formData.append('file', file, file.name);
+    }
+    if (droneSerial) formData.append('droneSerial', droneSerial);
+    const response = await fetch(`${API_BASE}/media/import`, {
+      method: 'POST',
+      body: formData,
+      headers: profileHeaders(),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('import_media_files', {
+    filePaths: filesOrPaths as string[],
+    droneSerial: droneSerial ?? null,
+  }) as Promise<MediaImportResult[]>;
+}
+
+/** All imported video clips, newest first */
+export async function getMediaClips(): Promise<MediaClip[]> {
+  if (isWeb) {
+    return fetchJson<MediaClip[]>('/media');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_media_clips') as Promise<MediaClip[]>;
+}
+
+/** Every frame (position and camera settings) of a video clip */
+export async function getMediaFrames(clipId: number): Promise<MediaFrame[]> {
+  if (isWeb) {
+    return fetchJson<MediaFrame[]>(`/media/frames?clip_id=${clipId}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_media_frames', { clipId }) as Promise<MediaFrame[]>;
+}
+
+export async function deleteMediaClip(clipId: number): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>(`/media/delete?clip_id=${clipId}`, { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('delete_media_clip', { clipId }) as Promise<boolean>;
+}
+
 /**
  * Create a manual flight entry without a log file.
  * Used for flights that don't have telemetry data available.
@@ -254,6 +522,29 @@ export async function computeFileHash(filePath: string): Promise<string> {
   return invoke('compute_file_hash', { filePath }) as Promise<string>;
 }
 
+export async function listSupportedFormats(): Promise<SupportedFormat[]> {
+  if (isWeb) {
+    return fetchJson<SupportedFormat[]>('/formats');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('list_supported_formats') as Promise<SupportedFormat[]>;
+}
+
+let logExtensions: Promise<string[]> | null = null;
+
+/** Extensions (lowercase, without the dot) of every log format the backend parses */
+export function getLogExtensions(): Promise<string[]> {
+  if (!logExtensions) {
+    logExtensions = listSupportedFormats()
+      .then((formats) => [...new Set(formats.flatMap((f) => f.extensions))])
+      .catch((err) => {
+        logExtensions = null;
+        throw err;
+      });
+  }
+  return logExtensions;
+}
+
 export async function deleteFlight(flightId: number): Promise<boolean> {
   if (isWeb) {
     return fetchJson<boolean>(`/flights/delete?flight_id=${flightId}`, {
@@ -286,6 +577,22 @@ export async function deduplicateFlights(): Promise<number> {
   return invoke('deduplicate_flights') as Promise<number>;
 }
 
+/**
+ * Re-parse flights from their kept original log files, replacing telemetry,
+ * stats and auto tags. Names, notes, colors and manual tags are kept.
+ * @param flightIds Flights to reprocess; all flights when omitted
+ */
+export async function reprocessFlights(flightIds?: number[]): Promise<ReprocessResult[]> {
+  if (isWeb) {
+    return fetchJson<ReprocessResult[]>('/flights/reprocess', {
+      method: 'POST',
+      body: JSON.stringify({ flight_ids: flightIds ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('reprocess_flights', { flightIds: flightIds ?? null }) as Promise<ReprocessResult[]>;
+}
+
 export async function updateFlightName(
   flightId: number,
   displayName: string,
@@ -408,7 +715,7 @@ export async function getEquipmentNames(): Promise<EquipmentNamesResponse> {
   return { battery_names, aircraft_names };
 }
 
-export async function setEquipmentName(serial: string, equipmentType: 'battery' | 'aircraft', displayName: string): Promise<boolean> {
+export async function setEquipmentName(serial: string, equipmentType: EquipmentType, displayName: string): Promise<boolean> {
   if (isWeb) {
     return fetchJson<boolean>('/equipment_names', {
       method: 'POST',
@@ -419,6 +726,76 @@ export async function setEquipmentName(serial: string, equipmentType: 'battery'
   return invoke('set_equipment_name', { serial, equipmentType, displayName }) as Promise<boolean>;
 }
 
+/** Every aircraft, battery, RC and camera seen in imported flights, with firmware history */
+export async function getEquipmentInventory(): Promise<EquipmentItem[]> {
+  if (isWeb) {
+    return fetchJson<EquipmentItem[]>('/equipment_inventory');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_equipment_inventory') as Promise<EquipmentItem[]>;
+}
+
+/** App, firmware and component serials recorded for one flight (null if none) */
+export async function getFlightEquipment(flightId: number): Promise<FlightEquipment | null> {
+  if (isWeb) {
+    const params = new URLSearchParams({ flight_id: String(flightId) });
+    return fetchJson<FlightEquipment | null>(`/flight_equipment?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_equipment', { flightId }) as Promise<FlightEquipment | null>;
+}
+
+// ============================================================================
+// Import Blacklist
+// ============================================================================
+
+/** List file hashes that every import path (manual, sync, scheduled) skips */
+export async function getBlacklist(): Promise<BlacklistEntry[]> {
+  if (isWeb) {
+    return fetchJson<BlacklistEntry[]>('/blacklist');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_blacklist') as Promise<BlacklistEntry[]>;
+}
+
+/** Blacklist a file hash, e.g. when its flight is deleted */
+export async function addToBlacklist(fileHash: string, fileName?: string | null): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/blacklist/add', {
+      method: 'POST',
+      body: JSON.stringify({ file_hash: fileHash, file_name: fileName ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('add_to_blacklist', { fileHash, fileName: fileName ?? null }) as Promise<boolean>;
+}
+
+/** Allow a blacklisted file to be imported again */
+export async function removeFromBlacklist(fileHash: string): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/blacklist/remove', {
+      method: 'POST',
+      body: JSON.stringify({ file_hash: fileHash }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('remove_from_blacklist', { fileHash }) as Promise<boolean>;
+}
+
+/** Remove every blacklist entry. Returns the number of entries removed. */
+export async function clearBlacklist(): Promise<number> {
+  if (isWeb) {
+    return fetchJson<number>('/blacklist/clear', { method: 'DELETE' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('clear_blacklist') as Promise<number>;
+}
+
+/** True when an import result was rejected because the file is blacklisted */
+export function isBlacklistedResult(result: ImportResult): boolean {
+  return !result.success && result.message.toLowerCase().includes('import blacklist');
+}
+
 // ============================================================================
 // Tag Management
 // ============================================================================
@@ -509,7 +886,9 @@ export async function regenerateFlightSmartTags(flightId: number, enabledTagType
 
 /** All available smart tag types that can be enabled/disabled */
 export const SMART_TAG_TYPES = [
-  { id: 'night_flight', label: 'Night Flight', description: 'Flights starting after 7 PM or before 6 AM' },
+  { id: 'night_flight', label: 'Night Flight', description: 'Flown after the end of civil twilight (sun >6° below horizon)' },
+  { id: 'twilight_flight', label: 'Twilight Flight', description: 'Flown during civil twilight (sun 0–6° below horizon)' },
+  { id: 'golden_hour', label: 'Golden Hour', description: 'Flown with the sun less than 6° above the horizon' },
   { id: 'high_speed', label: 'High Speed', description: 'Max speed exceeds 15 m/s' },
   { id: 'cold_battery', label: 'Cold Battery', description: 'Battery temp below 15°C at start' },
   { id: 'heavy_load', label: 'Heavy Load', description: 'Battery consumption >75% in <20 min' },
@@ -690,6 +1069,36 @@ export interface SyncFileResponse {
   fileHash: string | null;
 }
 
+/** What sync does with source files after importing them (per profile) */
+export interface SyncPostImport {
+  /** `archive` moves imported files to `imported/YYYY/MM/` in the sync folder */
+  imported: 'leave' | 'archive';
+  /** `reject` moves failed files to `rejected/` next to an `.error.json` sidecar */
+  failed: 'leave' | 'reject';
+}
+
+export interface SyncPostImportStatus extends SyncPostImport {
+  /** False for read-only mounts, where files are always left in place */
+  writable: boolean;
+}
+
+/** A folder a profile syncs logs from, in addition to SYNC_LOGS_PATH (web mode only) */
+export interface SyncSource {
+  /** Assigned by the server; ignored when adding a source */
+  id: string;
+  /** Absolute folder path on the server */
+  path: string;
+  recursive: boolean;
+  /** Glob patterns of files to sync; empty uses SYNC_INCLUDE */
+  include: string[];
+  /** Glob patterns of files or folders to skip; empty uses SYNC_EXCLUDE */
+  exclude: string[];
+  /** Cron expression with seconds (e.g. `0 0 * * * *`); null syncs on demand only */
+  schedule: string | null;
+  postImport: SyncPostImport;
+  enabled: boolean;
+}
+
 /**
  * Get the sync folder configuration (web mode only).
  * Returns the configured SYNC_LOGS_PATH if set on the server.
@@ -737,6 +1146,168 @@ export async function triggerSync(): Promise<SyncConfig> {
   return fetchJson<SyncConfig>('/sync', { method: 'POST' });
 }
 
+/**
+ * Get the post-import actions for sync folder files (web mode only).
+ */
+export async function getSyncPostImport(): Promise<SyncPostImportStatus> {
+  if (!isWeb) {
+    return { imported: 'leave', failed: 'leave', writable: false };
+  }
+  return fetchJson<SyncPostImportStatus>('/sync/post_import');
+}
+
+/**
+ * Set the post-import actions for sync folder files (web mode only).
+ */
+export async function setSyncPostImport(settings: SyncPostImport): Promise<SyncPostImportStatus> {
+  return fetchJson<SyncPostImportStatus>('/sync/post_import', {
+    method: 'POST',
+    body: JSON.stringify(settings),
+  });
+}
+
+/** List the sync sources of the current profile (web mode only) */
+export async function getSyncSources(): Promise<SyncSource[]> {
+  if (!isWeb) return [];
+  return fetchJson<SyncSource[]>('/sync/sources');
+}
+
+/** Add a sync source; returns it with its assigned id */
+export async function addSyncSource(source: Omit<SyncSource, 'id'>): Promise<SyncSource> {
+  return fetchJson<SyncSource>('/sync/sources', {
+    method: 'POST',
+    body: JSON.stringify(source),
+  });
+}
+
+export async function updateSyncSource(source: SyncSource): Promise<SyncSource> {
+  return fetchJson<SyncSource>(`/sync/sources/${encodeURIComponent(source.id)}`, {
+    method: 'PUT',
+    body: JSON.stringify(source),
+  });
+}
+
+/** Remove a sync source; returns false if it did not exist */
+export async function deleteSyncSource(id: string): Promise<boolean> {
+  return fetchJson<boolean>(`/sync/sources/${encodeURIComponent(id)}`, { method: 'DELETE' });
+}
+
+/** Sync a source now and wait for the result */
+export async function runSyncSource(id: string): Promise<SyncConfig> {
+  return fetchJson<SyncConfig>(`/sync/sources/${encodeURIComponent(id)}/run`, { method: 'POST' });
+}
+
+/** One sync of a folder, from the sync run history (web mode only) */
+export interface SyncRun {
+  id: number;
+  trigger: 'cron' | 'manual' | 'watch';
+  /** Sync source the run synced; null for the SYNC_LOGS_PATH folder */
+  sourceId: string | null;
+  syncPath: string;
+  startedAt: string;
+  finishedAt: string;
+  imported: number;
+  /** Already imported, duplicates, blacklisted or ignored */
+  skipped: number;
+  /** Skipped by the seen index without reading them */
+  unchanged: number;
+  queued: number;
+  failed: number;
+  cancelled: number;
+  archived: number;
+  rejected: number;
+  error: string | null;
+}
+
+export interface SyncRunFile {
+  path: string;
+  outcome: 'imported' | 'duplicate' | 'skipped' | 'queued' | 'failed' | 'cancelled';
+  message: string;
+  flightId: number | null;
+}
+
+export interface SyncRunDetail extends SyncRun {
+  files: SyncRunFile[];
+}
+
+export interface SyncSourceStatus {
+  id: string;
+  path: string;
+  enabled: boolean;
+  schedule: string | null;
+  nextRunAt: string | null;
+  running: boolean;
+  lastRun: SyncRun | null;
+}
+
+/** When the current profile syncs next and how its last runs went */
+export interface SyncStatus {
+  syncPath: string | null;
+  /** SYNC_INTERVAL cron expression, if scheduled */
+  interval: string | null;
+  nextRunAt: string | null;
+  watch: boolean;
+  lastRun: SyncRun | null;
+  sources: SyncSourceStatus[];
+}
+
+/** Get the sync scheduler status of the current profile (web mode only) */
+export async function getSyncStatus(): Promise<SyncStatus | null> {
+  if (!isWeb) return null;
+  return fetchJson<SyncStatus>('/sync/status');
+}
+
+/** List sync runs, newest first; `sourceId` 'default' is the sync folder */
+export async function getSyncRuns(limit = 50, sourceId?: string): Promise<SyncRun[]> {
+  if (!isWeb) return [];
+  const params = new URLSearchParams({ limit: String(limit) });
+  if (sourceId) params.set('sourceId', sourceId);
+  return fetchJson<SyncRun[]>(`/sync/runs?${params}`);
+}
+
+export async function getSyncRun(id: number): Promise<SyncRunDetail> {
+  return fetchJson<SyncRunDetail>(`/sync/runs/${id}`);
+}
+
+/**
+ * List files that failed to import during folder sync (web mode only).
+ */
+export async function getImportFailures(): Promise<ImportFailure[]> {
+  if (!isWeb) {
+    return [];
+  }
+  return fetchJson<ImportFailure[]>('/import_failures');
+}
+
+/**
+ * Retry importing a previously failed sync file (web mode only).
+ * Clears the ignored flag for the file.
+ */
+export async function retryImportFailure(fileHash: string): Promise<SyncFileResponse> {
+  if (!isWeb) {
+    return { success: false, message: 'Not in web mode', fileHash: null };
+  }
+  return fetchJson<SyncFileResponse>('/import_failures/retry', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ file_hash: fileHash }),
+  });
+}
+
+/**
+ * Permanently skip (or stop skipping) a failed sync file in future syncs (web mode only).
+ */
+export async function ignoreImportFailure(fileHash: string, ignored = true): Promise<boolean> {
+  if (!isWeb) {
+    return false;
+  }
+  return fetchJson<boolean>('/import_failures/ignore', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ file_hash: fileHash, ignored }),
+  });
+}
+
 // ============================================================================
 // Database backup & restore
 // ============================================================================
@@ -813,6 +1384,98 @@ export async function restoreDatabase(file?: File): Promise<string> {
   return invoke('import_backup', { srcPath: filePath }) as Promise<string>;
 }
 
+// ============================================================================
+// Keychain bundles & pending decryption
+// ============================================================================
+
+function getKeychainBundleFilename(): string {
+  const now = new Date();
+  const pad = (n: number) => n.toString().padStart(2, '0');
+  return `${now.getFullYear()}-${pad(now.getMonth() + 1)}-${pad(now.getDate())}_Open_Dronelog.keychains.json`;
+}
+
+/**
+ * Export cached decryption keychains of V13+ logs as a portable bundle,
+ * for importing on an instance without DJI API access.
+ * Returns false when the user cancelled the save dialog (Tauri).
+ */
+export async function exportKeychains(): Promise<boolean> {
+  if (isWeb) {
+    const bundle = await fetchJson<unknown>('/keychains/export');
+    downloadBlob(getKeychainBundleFilename(), new Blob([JSON.stringify(bundle, null, 2)], { type: 'application/json' }));
+    return true;
+  }
+
+  const { save } = await import('@tauri-apps/plugin-dialog');
+  const destPath = await save({
+    defaultPath: getKeychainBundleFilename(),
+    filters: [{ name: 'Keychain Bundle', extensions: ['json'] }],
+  });
+  if (!destPath) return false;
+  const invoke = await getTauriInvoke();
+  await invoke('export_keychains', { destPath });
+  return true;
+}
+
+/**
+ * Import a keychain bundle and retry the logs waiting for decryption.
+ * Returns null when the user cancelled the open dialog (Tauri).
+ */
+export async function importKeychains(file?: File): Promise<KeychainImportResult | null> {
+  if (isWeb) {
+    if (!file) throw new Error('No file provided');
+    return fetchJson<KeychainImportResult>('/keychains/import', {
+      method: 'POST',
+      body: await file.text(),
+    });
+  }
+
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const srcPath = await open({
+    multiple: false,
+    filters: [{ name: 'Keychain Bundle', extensions: ['json'] }],
+  });
+  if (!srcPath) return null;
+  const filePath = typeof srcPath === 'string' ? srcPath : (srcPath as { path: string }).path;
+  const invoke = await getTauriInvoke();
+  return invoke('import_keychains', { srcPath: filePath }) as Promise<KeychainImportResult>;
+}
+
+/** List encrypted logs waiting for their keychains */
+export async function getPendingDecryption(): Promise<PendingDecryption[]> {
+  if (isWeb) {
+    return fetchJson<PendingDecryption[]>('/pending_decryption');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_pending_decryption') as Promise<PendingDecryption[]>;
+}
+
+/** Retry every queued log now (also runs automatically every 10 minutes) */
+export async function retryPendingDecryption(): Promise<PendingRetryResult> {
+  if (isWeb) {
+    return fetchJson<PendingRetryResult>('/pending_decryption/retry', { method: 'POST' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('retry_pending_decryption') as Promise<PendingRetryResult>;
+}
+
+/** Drop a log from the pending decryption queue */
+export async function removePendingDecryption(fileHash: string): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/pending_decryption/remove', {
+      method: 'POST',
+      body: JSON.stringify({ file_hash: fileHash }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('remove_pending_decryption', { fileHash }) as Promise<boolean>;
+}
+
+/** True when an import result was queued for decryption instead of failing */
+export function isPendingDecryptionResult(result: ImportResult): boolean {
+  return !result.success && result.message.includes('queued for decryption');
+}
+
 // ============================================================================
 // Profile Management
 // ============================================================================
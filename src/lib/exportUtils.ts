@@ -126,21 +126,27 @@ export function buildCsv(data: FlightDataResponse): string {
     'height_m',
     'vps_height_m',
     'altitude_m',
+    'altitude_abs_m',
     'speed_ms',
     'velocity_x_ms',
     'velocity_y_ms',
     'velocity_z_ms',
     'battery_percent',
     'battery_voltage_v',
+    'battery_current_a',
     'battery_temp_c',
     'cell_voltages',
     'satellites',
+    'gps_signal',
     'rc_signal',
     'rc_uplink',
     'rc_downlink',
     'pitch_deg',
     'roll_deg',
     'yaw_deg',
+    'gimbal_pitch_deg',
+    'gimbal_roll_deg',
+    'gimbal_yaw_deg',
     'rc_aileron',
     'rc_elevator',
     'rc_throttle',
@@ -164,11 +170,13 @@ export function buildCsv(data: FlightDataResponse): string {
       '0', // distance_to_home at takeoff
       '', '', // height, vps_height
       flight.maxAltitude != null ? String(flight.maxAltitude) : '',
+      '', // altitude_abs_m
       '', '', '', '', // speed, velocities
-      '', '', '', '', // battery_percent, battery_voltage_v, battery_temp_c, cell_voltages
-      '', // satellites
+      '', '', '', '', '', // battery_percent, battery_voltage_v, battery_current_a, battery_temp_c, cell_voltages
+      '', '', // satellites, gps_signal
       '', '', '', // rc_signal, rc_uplink, rc_downlink
       '', '', '', // pitch, roll, yaw
+      '', '', '', // gimbal pitch, roll, yaw
       '', '', '', '', // rc controls
       '', '', '', // is_photo, is_video, flight_mode
       escapeCsv(messagesJson),
@@ -245,21 +253,27 @@ export function buildCsv(data: FlightDataResponse): string {
       getMetric(telemetry.height, index, 2),         // height_m
       getMetric(telemetry.vpsHeight, index, 2),      // vps_height_m
       getMetric(telemetry.altitude, index, 2),       // altitude_m
+      getMetric(telemetry.altitudeAbs, index, 2),    // altitude_abs_m
       getMetric(telemetry.speed, index, 2),          // speed_ms
       getMetric(telemetry.velocityX, index, 2),      // velocity_x_ms
       getMetric(telemetry.velocityY, index, 2),      // velocity_y_ms
       getMetric(telemetry.velocityZ, index, 2),      // velocity_z_ms
       getValue(telemetry.battery, index),            // battery_percent (integer)
       getMetric(telemetry.batteryVoltage, index, 3), // battery_voltage_v
+      getMetric(telemetry.batteryCurrent, index, 2), // battery_current_a
       getMetric(telemetry.batteryTemp, index, 1),    // battery_temp_c
       getArrayValue(telemetry.cellVoltages, index),  // cell_voltages (JSON)
       getValue(telemetry.satellites, index),         // satellites (integer)
+      getValue(telemetry.gpsSignal, index),          // gps_signal (integer)
       getValue(telemetry.rcSignal, index),           // rc_signal (integer)
       getValue(telemetry.rcUplink, index),           // rc_uplink (integer)
       getValue(telemetry.rcDownlink, index),         // rc_downlink (integer)
       getMetric(telemetry.pitch, index, 2),          // pitch_deg
       getMetric(telemetry.roll, index, 2),           // roll_deg
       getMetric(telemetry.yaw, index, 2),            // yaw_deg
+      getMetric(telemetry.gimbalPitch, index, 2),    // gimbal_pitch_deg
+      getMetric(telemetry.gimbalRoll, index, 2),     // gimbal_roll_deg
+      getMetric(telemetry.gimbalYaw, index, 2),      // gimbal_yaw_deg
       getMetric(telemetry.rcAileron, index, 1),      // rc_aileron
       getMetric(telemetry.rcElevator, index, 1),     // rc_elevator
       getMetric(telemetry.rcThrottle, index, 1),     // rc_throttle
@@ -21,6 +21,7 @@ export interface HtmlReportFieldConfig {
   duration: boolean;
   takeoffTime: boolean;
   landingTime: boolean;
+  nightTime: boolean;
   takeoffCoordinates: boolean;
   notes: boolean;
 
@@ -67,6 +68,7 @@ export const DEFAULT_FIELD_CONFIG: HtmlReportFieldConfig = {
   duration: true,
   takeoffTime: true,
   landingTime: true,
+  nightTime: true,
   takeoffCoordinates: true,
   notes: true,
   aircraftName: true,
@@ -109,6 +111,7 @@ export const FIELD_GROUPS: FieldGroup[] = [
       { key: 'duration', label: 'Duration' },
       { key: 'takeoffTime', label: 'Takeoff Time' },
       { key: 'landingTime', label: 'Landing Time' },
+      { key: 'nightTime', label: 'Night Time' },
       { key: 'takeoffCoordinates', label: 'Takeoff Coordinates' },
       { key: 'notes', label: 'Notes' },
     ],
@@ -240,7 +243,7 @@ function fmtAltitude(meters: number | null, unitSystem: UnitSystem, locale?: str
 }
 
 /** Format to "DD MMM YYYY, hh:mm:ss AM/PM TZ" */
-function fmtDateTimeFull(isoString: string | null, locale?: string, hour12?: boolean): string {
+function fmtDateTimeFull(isoString: string | null, locale?: string, hour12?: boolean, timeZone?: string | null): string {
   if (!isoString) return '—';
   try {
     const date = new Date(isoString);
@@ -253,6 +256,7 @@ function fmtDateTimeFull(isoString: string | null, locale?: string, hour12?: boo
       second: '2-digit',
       hour12: hour12 !== undefined ? hour12 : true,
       timeZoneName: 'short',
+      timeZone: timeZone ?? undefined,
     }));
   } catch {
     return isoString;
@@ -260,7 +264,7 @@ function fmtDateTimeFull(isoString: string | null, locale?: string, hour12?: boo
 }
 
 /** Format time only: "hh:mm:ss AM/PM TZ" */
-function fmtTimeFull(isoString: string | null, locale?: string, hour12?: boolean): string {
+function fmtTimeFull(isoString: string | null, locale?: string, hour12?: boolean, timeZone?: string | null): string {
   if (!isoString) return '—';
   try {
     const date = new Date(isoString);
@@ -270,6 +274,7 @@ function fmtTimeFull(isoString: string | null, locale?: string, hour12?: boolean
       second: '2-digit',
       hour12: hour12 !== undefined ? hour12 : true,
       timeZoneName: 'short',
+      timeZone: timeZone ?? undefined,
     }));
   } catch {
     return isoString;
@@ -277,7 +282,7 @@ function fmtTimeFull(isoString: string | null, locale?: string, hour12?: boolean
 }
 
 /** Format date for day header: "DD MMM YYYY" */
-function fmtDateHeader(isoString: string | null, locale?: string): string {
+function fmtDateHeader(isoString: string | null, locale?: string, timeZone?: string | null): string {
   if (!isoString) return '';
   try {
     const date = new Date(isoString);
@@ -286,13 +291,15 @@ function fmtDateHeader(isoString: string | null, locale?: string): string {
       day: '2-digit',
       month: 'short',
       year: 'numeric',
+      timeZone: timeZone ?? undefined,
     });
   } catch {
     return isoString;
   }
 }
 
-function fmtDateShort(isoString: string | null): string {
+function fmtDateShort(isoString: string | null, localStartTime?: string | null): string {
+  if (localStartTime) return localStartTime.slice(0, 10);
   if (!isoString) return '';
   return new Date(isoString).toISOString().split('T')[0];
 }
@@ -311,7 +318,7 @@ function fmtNow(locale?: string, hour12?: boolean): string {
   }));
 }
 
-function calculateLandingTime(takeoffTime: string | null, durationSecs: number | null, locale?: string, hour12?: boolean): string {
+function calculateLandingTime(takeoffTime: string | null, durationSecs: number | null, locale?: string, hour12?: boolean, timeZone?: string | null): string {
   if (!takeoffTime || !durationSecs) return '—';
   const landing = new Date(new Date(takeoffTime).getTime() + durationSecs * 1000);
   return ensureAmPmUpperCase(landing.toLocaleString(locale, {
@@ -320,6 +327,7 @@ function calculateLandingTime(takeoffTime: string | null, durationSecs: number |
     second: '2-digit',
     hour12: hour12 !== undefined ? hour12 : true,
     timeZoneName: 'short',
+    timeZone: timeZone ?? undefined,
   }));
 }
 
@@ -428,10 +436,11 @@ function buildFlightColumns(
   // 1. General Info Column
   const generalItems: { label: string; value: string }[] = [];
   if (fc.flightName) generalItems.push({ label: tr('report.flightName', 'Flight Name'), value: esc(fd.flight.displayName || fd.flight.fileName) });
-  if (fc.flightDateTime) generalItems.push({ label: tr('report.dateTime', 'Date/Time'), value: esc(fmtDateTimeFull(fd.flight.startTime, dl, hour12)) });
-  if (fc.takeoffTime) generalItems.push({ label: tr('report.takeoff', 'Takeoff'), value: esc(fmtTimeFull(fd.flight.startTime, dl, hour12)) });
-  if (fc.landingTime) generalItems.push({ label: tr('report.landing', 'Landing'), value: esc(calculateLandingTime(fd.flight.startTime, fd.flight.durationSecs, dl, hour12)) });
+  if (fc.flightDateTime) generalItems.push({ label: tr('report.dateTime', 'Date/Time'), value: esc(fmtDateTimeFull(fd.flight.startTime, dl, hour12, fd.flight.localTimezone)) });
+  if (fc.takeoffTime) generalItems.push({ label: tr('report.takeoff', 'Takeoff'), value: esc(fmtTimeFull(fd.flight.startTime, dl, hour12, fd.flight.localTimezone)) });
+  if (fc.landingTime) generalItems.push({ label: tr('report.landing', 'Landing'), value: esc(calculateLandingTime(fd.flight.startTime, fd.flight.durationSecs, dl, hour12, fd.flight.localTimezone)) });
   if (fc.duration) generalItems.push({ label: tr('report.duration', 'Duration'), value: esc(fmtDuration(fd.flight.durationSecs)) });
+  if (fc.nightTime && fd.flight.nightSecs != null) generalItems.push({ label: tr('report.nightTime', 'Night Time'), value: esc(fmtDuration(fd.flight.nightSecs)) });
   if (fc.takeoffCoordinates) {
     const lat = fd.flight.homeLat ?? fd.data.telemetry.latitude?.[0];
     const lon = fd.flight.homeLon ?? fd.data.telemetry.longitude?.[0];
@@ -594,8 +603,8 @@ export function buildHtmlReport(
   type DayGroup = { date: string; dateLabel: string; flights: FlightReportData[] };
   const dayMap = new Map<string, DayGroup>();
   for (const fd of flightsData) {
-    const dateKey = fmtDateShort(fd.flight.startTime) || 'Unknown';
-    const dateLabel = fmtDateHeader(fd.flight.startTime, dl) || 'Unknown Date';
+    const dateKey = fmtDateShort(fd.flight.startTime, fd.flight.localStartTime) || 'Unknown';
+    const dateLabel = fmtDateHeader(fd.flight.startTime, dl, fd.flight.localTimezone) || 'Unknown Date';
     if (!dayMap.has(dateKey)) dayMap.set(dateKey, { date: dateKey, dateLabel, flights: [] });
     dayMap.get(dateKey)!.flights.push(fd);
   }
@@ -21,7 +21,13 @@ export interface Flight {
   batterySerial: string | null;
   cycleCount: number | null;
   startTime: string | null;
+  /** IANA time zone at the home point */
+  localTimezone?: string | null;
+  /** Local wall-clock start time in localTimezone ("YYYY-MM-DD HH:MM:SS") */
+  localStartTime?: string | null;
   durationSecs: number | null;
+  /** Seconds flown after the end of civil twilight */
+  nightSecs?: number | null;
   totalDistance: number | null;
   maxAltitude: number | null;
   maxSpeed: number | null;
@@ -70,6 +76,15 @@ export interface TelemetryData {
   isVideo?: (boolean | null)[];
   /** Flight mode (e.g., "GPS", "ATTI", "Sport") */
   flightMode?: (string | null)[];
+  /** Absolute altitude above mean sea level (m) */
+  altitudeAbs?: (number | null)[];
+  /** Battery current in amps (positive while discharging) */
+  batteryCurrent?: (number | null)[];
+  gimbalPitch?: (number | null)[];
+  gimbalRoll?: (number | null)[];
+  gimbalYaw?: (number | null)[];
+  /** GPS signal level (0-5) */
+  gpsSignal?: (number | null)[];
 }
 
 /** App tip/warning/caution message from DJI flight log */
@@ -79,6 +94,153 @@ export interface FlightMessage {
   message: string;
 }
 
+/** Home point / RTH settings change recorded by the aircraft */
+export interface HomeUpdate {
+  timestampMs: number;
+  latitude: number;
+  longitude: number;
+  altitude: number;
+  goHomeHeight: number;
+  maxAllowedHeight: number;
+  goHomeMode: string | null;
+  dynamicHomePoint: boolean;
+}
+
+/** Remote controller (pilot) position */
+export interface RcGpsPoint {
+  timestampMs: number;
+  latitude: number;
+  longitude: number;
+  satellites: number;
+  accuracy: number | null;
+}
+
+/** Obstacle avoidance settings and downward sensor state */
+export interface AvoidanceState {
+  timestampMs: number;
+  obstacleAvoidanceEnabled: boolean | null;
+  userAvoidanceEnabled: boolean | null;
+  visionPositioningUsed: boolean;
+  ultrasonicWorking: boolean;
+  visionHeight: number | null;
+}
+
+/** Motor / ESC status flags */
+export interface MotorStatus {
+  timestampMs: number;
+  motorsOn: boolean;
+  motorBlocked: boolean;
+  notEnoughForce: boolean;
+  propellerCatapult: boolean;
+  startFailedCause: string | null;
+}
+
+/** Wind-related app warning */
+export interface WindWarning {
+  timestampMs: number;
+  messageType: 'tip' | 'warn';
+  message: string;
+}
+
+/** Extra DJI records stored as change-points (empty for other formats) */
+export interface FlightSideData {
+  homeUpdates: HomeUpdate[];
+  rcGps: RcGpsPoint[];
+  avoidance: AvoidanceState[];
+  motorStatus: MotorStatus[];
+  windWarnings: WindWarning[];
+}
+
+/** Kinds of equipment tracked in the inventory */
+export type EquipmentType = 'aircraft' | 'battery' | 'rc' | 'camera';
+
+/** App, firmware and component serials recorded in a flight log header */
+export interface FlightEquipment {
+  appName: string | null;
+  appPlatform: string | null;
+  appVersion: string | null;
+  aircraftFirmware: string | null;
+  batteryFirmware: string | null;
+  rcSerial: string | null;
+  rcFirmware: string | null;
+  cameraSerial: string | null;
+  cameraFirmware: string | null;
+  gimbalFirmware: string | null;
+}
+
+/** A firmware version flown on one piece of equipment */
+export interface FirmwareVersion {
+  version: string;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  flightCount: number;
+}
+
+/** One item in the equipment inventory */
+export interface EquipmentItem {
+  serial: string;
+  equipmentType: EquipmentType;
+  displayName: string | null;
+  firstSeen: string | null;
+  lastSeen: string | null;
+  firmware: string | null;
+  flightCount: number;
+  firmwareHistory: FirmwareVersion[];
+}
+
+/** One frame of a DJI video subtitle (.SRT) sidecar */
+export interface MediaFrame {
+  /** Position in the video */
+  offsetMs: number;
+  endMs: number;
+  latitude: number | null;
+  longitude: number | null;
+  /** Height above takeoff (m) */
+  relAltitude: number | null;
+  /** Altitude above sea level (m) */
+  absAltitude: number | null;
+  iso: number | null;
+  /** e.g. "1/1000.0" */
+  shutter: string | null;
+  fnum: number | null;
+  ev: number | null;
+  focalLength: number | null;
+  colorTemp: number | null;
+}
+
+/** A video clip imported from its DJI .SRT sidecar */
+export interface MediaClip {
+  id: number;
+  /** Flight the clip was shot during; null until a matching flight is imported */
+  flightId: number | null;
+  srtFile: string;
+  videoFile: string;
+  fileHash: string;
+  droneSerial: string | null;
+  /** Camera wall-clock time (no time zone) */
+  startTime: string;
+  endTime: string;
+  durationSecs: number;
+  frameCount: number;
+  /** Camera settings of the first frame */
+  iso: number | null;
+  shutter: string | null;
+  fnum: number | null;
+  /** Seconds from the flight's start to the clip's start */
+  flightOffsetSecs: number | null;
+  /** Thinned-out positions: [lng, lat, height][] */
+  track: [number, number, number][];
+}
+
+/** Result of importing one .SRT sidecar */
+export interface MediaImportResult {
+  success: boolean;
+  clipId: number | null;
+  flightId: number | null;
+  message: string;
+  frameCount: number;
+}
+
 /** Complete flight data response from backend */
 export interface FlightDataResponse {
   flight: Flight;
@@ -87,6 +249,8 @@ export interface FlightDataResponse {
   track: [number, number, number][];
   /** App tip/warning messages */
   messages?: FlightMessage[];
+  /** Videos shot during the flight */
+  mediaClips?: MediaClip[];
 }
 
 export interface BatteryUsage {
@@ -135,6 +299,7 @@ export interface OverviewStats {
   totalFlights: number;
   totalDistanceM: number;
   totalDurationSecs: number;
+  totalNightSecs: number;
   totalPoints: number;
   totalPhotos: number;
   totalVideos: number;
@@ -157,6 +322,148 @@ export interface ImportResult {
   fileHash: string | null;
 }
 
+/** Progress of a batch import, emitted as the "import-progress" event */
+export interface BatchImportProgress {
+  total: number;
+  completed: number;
+  imported: number;
+  skipped: number;
+  queued: number;
+  failed: number;
+  /** Files left unprocessed because the import was cancelled */
+  cancelled: number;
+  elapsedSecs: number;
+}
+
+/** Kind of an import job progress event */
+export type ImportJobEventKind =
+  | 'started'
+  | 'parsed'
+  | 'imported'
+  | 'duplicate'
+  | 'skipped'
+  | 'queued'
+  | 'failed'
+  | 'done';
+
+/** Progress event of a background import job (SSE in web mode, `import-job` event on desktop) */
+export interface ImportJobEvent {
+  jobId: string;
+  kind: ImportJobEventKind;
+  /** Position of the file in the job, null for `done` */
+  index: number | null;
+  fileName: string | null;
+  message: string | null;
+  flightId: number | null;
+  progress: BatchImportProgress;
+}
+
+/** Returned when an import job is started */
+export interface ImportJobStarted {
+  jobId: string;
+  total: number;
+}
+
+/** Import result for one log inside a ZIP/tar.gz archive */
+export interface ArchiveImportResult extends ImportResult {
+  /** Path of the log inside the archive */
+  entryName: string;
+}
+
+/** A file hash that is never imported again (e.g. a deleted flight) */
+export interface BlacklistEntry {
+  fileHash: string;
+  fileName: string | null;
+  addedAt: string;
+}
+
+/** A file that failed to import during folder sync */
+export interface ImportFailure {
+  fileHash: string;
+  filePath: string;
+  /** Error variant, e.g. "timeout" or "incompatible_file" */
+  errorKind: string;
+  message: string;
+  firstAttempt: string;
+  lastAttempt: string;
+  attempts: number;
+  /** Ignored files are skipped by later syncs */
+  ignored: boolean;
+}
+
+/** An encrypted (V13+) log waiting for its keychains before it can be imported */
+export interface PendingDecryption {
+  fileHash: string;
+  fileName: string;
+  /** Where the log was imported from */
+  sourcePath: string;
+  /** Last keychain error */
+  message: string;
+  addedAt: string;
+  lastAttempt: string;
+  attempts: number;
+}
+
+/** Outcome of retrying the pending decryption queue */
+export interface PendingRetryResult {
+  imported: number;
+  /** Still missing keychains, kept in the queue */
+  stillPending: number;
+  /** Decrypted but failed for another reason; moved to the import failures */
+  failed: number;
+}
+
+/** Result of importing a keychain bundle */
+export interface KeychainImportResult {
+  /** Keychains added or updated in the cache */
+  imported: number;
+  /** Pending logs retried with the new keychains */
+  pending: PendingRetryResult;
+}
+
+/** Result of re-parsing one flight from its kept original file */
+export interface ReprocessResult {
+  flightId: number;
+  success: boolean;
+  message: string;
+  pointCount: number;
+}
+
+/** Telemetry quality counters reported by an import preview */
+export interface TelemetryQuality {
+  pointCount: number;
+  gpsPoints: number;
+  missingGpsPoints: number;
+  zeroGpsPoints: number;
+  batteryPoints: number;
+  duplicateTimestamps: number;
+  outOfOrderTimestamps: number;
+  maxGapMs: number;
+  sampleRateHz: number;
+}
+
+/** Dry-run import result (nothing is written to the database) */
+export interface ImportPreview {
+  formatId: string;
+  formatName: string;
+  /** Parsed flight metadata (snake_case, id is 0) */
+  metadata: Record<string, unknown>;
+  tags: string[];
+  /** Flight with the same file hash, if already imported */
+  alreadyImported: string | null;
+  /** Flight with the same drone, battery and start time */
+  duplicateOf: string | null;
+  messageCount: number;
+  quality: TelemetryQuality;
+}
+
+/** Flight log format accepted by the importer */
+export interface SupportedFormat {
+  id: string;
+  name: string;
+  extensions: string[];
+}
+
 /** Flight statistics */
 export interface FlightStats {
   durationSecs: number;